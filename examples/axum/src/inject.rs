@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use sadi::{Error, ErrorKind, Injector};
+
+use crate::problem::Problem;
+
+/// Resolves `T` from the application's [`Injector`] before the handler runs.
+///
+/// A failed resolution rejects the request with [`InjectRejection`], whose
+/// status reflects the [`ErrorKind`] instead of a blanket 500.
+pub struct InjectResult<T: ?Sized>(pub Arc<T>);
+
+impl<S, T> FromRequestParts<S> for InjectResult<T>
+where
+    Arc<Injector>: FromRef<S>,
+    S: Send + Sync,
+    T: ?Sized + Send + Sync + 'static,
+{
+    type Rejection = InjectRejection;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Arc::<Injector>::from_ref(state)
+            .try_resolve::<T>()
+            .map(InjectResult)
+            .map_err(InjectRejection)
+    }
+}
+
+/// The rejection produced when [`InjectResult`] cannot resolve its service.
+pub struct InjectRejection(pub Error);
+
+impl InjectRejection {
+    /// Maps the resolution error to a response status.
+    ///
+    /// A missing provider means the service is unavailable right now, for
+    /// example because its gate is closed, and yields 503. Anything else is a
+    /// wiring mistake and yields 500.
+    pub fn status(&self) -> StatusCode {
+        match self.0.kind {
+            ErrorKind::ServiceNotProvided => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for InjectRejection {
+    fn into_response(self) -> Response {
+        Problem::new(
+            self.status(),
+            format!("Failed to resolve service: {}", self.0),
+        )
+        .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use sadi::{Provider, Shared};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    struct Flag(AtomicBool);
+    struct Session;
+
+    async fn session_handler(InjectResult(_): InjectResult<Session>) -> StatusCode {
+        StatusCode::OK
+    }
+
+    fn app() -> (Router, Arc<Injector>) {
+        let injector = Arc::new(Injector::root());
+        injector.provide::<Flag>(Provider::root(|_| Shared::new(Flag(AtomicBool::new(true)))));
+        injector.provide::<Session>(
+            Provider::transient(|_| Shared::new(Session))
+                .gated_by(|injector| injector.resolve::<Flag>().0.load(Ordering::Relaxed)),
+        );
+
+        let router = Router::new()
+            .route("/session", get(session_handler))
+            .with_state(injector.clone());
+
+        (router, injector)
+    }
+
+    async fn status_of(router: Router, uri: &str) -> StatusCode {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn unavailable_service_is_rejected_with_503() {
+        let (router, injector) = app();
+        assert_eq!(status_of(router.clone(), "/session").await, StatusCode::OK);
+
+        injector.resolve::<Flag>().0.store(false, Ordering::Relaxed);
+
+        assert_eq!(
+            status_of(router, "/session").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[test]
+    fn misconfiguration_is_rejected_with_500() {
+        let rejection = InjectRejection(Error::new(ErrorKind::CircularDependency, "A -> B -> A"));
+
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -1,16 +1,27 @@
+mod inject;
+mod problem;
+
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{FromRef, Path, State},
     http::StatusCode,
     routing::{delete, get, post, put},
 };
 use complex::core::application::use_case::{
-    todo::{CreateTodoUseCase, DeleteTodoUseCase, GetAllTodoUseCase, UpdateStatusTodoUseCase},
+    todo::{
+        CreateTodoUseCase, DeleteTodoUseCase, GetAllTodoUseCase, GetOverdueTodosUseCase,
+        UpdateStatusTodoUseCase,
+    },
     user::{CreateUserUseCase, DeleteUserUseCase, GetAllUserUseCase, GetByIdUserUseCase},
 };
+use complex::core::domain::event::EventBus;
+use complex::core::domain::id::Id;
+use complex::core::domain::time::{DateTime, now};
 use complex::core::domain::todo::Todo;
 use complex::core::domain::user::User;
-use sadi::Injector;
+use inject::InjectResult;
+use problem::Problem;
+use sadi::{HealthStatus, Injector};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -19,6 +30,12 @@ struct AppState {
     injector: Arc<Injector>,
 }
 
+impl FromRef<AppState> for Arc<Injector> {
+    fn from_ref(state: &AppState) -> Self {
+        state.injector.clone()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct CreateUserRequest {
     name: String,
@@ -27,9 +44,11 @@ struct CreateUserRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 struct CreateTodoRequest {
-    user_id: i64,
+    user_id: Id,
     title: String,
     description: String,
+    #[serde(default)]
+    due_date: Option<DateTime>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,185 +86,146 @@ impl ApiResponse<()> {
 
 // User Handlers
 async fn create_user(
-    State(state): State<AppState>,
+    InjectResult(create_user): InjectResult<CreateUserUseCase>,
     Json(req): Json<CreateUserRequest>,
-) -> Result<(StatusCode, Json<ApiResponse<User>>), (StatusCode, String)> {
-    let create_user = state
-        .injector
-        .try_resolve::<CreateUserUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+) -> Result<(StatusCode, Json<ApiResponse<User>>), Problem> {
     let user = create_user
         .execute(req.name, req.email)
         .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        .map_err(|e| Problem::new(StatusCode::BAD_REQUEST, e))?;
 
     Ok((StatusCode::CREATED, Json(ApiResponse::ok(user))))
 }
 
 async fn get_all_users(
-    State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<User>>>, (StatusCode, String)> {
-    let get_all = state
-        .injector
-        .try_resolve::<GetAllUserUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+    InjectResult(get_all): InjectResult<GetAllUserUseCase>,
+) -> Result<Json<ApiResponse<Vec<User>>>, Problem> {
     let users = get_all
         .execute()
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        .map_err(|e| Problem::new(StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(ApiResponse::ok(users)))
 }
 
 async fn get_user_by_id(
-    State(state): State<AppState>,
-    Path(id): Path<i64>,
-) -> Result<Json<ApiResponse<User>>, (StatusCode, String)> {
-    let get_by_id = state
-        .injector
-        .try_resolve::<GetByIdUserUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+    InjectResult(get_by_id): InjectResult<GetByIdUserUseCase>,
+    Path(id): Path<Id>,
+) -> Result<Json<ApiResponse<User>>, Problem> {
     let user = get_by_id
-        .execute(id as u32)
+        .execute(id)
         .await
-        .map_err(|e| (StatusCode::NOT_FOUND, e))?
-        .ok_or_else(|| (StatusCode::NOT_FOUND, "User not found".to_string()))?;
+        .map_err(|e| Problem::new(StatusCode::NOT_FOUND, e))?
+        .ok_or_else(|| Problem::new(StatusCode::NOT_FOUND, "User not found"))?;
 
     Ok(Json(ApiResponse::ok(user)))
 }
 
 async fn delete_user(
-    State(state): State<AppState>,
-    Path(id): Path<i64>,
-) -> Result<(StatusCode, Json<ApiResponse<bool>>), (StatusCode, String)> {
-    let delete = state
-        .injector
-        .try_resolve::<DeleteUserUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+    InjectResult(delete): InjectResult<DeleteUserUseCase>,
+    Path(id): Path<Id>,
+) -> Result<(StatusCode, Json<ApiResponse<bool>>), Problem> {
     let deleted = delete
-        .execute(id as u32)
+        .execute(id)
         .await
-        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+        .map_err(|e| Problem::new(StatusCode::NOT_FOUND, e))?;
 
     Ok((StatusCode::OK, Json(ApiResponse::ok(deleted))))
 }
 
 // Todo Handlers
 async fn create_todo(
-    State(state): State<AppState>,
+    InjectResult(create_todo): InjectResult<CreateTodoUseCase>,
     Json(req): Json<CreateTodoRequest>,
-) -> Result<(StatusCode, Json<ApiResponse<Todo>>), (StatusCode, String)> {
-    let create_todo = state
-        .injector
-        .try_resolve::<CreateTodoUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+) -> Result<(StatusCode, Json<ApiResponse<Todo>>), Problem> {
     let todo = create_todo
-        .execute(req.user_id as u32, req.title, req.description)
+        .execute(req.user_id, req.title, req.description, req.due_date)
         .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        .map_err(|e| Problem::new(StatusCode::BAD_REQUEST, e))?;
 
     Ok((StatusCode::CREATED, Json(ApiResponse::ok(todo))))
 }
 
 async fn get_all_todos(
-    State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<Todo>>>, (StatusCode, String)> {
-    let get_all = state
-        .injector
-        .try_resolve::<GetAllTodoUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+    InjectResult(get_all): InjectResult<GetAllTodoUseCase>,
+) -> Result<Json<ApiResponse<Vec<Todo>>>, Problem> {
     let todos = get_all
         .execute()
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        .map_err(|e| Problem::new(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(ApiResponse::ok(todos)))
+}
+
+async fn get_overdue_todos(
+    InjectResult(get_overdue): InjectResult<GetOverdueTodosUseCase>,
+) -> Result<Json<ApiResponse<Vec<Todo>>>, Problem> {
+    let todos = get_overdue
+        .execute(now())
+        .await
+        .map_err(|e| Problem::new(StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(ApiResponse::ok(todos)))
 }
 
 async fn update_todo_status(
-    State(state): State<AppState>,
-    Path(id): Path<i64>,
+    InjectResult(update): InjectResult<UpdateStatusTodoUseCase>,
+    Path(id): Path<Id>,
     Json(req): Json<UpdateTodoStatusRequest>,
-) -> Result<Json<ApiResponse<Todo>>, (StatusCode, String)> {
-    let update = state
-        .injector
-        .try_resolve::<UpdateStatusTodoUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+) -> Result<Json<ApiResponse<Todo>>, Problem> {
     let todo = update
-        .execute(id as u32, req.completed)
+        .execute(id, req.completed)
         .await
-        .map_err(|e| (StatusCode::NOT_FOUND, e))?
-        .ok_or_else(|| (StatusCode::NOT_FOUND, "Todo not found".to_string()))?;
+        .map_err(|e| Problem::new(StatusCode::NOT_FOUND, e))?
+        .ok_or_else(|| Problem::new(StatusCode::NOT_FOUND, "Todo not found"))?;
 
     Ok(Json(ApiResponse::ok(todo)))
 }
 
 async fn delete_todo(
-    State(state): State<AppState>,
-    Path(id): Path<i64>,
-) -> Result<(StatusCode, Json<ApiResponse<bool>>), (StatusCode, String)> {
-    let delete = state
-        .injector
-        .try_resolve::<DeleteTodoUseCase>()
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to resolve use case: {:?}", e),
-            )
-        })?;
-
+    InjectResult(delete): InjectResult<DeleteTodoUseCase>,
+    Path(id): Path<Id>,
+) -> Result<(StatusCode, Json<ApiResponse<bool>>), Problem> {
     let deleted = delete
-        .execute(id as u32)
+        .execute(id)
         .await
-        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+        .map_err(|e| Problem::new(StatusCode::NOT_FOUND, e))?;
 
     Ok((StatusCode::OK, Json(ApiResponse::ok(deleted))))
 }
 
-async fn health_check() -> &'static str {
-    "OK"
+#[derive(Debug, Serialize)]
+struct ServiceHealth {
+    service: &'static str,
+    healthy: bool,
+    reason: Option<String>,
+}
+
+/// Reports the health of every cached singleton marked with `dyn HealthCheck`,
+/// answering 503 if any of them is unhealthy.
+async fn health_check(
+    State(injector): State<Arc<Injector>>,
+) -> (StatusCode, Json<Vec<ServiceHealth>>) {
+    let report: Vec<_> = injector
+        .health_check_all()
+        .await
+        .into_iter()
+        .map(|(service, status)| ServiceHealth {
+            service,
+            healthy: status.is_healthy(),
+            reason: match status {
+                HealthStatus::Unhealthy(reason) => Some(reason),
+                _ => None,
+            },
+        })
+        .collect();
+
+    let status = if report.iter().all(|service| service.healthy) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
 }
 
 #[tokio::main]
@@ -254,7 +234,21 @@ async fn main() {
     tracing_subscriber::fmt::init();
 
     // Build the application with dependency injection
-    let app_di = complex::infra::di::build().expect("Failed to build application");
+    let app_di = match complex::infra::di::build() {
+        Ok(app_di) => app_di,
+        Err(err) => {
+            eprintln!(
+                "Failed to build application ({:?}): {}",
+                err.kind, err.message
+            );
+            std::process::exit(1);
+        }
+    };
+    app_di
+        .injector()
+        .resolve::<dyn EventBus>()
+        .subscribe(Box::new(|event| tracing::info!(?event, "domain event")));
+
     let state = AppState {
         injector: app_di.injector().clone(),
     };
@@ -271,6 +265,7 @@ async fn main() {
         // Todo routes
         .route("/todos", post(create_todo))
         .route("/todos", get(get_all_todos))
+        .route("/todos/overdue", get(get_overdue_todos))
         .route("/todos/{id}/status", put(update_todo_status))
         .route("/todos/{id}", delete(delete_todo))
         .with_state(state);
@@ -288,6 +283,7 @@ async fn main() {
     println!("  DELETE /users/:id");
     println!("  POST   /todos");
     println!("  GET    /todos");
+    println!("  GET    /todos/overdue");
     println!("  PUT    /todos/:id/status");
     println!("  DELETE /todos/:id");
 
@@ -0,0 +1,187 @@
+//! In-memory repositories and event bus shared by the use case tests.
+
+use std::sync::Mutex;
+
+use crate::core::domain::error::DomainError;
+use crate::core::domain::event::{DomainEvent, EventBus, Subscriber};
+use crate::core::domain::id::{Id, test_id};
+use crate::core::domain::time::DateTime;
+use crate::core::domain::todo::{Todo, TodoRepository};
+use crate::core::domain::user::{User, UserRepository};
+
+#[derive(Default)]
+pub(crate) struct InMemoryUserRepository {
+    users: Mutex<Vec<User>>,
+}
+
+#[async_trait::async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn get_all(&self) -> Result<Vec<User>, String> {
+        Ok(self.users.lock().unwrap().clone())
+    }
+
+    async fn get_by_id(&self, id: Id) -> Result<Option<User>, String> {
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.id == id)
+            .cloned())
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.email == email)
+            .cloned())
+    }
+
+    async fn create(&self, name: String, email: String) -> Result<User, String> {
+        let mut users = self.users.lock().unwrap();
+        if users.iter().any(|u| u.email == email) {
+            return Err(DomainError::Conflict { field: "email" }.to_string());
+        }
+
+        let user = User::builder()
+            .id(test_id(users.len() as u32 + 1))
+            .name(name)
+            .email(email)
+            .build()
+            .map_err(|e| e.to_string())?;
+        users.push(user.clone());
+        Ok(user)
+    }
+
+    async fn delete(&self, id: Id) -> Result<bool, String> {
+        let mut users = self.users.lock().unwrap();
+        let before = users.len();
+        users.retain(|u| u.id != id);
+        Ok(users.len() < before)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct InMemoryTodoRepository {
+    todos: Mutex<Vec<(Id, Todo)>>,
+}
+
+impl InMemoryTodoRepository {
+    pub(crate) fn todos_of(&self, user_id: Id) -> Vec<Todo> {
+        self.todos
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(owner, _)| *owner == user_id)
+            .map(|(_, todo)| todo.clone())
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl TodoRepository for InMemoryTodoRepository {
+    async fn get_all(&self) -> Result<Vec<Todo>, String> {
+        Ok(self
+            .todos
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, todo)| todo.clone())
+            .collect())
+    }
+
+    async fn get_by_id(&self, id: Id) -> Result<Option<Todo>, String> {
+        Ok(self
+            .todos
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, todo)| todo.id == id)
+            .map(|(_, todo)| todo.clone()))
+    }
+
+    async fn create(
+        &self,
+        user_id: Id,
+        title: String,
+        description: String,
+        due_date: Option<DateTime>,
+    ) -> Result<Todo, String> {
+        let mut todos = self.todos.lock().unwrap();
+        let todo = Todo::builder()
+            .id(test_id(todos.len() as u32 + 1))
+            .title(title)
+            .description(description)
+            .due_date(due_date)
+            .build()
+            .map_err(|e| e.to_string())?;
+        todos.push((user_id, todo.clone()));
+        Ok(todo)
+    }
+
+    async fn get_overdue(&self, now: DateTime) -> Result<Vec<Todo>, String> {
+        Ok(self
+            .todos
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, todo)| todo.is_overdue(now))
+            .map(|(_, todo)| todo.clone())
+            .collect())
+    }
+
+    async fn update_status(&self, id: Id, completed: bool) -> Result<Option<Todo>, String> {
+        let mut todos = self.todos.lock().unwrap();
+        Ok(todos
+            .iter_mut()
+            .find(|(_, todo)| todo.id == id)
+            .map(|(_, todo)| {
+                todo.completed = completed;
+                todo.clone()
+            }))
+    }
+
+    async fn delete(&self, id: Id) -> Result<bool, String> {
+        let mut todos = self.todos.lock().unwrap();
+        let before = todos.len();
+        todos.retain(|(_, todo)| todo.id != id);
+        Ok(todos.len() < before)
+    }
+
+    async fn delete_by_user(&self, user_id: Id) -> Result<usize, String> {
+        let mut todos = self.todos.lock().unwrap();
+        let before = todos.len();
+        todos.retain(|(owner, _)| *owner != user_id);
+        Ok(before - todos.len())
+    }
+}
+
+/// Records published events so tests can assert on them, and passes them on
+/// to any subscriber.
+#[derive(Default)]
+pub(crate) struct RecordingEventBus {
+    events: Mutex<Vec<DomainEvent>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+impl RecordingEventBus {
+    pub(crate) fn events(&self) -> Vec<DomainEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl EventBus for RecordingEventBus {
+    fn publish(&self, event: DomainEvent) {
+        for subscriber in self.subscribers.lock().unwrap().iter() {
+            subscriber(&event);
+        }
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn subscribe(&self, subscriber: Subscriber) {
+        self.subscribers.lock().unwrap().push(subscriber);
+    }
+}
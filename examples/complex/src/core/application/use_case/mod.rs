@@ -1,2 +1,4 @@
+#[cfg(test)]
+pub(crate) mod in_memory;
 pub mod todo;
 pub mod user;
@@ -1,26 +1,103 @@
 use std::sync::Arc;
 
+use crate::core::domain::event::{DomainEvent, EventBus};
+use crate::core::domain::id::Id;
+use crate::core::domain::time::DateTime;
 use crate::core::domain::todo::{Todo, TodoRepository};
 
 pub struct CreateTodoUseCase {
     todo_repository: Arc<dyn TodoRepository>,
+    event_bus: Arc<dyn EventBus>,
 }
 
 impl CreateTodoUseCase {
-    pub fn new(todo_repository: Arc<dyn TodoRepository>) -> Self {
-        Self { todo_repository }
+    pub fn new(todo_repository: Arc<dyn TodoRepository>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            todo_repository,
+            event_bus,
+        }
     }
 
     pub async fn execute(
         &self,
-        user_id: u32,
+        user_id: Id,
         title: String,
         description: String,
+        due_date: Option<DateTime>,
     ) -> Result<Todo, String> {
-        // Business logic can be added here (e.g., validation, logging, etc.)
-        // For simplicity, we directly call the repository to create the todo.
-        self.todo_repository
-            .create(user_id, title, description)
+        // Validate the input through the builder before it reaches the repository.
+        let draft = Todo::builder()
+            .title(title)
+            .description(description)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let todo = self
+            .todo_repository
+            .create(user_id, draft.title, draft.description, due_date)
+            .await?;
+
+        self.event_bus
+            .publish(DomainEvent::TodoCreated(todo.clone()));
+        Ok(todo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sadi::test::TestInjector;
+
+    use super::*;
+    use crate::core::application::use_case::in_memory::{
+        InMemoryTodoRepository, RecordingEventBus,
+    };
+    use crate::core::domain::id::test_id;
+
+    fn injector(
+        repository: Arc<InMemoryTodoRepository>,
+        events: Arc<RecordingEventBus>,
+    ) -> TestInjector {
+        TestInjector::new()
+            .with_fake::<dyn TodoRepository>(repository)
+            .with_fake::<dyn EventBus>(events)
+            .with_factory(|injector| {
+                Arc::new(CreateTodoUseCase::new(
+                    injector.resolve::<dyn TodoRepository>(),
+                    injector.resolve::<dyn EventBus>(),
+                ))
+            })
+    }
+
+    #[tokio::test]
+    async fn creates_todo_through_repository() {
+        let repository = Arc::new(InMemoryTodoRepository::default());
+        let events = Arc::new(RecordingEventBus::default());
+        let injector = injector(repository.clone(), events.clone());
+
+        let todo = injector
+            .resolve_or_panic::<CreateTodoUseCase>()
+            .execute(test_id(7), "Buy milk".into(), String::new(), None)
             .await
+            .unwrap();
+
+        assert_eq!(todo.title, "Buy milk");
+        assert_eq!(events.events(), [DomainEvent::TodoCreated(todo.clone())]);
+        assert_eq!(repository.todos_of(test_id(7)), [todo]);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_title_before_repository() {
+        let repository = Arc::new(InMemoryTodoRepository::default());
+        let events = Arc::new(RecordingEventBus::default());
+        let injector = injector(repository.clone(), events.clone());
+
+        let result = injector
+            .resolve_or_panic::<CreateTodoUseCase>()
+            .execute(test_id(7), "   ".into(), String::new(), None)
+            .await;
+
+        assert!(result.is_err());
+        assert!(repository.get_all().await.unwrap().is_empty());
+        assert!(events.events().is_empty());
     }
 }
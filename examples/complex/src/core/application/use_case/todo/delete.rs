@@ -1,19 +1,54 @@
 use std::sync::Arc;
 
+use crate::core::domain::event::{DomainEvent, EventBus};
+use crate::core::domain::id::Id;
 use crate::core::domain::todo::TodoRepository;
 
 pub struct DeleteTodoUseCase {
     todo_repository: Arc<dyn TodoRepository>,
+    event_bus: Arc<dyn EventBus>,
 }
 
 impl DeleteTodoUseCase {
-    pub fn new(todo_repository: Arc<dyn TodoRepository>) -> Self {
-        Self { todo_repository }
+    pub fn new(todo_repository: Arc<dyn TodoRepository>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            todo_repository,
+            event_bus,
+        }
     }
 
-    pub async fn execute(&self, id: u32) -> Result<bool, String> {
-        // Business logic can be added here (e.g., validation, logging, etc.)
-        // For simplicity, we directly call the repository to delete the todo.
-        self.todo_repository.delete(id).await
+    pub async fn execute(&self, id: Id) -> Result<bool, String> {
+        let deleted = self.todo_repository.delete(id).await?;
+
+        // Deleting a missing todo changes nothing, so there is nothing to announce.
+        if deleted {
+            self.event_bus.publish(DomainEvent::TodoDeleted { id });
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::application::use_case::in_memory::{
+        InMemoryTodoRepository, RecordingEventBus,
+    };
+    use crate::core::domain::id::test_id;
+
+    #[tokio::test]
+    async fn publishes_todo_deleted_only_for_existing_todos() {
+        let todos = Arc::new(InMemoryTodoRepository::default());
+        let events = Arc::new(RecordingEventBus::default());
+        let todo = todos
+            .create(test_id(1), "Buy milk".into(), String::new(), None)
+            .await
+            .unwrap();
+
+        let use_case = DeleteTodoUseCase::new(todos, events.clone());
+        assert!(use_case.execute(todo.id).await.unwrap());
+        assert!(!use_case.execute(todo.id).await.unwrap());
+
+        assert_eq!(events.events(), [DomainEvent::TodoDeleted { id: todo.id }]);
     }
 }
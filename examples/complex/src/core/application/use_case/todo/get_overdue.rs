@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::core::domain::time::DateTime;
+use crate::core::domain::todo::{Todo, TodoRepository};
+
+pub struct GetOverdueTodosUseCase {
+    todo_repository: Arc<dyn TodoRepository>,
+}
+
+impl GetOverdueTodosUseCase {
+    pub fn new(todo_repository: Arc<dyn TodoRepository>) -> Self {
+        Self { todo_repository }
+    }
+
+    /// Lists the open todos whose due date is before `now`.
+    pub async fn execute(&self, now: DateTime) -> Result<Vec<Todo>, String> {
+        self.todo_repository.get_overdue(now).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::core::application::use_case::in_memory::InMemoryTodoRepository;
+    use crate::core::domain::id::test_id;
+    use crate::core::domain::time::now;
+
+    #[tokio::test]
+    async fn lists_only_open_todos_past_due() {
+        let todos = Arc::new(InMemoryTodoRepository::default());
+        let now = now();
+        let create = |title: &str, due_date: Option<DateTime>| {
+            todos.create(test_id(1), title.into(), String::new(), due_date)
+        };
+
+        let overdue = create("Pay rent", Some(now - Duration::days(2)))
+            .await
+            .unwrap();
+        let done = create("File taxes", Some(now - Duration::days(1)))
+            .await
+            .unwrap();
+        create("Book flights", Some(now + Duration::days(1)))
+            .await
+            .unwrap();
+        create("Read a book", None).await.unwrap();
+        todos.update_status(done.id, true).await.unwrap();
+
+        let use_case = GetOverdueTodosUseCase::new(todos);
+
+        assert_eq!(use_case.execute(now).await.unwrap(), vec![overdue]);
+    }
+
+    #[tokio::test]
+    async fn nothing_is_overdue_before_the_first_due_date() {
+        let todos = Arc::new(InMemoryTodoRepository::default());
+        let now = now();
+        todos
+            .create(test_id(1), "Pay rent".into(), String::new(), Some(now))
+            .await
+            .unwrap();
+
+        let use_case = GetOverdueTodosUseCase::new(todos);
+
+        assert!(use_case.execute(now).await.unwrap().is_empty());
+    }
+}
@@ -2,10 +2,12 @@ mod create;
 mod delete;
 mod get_all;
 mod get_by_id;
+mod get_overdue;
 mod update_status;
 
 pub use create::*;
 pub use delete::*;
 pub use get_all::*;
 pub use get_by_id::*;
+pub use get_overdue::*;
 pub use update_status::*;
@@ -1,19 +1,58 @@
 use std::sync::Arc;
 
+use crate::core::domain::event::{DomainEvent, EventBus};
+use crate::core::domain::id::Id;
 use crate::core::domain::todo::{Todo, TodoRepository};
 
 pub struct UpdateStatusTodoUseCase {
     todo_repository: Arc<dyn TodoRepository>,
+    event_bus: Arc<dyn EventBus>,
 }
 
 impl UpdateStatusTodoUseCase {
-    pub fn new(todo_repository: Arc<dyn TodoRepository>) -> Self {
-        Self { todo_repository }
+    pub fn new(todo_repository: Arc<dyn TodoRepository>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            todo_repository,
+            event_bus,
+        }
     }
 
-    pub async fn execute(&self, todo_id: u32, completed: bool) -> Result<Option<Todo>, String> {
-        // Business logic can be added here (e.g., validation, logging, etc.)
-        // For simplicity, we directly call the repository to update the todo status.
-        self.todo_repository.update_status(todo_id, completed).await
+    pub async fn execute(&self, todo_id: Id, completed: bool) -> Result<Option<Todo>, String> {
+        let updated = self
+            .todo_repository
+            .update_status(todo_id, completed)
+            .await?;
+
+        if let Some(todo) = &updated {
+            self.event_bus
+                .publish(DomainEvent::TodoStatusUpdated(todo.clone()));
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::application::use_case::in_memory::{
+        InMemoryTodoRepository, RecordingEventBus,
+    };
+    use crate::core::domain::id::test_id;
+
+    #[tokio::test]
+    async fn publishes_the_updated_todo() {
+        let todos = Arc::new(InMemoryTodoRepository::default());
+        let events = Arc::new(RecordingEventBus::default());
+        let todo = todos
+            .create(test_id(1), "Buy milk".into(), String::new(), None)
+            .await
+            .unwrap();
+
+        let use_case = UpdateStatusTodoUseCase::new(todos, events.clone());
+        let completed = use_case.execute(todo.id, true).await.unwrap().unwrap();
+        assert_eq!(use_case.execute(test_id(99), true).await.unwrap(), None);
+
+        assert!(completed.completed);
+        assert_eq!(events.events(), [DomainEvent::TodoStatusUpdated(completed)]);
     }
 }
@@ -1,19 +1,61 @@
 use std::sync::Arc;
 
+use crate::core::domain::event::{DomainEvent, EventBus};
 use crate::core::domain::user::{User, UserRepository};
 
 pub struct CreateUserUseCase {
     user_repository: Arc<dyn UserRepository>,
+    event_bus: Arc<dyn EventBus>,
 }
 
 impl CreateUserUseCase {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        Self { user_repository }
+    pub fn new(user_repository: Arc<dyn UserRepository>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            user_repository,
+            event_bus,
+        }
     }
 
     pub async fn execute(&self, name: String, email: String) -> Result<User, String> {
-        // Business logic can be added here (e.g., validation, logging, etc.)
-        // For simplicity, we directly call the repository to create the user.
-        self.user_repository.create(name, email).await
+        // Validate the input through the builder before it reaches the repository.
+        let draft = User::builder()
+            .name(name)
+            .email(email)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let user = self.user_repository.create(draft.name, draft.email).await?;
+
+        self.event_bus
+            .publish(DomainEvent::UserCreated(user.clone()));
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::application::use_case::in_memory::{
+        InMemoryUserRepository, RecordingEventBus,
+    };
+
+    #[tokio::test]
+    async fn publishes_user_created_only_after_a_successful_create() {
+        let events = Arc::new(RecordingEventBus::default());
+        let use_case =
+            CreateUserUseCase::new(Arc::new(InMemoryUserRepository::default()), events.clone());
+
+        let alice = use_case
+            .execute("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+        assert!(
+            use_case
+                .execute("Impostor".into(), "alice@example.com".into())
+                .await
+                .is_err()
+        );
+
+        assert_eq!(events.events(), [DomainEvent::UserCreated(alice)]);
     }
 }
@@ -1,19 +1,99 @@
 use std::sync::Arc;
 
+use crate::core::domain::event::{DomainEvent, EventBus};
+use crate::core::domain::id::Id;
+use crate::core::domain::todo::TodoRepository;
 use crate::core::domain::user::UserRepository;
 
 pub struct DeleteUserUseCase {
     user_repository: Arc<dyn UserRepository>,
+    todo_repository: Arc<dyn TodoRepository>,
+    event_bus: Arc<dyn EventBus>,
 }
 
 impl DeleteUserUseCase {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        todo_repository: Arc<dyn TodoRepository>,
+        event_bus: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            user_repository,
+            todo_repository,
+            event_bus,
+        }
     }
 
-    pub async fn execute(&self, user_id: u32) -> Result<bool, String> {
-        // Business logic can be added here (e.g., validation, logging, etc.)
-        // For simplicity, we directly call the repository to delete the user.
-        self.user_repository.delete(user_id).await
+    pub async fn execute(&self, user_id: Id) -> Result<bool, String> {
+        // A user's todos go with the user. They are removed first so a failure
+        // here leaves the user in place rather than orphaning their todos.
+        self.todo_repository.delete_by_user(user_id).await?;
+
+        let deleted = self.user_repository.delete(user_id).await?;
+
+        if deleted {
+            self.event_bus
+                .publish(DomainEvent::UserDeleted { id: user_id });
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::application::use_case::in_memory::{
+        InMemoryTodoRepository, InMemoryUserRepository, RecordingEventBus,
+    };
+    use crate::core::domain::id::test_id;
+
+    #[tokio::test]
+    async fn user_id_round_trips_through_repository() {
+        let users = InMemoryUserRepository::default();
+
+        let alice = users
+            .create("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+
+        assert_eq!(users.get_by_id(alice.id).await.unwrap(), Some(alice));
+        assert_eq!(users.get_by_id(test_id(99)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn deleting_a_user_removes_only_their_todos() {
+        let users = Arc::new(InMemoryUserRepository::default());
+        let todos = Arc::new(InMemoryTodoRepository::default());
+
+        let alice = users
+            .create("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+        let bob = users
+            .create("Bob".into(), "bob@example.com".into())
+            .await
+            .unwrap();
+        todos
+            .create(alice.id, "Buy milk".into(), String::new(), None)
+            .await
+            .unwrap();
+        todos
+            .create(alice.id, "Walk dog".into(), String::new(), None)
+            .await
+            .unwrap();
+        let bobs_todo = todos
+            .create(bob.id, "Read book".into(), String::new(), None)
+            .await
+            .unwrap();
+
+        let events = Arc::new(RecordingEventBus::default());
+        let use_case = DeleteUserUseCase::new(users.clone(), todos.clone(), events.clone());
+
+        assert!(use_case.execute(alice.id).await.unwrap());
+        assert_eq!(users.get_all().await.unwrap(), vec![bob]);
+        assert_eq!(todos.get_all().await.unwrap(), vec![bobs_todo]);
+
+        assert!(!use_case.execute(alice.id).await.unwrap());
+        assert_eq!(events.events(), [DomainEvent::UserDeleted { id: alice.id }]);
     }
 }
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::core::domain::user::{User, UserRepository};
+
+pub struct GetByEmailUserUseCase {
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl GetByEmailUserUseCase {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        Self { user_repository }
+    }
+
+    /// Looks up a user by email, e.g. to authenticate a login.
+    ///
+    /// Surrounding whitespace is ignored; the comparison is otherwise exact.
+    pub async fn execute(&self, email: &str) -> Result<Option<User>, String> {
+        self.user_repository.get_by_email(email.trim()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::application::use_case::in_memory::InMemoryUserRepository;
+    use crate::core::domain::error::DomainError;
+
+    async fn repository_with_alice() -> (Arc<InMemoryUserRepository>, User) {
+        let users = Arc::new(InMemoryUserRepository::default());
+        let alice = users
+            .create("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+
+        (users, alice)
+    }
+
+    #[tokio::test]
+    async fn finds_user_by_email() {
+        let (users, alice) = repository_with_alice().await;
+        let use_case = GetByEmailUserUseCase::new(users);
+
+        let found = use_case.execute(" alice@example.com ").await.unwrap();
+
+        assert_eq!(found, Some(alice));
+    }
+
+    #[tokio::test]
+    async fn unknown_email_is_not_found() {
+        let (users, _) = repository_with_alice().await;
+        let use_case = GetByEmailUserUseCase::new(users);
+
+        assert_eq!(use_case.execute("bob@example.com").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn duplicate_email_conflicts() {
+        let (users, _) = repository_with_alice().await;
+
+        let err = users
+            .create("Impostor".into(), "alice@example.com".into())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err, DomainError::Conflict { field: "email" }.to_string());
+    }
+}
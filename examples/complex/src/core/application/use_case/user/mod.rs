@@ -1,9 +1,11 @@
 mod create;
 mod delete;
 mod get_all;
+mod get_by_email;
 mod get_by_id;
 
 pub use create::*;
 pub use delete::*;
 pub use get_all::*;
+pub use get_by_email::*;
 pub use get_by_id::*;
@@ -0,0 +1,59 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainError {
+    EmptyField {
+        field: &'static str,
+    },
+    FieldTooLong {
+        field: &'static str,
+        max: usize,
+        actual: usize,
+    },
+    InvalidField {
+        field: &'static str,
+        reason: &'static str,
+    },
+    /// Another entity already holds this value of a unique field.
+    Conflict {
+        field: &'static str,
+    },
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::EmptyField { field } => write!(f, "`{}` must not be empty", field),
+            DomainError::FieldTooLong { field, max, actual } => write!(
+                f,
+                "`{}` must be at most {} characters (got {})",
+                field, max, actual
+            ),
+            DomainError::InvalidField { field, reason } => {
+                write!(f, "`{}` is invalid: {}", field, reason)
+            }
+            DomainError::Conflict { field } => write!(f, "`{}` is already in use", field),
+        }
+    }
+}
+
+impl std::error::Error for DomainError {}
+
+pub(crate) fn require_non_empty(field: &'static str, value: &str) -> Result<(), DomainError> {
+    if value.trim().is_empty() {
+        return Err(DomainError::EmptyField { field });
+    }
+    Ok(())
+}
+
+pub(crate) fn require_max_len(
+    field: &'static str,
+    value: &str,
+    max: usize,
+) -> Result<(), DomainError> {
+    let actual = value.chars().count();
+    if actual > max {
+        return Err(DomainError::FieldTooLong { field, max, actual });
+    }
+    Ok(())
+}
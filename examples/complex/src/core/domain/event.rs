@@ -0,0 +1,23 @@
+use crate::core::domain::id::Id;
+use crate::core::domain::todo::Todo;
+use crate::core::domain::user::User;
+
+/// A change to the domain, published once the repository has persisted it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainEvent {
+    UserCreated(User),
+    UserDeleted { id: Id },
+    TodoCreated(Todo),
+    TodoStatusUpdated(Todo),
+    TodoDeleted { id: Id },
+}
+
+/// A handler run for every published event.
+pub type Subscriber = Box<dyn Fn(&DomainEvent) + Send + Sync>;
+
+/// Delivers domain events from the use cases to whoever subscribed.
+pub trait EventBus: Send + Sync {
+    fn publish(&self, event: DomainEvent);
+
+    fn subscribe(&self, subscriber: Subscriber);
+}
@@ -0,0 +1,23 @@
+//! The identifier type shared by every entity.
+//!
+//! By default ids are `u32` row ids assigned by the database. With the `uuid`
+//! feature they are random [`uuid::Uuid`]s generated by the repositories before
+//! a row is inserted.
+
+#[cfg(not(feature = "uuid"))]
+pub type Id = u32;
+
+#[cfg(feature = "uuid")]
+pub type Id = uuid::Uuid;
+
+/// A deterministic id for tests, distinct for each `n`.
+#[cfg(all(test, not(feature = "uuid")))]
+pub(crate) fn test_id(n: u32) -> Id {
+    n
+}
+
+/// A deterministic id for tests, distinct for each `n`.
+#[cfg(all(test, feature = "uuid"))]
+pub(crate) fn test_id(n: u32) -> Id {
+    uuid::Uuid::from_u128(n as u128)
+}
@@ -1,2 +1,6 @@
+pub mod error;
+pub mod event;
+pub mod id;
+pub mod time;
 pub mod todo;
 pub mod user;
@@ -0,0 +1,11 @@
+//! Points in time, such as a todo's due date.
+//!
+//! Timestamps are always UTC; converting to a user's time zone is left to the
+//! presentation layer.
+
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// The current time.
+pub fn now() -> DateTime {
+    chrono::Utc::now()
+}
@@ -1,9 +1,145 @@
 use serde::{Deserialize, Serialize};
 
+use crate::core::domain::error::{DomainError, require_max_len, require_non_empty};
+use crate::core::domain::id::Id;
+use crate::core::domain::time::DateTime;
+
+pub const TODO_TITLE_MAX_LEN: usize = 120;
+pub const TODO_DESCRIPTION_MAX_LEN: usize = 1000;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Todo {
-    pub id: u32,
+    pub id: Id,
     pub title: String,
     pub description: String,
     pub completed: bool,
+    #[serde(default)]
+    pub due_date: Option<DateTime>,
+}
+
+impl Todo {
+    pub fn builder() -> TodoBuilder {
+        TodoBuilder::default()
+    }
+
+    /// Whether the todo is still open past its due date. A todo without a due
+    /// date is never overdue.
+    pub fn is_overdue(&self, now: DateTime) -> bool {
+        !self.completed && self.due_date.is_some_and(|due_date| due_date < now)
+    }
+}
+
+/// Builds a [`Todo`] while enforcing its invariants.
+///
+/// `id` defaults to `Id::default()` (not yet persisted), `completed` defaults to `false`
+/// and `due_date` to none.
+#[derive(Debug, Default)]
+pub struct TodoBuilder {
+    id: Id,
+    title: String,
+    description: String,
+    completed: bool,
+    due_date: Option<DateTime>,
+}
+
+impl TodoBuilder {
+    pub fn id(mut self, id: Id) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn completed(mut self, completed: bool) -> Self {
+        self.completed = completed;
+        self
+    }
+
+    pub fn due_date(mut self, due_date: impl Into<Option<DateTime>>) -> Self {
+        self.due_date = due_date.into();
+        self
+    }
+
+    pub fn build(self) -> Result<Todo, DomainError> {
+        require_non_empty("title", &self.title)?;
+        require_max_len("title", &self.title, TODO_TITLE_MAX_LEN)?;
+        require_max_len("description", &self.description, TODO_DESCRIPTION_MAX_LEN)?;
+
+        Ok(Todo {
+            id: self.id,
+            title: self.title,
+            description: self.description,
+            completed: self.completed,
+            due_date: self.due_date,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::id::test_id;
+
+    #[test]
+    fn builder_defaults_completed_to_false() {
+        let todo = Todo::builder()
+            .id(test_id(1))
+            .title("Buy groceries")
+            .description("Milk, eggs, bread")
+            .build()
+            .unwrap();
+
+        assert_eq!(todo.id, test_id(1));
+        assert!(!todo.completed);
+    }
+
+    #[test]
+    fn only_open_todos_past_due_are_overdue() {
+        let now = crate::core::domain::time::now();
+        let todo = |due_date: Option<DateTime>, completed| {
+            Todo::builder()
+                .title("Pay rent")
+                .due_date(due_date)
+                .completed(completed)
+                .build()
+                .unwrap()
+        };
+
+        assert!(todo(Some(now - chrono::Duration::days(1)), false).is_overdue(now));
+        assert!(!todo(Some(now - chrono::Duration::days(1)), true).is_overdue(now));
+        assert!(!todo(Some(now + chrono::Duration::days(1)), false).is_overdue(now));
+        assert!(!todo(None, false).is_overdue(now));
+    }
+
+    #[test]
+    fn builder_rejects_empty_title() {
+        let err = Todo::builder().title("   ").build().unwrap_err();
+        assert_eq!(err, DomainError::EmptyField { field: "title" });
+    }
+
+    #[test]
+    fn builder_rejects_oversized_description() {
+        let err = Todo::builder()
+            .title("Write documentation")
+            .description("a".repeat(TODO_DESCRIPTION_MAX_LEN + 1))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            DomainError::FieldTooLong {
+                field: "description",
+                max: TODO_DESCRIPTION_MAX_LEN,
+                actual: TODO_DESCRIPTION_MAX_LEN + 1,
+            }
+        );
+    }
 }
@@ -1,19 +1,28 @@
+use crate::core::domain::id::Id;
+use crate::core::domain::time::DateTime;
 use crate::core::domain::todo::Todo;
 
 #[async_trait::async_trait]
 pub trait TodoRepository: Send + Sync {
     async fn get_all(&self) -> Result<Vec<Todo>, String>;
 
-    async fn get_by_id(&self, id: u32) -> Result<Option<Todo>, String>;
+    async fn get_by_id(&self, id: Id) -> Result<Option<Todo>, String>;
 
     async fn create(
         &self,
-        user_id: u32,
+        user_id: Id,
         title: String,
         description: String,
+        due_date: Option<DateTime>,
     ) -> Result<Todo, String>;
 
-    async fn update_status(&self, id: u32, completed: bool) -> Result<Option<Todo>, String>;
+    /// Returns the todos that are not completed and were due before `now`.
+    async fn get_overdue(&self, now: DateTime) -> Result<Vec<Todo>, String>;
 
-    async fn delete(&self, id: u32) -> Result<bool, String>;
+    async fn update_status(&self, id: Id, completed: bool) -> Result<Option<Todo>, String>;
+
+    async fn delete(&self, id: Id) -> Result<bool, String>;
+
+    /// Deletes every todo owned by `user_id`, returning how many were removed.
+    async fn delete_by_user(&self, user_id: Id) -> Result<usize, String>;
 }
@@ -1,8 +1,123 @@
 use serde::{Deserialize, Serialize};
 
+use crate::core::domain::error::{DomainError, require_max_len, require_non_empty};
+use crate::core::domain::id::Id;
+
+pub const USER_NAME_MAX_LEN: usize = 100;
+pub const USER_EMAIL_MAX_LEN: usize = 254;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct User {
-    pub id: u32,
+    pub id: Id,
     pub name: String,
     pub email: String,
 }
+
+impl User {
+    pub fn builder() -> UserBuilder {
+        UserBuilder::default()
+    }
+}
+
+/// Builds a [`User`] while enforcing its invariants.
+///
+/// `id` defaults to `Id::default()` (not yet persisted).
+#[derive(Debug, Default)]
+pub struct UserBuilder {
+    id: Id,
+    name: String,
+    email: String,
+}
+
+impl UserBuilder {
+    pub fn id(mut self, id: Id) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = email.into();
+        self
+    }
+
+    pub fn build(self) -> Result<User, DomainError> {
+        require_non_empty("name", &self.name)?;
+        require_max_len("name", &self.name, USER_NAME_MAX_LEN)?;
+        require_non_empty("email", &self.email)?;
+        require_max_len("email", &self.email, USER_EMAIL_MAX_LEN)?;
+
+        if !self.email.contains('@') {
+            return Err(DomainError::InvalidField {
+                field: "email",
+                reason: "missing `@`",
+            });
+        }
+
+        Ok(User {
+            id: self.id,
+            name: self.name,
+            email: self.email,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::id::test_id;
+
+    #[test]
+    fn builder_creates_user() {
+        let user = User::builder()
+            .id(test_id(7))
+            .name("Alice")
+            .email("alice@example.com")
+            .build()
+            .unwrap();
+
+        assert_eq!(user.id, test_id(7));
+        assert_eq!(user.name, "Alice");
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_id_serializes_as_string() {
+        let user = User::builder()
+            .id(test_id(7))
+            .name("Alice")
+            .email("alice@example.com")
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_value(&user).unwrap();
+        assert_eq!(json["id"], "00000000-0000-0000-0000-000000000007");
+        assert_eq!(serde_json::from_value::<User>(json).unwrap(), user);
+    }
+
+    #[test]
+    fn builder_rejects_empty_name() {
+        let err = User::builder()
+            .email("alice@example.com")
+            .build()
+            .unwrap_err();
+        assert_eq!(err, DomainError::EmptyField { field: "name" });
+    }
+
+    #[test]
+    fn builder_rejects_invalid_email() {
+        let err = User::builder()
+            .name("Alice")
+            .email("alice.example.com")
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::InvalidField { field: "email", .. }
+        ));
+    }
+}
@@ -1,12 +1,18 @@
+use crate::core::domain::id::Id;
 use crate::core::domain::user::User;
 
 #[async_trait::async_trait]
 pub trait UserRepository: Send + Sync {
     async fn get_all(&self) -> Result<Vec<User>, String>;
 
-    async fn get_by_id(&self, id: u32) -> Result<Option<User>, String>;
+    async fn get_by_id(&self, id: Id) -> Result<Option<User>, String>;
 
+    /// Looks up the user registered with `email`, which is unique.
+    async fn get_by_email(&self, email: &str) -> Result<Option<User>, String>;
+
+    /// Creates a user, failing with the `DomainError::Conflict` message if
+    /// `email` is already taken.
     async fn create(&self, name: String, email: String) -> Result<User, String>;
 
-    async fn delete(&self, id: u32) -> Result<bool, String>;
+    async fn delete(&self, id: Id) -> Result<bool, String>;
 }
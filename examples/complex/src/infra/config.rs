@@ -0,0 +1,67 @@
+use sadi::{ConfigModule, ConfigProvider};
+use serde::Deserialize;
+
+use crate::infra::persistence::sqlite::DEFAULT_POOL_SIZE;
+
+/// Settings read from `COMPLEX_*` environment variables at startup.
+#[derive(Debug, Deserialize)]
+pub struct AppConfig {
+    /// Connections opened by the SQLite pool, from `COMPLEX_SQLITE_POOL_SIZE`.
+    #[serde(default = "default_sqlite_pool_size")]
+    pub sqlite_pool_size: usize,
+}
+
+fn default_sqlite_pool_size() -> usize {
+    DEFAULT_POOL_SIZE
+}
+
+/// Registers the [`AppConfig`] read from the process environment.
+pub fn config_module() -> ConfigModule<AppConfig> {
+    validated(ConfigProvider::from_env::<AppConfig>())
+}
+
+fn validated(module: ConfigModule<AppConfig>) -> ConfigModule<AppConfig> {
+    module.prefixed("COMPLEX_").validate(|config| {
+        if config.sqlite_pool_size == 0 {
+            return Err("COMPLEX_SQLITE_POOL_SIZE must be at least 1".to_string());
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sadi::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn pool_size_defaults_when_unset() {
+        let config = validated(ConfigProvider::from_vars([("PATH", "/usr/bin")]))
+            .load()
+            .unwrap();
+
+        assert_eq!(config.sqlite_pool_size, DEFAULT_POOL_SIZE);
+    }
+
+    #[test]
+    fn pool_size_is_read_and_validated() {
+        let config = validated(ConfigProvider::from_vars([(
+            "COMPLEX_SQLITE_POOL_SIZE",
+            "8",
+        )]))
+        .load()
+        .unwrap();
+        assert_eq!(config.sqlite_pool_size, 8);
+
+        let Err(err) = validated(ConfigProvider::from_vars([(
+            "COMPLEX_SQLITE_POOL_SIZE",
+            "0",
+        )]))
+        .load() else {
+            panic!("an empty pool was accepted");
+        };
+        assert_eq!(err.kind, ErrorKind::ModuleLoadFailed);
+        assert!(err.message.contains("at least 1"));
+    }
+}
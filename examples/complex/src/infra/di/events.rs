@@ -0,0 +1,14 @@
+use sadi::{Error, Injector, Module, Provider, Shared};
+
+use crate::core::domain::event::EventBus;
+use crate::infra::events::InMemoryEventBus;
+
+pub struct EventsModule;
+
+impl Module for EventsModule {
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+        injector.try_provide::<dyn EventBus>(Provider::root(|_| {
+            Shared::new(InMemoryEventBus::default()) as Shared<dyn EventBus>
+        }))
+    }
+}
@@ -1,31 +1,119 @@
-use sadi::{Application, Module, Provider, Shared};
+use sadi::{Application, CompositeModule, Error, Module, Provider, Shared, modules};
 
-use crate::infra::persistence::sqlite::SqliteClient;
+use crate::infra::config::{AppConfig, config_module};
+use crate::infra::persistence::sqlite::AsyncSqliteClient;
 
+mod events;
 mod repositories;
 mod use_cases;
 
+pub use events::*;
 pub use repositories::*;
 pub use use_cases::*;
 
-pub struct RootModule;
+/// Bundles every module the application needs, so it can be imported as one.
+pub fn app_module() -> CompositeModule {
+    CompositeModule::from(modules![
+        config_module(),
+        EventsModule,
+        RepositoriesModule,
+        UseCasesModule,
+    ])
+}
 
-impl Module for RootModule {
-    fn imports(&self) -> Vec<Box<dyn Module>> {
-        vec![Box::new(RepositoriesModule), Box::new(UseCasesModule)]
-    }
+pub fn build() -> Result<Application, Error> {
+    build_from(app_module())
 }
 
-pub fn build() -> Result<Application, String> {
-    let mut app = Application::new(RootModule);
+/// Builds the application around `root`, returning the first module failure.
+pub fn build_from(root: impl Module + 'static) -> Result<Application, Error> {
+    let mut app = Application::new(root);
 
-    // Register SqliteClient first
-    app.injector().provide::<SqliteClient>(Provider::root(|_| {
-        let client = SqliteClient::new().expect("Failed to load sqlite client");
-        Shared::new(client)
-    }));
+    // Register AsyncSqliteClient first
+    app.injector()
+        .provide::<AsyncSqliteClient>(Provider::root(|injector| {
+            let pool_size = injector.resolve::<AppConfig>().sqlite_pool_size;
+            let client =
+                AsyncSqliteClient::with_pool_size(pool_size).expect("Failed to load sqlite client");
+            Shared::new(client)
+        }));
 
-    app.bootstrap();
+    app.try_bootstrap()?;
 
     Ok(app)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::type_name;
+
+    use sadi::{ErrorKind, Injector};
+
+    use super::*;
+    use crate::core::application::use_case::user::CreateUserUseCase;
+    use crate::core::domain::event::EventBus;
+    use crate::core::domain::user::UserRepository;
+    use crate::infra::persistence::sqlite::repository::UserSqliteRepository;
+
+    /// Composes the repositories twice, as happens when two feature modules
+    /// both import them.
+    struct DuplicateRepositoriesModule;
+
+    impl Module for DuplicateRepositoriesModule {
+        fn imports(&self) -> Vec<Box<dyn Module>> {
+            modules![RepositoriesModule, RepositoriesModule]
+        }
+    }
+
+    #[test]
+    fn build_succeeds() {
+        assert!(build().is_ok());
+    }
+
+    #[test]
+    fn app_module_registers_repositories_and_use_cases() {
+        let app = build().unwrap();
+        let injector = app.injector();
+
+        assert!(injector.contains::<AppConfig>());
+        assert!(injector.contains::<dyn UserRepository>());
+        assert!(injector.contains::<CreateUserUseCase>());
+        assert!(injector.assert_loaded::<RepositoriesModule>().is_ok());
+        assert!(injector.assert_loaded::<UseCasesModule>().is_ok());
+    }
+
+    #[test]
+    fn build_reports_module_failure() {
+        let Err(err) = build_from(DuplicateRepositoriesModule) else {
+            panic!("conflicting modules were loaded");
+        };
+
+        assert_eq!(err.kind, ErrorKind::ProviderAlreadyRegistered);
+        assert!(err.message.contains("UserSqliteRepository"));
+    }
+
+    #[tokio::test]
+    async fn create_user_resolves_the_expected_services() {
+        let app = build().unwrap();
+        let scope = Injector::child(app.injector());
+        let trace = scope.record_resolutions();
+
+        let create_user = scope.resolve::<CreateUserUseCase>();
+        create_user
+            .execute("Alice".to_string(), "alice@example.com".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            trace.type_names(),
+            [
+                type_name::<CreateUserUseCase>(),
+                type_name::<dyn UserRepository>(),
+                type_name::<UserSqliteRepository>(),
+                type_name::<AsyncSqliteClient>(),
+                type_name::<AppConfig>(),
+                type_name::<dyn EventBus>(),
+            ]
+        );
+    }
+}
@@ -1,22 +1,32 @@
-use sadi::{Module, Provider, Shared};
+use sadi::{Error, Module, Provider, Shared, register_as};
 
 use crate::core::domain::todo::TodoRepository;
 use crate::core::domain::user::UserRepository;
-use crate::infra::persistence::sqlite::SqliteClient;
+use crate::infra::persistence::sqlite::AsyncSqliteClient;
 use crate::infra::persistence::sqlite::repository::{TodoSqliteRepository, UserSqliteRepository};
 
 pub struct RepositoriesModule;
 
 impl Module for RepositoriesModule {
-    fn providers(&self, injector: &sadi::Injector) {
-        injector.provide::<dyn UserRepository>(Provider::root(|injector| {
-            let sqlite_client = injector.resolve::<SqliteClient>();
-            Shared::new(UserSqliteRepository::new(sqlite_client)) as Shared<dyn UserRepository>
-        }));
+    fn try_providers(&self, injector: &sadi::Injector) -> Result<(), Error> {
+        register_as!(
+            injector,
+            UserSqliteRepository as dyn UserRepository,
+            Provider::root(|injector| {
+                let sqlite_client = injector.resolve::<AsyncSqliteClient>();
+                Shared::new(UserSqliteRepository::new(sqlite_client))
+            }),
+        )?;
 
-        injector.provide::<dyn TodoRepository>(Provider::root(|injector| {
-            let sqlite_client = injector.resolve::<SqliteClient>();
-            Shared::new(TodoSqliteRepository::new(sqlite_client)) as Shared<dyn TodoRepository>
-        }));
+        register_as!(
+            injector,
+            TodoSqliteRepository as dyn TodoRepository,
+            Provider::root(|injector| {
+                let sqlite_client = injector.resolve::<AsyncSqliteClient>();
+                Shared::new(TodoSqliteRepository::new(sqlite_client))
+            }),
+        )?;
+
+        Ok(())
     }
 }
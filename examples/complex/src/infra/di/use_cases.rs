@@ -1,67 +1,88 @@
-use sadi::{Injector, Module, Provider};
+use sadi::{Error, Injector, Module, Provider};
 
 use crate::core::{
     application::use_case::{
         todo::{
             CreateTodoUseCase, DeleteTodoUseCase, GetAllTodoUseCase, GetByIdTodoUseCase,
-            UpdateStatusTodoUseCase,
+            GetOverdueTodosUseCase, UpdateStatusTodoUseCase,
+        },
+        user::{
+            CreateUserUseCase, DeleteUserUseCase, GetAllUserUseCase, GetByEmailUserUseCase,
+            GetByIdUserUseCase,
         },
-        user::{CreateUserUseCase, DeleteUserUseCase, GetAllUserUseCase, GetByIdUserUseCase},
     },
-    domain::{todo::TodoRepository, user::UserRepository},
+    domain::{event::EventBus, todo::TodoRepository, user::UserRepository},
 };
 
 pub struct UseCasesModule;
 
 impl Module for UseCasesModule {
-    fn providers(&self, injector: &Injector) {
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
         // User use cases
 
-        injector.provide::<CreateUserUseCase>(Provider::root(|injector| {
+        injector.try_provide::<CreateUserUseCase>(Provider::root(|injector| {
             let user_repository = injector.resolve::<dyn UserRepository>();
-            CreateUserUseCase::new(user_repository).into()
-        }));
+            let event_bus = injector.resolve::<dyn EventBus>();
+            CreateUserUseCase::new(user_repository, event_bus).into()
+        }))?;
 
-        injector.provide::<DeleteUserUseCase>(Provider::root(|injector| {
+        injector.try_provide::<DeleteUserUseCase>(Provider::root(|injector| {
             let user_repository = injector.resolve::<dyn UserRepository>();
-            DeleteUserUseCase::new(user_repository).into()
-        }));
+            let todo_repository = injector.resolve::<dyn TodoRepository>();
+            let event_bus = injector.resolve::<dyn EventBus>();
+            DeleteUserUseCase::new(user_repository, todo_repository, event_bus).into()
+        }))?;
 
-        injector.provide::<GetAllUserUseCase>(Provider::root(|injector| {
+        injector.try_provide::<GetAllUserUseCase>(Provider::root(|injector| {
             let user_repository = injector.resolve::<dyn UserRepository>();
             GetAllUserUseCase::new(user_repository).into()
-        }));
+        }))?;
 
-        injector.provide::<GetByIdUserUseCase>(Provider::root(|injector| {
+        injector.try_provide::<GetByIdUserUseCase>(Provider::root(|injector| {
             let user_repository = injector.resolve::<dyn UserRepository>();
             GetByIdUserUseCase::new(user_repository).into()
-        }));
+        }))?;
+
+        injector.try_provide::<GetByEmailUserUseCase>(Provider::root(|injector| {
+            let user_repository = injector.resolve::<dyn UserRepository>();
+            GetByEmailUserUseCase::new(user_repository).into()
+        }))?;
 
         // Todos use cases
 
-        injector.provide::<CreateTodoUseCase>(Provider::root(|injector| {
+        injector.try_provide::<CreateTodoUseCase>(Provider::root(|injector| {
             let todo_repository = injector.resolve::<dyn TodoRepository>();
-            CreateTodoUseCase::new(todo_repository).into()
-        }));
+            let event_bus = injector.resolve::<dyn EventBus>();
+            CreateTodoUseCase::new(todo_repository, event_bus).into()
+        }))?;
 
-        injector.provide::<DeleteTodoUseCase>(Provider::root(|injector| {
+        injector.try_provide::<DeleteTodoUseCase>(Provider::root(|injector| {
             let todo_repository = injector.resolve::<dyn TodoRepository>();
-            DeleteTodoUseCase::new(todo_repository).into()
-        }));
+            let event_bus = injector.resolve::<dyn EventBus>();
+            DeleteTodoUseCase::new(todo_repository, event_bus).into()
+        }))?;
 
-        injector.provide::<GetAllTodoUseCase>(Provider::root(|injector| {
+        injector.try_provide::<GetAllTodoUseCase>(Provider::root(|injector| {
             let todo_repository = injector.resolve::<dyn TodoRepository>();
             GetAllTodoUseCase::new(todo_repository).into()
-        }));
+        }))?;
 
-        injector.provide::<GetByIdTodoUseCase>(Provider::root(|injector| {
+        injector.try_provide::<GetByIdTodoUseCase>(Provider::root(|injector| {
             let todo_repository = injector.resolve::<dyn TodoRepository>();
             GetByIdTodoUseCase::new(todo_repository).into()
-        }));
+        }))?;
 
-        injector.provide::<UpdateStatusTodoUseCase>(Provider::root(|injector| {
+        injector.try_provide::<GetOverdueTodosUseCase>(Provider::root(|injector| {
             let todo_repository = injector.resolve::<dyn TodoRepository>();
-            UpdateStatusTodoUseCase::new(todo_repository).into()
-        }));
+            GetOverdueTodosUseCase::new(todo_repository).into()
+        }))?;
+
+        injector.try_provide::<UpdateStatusTodoUseCase>(Provider::root(|injector| {
+            let todo_repository = injector.resolve::<dyn TodoRepository>();
+            let event_bus = injector.resolve::<dyn EventBus>();
+            UpdateStatusTodoUseCase::new(todo_repository, event_bus).into()
+        }))?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,51 @@
+use std::sync::RwLock;
+
+use crate::core::domain::event::{DomainEvent, EventBus, Subscriber};
+
+/// Delivers each event synchronously to every subscriber, in subscription order.
+#[derive(Default)]
+pub struct InMemoryEventBus {
+    subscribers: RwLock<Vec<Subscriber>>,
+}
+
+impl EventBus for InMemoryEventBus {
+    fn publish(&self, event: DomainEvent) {
+        for subscriber in self.subscribers.read().unwrap().iter() {
+            subscriber(&event);
+        }
+    }
+
+    fn subscribe(&self, subscriber: Subscriber) {
+        self.subscribers.write().unwrap().push(subscriber);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::core::domain::id::test_id;
+
+    #[test]
+    fn publish_reaches_every_subscriber_in_order() {
+        let bus = InMemoryEventBus::default();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        for name in ["audit", "mailer"] {
+            let received = received.clone();
+            bus.subscribe(Box::new(move |event| {
+                received.lock().unwrap().push((name, event.clone()));
+            }));
+        }
+        bus.publish(DomainEvent::UserDeleted { id: test_id(3) });
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                ("audit", DomainEvent::UserDeleted { id: test_id(3) }),
+                ("mailer", DomainEvent::UserDeleted { id: test_id(3) }),
+            ]
+        );
+    }
+}
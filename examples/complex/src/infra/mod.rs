@@ -1,2 +1,4 @@
+pub mod config;
 pub mod di;
+pub mod events;
 pub mod persistence;
@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::sync::Mutex;
+
+use super::client::{DEFAULT_POOL_SIZE, migrate, open_database, remove_database};
+
+/// A pool of connections to a scratch SQLite database for async callers.
+///
+/// This mirrors [`SqliteClient`](super::SqliteClient), but each connection
+/// sits behind a [`tokio::sync::Mutex`]. When every connection is busy,
+/// [`with_connection`](Self::with_connection) suspends the calling task
+/// instead of parking the executor thread, so other tasks keep running while
+/// it waits. The file is removed when the client is dropped.
+pub struct AsyncSqliteClient {
+    path: PathBuf,
+    connections: Vec<Mutex<sqlite::Connection>>,
+    next: AtomicUsize,
+}
+
+impl std::fmt::Debug for AsyncSqliteClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncSqliteClient")
+            .field("pool_size", &self.connections.len())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for AsyncSqliteClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncSqliteClient")
+            .field("pool_size", &self.connections.len())
+            .finish()
+    }
+}
+
+impl AsyncSqliteClient {
+    pub fn new() -> Result<Self, String> {
+        Self::with_pool_size(DEFAULT_POOL_SIZE)
+    }
+
+    /// Opens a migrated client backed by `pool_size` connections (at least one).
+    pub fn with_pool_size(pool_size: usize) -> Result<Self, String> {
+        let (path, connections) = open_database(pool_size)?;
+        migrate(&connections[0])?;
+
+        Ok(Self {
+            path,
+            connections: connections.into_iter().map(Mutex::new).collect(),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn pool_size(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Runs `f` on an idle connection from the pool.
+    ///
+    /// Connections are tried round-robin; if all of them are busy, this waits
+    /// asynchronously for the first one tried.
+    pub async fn with_connection<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&sqlite::Connection) -> Result<T, String>,
+    {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        for offset in 0..self.connections.len() {
+            let slot = &self.connections[(start + offset) % self.connections.len()];
+
+            if let Ok(connection) = slot.try_lock() {
+                return f(&connection);
+            }
+        }
+
+        let connection = self.connections[start % self.connections.len()]
+            .lock()
+            .await;
+        f(&connection)
+    }
+}
+
+impl Drop for AsyncSqliteClient {
+    fn drop(&mut self) {
+        remove_database(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn count_users(connection: &sqlite::Connection) -> Result<i64, String> {
+        let mut statement = connection
+            .prepare("SELECT COUNT(*) FROM users")
+            .map_err(|e| e.to_string())?;
+        statement.next().map_err(|e| e.to_string())?;
+        statement.read::<i64, _>(0).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn with_pool_size_opens_at_least_one_connection() {
+        assert_eq!(AsyncSqliteClient::with_pool_size(0).unwrap().pool_size(), 1);
+        assert_eq!(AsyncSqliteClient::with_pool_size(3).unwrap().pool_size(), 3);
+    }
+
+    #[tokio::test]
+    async fn waiting_for_a_busy_connection_does_not_block_the_runtime() {
+        let client = Arc::new(AsyncSqliteClient::with_pool_size(1).unwrap());
+        let held = client.connections[0].lock().await;
+
+        // The test runtime has a single thread, so a blocking lock in any of
+        // these tasks would stall the runtime and never let `held` go.
+        let writers: Vec<_> = (0..4)
+            .map(|n| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    client
+                        .with_connection(|connection| {
+                            connection
+                                .execute(format!(
+                                    "INSERT INTO users (name, email) \
+                                     VALUES ('User {n}', 'user{n}@example.com')"
+                                ))
+                                .map_err(|e| e.to_string())
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        tokio::task::yield_now().await;
+        assert!(writers.iter().all(|writer| !writer.is_finished()));
+
+        drop(held);
+        for writer in writers {
+            assert_eq!(writer.await.unwrap(), Ok(()));
+        }
+        assert_eq!(client.with_connection(count_users).await, Ok(4));
+    }
+}
@@ -1,15 +1,34 @@
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, TryLockError};
 
+use super::id::{FOREIGN_KEY, PRIMARY_KEY};
+
+/// Number of connections opened by [`SqliteClient::new`].
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// How long a connection waits on a locked database before failing.
+const BUSY_TIMEOUT_MS: usize = 5_000;
+
+static NEXT_DATABASE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A small pool of connections to a scratch SQLite database.
+///
+/// Every connection points at the same temporary database file in WAL mode,
+/// so readers proceed in parallel and only writers wait on each other. The
+/// file is removed when the client is dropped.
 pub struct SqliteClient {
     migrated: bool,
-    connection: Mutex<sqlite::Connection>,
+    path: PathBuf,
+    connections: Vec<Mutex<sqlite::Connection>>,
+    next: AtomicUsize,
 }
 
 impl std::fmt::Debug for SqliteClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SqliteClient")
             .field("migrated", &self.migrated)
-            .field("connection", &"<Mutex<sqlite::Connection>>")
+            .field("pool_size", &self.connections.len())
             .finish()
     }
 }
@@ -18,17 +37,25 @@ impl std::fmt::Display for SqliteClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SqliteClient")
             .field("migrated", &self.migrated)
-            .field("connection", &"<Mutex<sqlite::Connection>>")
+            .field("pool_size", &self.connections.len())
             .finish()
     }
 }
 
 impl SqliteClient {
     pub fn new() -> Result<Self, String> {
-        let connection = sqlite::open(":memory:").map_err(|e| e.to_string())?;
+        Self::with_pool_size(DEFAULT_POOL_SIZE)
+    }
+
+    /// Opens a client backed by `pool_size` connections (at least one).
+    pub fn with_pool_size(pool_size: usize) -> Result<Self, String> {
+        let (path, connections) = open_database(pool_size)?;
+
         let mut client = Self {
             migrated: false,
-            connection: Mutex::new(connection),
+            path,
+            connections: connections.into_iter().map(Mutex::new).collect(),
+            next: AtomicUsize::new(0),
         };
         client.run_migrations()?;
         Ok(client)
@@ -39,35 +66,7 @@ impl SqliteClient {
             return Ok(());
         }
 
-        let connection = self
-            .connection
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        // Create users table
-        connection
-            .execute(
-                "CREATE TABLE IF NOT EXISTS users (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    name TEXT NOT NULL,
-                    email TEXT NOT NULL
-                )",
-            )
-            .map_err(|e| format!("Failed to create users table: {}", e))?;
-
-        // Create todos table
-        connection
-            .execute(
-                "CREATE TABLE IF NOT EXISTS todos (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    user_id INTEGER NOT NULL,
-                    title TEXT NOT NULL,
-                    description TEXT NOT NULL,
-                    completed INTEGER NOT NULL DEFAULT 0,
-                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
-                )",
-            )
-            .map_err(|e| format!("Failed to create todos table: {}", e))?;
+        self.with_connection(migrate)?;
 
         self.migrated = true;
         Ok(())
@@ -77,7 +76,161 @@ impl SqliteClient {
         self.migrated
     }
 
-    pub fn connection(&self) -> &Mutex<sqlite::Connection> {
-        &self.connection
+    pub fn pool_size(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Runs `f` on an idle connection from the pool.
+    ///
+    /// Connections are tried round-robin; if all of them are busy, this waits
+    /// for the first one tried.
+    pub fn with_connection<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&sqlite::Connection) -> Result<T, String>,
+    {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        for offset in 0..self.connections.len() {
+            let slot = &self.connections[(start + offset) % self.connections.len()];
+
+            match slot.try_lock() {
+                Ok(connection) => return f(&connection),
+                Err(TryLockError::WouldBlock) => continue,
+                Err(TryLockError::Poisoned(e)) => {
+                    return Err(format!("Failed to lock connection: {}", e));
+                }
+            }
+        }
+
+        let connection = self.connections[start % self.connections.len()]
+            .lock()
+            .map_err(|e| format!("Failed to lock connection: {}", e))?;
+        f(&connection)
+    }
+}
+
+impl Drop for SqliteClient {
+    fn drop(&mut self) {
+        remove_database(&self.path);
+    }
+}
+
+/// Opens `pool_size` connections (at least one) to a fresh scratch database.
+pub(super) fn open_database(
+    pool_size: usize,
+) -> Result<(PathBuf, Vec<sqlite::Connection>), String> {
+    let path = std::env::temp_dir().join(format!(
+        "sadi-complex-{}-{}.db",
+        std::process::id(),
+        NEXT_DATABASE_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let mut connections = Vec::with_capacity(pool_size.max(1));
+    for _ in 0..pool_size.max(1) {
+        let mut connection = sqlite::open(&path).map_err(|e| e.to_string())?;
+        connection
+            .set_busy_timeout(BUSY_TIMEOUT_MS)
+            .map_err(|e| format!("Failed to set busy timeout: {}", e))?;
+        connections.push(connection);
+    }
+
+    Ok((path, connections))
+}
+
+/// Creates the schema on `connection` and switches the database to WAL mode.
+pub(super) fn migrate(connection: &sqlite::Connection) -> Result<(), String> {
+    connection
+        .execute("PRAGMA journal_mode = WAL")
+        .map_err(|e| format!("Failed to enable WAL: {}", e))?;
+
+    // Create users table
+    connection
+        .execute(format!(
+            "CREATE TABLE IF NOT EXISTS users (
+                id {PRIMARY_KEY},
+                name TEXT NOT NULL,
+                email TEXT NOT NULL
+            )"
+        ))
+        .map_err(|e| format!("Failed to create users table: {}", e))?;
+
+    connection
+        .execute("CREATE UNIQUE INDEX IF NOT EXISTS users_email ON users (email)")
+        .map_err(|e| format!("Failed to create users email index: {}", e))?;
+
+    // Create todos table
+    connection
+        .execute(format!(
+            "CREATE TABLE IF NOT EXISTS todos (
+                id {PRIMARY_KEY},
+                user_id {FOREIGN_KEY} NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                completed INTEGER NOT NULL DEFAULT 0,
+                due_date INTEGER,
+                FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+            )"
+        ))
+        .map_err(|e| format!("Failed to create todos table: {}", e))
+}
+
+/// Removes the database file at `path` along with its WAL and shared-memory files.
+pub(super) fn remove_database(path: &Path) {
+    let path = path.to_string_lossy().into_owned();
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Barrier};
+
+    use super::*;
+
+    #[test]
+    fn with_pool_size_opens_at_least_one_connection() {
+        assert_eq!(SqliteClient::with_pool_size(0).unwrap().pool_size(), 1);
+        assert_eq!(SqliteClient::with_pool_size(3).unwrap().pool_size(), 3);
+    }
+
+    #[test]
+    fn parallel_reads_use_separate_connections() {
+        const READERS: usize = 4;
+
+        let client = Arc::new(SqliteClient::with_pool_size(READERS).unwrap());
+        client
+            .with_connection(|connection| {
+                connection
+                    .execute("INSERT INTO users (name, email) VALUES ('Ada', 'ada@example.com')")
+                    .map_err(|e| e.to_string())
+            })
+            .unwrap();
+
+        // Every reader holds its connection until all of them have one, which
+        // would deadlock if the pool handed out a single shared connection.
+        let barrier = Arc::new(Barrier::new(READERS));
+        let handles: Vec<_> = (0..READERS)
+            .map(|_| {
+                let client = client.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    client.with_connection(|connection| {
+                        barrier.wait();
+
+                        let mut statement = connection
+                            .prepare("SELECT COUNT(*) FROM users")
+                            .map_err(|e| e.to_string())?;
+                        statement.next().map_err(|e| e.to_string())?;
+                        statement.read::<i64, _>(0).map_err(|e| e.to_string())
+                    })
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), Ok(1));
+        }
     }
 }
@@ -0,0 +1,79 @@
+//! How [`Id`] is stored in SQLite.
+//!
+//! Integer ids live in an `INTEGER PRIMARY KEY AUTOINCREMENT` column and are
+//! assigned by SQLite on insert. UUIDs (the `uuid` feature) are stored as
+//! hyphenated text and generated here before the row is written.
+
+use crate::core::domain::id::Id;
+
+/// Column type of a table's primary key.
+#[cfg(not(feature = "uuid"))]
+pub(crate) const PRIMARY_KEY: &str = "INTEGER PRIMARY KEY AUTOINCREMENT";
+
+/// Column type of a table's primary key.
+#[cfg(feature = "uuid")]
+pub(crate) const PRIMARY_KEY: &str = "TEXT PRIMARY KEY";
+
+/// Column type of a reference to another table's primary key.
+#[cfg(not(feature = "uuid"))]
+pub(crate) const FOREIGN_KEY: &str = "INTEGER";
+
+/// Column type of a reference to another table's primary key.
+#[cfg(feature = "uuid")]
+pub(crate) const FOREIGN_KEY: &str = "TEXT";
+
+/// The id for a row about to be inserted, or `None` to let SQLite assign one.
+#[cfg(not(feature = "uuid"))]
+pub(crate) fn new_id() -> Option<Id> {
+    None
+}
+
+/// The id for a row about to be inserted, or `None` to let SQLite assign one.
+#[cfg(feature = "uuid")]
+pub(crate) fn new_id() -> Option<Id> {
+    Some(uuid::Uuid::new_v4())
+}
+
+#[cfg(not(feature = "uuid"))]
+pub(crate) fn id_value(id: Id) -> sqlite::Value {
+    sqlite::Value::Integer(id as i64)
+}
+
+#[cfg(feature = "uuid")]
+pub(crate) fn id_value(id: Id) -> sqlite::Value {
+    sqlite::Value::String(id.hyphenated().to_string())
+}
+
+#[cfg(not(feature = "uuid"))]
+pub(crate) fn read_id(statement: &sqlite::Statement, column: usize) -> Result<Id, String> {
+    Ok(statement
+        .read::<i64, _>(column)
+        .map_err(|e| e.to_string())? as Id)
+}
+
+#[cfg(feature = "uuid")]
+pub(crate) fn read_id(statement: &sqlite::Statement, column: usize) -> Result<Id, String> {
+    let text = statement
+        .read::<String, _>(column)
+        .map_err(|e| e.to_string())?;
+    text.parse()
+        .map_err(|e| format!("Invalid id {:?}: {}", text, e))
+}
+
+/// Returns the id of the row just inserted with `new_id`.
+pub(crate) fn inserted_id(
+    connection: &sqlite::Connection,
+    new_id: Option<Id>,
+) -> Result<Id, String> {
+    if let Some(id) = new_id {
+        return Ok(id);
+    }
+
+    let mut statement = connection
+        .prepare("SELECT last_insert_rowid()")
+        .map_err(|e| format!("Failed to get last insert id: {}", e))?;
+    statement
+        .next()
+        .map_err(|e| format!("Failed to get last insert id: {}", e))?;
+    read_id(&statement, 0)
+}
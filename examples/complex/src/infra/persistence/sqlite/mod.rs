@@ -1,4 +1,8 @@
+mod async_client;
 mod client;
+mod id;
 pub mod repository;
+mod time;
 
+pub use async_client::*;
 pub use client::*;
@@ -1,147 +1,169 @@
 use std::sync::Arc;
 
+use crate::core::domain::id::Id;
+use crate::core::domain::time::DateTime;
 use crate::core::domain::todo::{Todo, TodoRepository};
-use crate::infra::persistence::sqlite::SqliteClient;
+use crate::infra::persistence::sqlite::AsyncSqliteClient;
+use crate::infra::persistence::sqlite::id::{id_value, inserted_id, new_id, read_id};
+use crate::infra::persistence::sqlite::time::{date_value, read_date};
 
 pub struct TodoSqliteRepository {
-    sqlite_client: Arc<SqliteClient>,
+    sqlite_client: Arc<AsyncSqliteClient>,
 }
 
 impl TodoSqliteRepository {
-    pub fn new(sqlite_client: Arc<SqliteClient>) -> Self {
+    pub fn new(sqlite_client: Arc<AsyncSqliteClient>) -> Self {
         Self { sqlite_client }
     }
 }
 
+fn read_todo(statement: &sqlite::Statement) -> Result<Todo, String> {
+    Todo::builder()
+        .id(read_id(statement, 0)?)
+        .title(statement.read::<String, _>(1).map_err(|e| e.to_string())?)
+        .description(statement.read::<String, _>(2).map_err(|e| e.to_string())?)
+        .completed(statement.read::<i64, _>(3).map_err(|e| e.to_string())? != 0)
+        .due_date(read_date(statement, 4)?)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
 #[async_trait::async_trait]
 impl TodoRepository for TodoSqliteRepository {
     async fn get_all(&self) -> Result<Vec<Todo>, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        let query = "SELECT id, title, description, completed FROM todos";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        let mut todos = Vec::new();
-        while let Ok(sqlite::State::Row) = statement.next() {
-            todos.push(Todo {
-                id: statement.read::<i64, _>(0).map_err(|e| e.to_string())? as u32,
-                title: statement.read::<String, _>(1).map_err(|e| e.to_string())?,
-                description: statement.read::<String, _>(2).map_err(|e| e.to_string())?,
-                completed: statement.read::<i64, _>(3).map_err(|e| e.to_string())? != 0,
-            });
-        }
-
-        Ok(todos)
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "SELECT id, title, description, completed, due_date FROM todos";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                let mut todos = Vec::new();
+                while let Ok(sqlite::State::Row) = statement.next() {
+                    todos.push(read_todo(&statement)?);
+                }
+
+                Ok(todos)
+            })
+            .await
     }
 
-    async fn get_by_id(&self, id: u32) -> Result<Option<Todo>, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        let query = "SELECT id, title, description, completed FROM todos WHERE id = ?";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        statement
-            .bind((1, id as i64))
-            .map_err(|e| format!("Failed to bind parameter: {}", e))?;
-
-        if let Ok(sqlite::State::Row) = statement.next() {
-            Ok(Some(Todo {
-                id: statement.read::<i64, _>(0).map_err(|e| e.to_string())? as u32,
-                title: statement.read::<String, _>(1).map_err(|e| e.to_string())?,
-                description: statement.read::<String, _>(2).map_err(|e| e.to_string())?,
-                completed: statement.read::<i64, _>(3).map_err(|e| e.to_string())? != 0,
-            }))
-        } else {
-            Ok(None)
-        }
+    async fn get_by_id(&self, id: Id) -> Result<Option<Todo>, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query =
+                    "SELECT id, title, description, completed, due_date FROM todos WHERE id = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, id_value(id)))
+                    .map_err(|e| format!("Failed to bind parameter: {}", e))?;
+
+                if let Ok(sqlite::State::Row) = statement.next() {
+                    Ok(Some(read_todo(&statement)?))
+                } else {
+                    Ok(None)
+                }
+            })
+            .await
     }
 
     async fn create(
         &self,
-        user_id: u32,
+        user_id: Id,
         title: String,
         description: String,
+        due_date: Option<DateTime>,
     ) -> Result<Todo, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        let query =
-            "INSERT INTO todos (user_id, title, description, completed) VALUES (?, ?, ?, 0)";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        statement
-            .bind((1, user_id as i64))
-            .map_err(|e| format!("Failed to bind user_id: {}", e))?;
-        statement
-            .bind((2, title.as_str()))
-            .map_err(|e| format!("Failed to bind title: {}", e))?;
-        statement
-            .bind((3, description.as_str()))
-            .map_err(|e| format!("Failed to bind description: {}", e))?;
-
-        statement
-            .next()
-            .map_err(|e| format!("Failed to execute insert: {}", e))?;
-
-        let query = "SELECT last_insert_rowid()";
-        let mut stmt = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to get last insert id: {}", e))?;
-        stmt.next()
-            .map_err(|e| format!("Failed to get last insert id: {}", e))?;
-        let id = stmt.read::<i64, _>(0).map_err(|e| e.to_string())? as u32;
-
-        Ok(Todo {
-            id,
-            title,
-            description,
-            completed: false,
-        })
-    }
-
-    async fn update_status(&self, id: u32, completed: bool) -> Result<Option<Todo>, String> {
-        let updated = {
-            let connection = self
-                .sqlite_client
-                .connection()
-                .lock()
-                .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-            let query = "UPDATE todos SET completed = ? WHERE id = ?";
+        self.sqlite_client.with_connection(|connection| {
+            let id = new_id();
+            let query = "INSERT INTO todos (id, user_id, title, description, completed, due_date) \
+                 VALUES (?, ?, ?, ?, 0, ?)";
             let mut statement = connection
                 .prepare(query)
                 .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
             statement
-                .bind((1, if completed { 1i64 } else { 0i64 }))
-                .map_err(|e| format!("Failed to bind completed: {}", e))?;
-            statement
-                .bind((2, id as i64))
+                .bind((1, id.map(id_value)))
                 .map_err(|e| format!("Failed to bind id: {}", e))?;
+            statement
+                .bind((2, id_value(user_id)))
+                .map_err(|e| format!("Failed to bind user_id: {}", e))?;
+            statement
+                .bind((3, title.as_str()))
+                .map_err(|e| format!("Failed to bind title: {}", e))?;
+            statement
+                .bind((4, description.as_str()))
+                .map_err(|e| format!("Failed to bind description: {}", e))?;
+            statement
+                .bind((5, due_date.map(date_value)))
+                .map_err(|e| format!("Failed to bind due_date: {}", e))?;
 
             statement
                 .next()
-                .map_err(|e| format!("Failed to execute update: {}", e))?;
+                .map_err(|e| format!("Failed to execute insert: {}", e))?;
 
-            connection.change_count() > 0
-        };
+            let id = inserted_id(connection, id)?;
+
+            Todo::builder()
+                .id(id)
+                .title(title)
+                .description(description)
+                .due_date(due_date)
+                .build()
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    async fn get_overdue(&self, now: DateTime) -> Result<Vec<Todo>, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "SELECT id, title, description, completed, due_date FROM todos \
+                 WHERE completed = 0 AND due_date < ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, date_value(now)))
+                    .map_err(|e| format!("Failed to bind now: {}", e))?;
+
+                let mut todos = Vec::new();
+                while let Ok(sqlite::State::Row) = statement.next() {
+                    todos.push(read_todo(&statement)?);
+                }
+
+                Ok(todos)
+            })
+            .await
+    }
+
+    async fn update_status(&self, id: Id, completed: bool) -> Result<Option<Todo>, String> {
+        let updated = self
+            .sqlite_client
+            .with_connection(|connection| {
+                let query = "UPDATE todos SET completed = ? WHERE id = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, if completed { 1i64 } else { 0i64 }))
+                    .map_err(|e| format!("Failed to bind completed: {}", e))?;
+                statement
+                    .bind((2, id_value(id)))
+                    .map_err(|e| format!("Failed to bind id: {}", e))?;
+
+                statement
+                    .next()
+                    .map_err(|e| format!("Failed to execute update: {}", e))?;
+
+                Ok(connection.change_count() > 0)
+            })
+            .await?;
 
         if updated {
             self.get_by_id(id).await
@@ -150,26 +172,84 @@ impl TodoRepository for TodoSqliteRepository {
         }
     }
 
-    async fn delete(&self, id: u32) -> Result<bool, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
+    async fn delete(&self, id: Id) -> Result<bool, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "DELETE FROM todos WHERE id = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, id_value(id)))
+                    .map_err(|e| format!("Failed to bind id: {}", e))?;
+
+                statement
+                    .next()
+                    .map_err(|e| format!("Failed to execute delete: {}", e))?;
+
+                Ok(connection.change_count() > 0)
+            })
+            .await
+    }
 
-        let query = "DELETE FROM todos WHERE id = ?";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    async fn delete_by_user(&self, user_id: Id) -> Result<usize, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "DELETE FROM todos WHERE user_id = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, id_value(user_id)))
+                    .map_err(|e| format!("Failed to bind user_id: {}", e))?;
+
+                statement
+                    .next()
+                    .map_err(|e| format!("Failed to execute delete: {}", e))?;
+
+                Ok(connection.change_count())
+            })
+            .await
+    }
+}
 
-        statement
-            .bind((1, id as i64))
-            .map_err(|e| format!("Failed to bind id: {}", e))?;
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
 
-        statement
-            .next()
-            .map_err(|e| format!("Failed to execute delete: {}", e))?;
+    use super::*;
+    use crate::core::domain::id::test_id;
+    use crate::core::domain::time::now;
+
+    #[tokio::test]
+    async fn get_overdue_skips_completed_and_future_todos() {
+        let repository = TodoSqliteRepository::new(Arc::new(AsyncSqliteClient::new().unwrap()));
+        let now = now();
+        let create = |title: &str, due_date: Option<DateTime>| {
+            repository.create(test_id(1), title.into(), String::new(), due_date)
+        };
 
-        Ok(connection.change_count() > 0)
+        let overdue = create("Pay rent", Some(now - Duration::days(2)))
+            .await
+            .unwrap();
+        let done = create("File taxes", Some(now - Duration::days(1)))
+            .await
+            .unwrap();
+        create("Book flights", Some(now + Duration::days(1)))
+            .await
+            .unwrap();
+        create("Read a book", None).await.unwrap();
+        repository.update_status(done.id, true).await.unwrap();
+
+        let found = repository.get_overdue(now).await.unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, overdue.id);
+        assert_eq!(
+            found[0].due_date.map(|d| d.timestamp_millis()),
+            overdue.due_date.map(|d| d.timestamp_millis())
+        );
     }
 }
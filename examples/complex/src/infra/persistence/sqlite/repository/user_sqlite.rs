@@ -1,125 +1,210 @@
 use std::sync::Arc;
 
+use crate::core::domain::error::DomainError;
+use crate::core::domain::id::Id;
 use crate::core::domain::user::{User, UserRepository};
-use crate::infra::persistence::sqlite::SqliteClient;
+use crate::infra::persistence::sqlite::AsyncSqliteClient;
+use crate::infra::persistence::sqlite::id::{id_value, inserted_id, new_id, read_id};
 
 pub struct UserSqliteRepository {
-    sqlite_client: Arc<SqliteClient>,
+    sqlite_client: Arc<AsyncSqliteClient>,
 }
 
 impl UserSqliteRepository {
-    pub fn new(sqlite_client: Arc<SqliteClient>) -> Self {
+    pub fn new(sqlite_client: Arc<AsyncSqliteClient>) -> Self {
         Self { sqlite_client }
     }
 }
 
+/// Result code of a violated constraint, such as the unique index on `email`.
+const SQLITE_CONSTRAINT: isize = 19;
+
+fn read_user(statement: &sqlite::Statement) -> Result<User, String> {
+    User::builder()
+        .id(read_id(statement, 0)?)
+        .name(statement.read::<String, _>(1).map_err(|e| e.to_string())?)
+        .email(statement.read::<String, _>(2).map_err(|e| e.to_string())?)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
 #[async_trait::async_trait]
 impl UserRepository for UserSqliteRepository {
     async fn get_all(&self) -> Result<Vec<User>, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        let query = "SELECT id, name, email FROM users";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        let mut users = Vec::new();
-        while let Ok(sqlite::State::Row) = statement.next() {
-            users.push(User {
-                id: statement.read::<i64, _>(0).map_err(|e| e.to_string())? as u32,
-                name: statement.read::<String, _>(1).map_err(|e| e.to_string())?,
-                email: statement.read::<String, _>(2).map_err(|e| e.to_string())?,
-            });
-        }
-
-        Ok(users)
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "SELECT id, name, email FROM users";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                let mut users = Vec::new();
+                while let Ok(sqlite::State::Row) = statement.next() {
+                    users.push(read_user(&statement)?);
+                }
+
+                Ok(users)
+            })
+            .await
     }
 
-    async fn get_by_id(&self, id: u32) -> Result<Option<User>, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        let query = "SELECT id, name, email FROM users WHERE id = ?";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        statement
-            .bind((1, id as i64))
-            .map_err(|e| format!("Failed to bind parameter: {}", e))?;
-
-        if let Ok(sqlite::State::Row) = statement.next() {
-            Ok(Some(User {
-                id: statement.read::<i64, _>(0).map_err(|e| e.to_string())? as u32,
-                name: statement.read::<String, _>(1).map_err(|e| e.to_string())?,
-                email: statement.read::<String, _>(2).map_err(|e| e.to_string())?,
-            }))
-        } else {
-            Ok(None)
-        }
+    async fn get_by_id(&self, id: Id) -> Result<Option<User>, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "SELECT id, name, email FROM users WHERE id = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, id_value(id)))
+                    .map_err(|e| format!("Failed to bind parameter: {}", e))?;
+
+                if let Ok(sqlite::State::Row) = statement.next() {
+                    Ok(Some(read_user(&statement)?))
+                } else {
+                    Ok(None)
+                }
+            })
+            .await
     }
 
-    async fn create(&self, name: String, email: String) -> Result<User, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
-
-        let query = "INSERT INTO users (name, email) VALUES (?, ?)";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        statement
-            .bind((1, name.as_str()))
-            .map_err(|e| format!("Failed to bind name: {}", e))?;
-        statement
-            .bind((2, email.as_str()))
-            .map_err(|e| format!("Failed to bind email: {}", e))?;
-
-        statement
-            .next()
-            .map_err(|e| format!("Failed to execute insert: {}", e))?;
-
-        let query = "SELECT last_insert_rowid()";
-        let mut stmt = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to get last insert id: {}", e))?;
-        stmt.next()
-            .map_err(|e| format!("Failed to get last insert id: {}", e))?;
-        let id = stmt.read::<i64, _>(0).map_err(|e| e.to_string())? as u32;
-
-        Ok(User { id, name, email })
+    async fn get_by_email(&self, email: &str) -> Result<Option<User>, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "SELECT id, name, email FROM users WHERE email = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, email))
+                    .map_err(|e| format!("Failed to bind parameter: {}", e))?;
+
+                if let Ok(sqlite::State::Row) = statement.next() {
+                    Ok(Some(read_user(&statement)?))
+                } else {
+                    Ok(None)
+                }
+            })
+            .await
     }
 
-    async fn delete(&self, id: u32) -> Result<bool, String> {
-        let connection = self
-            .sqlite_client
-            .connection()
-            .lock()
-            .map_err(|e| format!("Failed to lock connection: {}", e))?;
+    async fn create(&self, name: String, email: String) -> Result<User, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let id = new_id();
+                let query = "INSERT INTO users (id, name, email) VALUES (?, ?, ?)";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, id.map(id_value)))
+                    .map_err(|e| format!("Failed to bind id: {}", e))?;
+                statement
+                    .bind((2, name.as_str()))
+                    .map_err(|e| format!("Failed to bind name: {}", e))?;
+                statement
+                    .bind((3, email.as_str()))
+                    .map_err(|e| format!("Failed to bind email: {}", e))?;
+
+                statement.next().map_err(|e| {
+                    // The unique index on `email` rejects duplicates atomically.
+                    if e.code == Some(SQLITE_CONSTRAINT) {
+                        DomainError::Conflict { field: "email" }.to_string()
+                    } else {
+                        format!("Failed to execute insert: {}", e)
+                    }
+                })?;
+
+                let id = inserted_id(connection, id)?;
+
+                User::builder()
+                    .id(id)
+                    .name(name)
+                    .email(email)
+                    .build()
+                    .map_err(|e| e.to_string())
+            })
+            .await
+    }
 
-        let query = "DELETE FROM users WHERE id = ?";
-        let mut statement = connection
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    async fn delete(&self, id: Id) -> Result<bool, String> {
+        self.sqlite_client
+            .with_connection(|connection| {
+                let query = "DELETE FROM users WHERE id = ?";
+                let mut statement = connection
+                    .prepare(query)
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+                statement
+                    .bind((1, id_value(id)))
+                    .map_err(|e| format!("Failed to bind id: {}", e))?;
+
+                statement
+                    .next()
+                    .map_err(|e| format!("Failed to execute delete: {}", e))?;
+
+                Ok(connection.change_count() > 0)
+            })
+            .await
+    }
+}
 
-        statement
-            .bind((1, id as i64))
-            .map_err(|e| format!("Failed to bind id: {}", e))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn created_user_is_found_by_id() {
+        let repository = UserSqliteRepository::new(Arc::new(AsyncSqliteClient::new().unwrap()));
+
+        let alice = repository
+            .create("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+        let bob = repository
+            .create("Bob".into(), "bob@example.com".into())
+            .await
+            .unwrap();
+
+        assert_ne!(alice.id, bob.id);
+        assert_eq!(repository.get_by_id(bob.id).await.unwrap(), Some(bob));
+    }
 
-        statement
-            .next()
-            .map_err(|e| format!("Failed to execute delete: {}", e))?;
+    #[tokio::test]
+    async fn get_by_email_finds_only_registered_address() {
+        let repository = UserSqliteRepository::new(Arc::new(AsyncSqliteClient::new().unwrap()));
+        let alice = repository
+            .create("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.get_by_email("alice@example.com").await.unwrap(),
+            Some(alice)
+        );
+        assert_eq!(
+            repository.get_by_email("bob@example.com").await.unwrap(),
+            None
+        );
+    }
 
-        Ok(connection.change_count() > 0)
+    #[tokio::test]
+    async fn create_rejects_duplicate_email() {
+        let repository = UserSqliteRepository::new(Arc::new(AsyncSqliteClient::new().unwrap()));
+        repository
+            .create("Alice".into(), "alice@example.com".into())
+            .await
+            .unwrap();
+
+        let err = repository
+            .create("Impostor".into(), "alice@example.com".into())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err, DomainError::Conflict { field: "email" }.to_string());
+        assert_eq!(repository.get_all().await.unwrap().len(), 1);
     }
 }
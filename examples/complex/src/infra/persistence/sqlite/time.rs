@@ -0,0 +1,27 @@
+//! How [`DateTime`] is stored in SQLite.
+//!
+//! Timestamps are stored as milliseconds since the Unix epoch in an `INTEGER`
+//! column, so comparing them in a `WHERE` clause orders them correctly.
+
+use crate::core::domain::time::DateTime;
+
+pub(crate) fn date_value(date: DateTime) -> sqlite::Value {
+    sqlite::Value::Integer(date.timestamp_millis())
+}
+
+/// Reads a nullable timestamp column.
+pub(crate) fn read_date(
+    statement: &sqlite::Statement,
+    column: usize,
+) -> Result<Option<DateTime>, String> {
+    let Some(millis) = statement
+        .read::<Option<i64>, _>(column)
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+
+    DateTime::from_timestamp_millis(millis)
+        .map(Some)
+        .ok_or_else(|| format!("Invalid timestamp: {}", millis))
+}
@@ -1,8 +1,14 @@
+use chrono::Duration;
+
 use crate::core::application::use_case::{
-    todo::{CreateTodoUseCase, DeleteTodoUseCase, GetAllTodoUseCase, UpdateStatusTodoUseCase},
+    todo::{
+        CreateTodoUseCase, DeleteTodoUseCase, GetAllTodoUseCase, GetOverdueTodosUseCase,
+        UpdateStatusTodoUseCase,
+    },
     user::{CreateUserUseCase, DeleteUserUseCase, GetAllUserUseCase, GetByIdUserUseCase},
 };
-use crate::infra::persistence::sqlite::SqliteClient;
+use crate::core::domain::time::now;
+use crate::infra::persistence::sqlite::AsyncSqliteClient;
 
 pub mod core;
 pub mod infra;
@@ -12,15 +18,20 @@ async fn main() -> Result<(), String> {
     println!("=== Complex Example: DI with SQLite Repositories ===\n");
 
     // Build the application with dependency injection
-    let app = infra::di::build().expect("Failed to build application");
+    let app = infra::di::build().map_err(|err| {
+        format!(
+            "Failed to build application ({:?}): {}",
+            err.kind, err.message
+        )
+    })?;
     println!("✓ Application built successfully\n");
 
-    // Verify SqliteClient was initialized
+    // Verify AsyncSqliteClient was initialized
     let sqlite_client = app
         .injector()
-        .try_resolve::<SqliteClient>()
-        .map_err(|e| format!("Failed to resolve SqliteClient: {:?}", e))?;
-    println!("✓ SqliteClient initialized: {}\n", sqlite_client);
+        .try_resolve::<AsyncSqliteClient>()
+        .map_err(|e| format!("Failed to resolve AsyncSqliteClient: {:?}", e))?;
+    println!("✓ AsyncSqliteClient initialized: {}\n", sqlite_client);
 
     // === User Operations ===
     println!("--- User Operations ---");
@@ -76,6 +87,7 @@ async fn main() -> Result<(), String> {
             user1.id,
             "Buy groceries".to_string(),
             "Milk, eggs, bread".to_string(),
+            Some(now() - Duration::days(1)),
         )
         .await?;
     println!("✓ Created todo: {:?}", todo1);
@@ -85,6 +97,7 @@ async fn main() -> Result<(), String> {
             user1.id,
             "Write documentation".to_string(),
             "Document the new API endpoints".to_string(),
+            Some(now() + Duration::days(7)),
         )
         .await?;
     println!("✓ Created todo: {:?}", todo2);
@@ -94,6 +107,7 @@ async fn main() -> Result<(), String> {
             user2.id,
             "Review PRs".to_string(),
             "Review pending pull requests".to_string(),
+            Some(now() - Duration::hours(2)),
         )
         .await?;
     println!("✓ Created todo: {:?}\n", todo3);
@@ -118,6 +132,14 @@ async fn main() -> Result<(), String> {
     let todos = get_all_todos.execute().await?;
     println!("✓ All todos after update: {:?}\n", todos);
 
+    // Get overdue todos; the completed one no longer counts
+    let get_overdue_todos = app
+        .injector()
+        .try_resolve::<GetOverdueTodosUseCase>()
+        .map_err(|e| format!("Failed to resolve GetOverdueTodosUseCase: {:?}", e))?;
+    let overdue = get_overdue_todos.execute(now()).await?;
+    println!("✓ Overdue todos ({}): {:?}\n", overdue.len(), overdue);
+
     // Delete a todo
     let delete_todo = app
         .injector()
@@ -0,0 +1,162 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use sadi::{Injector, Provider, Shared};
+
+struct Config {
+    port: u16,
+}
+
+fn cached_singleton(c: &mut Criterion) {
+    let injector = Injector::root();
+    injector.provide::<Config>(Provider::singleton(|_| Shared::new(Config { port: 8080 })));
+    injector.resolve::<Config>();
+
+    c.bench_function("resolve cached singleton", |b| {
+        b.iter(|| black_box(injector.resolve::<Config>().port))
+    });
+}
+
+fn cached_singleton_from_child(c: &mut Criterion) {
+    let root = Shared::new(Injector::root());
+    root.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+    let child = Injector::child(Injector::child(root).into());
+    child.resolve::<Config>();
+
+    c.bench_function("resolve cached root singleton from grandchild", |b| {
+        b.iter(|| black_box(child.resolve::<Config>().port))
+    });
+}
+
+trait Repo: Send + Sync {
+    fn port(&self) -> u16;
+}
+
+impl Repo for Config {
+    fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+fn cached_trait_object(c: &mut Criterion) {
+    let injector = Injector::root();
+    injector.provide::<dyn Repo>(Provider::singleton(|_| {
+        Shared::new(Config { port: 8080 }) as Shared<dyn Repo>
+    }));
+    injector.resolve::<dyn Repo>();
+
+    c.bench_function("resolve cached trait object", |b| {
+        b.iter(|| black_box(injector.resolve::<dyn Repo>().port()))
+    });
+}
+
+fn transient(c: &mut Criterion) {
+    let injector = Injector::root();
+    injector.provide::<Config>(Provider::transient(|_| Shared::new(Config { port: 8080 })));
+
+    c.bench_function("resolve transient", |b| {
+        b.iter(|| black_box(injector.resolve::<Config>().port))
+    });
+}
+
+struct Missing<const N: usize>;
+
+fn optional_mostly_miss(c: &mut Criterion) {
+    let injector = Injector::root();
+    injector.provide::<Config>(Provider::singleton(|_| Shared::new(Config { port: 8080 })));
+    injector.resolve::<Config>();
+
+    c.bench_function("optional_resolve mostly miss (1 hit / 8 probes)", |b| {
+        b.iter(|| {
+            black_box(injector.optional_resolve::<Config>());
+            black_box(injector.optional_resolve::<Missing<1>>());
+            black_box(injector.optional_resolve::<Missing<2>>());
+            black_box(injector.optional_resolve::<Missing<3>>());
+            black_box(injector.optional_resolve::<Missing<4>>());
+            black_box(injector.optional_resolve::<Missing<5>>());
+            black_box(injector.optional_resolve::<Missing<6>>());
+            black_box(injector.optional_resolve::<Missing<7>>());
+        })
+    });
+}
+
+fn optional_miss_from_grandchild(c: &mut Criterion) {
+    let root = Shared::new(Injector::root());
+    root.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+    let module = Shared::new(Injector::child(root));
+    module.provide::<u32>(Provider::singleton(|_| Shared::new(1)));
+    let child = Injector::child(module);
+    child.provide::<u64>(Provider::singleton(|_| Shared::new(2)));
+
+    c.bench_function("optional_resolve repeated miss from grandchild", |b| {
+        b.iter(|| black_box(child.optional_resolve::<Missing<1>>()))
+    });
+}
+
+#[derive(Clone)]
+struct LargeConfig {
+    database_url: String,
+    allowed_origins: Vec<String>,
+    feature_flags: std::collections::HashMap<String, bool>,
+}
+
+fn large_config() -> Injector {
+    let injector = Injector::root();
+    injector.provide::<LargeConfig>(Provider::root(|_| {
+        Shared::new(LargeConfig {
+            database_url: "postgres://localhost/app".to_string(),
+            allowed_origins: (0..64)
+                .map(|n| format!("https://app{n}.example.com"))
+                .collect(),
+            feature_flags: (0..64).map(|n| (format!("flag_{n}"), n % 2 == 0)).collect(),
+        })
+    }));
+    injector.resolve::<LargeConfig>();
+    injector
+}
+
+fn large_config_access(c: &mut Criterion) {
+    let injector = large_config();
+    let mut group = c.benchmark_group("read one field of a large config");
+
+    group.bench_function("resolve", |b| {
+        b.iter(|| black_box(injector.resolve::<LargeConfig>().allowed_origins.len()))
+    });
+    group.bench_function("with", |b| {
+        b.iter(|| black_box(injector.with::<LargeConfig, _>(|config| config.allowed_origins.len())))
+    });
+    group.bench_function("resolve_cloned", |b| {
+        b.iter(|| {
+            let config = injector.resolve_cloned::<LargeConfig>();
+            black_box((config.database_url.len(), config.feature_flags.len()))
+        })
+    });
+    group.finish();
+}
+
+fn copy_value(c: &mut Criterion) {
+    let injector = Injector::root();
+    injector.provide::<u16>(Provider::root(|_| Shared::new(8080)));
+    injector.provide_copy::<u16>(8080);
+    injector.resolve::<u16>();
+
+    let mut group = c.benchmark_group("resolve a Copy config value");
+    group.bench_function("resolve", |b| {
+        b.iter(|| black_box(*injector.resolve::<u16>()))
+    });
+    group.bench_function("resolve_copy", |b| {
+        b.iter(|| black_box(injector.resolve_copy::<u16>()))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    cached_singleton,
+    cached_singleton_from_child,
+    cached_trait_object,
+    transient,
+    optional_mostly_miss,
+    optional_miss_from_grandchild,
+    large_config_access,
+    copy_value
+);
+criterion_main!(benches);
@@ -38,6 +38,7 @@
 //! // Use injector to resolve dependencies
 //! ```
 
+use crate::error::Error;
 use crate::injector::Injector;
 use crate::module::Module;
 use crate::runtime::Shared;
@@ -99,8 +100,8 @@ pub struct Application {
 impl std::fmt::Debug for Application {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Application")
-            .field("injector", &"...")
-            .field("root", &"<dyn Module>")
+            .field("bootstrapped", &self.is_bootstrapped())
+            .field("injector", &self.injector)
             .finish()
     }
 }
@@ -154,7 +155,9 @@ impl Application {
     ///
     /// # Panics
     ///
-    /// Panics if called more than once on the same application instance.
+    /// Panics if called more than once on the same application instance, or if a
+    /// module fails to load. Use [`try_bootstrap()`](Application::try_bootstrap)
+    /// to handle the latter as an error.
     ///
     /// # Examples
     ///
@@ -193,15 +196,55 @@ impl Application {
     /// app.bootstrap(); // Panics: Application already bootstrapped
     /// ```
     pub fn bootstrap(&mut self) {
+        if let Err(err) = self.try_bootstrap() {
+            panic!("Application bootstrap failed: {}", err);
+        }
+    }
+
+    /// Bootstraps the application, returning the first module loading error.
+    ///
+    /// Behaves like [`bootstrap()`](Application::bootstrap), except that an error
+    /// from a module's [`try_providers`](Module::try_providers) is returned
+    /// instead of panicking. The application counts as bootstrapped either way.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first module that failed to load.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called more than once on the same application instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Application, Error, ErrorKind, Injector, Module, Provider, Shared};
+    ///
+    /// struct AppModule;
+    ///
+    /// impl Module for AppModule {
+    ///     fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+    ///         injector.try_provide::<u8>(Provider::root(|_| Shared::new(1)))?;
+    ///         injector.try_provide::<u8>(Provider::root(|_| Shared::new(2)))
+    ///     }
+    /// }
+    ///
+    /// let mut app = Application::new(AppModule);
+    /// let err = app.try_bootstrap().unwrap_err();
+    /// assert_eq!(err.kind, ErrorKind::ProviderAlreadyRegistered);
+    /// ```
+    pub fn try_bootstrap(&mut self) -> Result<(), Error> {
         let root = self.root.take().expect("Application already bootstrapped");
 
         #[cfg(feature = "tracing")]
         info!("Starting application bootstrap process");
 
-        Self::load_module(self.injector.clone(), root);
+        Injector::load_module(self.injector.clone(), root.as_ref())?;
 
         #[cfg(feature = "tracing")]
         info!("Application bootstrap completed successfully");
+
+        Ok(())
     }
 
     /// Returns a shared reference to the root injector.
@@ -285,48 +328,6 @@ impl Application {
 
         bootstrapped
     }
-
-    /// Recursively loads a module and its imports into the injector hierarchy.
-    ///
-    /// Creates a child injector for the module, loads all imported modules first,
-    /// then registers the module's own providers. This ensures proper dependency
-    /// resolution order.
-    ///
-    /// # Parameters
-    ///
-    /// - `parent`: The parent injector to create a child from
-    /// - `module`: The module to load
-    fn load_module(parent: Shared<Injector>, module: Box<dyn Module>) {
-        #[cfg(feature = "tracing")]
-        debug!("Loading module into injector hierarchy");
-
-        let module_injector = Shared::new(Injector::child(parent.clone()));
-
-        #[cfg(feature = "tracing")]
-        debug!("Created child injector for module");
-
-        let imports = module.imports();
-        #[cfg(feature = "tracing")]
-        if !imports.is_empty() {
-            debug!("Module has {} imports, loading them first", imports.len());
-        }
-
-        #[allow(unused_variables)]
-        for (index, import) in imports.into_iter().enumerate() {
-            #[cfg(feature = "tracing")]
-            debug!("Loading import {}", index + 1);
-
-            Self::load_module(module_injector.clone(), import);
-        }
-
-        #[cfg(feature = "tracing")]
-        debug!("Registering module providers");
-
-        module.providers(&module_injector);
-
-        #[cfg(feature = "tracing")]
-        debug!("Module loaded successfully");
-    }
 }
 
 #[cfg(test)]
@@ -642,4 +643,41 @@ mod tests {
             "All nested modules should be loaded"
         );
     }
+
+    struct ConflictingModule;
+
+    impl Module for ConflictingModule {
+        fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+            injector.try_provide::<u8>(crate::Provider::root(|_| Shared::new(1)))
+        }
+    }
+
+    struct ConflictingImportsModule;
+
+    impl Module for ConflictingImportsModule {
+        fn imports(&self) -> Vec<Box<dyn Module>> {
+            vec![Box::new(ConflictingModule), Box::new(ConflictingModule)]
+        }
+
+        fn providers(&self, _injector: &Injector) {
+            panic!("providers ran after a failed import");
+        }
+    }
+
+    #[test]
+    fn test_try_bootstrap_returns_module_error() {
+        let mut app = Application::new(ConflictingImportsModule);
+
+        let err = app.try_bootstrap().unwrap_err();
+
+        assert_eq!(err.kind, crate::ErrorKind::ProviderAlreadyRegistered);
+        assert!(err.message.contains("u8"));
+        assert!(app.is_bootstrapped());
+    }
+
+    #[test]
+    #[should_panic(expected = "Application bootstrap failed")]
+    fn test_bootstrap_panics_on_module_error() {
+        Application::new(ConflictingImportsModule).bootstrap();
+    }
 }
@@ -0,0 +1,391 @@
+//! Providers with asynchronous, fallible factories.
+//!
+//! Some singletons can only be built by awaiting I/O, such as a connection pool
+//! that must reach its database first. An [`AsyncProvider`], created with
+//! [`Provider::async_root`](crate::Provider::async_root), holds a factory that
+//! returns a future of `Result<Shared<T>, Error>`. It is registered with
+//! [`Injector::provide_async`] and resolved with [`Injector::resolve_async`];
+//! the first successful result is cached on the root injector and is then also
+//! visible to the synchronous [`Injector::resolve`]. As with synchronous
+//! providers, `T` may be a trait object, so a repository that must connect
+//! before it is usable can be registered as a `Shared<dyn Repository>`.
+//!
+//! Concurrent resolves of a singleton wait for a single factory run. Dropping
+//! the resolve that runs it, for example because it lost a `select!` or hit a
+//! timeout, does not leave the singleton stuck: a waiting or later resolve
+//! runs the factory again.
+//!
+//! Transient failures can be retried with a [`RetryPolicy`]. Only errors of
+//! kind [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed)
+//! are retried; anything else, such as a missing dependency, fails at once.
+//!
+//! This module requires the `async` feature and works with any executor.
+//! Backoff delays sleep on the tokio timer, so a policy with a non-zero backoff
+//! needs the `tokio` feature and a tokio runtime; without the feature it is
+//! rejected by [`AsyncProvider::try_with_retry`].
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use sadi::{Error, Injector, Provider, RetryPolicy, Shared};
+//!
+//! struct Pool {
+//!     url: String,
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide_async::<Pool>(
+//!     Provider::async_root(|_| async {
+//!         Ok::<_, Error>(Shared::new(Pool {
+//!             url: "postgres://localhost".to_string(),
+//!         }))
+//!     })
+//!     .with_retry(RetryPolicy {
+//!         attempts: 3,
+//!         backoff: Duration::ZERO,
+//!     }),
+//! );
+//!
+//! let pool = futures::executor::block_on(injector.resolve_async::<Pool>()).unwrap();
+//! assert_eq!(pool.url, "postgres://localhost");
+//! ```
+
+use std::{future::Future, pin::Pin, time::Duration};
+
+use crate::error::{Error, ErrorKind};
+use crate::injector::Injector;
+use crate::runtime::Shared;
+use crate::scope::Scope;
+
+#[cfg(feature = "tracing")]
+use tracing::debug;
+
+/// The boxed future returned by an async factory.
+#[cfg(not(feature = "thread-safe"))]
+pub type AsyncFactoryFuture<T> = Pin<Box<dyn Future<Output = Result<Shared<T>, Error>> + 'static>>;
+
+/// The boxed future returned by an async factory (thread-safe variant).
+#[cfg(feature = "thread-safe")]
+pub type AsyncFactoryFuture<T> =
+    Pin<Box<dyn Future<Output = Result<Shared<T>, Error>> + Send + 'static>>;
+
+/// How often, and how patiently, an async factory is re-run after a failure.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RetryPolicy {
+    /// Total number of times the factory may run, including the first; `0`
+    /// behaves like `1`.
+    pub attempts: u32,
+    /// The delay before each retry; anything but zero needs the `tokio` feature.
+    pub backoff: Duration,
+}
+
+/// A provider whose factory is asynchronous and may fail.
+///
+/// Created with [`Provider::async_root`](crate::Provider::async_root).
+pub struct AsyncProvider<T: ?Sized + 'static> {
+    /// The lifecycle scope of this provider
+    pub scope: Scope,
+
+    /// Retry policy applied to failing factory runs; see [`AsyncProvider::with_retry`]
+    pub retry: Option<RetryPolicy>,
+
+    /// The factory function that creates instances
+    ///
+    /// It receives a handle to the resolving injector, so the returned future
+    /// can resolve dependencies without borrowing it.
+    #[allow(clippy::type_complexity)]
+    #[cfg(not(feature = "thread-safe"))]
+    pub factory: Box<dyn Fn(Injector) -> AsyncFactoryFuture<T> + 'static>,
+
+    /// The factory function that creates instances (thread-safe variant)
+    #[allow(clippy::type_complexity)]
+    #[cfg(feature = "thread-safe")]
+    pub factory: Box<dyn Fn(Injector) -> AsyncFactoryFuture<T> + Send + Sync + 'static>,
+}
+
+#[cfg(feature = "debug")]
+impl<T: ?Sized + 'static> std::fmt::Debug for AsyncProvider<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(std::any::type_name::<Self>())
+            .field("scope", &self.scope)
+            .field("retry", &self.retry)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: ?Sized + 'static> AsyncProvider<T> {
+    /// Re-runs the factory according to `policy` when it fails with
+    /// [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed).
+    ///
+    /// Once the attempts are exhausted the last error is returned and nothing is
+    /// cached, so a later resolve starts over with a fresh budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if `policy`
+    /// has a non-zero backoff and the `tokio` feature, which provides the
+    /// timer, is disabled.
+    pub fn try_with_retry(mut self, policy: RetryPolicy) -> Result<Self, Error> {
+        #[cfg(not(feature = "tokio"))]
+        if !policy.backoff.is_zero() {
+            return Err(Error::invalid_scope(
+                "a retry backoff needs the `tokio` feature for its timer",
+            ));
+        }
+
+        self.retry = Some(policy);
+        Ok(self)
+    }
+
+    /// Like [`try_with_retry`](AsyncProvider::try_with_retry), but panics on error.
+    #[track_caller]
+    pub fn with_retry(self, policy: RetryPolicy) -> Self {
+        self.try_with_retry(policy).unwrap()
+    }
+
+    /// Runs the factory, retrying as configured, and returns the first success.
+    #[cfg_attr(not(feature = "tokio"), allow(unused_variables))]
+    pub(crate) async fn create(&self, injector: &Injector) -> Result<Shared<T>, Error> {
+        let (attempts, backoff) = match self.retry {
+            Some(policy) => (policy.attempts.max(1), policy.backoff),
+            None => (1, Duration::ZERO),
+        };
+
+        let mut attempt = 1;
+        loop {
+            match (self.factory)(injector.clone()).await {
+                Err(err) if err.kind == ErrorKind::FactoryExecutionFailed && attempt < attempts => {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Retrying factory for {} after attempt {}/{}: {}",
+                        std::any::type_name::<T>(),
+                        attempt,
+                        attempts,
+                        err.message
+                    );
+
+                    #[cfg(feature = "tokio")]
+                    if !backoff.is_zero() {
+                        tokio::time::sleep(backoff).await;
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::Provider;
+
+    struct Pool;
+
+    fn flaky_pool(calls: Shared<AtomicU32>, failures: u32) -> AsyncProvider<Pool> {
+        Provider::async_root(move |_| {
+            let calls = calls.clone();
+            async move {
+                if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                    Err(Error::factory_execution_failed(
+                        std::any::type_name::<Pool>(),
+                        "connection refused",
+                    ))
+                } else {
+                    Ok(Shared::new(Pool))
+                }
+            }
+        })
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_retry_resolves_within_attempt_budget() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let injector = Injector::root();
+        injector.provide_async::<Pool>(flaky_pool(calls.clone(), 2).with_retry(RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(1),
+        }));
+
+        let first = injector.resolve_async::<Pool>().await.unwrap();
+        let second = injector.resolve_async::<Pool>().await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(Shared::ptr_eq(&first, &second));
+        assert!(Shared::ptr_eq(&first, &injector.resolve::<Pool>()));
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    #[test]
+    fn test_backoff_without_a_timer_is_rejected() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let Err(err) = flaky_pool(calls.clone(), 2).try_with_retry(RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(1),
+        }) else {
+            panic!("accepted a backoff with no timer to wait it out");
+        };
+        assert_eq!(err.kind, ErrorKind::InvalidScope);
+
+        let injector = Injector::root();
+        injector.provide_async::<Pool>(flaky_pool(calls.clone(), 2).with_retry(RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        }));
+        assert!(futures::executor::block_on(injector.resolve_async::<Pool>()).is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_return_last_error_and_cache_nothing() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let injector = Injector::root();
+        injector.provide_async::<Pool>(flaky_pool(calls.clone(), 2).with_retry(RetryPolicy {
+            attempts: 2,
+            backoff: Duration::ZERO,
+        }));
+
+        let Err(err) = injector.resolve_async::<Pool>().await else {
+            panic!("expected the factory to fail");
+        };
+        assert_eq!(err.kind, ErrorKind::FactoryExecutionFailed);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Nothing was cached, so the next resolve runs the factory again.
+        assert!(injector.resolve_async::<Pool>().await.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_retried() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let injector = Injector::root();
+        let counted = calls.clone();
+        injector.provide_async::<Pool>(
+            Provider::async_root(move |_| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                async { Err(Error::service_not_provided("Config")) }
+            })
+            .with_retry(RetryPolicy {
+                attempts: 5,
+                backoff: Duration::ZERO,
+            }),
+        );
+
+        let Err(err) = injector.resolve_async::<Pool>().await else {
+            panic!("expected the factory to fail");
+        };
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    trait UserRepository: Send + Sync {
+        fn name(&self) -> &'static str;
+    }
+
+    struct PreparedUsers;
+    impl UserRepository for PreparedUsers {
+        fn name(&self) -> &'static str {
+            "prepared"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trait_object_singleton_is_shared_across_awaits() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let injector = Injector::root();
+        let counted = calls.clone();
+        injector.provide_async::<dyn UserRepository>(Provider::async_root(move |_| {
+            let counted = counted.clone();
+            async move {
+                // Preparing statements would await here.
+                tokio::task::yield_now().await;
+                counted.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, Error>(Shared::new(PreparedUsers) as Shared<dyn UserRepository>)
+            }
+        }));
+
+        let first = injector
+            .resolve_async::<dyn UserRepository>()
+            .await
+            .unwrap();
+        tokio::task::yield_now().await;
+        let second = injector
+            .resolve_async::<dyn UserRepository>()
+            .await
+            .unwrap();
+
+        assert_eq!(first.name(), "prepared");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(Shared::ptr_eq(&first, &second));
+        assert!(Shared::ptr_eq(
+            &first,
+            &injector.resolve::<dyn UserRepository>()
+        ));
+    }
+
+    /// A pool whose first factory run never finishes, as if its connect hung.
+    fn hanging_pool(calls: Shared<AtomicU32>) -> AsyncProvider<Pool> {
+        Provider::async_root(move |_| {
+            let calls = calls.clone();
+            async move {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    std::future::pending::<()>().await;
+                }
+                Ok(Shared::new(Pool))
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_initializer_hands_over_to_a_waiting_resolve() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let injector = Injector::root();
+        injector.provide_async::<Pool>(hanging_pool(calls.clone()));
+
+        let mut first = Box::pin(injector.resolve_async::<Pool>());
+        assert!(futures::poll!(&mut first).is_pending());
+
+        // The second resolve waits for the first instead of running the factory.
+        let mut second = Box::pin(injector.resolve_async::<Pool>());
+        assert!(futures::poll!(&mut second).is_pending());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        drop(first);
+        let pool = second.await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(Shared::ptr_eq(
+            &pool,
+            &injector.resolve_async::<Pool>().await.unwrap()
+        ));
+        assert!(Shared::ptr_eq(&pool, &injector.resolve::<Pool>()));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_initializer_lets_a_later_resolve_retry() {
+        let calls = Shared::new(AtomicU32::new(0));
+        let injector = Injector::root();
+        injector.provide_async::<Pool>(hanging_pool(calls.clone()));
+
+        let mut first = Box::pin(injector.resolve_async::<Pool>());
+        assert!(futures::poll!(&mut first).is_pending());
+        drop(first);
+
+        let pool = injector.resolve_async::<Pool>().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Clearing the singleton lets the next resolve initialize it afresh.
+        assert!(injector.clear_singleton::<Pool>());
+        let fresh = injector.resolve_async::<Pool>().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(!Shared::ptr_eq(&pool, &fresh));
+    }
+}
@@ -0,0 +1,142 @@
+//! Transient trait objects handed out as owned boxes.
+//!
+//! [`Injector::resolve`](crate::Injector::resolve) always returns a
+//! [`Shared`](crate::Shared) handle, even for transient providers. Some callers
+//! want a value they own outright instead, such as a fresh strategy object they
+//! may mutate through `&mut self`. [`Injector::provide_transient_dyn`](crate::Injector::provide_transient_dyn)
+//! registers a factory returning `Box<T>`, and
+//! [`Injector::resolve_boxed`](crate::Injector::resolve_boxed) runs it on every
+//! call, so each caller gets a new, unshared box.
+//!
+//! The factory is registered under [`BoxedFactory<T>`], so it does not conflict
+//! with a [`Shared`](crate::Shared) registration of the same `T`. Because the
+//! boxes are never shared, `T` need not be `Send + Sync` even with the
+//! `thread-safe` feature; only the factory must be.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::Injector;
+//!
+//! trait Strategy {
+//!     fn next(&mut self) -> u32;
+//! }
+//!
+//! struct Counter(u32);
+//! impl Strategy for Counter {
+//!     fn next(&mut self) -> u32 {
+//!         self.0 += 1;
+//!         self.0
+//!     }
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide_transient_dyn::<dyn Strategy, _>(|_| Box::new(Counter(0)) as Box<dyn Strategy>);
+//!
+//! let mut first = injector.resolve_boxed::<dyn Strategy>();
+//! first.next();
+//! assert_eq!(first.next(), 2);
+//!
+//! let mut second = injector.resolve_boxed::<dyn Strategy>();
+//! assert_eq!(second.next(), 1);
+//! ```
+
+use crate::injector::Injector;
+
+/// The factory behind [`Injector::provide_transient_dyn`](crate::Injector::provide_transient_dyn).
+pub struct BoxedFactory<T: ?Sized + 'static> {
+    #[allow(clippy::type_complexity)]
+    #[cfg(not(feature = "thread-safe"))]
+    factory: Box<dyn Fn(&Injector) -> Box<T>>,
+
+    #[allow(clippy::type_complexity)]
+    #[cfg(feature = "thread-safe")]
+    factory: Box<dyn Fn(&Injector) -> Box<T> + Send + Sync>,
+}
+
+#[cfg(feature = "debug")]
+impl<T: ?Sized + 'static> std::fmt::Debug for BoxedFactory<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(std::any::type_name::<Self>())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: ?Sized + 'static> BoxedFactory<T> {
+    /// Wraps `factory`.
+    pub fn new(factory: impl Fn(&Injector) -> Box<T> + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + 'static> BoxedFactory<T> {
+    /// Wraps `factory`.
+    pub fn new(factory: impl Fn(&Injector) -> Box<T> + Send + Sync + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> BoxedFactory<T> {
+    /// Runs the factory, returning a new box.
+    pub fn create(&self, injector: &Injector) -> Box<T> {
+        (self.factory)(injector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{Provider, Shared};
+
+    use super::*;
+
+    trait Strategy {
+        fn id(&self) -> usize;
+    }
+
+    struct Numbered(usize);
+    impl Strategy for Numbered {
+        fn id(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_resolve_boxed_builds_a_distinct_box_each_time() {
+        let next_id = Shared::new(AtomicUsize::new(0));
+        let injector = Injector::root();
+
+        let ids = next_id.clone();
+        injector.provide_transient_dyn::<dyn Strategy, _>(move |_| {
+            Box::new(Numbered(ids.fetch_add(1, Ordering::SeqCst))) as Box<dyn Strategy>
+        });
+
+        let first = injector.resolve_boxed::<dyn Strategy>();
+        let second = injector.resolve_boxed::<dyn Strategy>();
+
+        assert_eq!((first.id(), second.id()), (0, 1));
+        assert!(!std::ptr::addr_eq(&*first, &*second));
+        assert!(injector.contains::<BoxedFactory<dyn Strategy>>());
+    }
+
+    #[test]
+    fn test_boxed_factory_resolves_dependencies_from_the_caller() {
+        let root = Shared::new(Injector::root());
+        root.provide_transient_dyn::<dyn Strategy, _>(|inj| {
+            Box::new(Numbered(*inj.resolve::<usize>())) as Box<dyn Strategy>
+        });
+
+        let child = Injector::child(root.clone());
+        child.provide::<usize>(Provider::singleton(|_| Shared::new(7)));
+
+        assert_eq!(child.resolve_boxed::<dyn Strategy>().id(), 7);
+        assert!(root.try_resolve_boxed::<u8>().is_err());
+    }
+}
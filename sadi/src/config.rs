@@ -0,0 +1,509 @@
+//! Typed configuration loaded from environment variables.
+//!
+//! [`ConfigProvider::from_env`] returns a [`ConfigModule`] that, when loaded,
+//! deserializes the process environment into a `serde` type and registers the
+//! result as a root singleton. Field names are matched case-insensitively
+//! against variable names, so `database_url` reads `DATABASE_URL`; with
+//! [`prefixed`](ConfigModule::prefixed), only variables carrying the prefix are
+//! considered and the prefix is stripped first. Optional fields and
+//! `#[serde(default)]` work as usual, and values are parsed into the field's
+//! type.
+//!
+//! A missing or unparsable variable, or a config rejected by
+//! [`validate`](ConfigModule::validate), fails the load with
+//! [`ModuleLoadFailed`](crate::ErrorKind::ModuleLoadFailed) instead of
+//! surfacing later as a panic inside a factory.
+//!
+//! Tests can feed a fixed set of variables with [`ConfigProvider::from_vars`]
+//! rather than mutating the process environment.
+//!
+//! A loaded config can also pick the implementation behind an abstract
+//! service: [`bind_impl`] reads a name from it and makes the
+//! [keyed provider](crate::Injector::provide_keyed) registered under that name
+//! the default for the service.
+//!
+//! Requires the `config` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{ConfigProvider, Injector, Module};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     port: u16,
+//!     #[serde(default)]
+//!     verbose: bool,
+//! }
+//!
+//! let injector = Injector::root();
+//! ConfigProvider::from_vars::<Config>([("APP_PORT", "8080")])
+//!     .prefixed("APP_")
+//!     .validate(|config| match config.port {
+//!         0 => Err("port must not be 0".to_string()),
+//!         _ => Ok(()),
+//!     })
+//!     .try_providers(&injector)
+//!     .unwrap();
+//!
+//! assert_eq!(injector.resolve::<Config>().port, 8080);
+//! assert!(!injector.resolve::<Config>().verbose);
+//! ```
+
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+use crate::injector::Injector;
+use crate::module::Module;
+use crate::provider::Provider;
+use crate::runtime::Shared;
+
+#[cfg(not(feature = "thread-safe"))]
+type Validator<C> = Box<dyn Fn(&C) -> Result<(), String>>;
+
+#[cfg(feature = "thread-safe")]
+type Validator<C> = Box<dyn Fn(&C) -> Result<(), String> + Send + Sync>;
+
+/// Entry point for building a [`ConfigModule`].
+pub struct ConfigProvider;
+
+impl ConfigProvider {
+    /// Reads `C` from the process environment when the module is loaded.
+    pub fn from_env<C>() -> ConfigModule<C>
+    where
+        C: DeserializeOwned + 'static,
+    {
+        ConfigModule {
+            vars: None,
+            prefix: None,
+            validator: None,
+            _config: PhantomData,
+        }
+    }
+
+    /// Reads `C` from `vars` instead of the process environment.
+    pub fn from_vars<C>(
+        vars: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> ConfigModule<C>
+    where
+        C: DeserializeOwned + 'static,
+    {
+        ConfigModule {
+            vars: Some(
+                vars.into_iter()
+                    .map(|(name, value)| (name.into(), value.into()))
+                    .collect(),
+            ),
+            ..Self::from_env::<C>()
+        }
+    }
+}
+
+/// A module that registers a `C` deserialized from environment variables.
+///
+/// Built with [`ConfigProvider`]; see the [module docs](self).
+pub struct ConfigModule<C> {
+    /// Fixed variables, or `None` to read the process environment on load.
+    vars: Option<Vec<(String, String)>>,
+    prefix: Option<String>,
+    validator: Option<Validator<C>>,
+    _config: PhantomData<fn() -> C>,
+}
+
+#[cfg(feature = "debug")]
+impl<C> std::fmt::Debug for ConfigModule<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigModule")
+            .field("config", &std::any::type_name::<C>())
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C> ConfigModule<C>
+where
+    C: DeserializeOwned + 'static,
+{
+    /// Only reads variables starting with `prefix`, matching fields against the rest of the name.
+    pub fn prefixed(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Deserializes and validates `C` without registering it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModuleLoadFailed`](crate::ErrorKind::ModuleLoadFailed) if a
+    /// variable is missing or cannot be parsed, or if the validator rejects
+    /// the result.
+    pub fn load(&self) -> Result<C, Error> {
+        let vars = match &self.vars {
+            Some(vars) => vars.clone(),
+            None => std::env::vars().collect(),
+        };
+
+        let parsed = match &self.prefix {
+            Some(prefix) => envy::prefixed(prefix.as_str()).from_iter::<_, C>(vars),
+            None => envy::from_iter::<_, C>(vars),
+        };
+        let config = parsed.map_err(|err| Self::failure(&err.to_string()))?;
+
+        if let Some(validator) = &self.validator {
+            validator(&config).map_err(|reason| Self::failure(&reason))?;
+        }
+
+        Ok(config)
+    }
+
+    fn failure(reason: &str) -> Error {
+        Error::module_load_failed(std::any::type_name::<Self>(), reason)
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<C> ConfigModule<C>
+where
+    C: DeserializeOwned + 'static,
+{
+    /// Rejects the loaded config when `validator` returns an error message.
+    pub fn validate(mut self, validator: impl Fn(&C) -> Result<(), String> + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<C> ConfigModule<C>
+where
+    C: DeserializeOwned + 'static,
+{
+    /// Rejects the loaded config when `validator` returns an error message.
+    pub fn validate(
+        mut self,
+        validator: impl Fn(&C) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<C> Module for ConfigModule<C>
+where
+    C: DeserializeOwned + 'static,
+{
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+        let config = Shared::new(self.load()?);
+        injector.try_provide::<C>(Provider::root(move |_| config.clone()))
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<C> Module for ConfigModule<C>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+{
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+        let config = Shared::new(self.load()?);
+        injector.try_provide::<C>(Provider::root(move |_| config.clone()))
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+/// Makes the implementation of `T` that the config `C` names the default `T`.
+///
+/// `select` reads the name from `C`, which is resolved once, when
+/// `bind_impl` is called. The implementations are registered beforehand with
+/// [`provide_keyed`](Injector::provide_keyed) under `String` keys, and the one
+/// under the selected name is registered as `T` on `injector`. Resolving `T`
+/// then resolves that keyed provider, so its scope decides whether instances
+/// are shared.
+///
+/// # Errors
+///
+/// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+/// `C` cannot be resolved or no implementation is registered under the
+/// selected name, and like [`try_provide`](Injector::try_provide) if `T` is
+/// already registered.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{ConfigProvider, Injector, Module, Provider, Shared, bind_impl};
+/// use serde::Deserialize;
+///
+/// trait Cache: Send + Sync {
+///     fn backend(&self) -> &'static str;
+/// }
+///
+/// struct Redis;
+/// impl Cache for Redis {
+///     fn backend(&self) -> &'static str {
+///         "redis"
+///     }
+/// }
+///
+/// struct InMemory;
+/// impl Cache for InMemory {
+///     fn backend(&self) -> &'static str {
+///         "in-memory"
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     cache: String,
+/// }
+///
+/// let injector = Injector::root();
+/// ConfigProvider::from_vars::<Config>([("CACHE", "redis")])
+///     .try_providers(&injector)
+///     .unwrap();
+/// injector.provide_keyed::<dyn Cache, String>(
+///     "redis".to_string(),
+///     Provider::root(|_| Shared::new(Redis) as Shared<dyn Cache>),
+/// );
+/// injector.provide_keyed::<dyn Cache, String>(
+///     "memory".to_string(),
+///     Provider::root(|_| Shared::new(InMemory) as Shared<dyn Cache>),
+/// );
+///
+/// bind_impl::<dyn Cache, Config>(&injector, |config| &config.cache).unwrap();
+///
+/// assert_eq!(injector.resolve::<dyn Cache>().backend(), "redis");
+/// ```
+pub fn bind_impl<T, C>(injector: &Injector, select: impl Fn(&C) -> &str) -> Result<(), Error>
+where
+    T: ?Sized + 'static,
+    C: 'static,
+{
+    let config = injector.try_resolve::<C>()?;
+    let name = select(&config).to_string();
+
+    if !injector.contains_keyed::<T, String>(&name) {
+        return Err(Error::service_not_provided(&format!(
+            "{} named {:?}",
+            std::any::type_name::<T>(),
+            name
+        )));
+    }
+
+    injector.try_provide::<T>(
+        Provider::transient(move |injector| injector.resolve_keyed::<T, String>(&name))
+            .depends_on::<C>(),
+    )
+}
+
+#[cfg(feature = "thread-safe")]
+/// Makes the implementation of `T` that the config `C` names the default `T`.
+///
+/// `select` reads the name from `C`, which is resolved once, when
+/// `bind_impl` is called. The implementations are registered beforehand with
+/// [`provide_keyed`](Injector::provide_keyed) under `String` keys, and the one
+/// under the selected name is registered as `T` on `injector`. Resolving `T`
+/// then resolves that keyed provider, so its scope decides whether instances
+/// are shared.
+///
+/// # Errors
+///
+/// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+/// `C` cannot be resolved or no implementation is registered under the
+/// selected name, and like [`try_provide`](Injector::try_provide) if `T` is
+/// already registered.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{ConfigProvider, Injector, Module, Provider, Shared, bind_impl};
+/// use serde::Deserialize;
+///
+/// trait Cache: Send + Sync {
+///     fn backend(&self) -> &'static str;
+/// }
+///
+/// struct Redis;
+/// impl Cache for Redis {
+///     fn backend(&self) -> &'static str {
+///         "redis"
+///     }
+/// }
+///
+/// struct InMemory;
+/// impl Cache for InMemory {
+///     fn backend(&self) -> &'static str {
+///         "in-memory"
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     cache: String,
+/// }
+///
+/// let injector = Injector::root();
+/// ConfigProvider::from_vars::<Config>([("CACHE", "redis")])
+///     .try_providers(&injector)
+///     .unwrap();
+/// injector.provide_keyed::<dyn Cache, String>(
+///     "redis".to_string(),
+///     Provider::root(|_| Shared::new(Redis) as Shared<dyn Cache>),
+/// );
+/// injector.provide_keyed::<dyn Cache, String>(
+///     "memory".to_string(),
+///     Provider::root(|_| Shared::new(InMemory) as Shared<dyn Cache>),
+/// );
+///
+/// bind_impl::<dyn Cache, Config>(&injector, |config| &config.cache).unwrap();
+///
+/// assert_eq!(injector.resolve::<dyn Cache>().backend(), "redis");
+/// ```
+pub fn bind_impl<T, C>(injector: &Injector, select: impl Fn(&C) -> &str) -> Result<(), Error>
+where
+    T: ?Sized + Send + Sync + 'static,
+    C: Send + Sync + 'static,
+{
+    let config = injector.try_resolve::<C>()?;
+    let name = select(&config).to_string();
+
+    if !injector.contains_keyed::<T, String>(&name) {
+        return Err(Error::service_not_provided(&format!(
+            "{} named {:?}",
+            std::any::type_name::<T>(),
+            name
+        )));
+    }
+
+    injector.try_provide::<T>(
+        Provider::transient(move |injector| injector.resolve_keyed::<T, String>(&name))
+            .depends_on::<C>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::ErrorKind;
+
+    #[derive(Deserialize)]
+    struct DatabaseConfig {
+        url: String,
+        pool_size: usize,
+        replica_url: Option<String>,
+    }
+
+    fn database_config(vars: &[(&str, &str)]) -> ConfigModule<DatabaseConfig> {
+        ConfigProvider::from_vars::<DatabaseConfig>(vars.iter().copied())
+            .prefixed("DB_")
+            .validate(|config| match config.pool_size {
+                0 => Err("pool_size must be at least 1".to_string()),
+                _ => Ok(()),
+            })
+    }
+
+    #[test]
+    fn test_valid_env_registers_a_singleton() {
+        let injector = Injector::root();
+        database_config(&[
+            ("DB_URL", "sqlite://app.db"),
+            ("DB_POOL_SIZE", "4"),
+            ("POOL_SIZE", "99"),
+        ])
+        .try_providers(&injector)
+        .unwrap();
+
+        let config = injector.resolve::<DatabaseConfig>();
+        assert_eq!(config.url, "sqlite://app.db");
+        assert_eq!(config.pool_size, 4);
+        assert_eq!(config.replica_url, None);
+        assert!(Shared::ptr_eq(
+            &config,
+            &injector.resolve::<DatabaseConfig>()
+        ));
+    }
+
+    #[test]
+    fn test_missing_unparsable_or_invalid_vars_fail_the_load() {
+        let cases: [&[(&str, &str)]; 3] = [
+            &[("DB_URL", "sqlite://app.db")],
+            &[("DB_URL", "sqlite://app.db"), ("DB_POOL_SIZE", "many")],
+            &[("DB_URL", "sqlite://app.db"), ("DB_POOL_SIZE", "0")],
+        ];
+
+        for vars in cases {
+            let injector = Injector::root();
+            let Err(err) = database_config(vars).try_providers(&injector) else {
+                panic!("loaded a bad config from {vars:?}");
+            };
+
+            assert_eq!(err.kind, ErrorKind::ModuleLoadFailed);
+            assert!(err.message.contains("DatabaseConfig"));
+            assert!(!injector.contains::<DatabaseConfig>());
+        }
+    }
+
+    trait Cache: Send + Sync {
+        fn backend(&self) -> &'static str;
+    }
+
+    struct Backend(&'static str);
+    impl Cache for Backend {
+        fn backend(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct CacheConfig {
+        cache: String,
+    }
+
+    fn cache_injector(cache: &str) -> Injector {
+        let injector = Injector::root();
+        ConfigProvider::from_vars::<CacheConfig>([("CACHE", cache)])
+            .try_providers(&injector)
+            .unwrap();
+
+        for name in ["redis", "memory"] {
+            injector.provide_keyed::<dyn Cache, String>(
+                name.to_string(),
+                Provider::root(move |_| Shared::new(Backend(name)) as Shared<dyn Cache>),
+            );
+        }
+
+        injector
+    }
+
+    #[test]
+    fn test_bind_impl_makes_the_configured_implementation_the_default() {
+        let injector = cache_injector("memory");
+        bind_impl::<dyn Cache, CacheConfig>(&injector, |config| &config.cache).unwrap();
+
+        let cache = injector.resolve::<dyn Cache>();
+        assert_eq!(cache.backend(), "memory");
+        assert!(Shared::ptr_eq(&cache, &injector.resolve::<dyn Cache>()));
+        assert!(Shared::ptr_eq(
+            &cache,
+            &injector.resolve_keyed::<dyn Cache, String>(&"memory".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_bind_impl_rejects_an_unknown_implementation() {
+        let injector = cache_injector("memcached");
+
+        let Err(err) = bind_impl::<dyn Cache, CacheConfig>(&injector, |config| &config.cache)
+        else {
+            panic!("bound an implementation that was never registered");
+        };
+
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+        assert!(err.message.contains("memcached"));
+        assert!(!injector.contains::<dyn Cache>());
+    }
+}
@@ -0,0 +1,142 @@
+//! Resolution tree diagnostics.
+//!
+//! When diagnostics are enabled on an [`Injector`](crate::Injector), every
+//! top-level resolve records the types it resolves transitively and, once the
+//! outermost resolve returns, emits a single indented tree describing them.
+//!
+//! Nesting is derived from the [`ResolveGuard`] stack, so the tree mirrors the
+//! actual resolution order. The tree is emitted with `tracing::debug!`, so
+//! without the `tracing` feature no tree is recorded or emitted.
+
+use std::cell::RefCell;
+
+use crate::resolve_guard::ResolveGuard;
+use crate::scope::Scope;
+
+#[cfg(feature = "tracing")]
+use tracing::debug;
+
+thread_local! {
+    static TREE: RefCell<Option<Vec<Node>>> = const { RefCell::new(None) };
+}
+
+/// Outcome recorded for a node in the resolution tree.
+pub(crate) enum Note {
+    Cached,
+    Created(Scope),
+    Failed(String),
+}
+
+struct Node {
+    depth: usize,
+    type_name: &'static str,
+    note: Option<Note>,
+}
+
+/// A single entry in the resolution tree, alive for the duration of one resolve.
+///
+/// The node created at depth zero owns the tree and emits it when dropped.
+pub(crate) struct TreeNode {
+    index: usize,
+    root: bool,
+}
+
+impl TreeNode {
+    /// Records that `T` is being resolved at the current stack depth.
+    ///
+    /// Returns `None` when called below a resolve that was not itself traced,
+    /// which happens if diagnostics were only enabled on a child injector.
+    /// Also returns `None` without the `tracing` feature, which is the only way
+    /// a tree is emitted.
+    pub(crate) fn enter<T: ?Sized + 'static>() -> Option<Self> {
+        if !cfg!(feature = "tracing") {
+            return None;
+        }
+
+        let depth = ResolveGuard::depth();
+
+        TREE.with(|tree| {
+            let mut tree = tree.borrow_mut();
+
+            if depth == 0 {
+                *tree = Some(Vec::new());
+            }
+
+            let nodes = tree.as_mut()?;
+            nodes.push(Node {
+                depth,
+                type_name: std::any::type_name::<T>(),
+                note: None,
+            });
+
+            Some(Self {
+                index: nodes.len() - 1,
+                root: depth == 0,
+            })
+        })
+    }
+
+    pub(crate) fn note(&self, note: Note) {
+        TREE.with(|tree| {
+            if let Some(node) = tree
+                .borrow_mut()
+                .as_mut()
+                .and_then(|nodes| nodes.get_mut(self.index))
+            {
+                node.note = Some(note);
+            }
+        });
+    }
+}
+
+impl Drop for TreeNode {
+    fn drop(&mut self) {
+        if !self.root {
+            return;
+        }
+
+        if let Some(nodes) = TREE.with(|tree| tree.borrow_mut().take()) {
+            emit(render(&nodes));
+        }
+    }
+}
+
+/// Records `note` on `node` if diagnostics are active for this resolve.
+pub(crate) fn note(node: &Option<TreeNode>, note: impl FnOnce() -> Note) {
+    if let Some(node) = node {
+        node.note(note());
+    }
+}
+
+fn render(nodes: &[Node]) -> String {
+    let mut out = String::from("resolution tree:");
+
+    for node in nodes {
+        out.push('\n');
+        out.push_str(&"  ".repeat(node.depth + 1));
+        out.push_str(node.type_name);
+
+        match &node.note {
+            Some(Note::Cached) => out.push_str(" [cached]"),
+            Some(Note::Created(scope)) => {
+                out.push_str(" [created, ");
+                out.push_str(&scope.to_string());
+                out.push(']');
+            }
+            Some(Note::Failed(message)) => {
+                out.push_str(" [failed: ");
+                out.push_str(message);
+                out.push(']');
+            }
+            None => {}
+        }
+    }
+
+    out
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn emit(tree: String) {
+    #[cfg(feature = "tracing")]
+    debug!("{}", tree);
+}
@@ -0,0 +1,46 @@
+//! Eager initialization at startup.
+//!
+//! Providers marked with [`Provider::eager`](crate::Provider::eager) are created
+//! by [`Injector::init_eager`](crate::Injector::init_eager) instead of on first resolve. The returned
+//! [`InitReport`] says how long each one took, which makes it easy to find the
+//! singleton that slows down boot.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! struct Pool;
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Pool>(Provider::root(|_| Shared::new(Pool)).eager());
+//!
+//! let report = injector.init_eager();
+//! assert_eq!(report.per_type.len(), 1);
+//! assert!(report.per_type[0].0.ends_with("Pool"));
+//! ```
+
+use std::time::Duration;
+
+/// How long eager initialization took, per type and in total.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct InitReport {
+    /// Each eagerly created type with the time its resolve took, sorted by type name.
+    ///
+    /// A resolve includes the dependencies it creates along the way, so an eager
+    /// type that another one depends on may show up as already cached.
+    pub per_type: Vec<(&'static str, Duration)>,
+    /// Wall-clock time for the whole initialization.
+    pub total: Duration,
+}
+
+impl InitReport {
+    /// Returns the type that took longest to create, if any.
+    pub fn slowest(&self) -> Option<(&'static str, Duration)> {
+        self.per_type
+            .iter()
+            .copied()
+            .max_by_key(|(_, elapsed)| *elapsed)
+    }
+}
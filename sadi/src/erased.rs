@@ -0,0 +1,142 @@
+//! Decorators that wrap every type whose name matches a predicate.
+//!
+//! [`Injector::decorate`](crate::Injector::decorate) wraps one type, named at
+//! compile time. [`Injector::decorate_matching`](crate::Injector::decorate_matching)
+//! instead selects types at runtime by their [`std::any::type_name`], for
+//! example every trait object whose name ends in `Repository`. Because one
+//! decorator then serves many types, it cannot be generic over them: it
+//! receives each new instance as an [`ErasedInstance`], downcasts it to the
+//! types it knows how to wrap, and returns an [`ErasedInstance`] of the same
+//! type.
+//!
+//! Some constraints follow from working on erased values:
+//!
+//! - Type names are not guaranteed to be stable across compiler versions, so
+//!   match on suffixes or substrings rather than full paths.
+//! - A decorator can only wrap types it downcasts explicitly; anything else
+//!   should be returned unchanged.
+//! - Returning an instance of a different type fails the resolve with
+//!   [`TypeMismatch`](crate::ErrorKind::TypeMismatch).
+//! - Decorators run when a provider's factory creates an instance, so cached
+//!   singletons are wrapped once and instances cached before the decorator was
+//!   added are left alone. Keyed and async providers are not decorated.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{ErasedInstance, Injector, Provider, Shared};
+//!
+//! trait UserRepository: Send + Sync {
+//!     fn name(&self) -> String;
+//! }
+//!
+//! struct SqlUsers;
+//! impl UserRepository for SqlUsers {
+//!     fn name(&self) -> String {
+//!         "sql".to_string()
+//!     }
+//! }
+//!
+//! struct Labeled(String);
+//! impl UserRepository for Labeled {
+//!     fn name(&self) -> String {
+//!         self.0.clone()
+//!     }
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide::<dyn UserRepository>(Provider::root(|_| {
+//!     Shared::new(SqlUsers) as Shared<dyn UserRepository>
+//! }));
+//! injector.decorate_matching(
+//!     |type_name| type_name.ends_with("Repository"),
+//!     |instance, _| match instance.downcast::<dyn UserRepository>() {
+//!         Some(users) => {
+//!             let labeled = Shared::new(Labeled(format!("logged {}", users.name())));
+//!             ErasedInstance::new(labeled as Shared<dyn UserRepository>)
+//!         }
+//!         None => instance,
+//!     },
+//! );
+//!
+//! assert_eq!(injector.resolve::<dyn UserRepository>().name(), "logged sql");
+//! ```
+
+use std::any::Any;
+
+use crate::injector::Injector;
+use crate::runtime::Shared;
+
+/// A resolved instance whose type is only known at runtime.
+///
+/// It holds a `Shared<T>`, so `T` may be a trait object.
+#[derive(Clone)]
+pub struct ErasedInstance {
+    type_name: &'static str,
+
+    #[cfg(not(feature = "thread-safe"))]
+    value: Shared<dyn Any>,
+
+    #[cfg(feature = "thread-safe")]
+    value: Shared<dyn Any + Send + Sync>,
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for ErasedInstance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErasedInstance")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl ErasedInstance {
+    /// Erases `value`, remembering `T` for [`downcast`](Self::downcast).
+    pub fn new<T: ?Sized + 'static>(value: Shared<T>) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            value: Shared::new(value),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl ErasedInstance {
+    /// Erases `value`, remembering `T` for [`downcast`](Self::downcast).
+    pub fn new<T: ?Sized + Send + Sync + 'static>(value: Shared<T>) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            value: Shared::new(value),
+        }
+    }
+}
+
+impl ErasedInstance {
+    /// The name of the erased type, as given by [`std::any::type_name`].
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the instance as a `Shared<T>`, or `None` if it is not a `T`.
+    pub fn downcast<T: ?Sized + 'static>(&self) -> Option<Shared<T>> {
+        self.value.downcast_ref::<Shared<T>>().cloned()
+    }
+}
+
+/// A decorator registered with [`Injector::decorate_matching`].
+pub(crate) struct MatchingDecorator {
+    #[cfg(not(feature = "thread-safe"))]
+    pub(crate) matches: Box<dyn Fn(&str) -> bool>,
+
+    #[cfg(feature = "thread-safe")]
+    pub(crate) matches: Box<dyn Fn(&str) -> bool + Send + Sync>,
+
+    #[allow(clippy::type_complexity)]
+    #[cfg(not(feature = "thread-safe"))]
+    pub(crate) wrap: Box<dyn Fn(ErasedInstance, &Injector) -> ErasedInstance>,
+
+    #[allow(clippy::type_complexity)]
+    #[cfg(feature = "thread-safe")]
+    pub(crate) wrap: Box<dyn Fn(ErasedInstance, &Injector) -> ErasedInstance + Send + Sync>,
+}
@@ -8,6 +8,8 @@
 //!
 //! - `ErrorKind` captures the error category.
 //! - `Error` stores the category and a human-readable message.
+//! - An `Error` wrapping a nested failure keeps it as its `source`, so error
+//!   reporters such as `anyhow` and `eyre` can print the full chain.
 //!
 //! The helpers in `Error` are provided to keep call sites concise and to
 //! maintain consistent error messages.
@@ -35,8 +37,25 @@ use tracing::error;
 ///
 /// These variants are intentionally coarse-grained to keep error handling
 /// straightforward while still expressive enough for diagnostics.
+///
+/// The enum is `#[non_exhaustive]`: new kinds may be added in minor releases,
+/// so matches outside this crate need a catch-all arm.
+///
+/// ```
+/// use sadi::error::{Error, ErrorKind};
+///
+/// let err = Error::service_not_provided("MyService");
+///
+/// let retryable = match err.kind {
+///     ErrorKind::ServiceNotProvided => false,
+///     ErrorKind::CircularDependency => false,
+///     _ => true,
+/// };
+/// assert!(!retryable);
+/// ```
 #[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// Service provider not found for the requested type.
     ServiceNotProvided,
@@ -46,16 +65,28 @@ pub enum ErrorKind {
     ProviderAlreadyRegistered,
     /// Circular dependency detected in resolution chain.
     CircularDependency,
+    /// A module expected to be loaded was never loaded.
+    ModuleNotLoaded,
+    /// The live registrations differ from a recorded manifest.
+    ManifestMismatch,
+    /// A factory ran but could not produce an instance.
+    FactoryExecutionFailed,
+    /// The operation is not allowed in the injector's current state.
+    InvalidScope,
+    /// A module could not register its providers.
+    ModuleLoadFailed,
 }
 
 /// Container error structure.
 ///
 /// `kind` enables programmatic handling, while `message` is human-readable.
+/// `source` holds the nested failure this error wraps, if any.
 #[derive(Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct Error {
     pub kind: ErrorKind,
     pub message: String,
+    pub source: Option<Box<Error>>,
 }
 
 impl Error {
@@ -66,6 +97,7 @@ impl Error {
         let error = Self {
             kind: kind.clone(),
             message: message.into(),
+            source: None,
         };
 
         #[cfg(feature = "tracing")]
@@ -74,6 +106,15 @@ impl Error {
         error
     }
 
+    /// Records `source` as the nested failure that caused this error.
+    ///
+    /// The message is left as is; [`source`](std::error::Error::source)
+    /// returns the nested error.
+    pub fn with_source(mut self, source: Error) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
     /// Service provider not found for the requested type.
     pub fn service_not_provided(type_name: &str) -> Self {
         Self::new(
@@ -92,6 +133,20 @@ impl Error {
         )
     }
 
+    /// Type mismatch where the type actually registered is known.
+    ///
+    /// Names both sides, so a provider stored under the wrong type can be traced
+    /// back to its registration.
+    pub fn type_mismatch_found(expected: &str, found: &str) -> Self {
+        Self::new(
+            ErrorKind::TypeMismatch,
+            format!(
+                "Type mismatch when resolving: expected {}, found a provider for {}",
+                expected, found
+            ),
+        )
+    }
+
     /// Provider already registered for this type.
     ///
     /// Attempting to register a provider for a type that already has one.
@@ -115,6 +170,79 @@ impl Error {
             ),
         )
     }
+
+    /// Module expected to be loaded was never loaded.
+    pub fn module_not_loaded(module_name: &str) -> Self {
+        Self::new(
+            ErrorKind::ModuleNotLoaded,
+            format!("Module was not loaded: {}", module_name),
+        )
+    }
+
+    /// Live registrations differ from a recorded manifest.
+    pub fn manifest_mismatch(missing: &[String], unexpected: &[String]) -> Self {
+        Self::new(
+            ErrorKind::ManifestMismatch,
+            format!(
+                "Registrations do not match manifest: missing [{}], unexpected [{}]",
+                missing.join(", "),
+                unexpected.join(", ")
+            ),
+        )
+    }
+
+    /// Factory could not produce an instance of the given type.
+    ///
+    /// Fallible factories return this to report a failure that may be
+    /// transient, such as a refused connection.
+    pub fn factory_execution_failed(type_name: &str, reason: &str) -> Self {
+        Self::new(
+            ErrorKind::FactoryExecutionFailed,
+            format!("Factory failed for type {}: {}", type_name, reason),
+        )
+    }
+
+    /// Factory could not produce an instance because a nested resolve failed.
+    ///
+    /// Fallible factories return this when a dependency they resolve with
+    /// `try_resolve` fails. The dependency's error is kept as the
+    /// [`source`](std::error::Error::source) rather than repeated in the
+    /// message.
+    ///
+    /// ```
+    /// use sadi::error::{Error, ErrorKind};
+    ///
+    /// let cause = Error::service_not_provided("Database");
+    /// let err = Error::factory_execution_failed_by("UserRepository", cause);
+    ///
+    /// assert!(err.kind == ErrorKind::FactoryExecutionFailed);
+    /// assert!(err.source.is_some_and(|source| source.kind == ErrorKind::ServiceNotProvided));
+    /// ```
+    pub fn factory_execution_failed_by(type_name: &str, source: Error) -> Self {
+        Self::factory_execution_failed(type_name, "a dependency failed to resolve")
+            .with_source(source)
+    }
+
+    /// Operation rejected by the injector's current state.
+    ///
+    /// Returned, for example, when registering on a frozen injector.
+    pub fn invalid_scope(reason: &str) -> Self {
+        Self::new(
+            ErrorKind::InvalidScope,
+            format!("Invalid scope: {}", reason),
+        )
+    }
+
+    /// Module could not register its providers.
+    ///
+    /// Returned, for example, when a configuration module finds a missing or
+    /// malformed variable.
+    pub fn module_load_failed(module_name: &str, reason: &str) -> Self {
+        Self::new(
+            ErrorKind::ModuleLoadFailed,
+            format!("Module {} failed to load: {}", module_name, reason),
+        )
+    }
 }
 
 impl fmt::Display for Error {
@@ -131,7 +259,13 @@ impl fmt::Display for Error {
 }
 
 #[cfg(feature = "debug")]
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -152,6 +286,14 @@ mod tests {
         assert!(err.message.contains("OtherType"));
     }
 
+    #[test]
+    fn type_mismatch_found_error() {
+        let err = Error::type_mismatch_found("dyn Repo", "WrongRepo");
+        assert!(err.kind == ErrorKind::TypeMismatch);
+        assert!(err.message.contains("expected dyn Repo"));
+        assert!(err.message.contains("WrongRepo"));
+    }
+
     #[test]
     fn provider_already_registered_error() {
         let err = Error::provider_already_registered("Foo", "transient");
@@ -168,6 +310,103 @@ mod tests {
         assert!(err.message.contains("A -> B -> A"));
     }
 
+    #[test]
+    fn module_not_loaded_error() {
+        let err = Error::module_not_loaded("AppModule");
+        assert!(err.kind == ErrorKind::ModuleNotLoaded);
+        assert!(err.message.contains("AppModule"));
+    }
+
+    #[test]
+    fn manifest_mismatch_error() {
+        let err = Error::manifest_mismatch(&["A (Root)".to_string()], &[]);
+        assert!(err.kind == ErrorKind::ManifestMismatch);
+        assert!(err.message.contains("missing [A (Root)]"));
+    }
+
+    #[test]
+    fn factory_execution_failed_error() {
+        let err = Error::factory_execution_failed("Pool", "connection refused");
+        assert!(err.kind == ErrorKind::FactoryExecutionFailed);
+        assert!(err.message.contains("Pool"));
+        assert!(err.message.contains("connection refused"));
+    }
+
+    #[test]
+    fn factory_execution_failed_by_error() {
+        let cause = Error::service_not_provided("Database");
+        let err = Error::factory_execution_failed_by("Repository", cause);
+        assert!(err.kind == ErrorKind::FactoryExecutionFailed);
+        assert!(err.message.contains("Repository"));
+        assert!(!err.message.contains("Database"));
+        assert!(
+            err.source
+                .is_some_and(|source| source.message.contains("Database"))
+        );
+    }
+
+    #[test]
+    fn with_source_records_nested_error() {
+        let err = Error::invalid_scope("injector is frozen").with_source(Error::type_mismatch("A"));
+        assert!(err.source.is_some());
+        assert!(Error::type_mismatch("A").source.is_none());
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn source_chain_reaches_root_cause() {
+        use crate::Injector;
+
+        struct Database;
+        struct Repository;
+        struct Service;
+
+        let injector = Injector::root();
+
+        let resolve_repository = |injector: &Injector| {
+            injector.try_resolve::<Database>().map_err(|err| {
+                Error::factory_execution_failed_by(std::any::type_name::<Repository>(), err)
+            })
+        };
+        let resolve_service = |injector: &Injector| {
+            resolve_repository(injector).map_err(|err| {
+                Error::factory_execution_failed_by(std::any::type_name::<Service>(), err)
+            })
+        };
+
+        let Err(err) = resolve_service(&injector) else {
+            panic!("the missing database should fail the service");
+        };
+
+        let mut chain = vec![err.to_string()];
+        let mut current: &dyn std::error::Error = &err;
+        while let Some(source) = current.source() {
+            chain.push(source.to_string());
+            current = source;
+        }
+
+        assert_eq!(chain.len(), 3);
+        assert!(chain[0].contains("Service"));
+        assert!(chain[1].contains("Repository"));
+        assert!(chain[2].contains("No provider registered"));
+        assert!(chain[2].contains("Database"));
+    }
+
+    #[test]
+    fn invalid_scope_error() {
+        let err = Error::invalid_scope("injector is frozen");
+        assert!(err.kind == ErrorKind::InvalidScope);
+        assert!(err.message.contains("injector is frozen"));
+    }
+
+    #[test]
+    fn module_load_failed_error() {
+        let err = Error::module_load_failed("ConfigModule", "missing value for field port");
+        assert!(err.kind == ErrorKind::ModuleLoadFailed);
+        assert!(err.message.contains("ConfigModule"));
+        assert!(err.message.contains("missing value for field port"));
+    }
+
     #[test]
     fn display_trait() {
         let err = Error::service_not_provided("X");
@@ -184,4 +423,16 @@ mod tests {
         assert!(err1.kind == err2.kind);
         assert_ne!(err1.message, err2.message);
     }
+
+    #[test]
+    fn error_kind_match_with_catch_all() {
+        let err = Error::circular_dependency(&["A", "A"]);
+
+        let label = match err.kind {
+            ErrorKind::CircularDependency => "cycle",
+            _ => "other",
+        };
+
+        assert_eq!(label, "cycle");
+    }
 }
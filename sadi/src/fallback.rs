@@ -0,0 +1,166 @@
+//! Alternative providers tried in order until one succeeds.
+//!
+//! [`Injector::provide_fallback_chain`](crate::Injector::provide_fallback_chain)
+//! registers several providers for the same type, such as a Redis-backed cache
+//! and an in-memory one. Resolving the type tries them in order and caches the
+//! first instance that is created successfully, giving runtime failover.
+//!
+//! A provider fails by way of the resolve errors it can already produce:
+//!
+//! - A closed [`gate`](crate::Provider::gated_by) fails with
+//!   [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided).
+//! - A rejecting [`after_init`](crate::Provider::after_init) hook, typically a
+//!   health check such as a ping, or an exceeded deadline (see
+//!   `Provider::with_deadline`), fails with
+//!   [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed).
+//!
+//! Either moves the resolve on to the next provider. Any other error, such as
+//! a cycle or a type mismatch from a [matching decorator](crate::erased),
+//! aborts the resolve instead of being masked by a fallback. A factory that
+//! panics is not a failure the chain can observe, so the panic propagates. If
+//! every provider fails, the resolve returns the last provider's error.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! trait Cache: Send + Sync {
+//!     fn backend(&self) -> &'static str;
+//! }
+//!
+//! struct Redis;
+//! impl Cache for Redis {
+//!     fn backend(&self) -> &'static str {
+//!         "redis"
+//!     }
+//! }
+//!
+//! struct InMemory;
+//! impl Cache for InMemory {
+//!     fn backend(&self) -> &'static str {
+//!         "in-memory"
+//!     }
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide_fallback_chain::<dyn Cache>(vec![
+//!     Provider::root(|_| Shared::new(Redis) as Shared<dyn Cache>)
+//!         .after_init(|_, _| Err("connection refused".to_string())),
+//!     Provider::root(|_| Shared::new(InMemory) as Shared<dyn Cache>),
+//! ]);
+//!
+//! assert_eq!(injector.resolve::<dyn Cache>().backend(), "in-memory");
+//! ```
+
+use crate::error::Error;
+use crate::injector::Injector;
+use crate::provider::Provider;
+use crate::runtime::Shared;
+
+#[cfg(not(feature = "thread-safe"))]
+type ChainResolver<T> = Shared<dyn Fn(&Injector) -> Result<Shared<T>, Error>>;
+
+#[cfg(feature = "thread-safe")]
+type ChainResolver<T> = Shared<dyn Fn(&Injector) -> Result<Shared<T>, Error> + Send + Sync>;
+
+/// The providers registered with [`Injector::provide_fallback_chain`](crate::Injector::provide_fallback_chain), in order.
+///
+/// A chain keeps failing over after [`Provider::map`] or
+/// [`Injector::decorate`](crate::Injector::decorate): those pass whichever
+/// provider wins through their function, rather than hiding the chain in a
+/// factory that could not report its failure.
+pub struct FallbackChain<T: ?Sized + 'static> {
+    len: usize,
+    resolve: ChainResolver<T>,
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: ?Sized + 'static> FallbackChain<T> {
+    pub(crate) fn new(providers: Vec<Provider<T>>) -> Self {
+        let providers: Vec<_> = providers.into_iter().map(Shared::new).collect();
+
+        Self {
+            len: providers.len(),
+            resolve: Shared::new(move |injector: &Injector| injector.resolve_chain(&providers)),
+        }
+    }
+
+    /// Returns a chain that passes the winning instance through `then`.
+    pub(crate) fn then<U, F>(&self, then: F) -> FallbackChain<U>
+    where
+        U: ?Sized + 'static,
+        F: Fn(Shared<T>, &Injector) -> Shared<U> + 'static,
+    {
+        let resolve = self.resolve.clone();
+
+        FallbackChain {
+            len: self.len,
+            resolve: Shared::new(move |injector: &Injector| Ok(then(resolve(injector)?, injector))),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + Send + Sync + 'static> FallbackChain<T> {
+    pub(crate) fn new(providers: Vec<Provider<T>>) -> Self {
+        let providers: Vec<_> = providers.into_iter().map(Shared::new).collect();
+
+        Self {
+            len: providers.len(),
+            resolve: Shared::new(move |injector: &Injector| injector.resolve_chain(&providers)),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + 'static> FallbackChain<T> {
+    /// Returns a chain that passes the winning instance through `then`.
+    pub(crate) fn then<U, F>(&self, then: F) -> FallbackChain<U>
+    where
+        U: ?Sized + 'static,
+        F: Fn(Shared<T>, &Injector) -> Shared<U> + Send + Sync + 'static,
+    {
+        let resolve = self.resolve.clone();
+
+        FallbackChain {
+            len: self.len,
+            resolve: Shared::new(move |injector: &Injector| Ok(then(resolve(injector)?, injector))),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> FallbackChain<T> {
+    /// Creates `T` from the first provider that succeeds.
+    pub(crate) fn resolve(&self, injector: &Injector) -> Result<Shared<T>, Error> {
+        (self.resolve)(injector)
+    }
+
+    /// Returns the number of providers in the chain.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the chain has no providers.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T: ?Sized + 'static> Clone for FallbackChain<T> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            resolve: self.resolve.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T: ?Sized + 'static> std::fmt::Debug for FallbackChain<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackChain")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
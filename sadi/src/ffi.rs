@@ -0,0 +1,178 @@
+//! Integer handles to resolved instances, for hosts that cannot name Rust types.
+//!
+//! A host embedding the container through a C ABI has no way to call a
+//! generic method such as [`Injector::resolve`](crate::Injector::resolve).
+//! [`Injector::resolve_handle`](crate::Injector::resolve_handle) resolves a
+//! registration by its type name instead, as reported by
+//! [`std::any::type_name`] and listed in the [manifest](crate::Manifest), and
+//! returns an opaque `u64` the host can store and pass back. Rust code on the
+//! other side of the boundary turns a handle back into the instance with
+//! [`Injector::handle`](crate::Injector::handle) and downcasts it like the
+//! result of [`resolve_erased`](crate::Injector::resolve_erased).
+//!
+//! Requires the `ffi` feature.
+//!
+//! # Safety and ownership
+//!
+//! Handles are plain integers looked up in a side table of the injector that
+//! issued them, never pointers, so no `unsafe` code is involved and a stale or
+//! forged handle is detected rather than dereferenced: looking it up yields
+//! `None`, and releasing it does nothing.
+//!
+//! - Each handle owns one strong reference to its instance, keeping it alive
+//!   until [`Injector::release_handle`](crate::Injector::release_handle) is
+//!   called, even if the injector's cache is cleared in the meantime.
+//! - Handles are never reused, so releasing one twice is harmless and cannot
+//!   free another caller's reference.
+//! - Handles belong to the injector they were resolved on and mean nothing to
+//!   any other. Dropping that injector releases every handle it issued.
+//! - `0` is never a valid handle, so [`resolve_handle`](crate::Injector::resolve_handle)
+//!   returns it to report failure without panicking across the boundary.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Instance, Provider, Shared};
+//!
+//! struct Config {
+//!     port: u16,
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+//!
+//! let handle = injector.resolve_handle(std::any::type_name::<Config>());
+//! assert_ne!(handle, 0);
+//!
+//! let erased = injector.handle(handle).unwrap();
+//! let config = erased.downcast_ref::<Instance<Config>>().unwrap().value();
+//! assert_eq!(config.port, 8080);
+//!
+//! assert!(injector.release_handle(handle));
+//! assert!(injector.handle(handle).is_none());
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::runtime::{Shared, Store};
+
+/// The instance behind a handle, wrapped in an [`Instance`](crate::Instance).
+#[cfg(not(feature = "thread-safe"))]
+pub type HandleValue = Shared<dyn Any>;
+
+#[cfg(feature = "thread-safe")]
+pub type HandleValue = Shared<dyn Any + Send + Sync>;
+
+/// The handles an injector has issued and not yet released.
+pub(crate) struct HandleTable {
+    next: AtomicU64,
+    entries: Store<HashMap<u64, HandleValue>>,
+}
+
+impl Default for HandleTable {
+    fn default() -> Self {
+        Self {
+            // `0` is reserved to report failure.
+            next: AtomicU64::new(1),
+            entries: Store::new(HashMap::new()),
+        }
+    }
+}
+
+impl HandleTable {
+    pub(crate) fn insert(&self, value: HandleValue) -> u64 {
+        let handle = self.next.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(not(feature = "thread-safe"))]
+        let mut entries = self.entries.borrow_mut();
+        #[cfg(feature = "thread-safe")]
+        let mut entries = self.entries.write().unwrap();
+
+        entries.insert(handle, value);
+        handle
+    }
+
+    pub(crate) fn get(&self, handle: u64) -> Option<HandleValue> {
+        #[cfg(not(feature = "thread-safe"))]
+        let entries = self.entries.borrow();
+        #[cfg(feature = "thread-safe")]
+        let entries = self.entries.read().unwrap();
+
+        entries.get(&handle).cloned()
+    }
+
+    pub(crate) fn remove(&self, handle: u64) -> Option<HandleValue> {
+        #[cfg(not(feature = "thread-safe"))]
+        let mut entries = self.entries.borrow_mut();
+        #[cfg(feature = "thread-safe")]
+        let mut entries = self.entries.write().unwrap();
+
+        entries.remove(&handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorKind, Injector, Instance, Provider, Shared};
+
+    struct Config {
+        port: u16,
+    }
+
+    fn config_injector() -> Injector {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+        injector
+    }
+
+    #[test]
+    fn test_handle_holds_a_reference_until_released() {
+        let injector = config_injector();
+        let config = injector.resolve::<Config>();
+        let baseline = Shared::strong_count(&config);
+
+        let handle = injector.resolve_handle(std::any::type_name::<Config>());
+        assert_ne!(handle, 0);
+        assert_eq!(Shared::strong_count(&config), baseline + 1);
+
+        let erased = injector.handle(handle).unwrap();
+        let resolved = erased.downcast_ref::<Instance<Config>>().unwrap().value();
+        assert_eq!(resolved.port, 8080);
+        assert!(Shared::ptr_eq(&resolved, &config));
+        drop((erased, resolved));
+
+        assert!(injector.release_handle(handle));
+        assert_eq!(Shared::strong_count(&config), baseline);
+        assert!(injector.handle(handle).is_none());
+        assert!(!injector.release_handle(handle));
+    }
+
+    #[test]
+    fn test_handles_are_distinct_and_owned_by_their_injector() {
+        let injector = config_injector();
+        let name = std::any::type_name::<Config>();
+
+        let first = injector.resolve_handle(name);
+        let second = injector.resolve_handle(name);
+        assert_ne!(first, second);
+        assert!(config_injector().handle(first).is_none());
+
+        let child = Injector::child(Shared::new(injector.clone()));
+        assert_ne!(child.resolve_handle(name), 0);
+    }
+
+    #[test]
+    fn test_unknown_type_name_yields_no_handle() {
+        let injector = config_injector();
+
+        assert_eq!(injector.resolve_handle("Config"), 0);
+
+        let Err(err) = injector.try_resolve_handle("app::Missing") else {
+            panic!("resolved a handle for an unregistered type");
+        };
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+        assert!(err.message.contains("app::Missing"));
+    }
+}
@@ -0,0 +1,70 @@
+//! One registration serving every instantiation of a generic type.
+//!
+//! A generic service such as `Repository<T>` normally needs a provider per
+//! type argument. [`Injector::provide_generic_fn`](crate::Injector::provide_generic_fn)
+//! registers a single factory instead. When a type has no provider, the
+//! factories registered on the injector and its ancestors are asked for it in
+//! turn, with its [`TypeId`]; the first one to return an [`ErasedInstance`]
+//! provides it. The result is cached on the injector holding the factory, so
+//! each concrete type is created once, on its first resolve, and then behaves
+//! like a root singleton.
+//!
+//! Rust has no way to name `Repository` without its type argument, nor to
+//! call a generic function for a type only known at runtime, so some limits
+//! follow:
+//!
+//! - The factory must recognize the concrete types it supports, typically by
+//!   comparing the requested id with `TypeId::of::<Repository<User>>()`
+//!   through a small generic helper, and return `None` for anything else.
+//! - It must return an instance of exactly the requested type; anything else
+//!   fails the resolve with [`TypeMismatch`](crate::ErrorKind::TypeMismatch).
+//! - The registration is named by a family type `F`, which only serves to
+//!   reject a second factory for the same family. Any type will do, such as a
+//!   marker struct or `Repository<()>`.
+//! - Types provided this way are found by a resolve, but they are not
+//!   registrations: [`contains`](crate::Injector::contains) and the
+//!   introspection methods do not see them.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::any::TypeId;
+//! use std::marker::PhantomData;
+//!
+//! use sadi::{ErasedInstance, Injector, Shared};
+//!
+//! struct Repository<T>(PhantomData<T>);
+//! struct User;
+//! struct Todo;
+//!
+//! fn repository<T: Send + Sync + 'static>(type_id: TypeId) -> Option<ErasedInstance> {
+//!     (type_id == TypeId::of::<Repository<T>>())
+//!         .then(|| ErasedInstance::new(Shared::new(Repository::<T>(PhantomData))))
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide_generic_fn::<Repository<()>, _>(|_, type_id| {
+//!     repository::<User>(type_id).or_else(|| repository::<Todo>(type_id))
+//! });
+//!
+//! let users = injector.resolve::<Repository<User>>();
+//! assert!(Shared::ptr_eq(&users, &injector.resolve::<Repository<User>>()));
+//! assert!(injector.try_resolve::<Repository<Todo>>().is_ok());
+//! assert!(injector.try_resolve::<Repository<u8>>().is_err());
+//! ```
+
+use std::any::TypeId;
+
+use crate::erased::ErasedInstance;
+use crate::injector::Injector;
+
+/// A factory registered with [`Injector::provide_generic_fn`].
+pub(crate) struct GenericFactory {
+    #[allow(clippy::type_complexity)]
+    #[cfg(not(feature = "thread-safe"))]
+    pub(crate) create: Box<dyn Fn(&Injector, TypeId) -> Option<ErasedInstance>>,
+
+    #[allow(clippy::type_complexity)]
+    #[cfg(feature = "thread-safe")]
+    pub(crate) create: Box<dyn Fn(&Injector, TypeId) -> Option<ErasedInstance> + Send + Sync>,
+}
@@ -0,0 +1,134 @@
+//! An opt-in, process-wide default injector.
+//!
+//! Small applications and examples sometimes prefer resolving services from
+//! anywhere over passing a [`Shared<Injector>`](crate::Shared) around.
+//! [`init`] installs an injector once for the whole process, and [`resolve`]
+//! resolves from it.
+//!
+//! This is a convenience for binaries only. Libraries should not use it: a
+//! global hides dependencies, cannot be replaced once set, and ties every
+//! caller to the one injector the application happened to install. Take an
+//! [`Injector`] or the resolved services as parameters instead.
+//!
+//! Requires the `thread-safe` feature, since the injector is shared by every
+//! thread.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared, global};
+//!
+//! struct Config {
+//!     port: u16,
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+//! global::init(injector).unwrap();
+//!
+//! assert_eq!(global::resolve::<Config>().unwrap().port, 8080);
+//! ```
+
+use std::sync::OnceLock;
+
+use crate::error::Error;
+use crate::injector::Injector;
+use crate::runtime::Shared;
+
+static GLOBAL: OnceLock<Injector> = OnceLock::new();
+
+/// Installs `injector` as the global injector.
+///
+/// # Errors
+///
+/// Fails with [`InvalidScope`](crate::ErrorKind::InvalidScope) if a global
+/// injector is already installed; the existing one is kept.
+pub fn init(injector: Injector) -> Result<(), Error> {
+    init_in(&GLOBAL, injector)
+}
+
+/// Returns the global injector.
+///
+/// # Errors
+///
+/// Fails with [`InvalidScope`](crate::ErrorKind::InvalidScope) if [`init`]
+/// has not been called yet.
+pub fn injector() -> Result<&'static Injector, Error> {
+    injector_in(&GLOBAL)
+}
+
+/// Resolves `T` from the global injector.
+///
+/// # Errors
+///
+/// Fails like [`injector`] if no global injector is installed, and like
+/// [`Injector::try_resolve`] otherwise.
+pub fn resolve<T>() -> Result<Shared<T>, Error>
+where
+    T: ?Sized + Send + Sync + 'static,
+{
+    injector()?.try_resolve::<T>()
+}
+
+fn init_in(cell: &OnceLock<Injector>, injector: Injector) -> Result<(), Error> {
+    cell.set(injector)
+        .map_err(|_| Error::invalid_scope("the global injector is already initialized"))
+}
+
+fn injector_in(cell: &OnceLock<Injector>) -> Result<&Injector, Error> {
+    cell.get()
+        .ok_or_else(|| Error::invalid_scope("the global injector is not initialized"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind, Provider};
+
+    // The process-wide cell is shared by every test, so these use their own.
+
+    fn configured() -> Injector {
+        let injector = Injector::root();
+        injector.provide::<u16>(Provider::root(|_| Shared::new(8080)));
+        injector
+    }
+
+    #[test]
+    fn test_init_then_resolve() {
+        let cell = OnceLock::new();
+        init_in(&cell, configured()).unwrap();
+
+        let injector = injector_in(&cell).unwrap();
+        assert_eq!(*injector.resolve::<u16>(), 8080);
+        assert!(Shared::ptr_eq(
+            &injector.resolve::<u16>(),
+            &injector_in(&cell).unwrap().resolve::<u16>()
+        ));
+    }
+
+    #[test]
+    fn test_resolve_before_init_fails() {
+        let cell = OnceLock::new();
+
+        let Err(err) = injector_in(&cell) else {
+            panic!("found a global injector that was never installed");
+        };
+
+        assert_eq!(err.kind, ErrorKind::InvalidScope);
+        assert!(err.message.contains("not initialized"));
+    }
+
+    #[test]
+    fn test_double_init_fails_and_keeps_the_first() {
+        let cell = OnceLock::new();
+        init_in(&cell, configured()).unwrap();
+
+        let Err(err) = init_in(&cell, Injector::root()) else {
+            panic!("replaced the global injector");
+        };
+
+        assert_eq!(err.kind, ErrorKind::InvalidScope);
+        assert!(err.message.contains("already initialized"));
+        assert!(injector_in(&cell).unwrap().contains::<u16>());
+    }
+}
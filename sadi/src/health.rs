@@ -0,0 +1,173 @@
+//! Readiness checks over the singletons an injector has already built.
+//!
+//! A service that can tell whether it is usable, such as a connection pool
+//! that can ping its database, implements [`HealthCheck`] and opts in by
+//! [marking](crate::Provider::mark) its provider with `dyn HealthCheck`.
+//! [`Injector::health_check_all`](crate::Injector::health_check_all) then asks
+//! every such singleton that is currently cached and reports one
+//! [`HealthStatus`] per service, which is what a readiness probe needs.
+//!
+//! Only cached instances are checked: a service nobody has resolved yet has
+//! nothing to report, and a probe should not be the thing that constructs it.
+//!
+//! The checks are plain futures and need no particular runtime, so the
+//! `async` feature is not required.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{HealthCheck, HealthFuture, HealthStatus, Injector, Provider, Shared};
+//!
+//! struct Database {
+//!     connected: bool,
+//! }
+//!
+//! impl HealthCheck for Database {
+//!     fn check(&self) -> HealthFuture<'_> {
+//!         Box::pin(async move {
+//!             if self.connected {
+//!                 HealthStatus::Healthy
+//!             } else {
+//!                 HealthStatus::unhealthy("connection lost")
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Database>(
+//!     Provider::root(|_| Shared::new(Database { connected: true }))
+//!         .mark::<dyn HealthCheck, _>(|database| database as Shared<dyn HealthCheck>),
+//! );
+//! injector.resolve::<Database>();
+//!
+//! let report = futures::executor::block_on(injector.health_check_all());
+//! assert_eq!(report, [(std::any::type_name::<Database>(), HealthStatus::Healthy)]);
+//! ```
+
+use std::{future::Future, pin::Pin};
+
+/// The boxed future returned by [`HealthCheck::check`].
+#[cfg(not(feature = "thread-safe"))]
+pub type HealthFuture<'a> = Pin<Box<dyn Future<Output = HealthStatus> + 'a>>;
+
+/// The boxed future returned by [`HealthCheck::check`] (thread-safe variant).
+#[cfg(feature = "thread-safe")]
+pub type HealthFuture<'a> = Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>>;
+
+/// The outcome of a single [`HealthCheck`].
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum HealthStatus {
+    /// The service is ready to serve.
+    Healthy,
+    /// The service is not ready, for the given reason.
+    Unhealthy(String),
+}
+
+impl HealthStatus {
+    /// Creates an [`Unhealthy`](HealthStatus::Unhealthy) status with `reason`.
+    pub fn unhealthy(reason: impl Into<String>) -> Self {
+        HealthStatus::Unhealthy(reason.into())
+    }
+
+    /// Returns `true` for [`Healthy`](HealthStatus::Healthy).
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, HealthStatus::Healthy)
+    }
+}
+
+/// A service that can report whether it is ready.
+///
+/// The check returns a boxed future so that the trait stays object-safe and
+/// services of different types can be checked through `dyn HealthCheck`.
+#[cfg(not(feature = "thread-safe"))]
+pub trait HealthCheck {
+    /// Checks the service.
+    fn check(&self) -> HealthFuture<'_>;
+}
+
+/// A service that can report whether it is ready (thread-safe variant).
+///
+/// The check returns a boxed future so that the trait stays object-safe and
+/// services of different types can be checked through `dyn HealthCheck`.
+#[cfg(feature = "thread-safe")]
+pub trait HealthCheck: Send + Sync {
+    /// Checks the service.
+    fn check(&self) -> HealthFuture<'_>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Injector, Provider, Shared};
+
+    struct Cache;
+
+    impl HealthCheck for Cache {
+        fn check(&self) -> HealthFuture<'_> {
+            Box::pin(async { HealthStatus::Healthy })
+        }
+    }
+
+    struct Database;
+
+    impl HealthCheck for Database {
+        fn check(&self) -> HealthFuture<'_> {
+            Box::pin(async { HealthStatus::unhealthy("connection refused") })
+        }
+    }
+
+    struct Mailer;
+
+    impl HealthCheck for Mailer {
+        fn check(&self) -> HealthFuture<'_> {
+            unreachable!("checked a service that was never resolved")
+        }
+    }
+
+    fn checked_injector() -> Injector {
+        let injector = Injector::root();
+        injector.provide::<Cache>(
+            Provider::root(|_| Shared::new(Cache))
+                .mark::<dyn HealthCheck, _>(|cache| cache as Shared<dyn HealthCheck>),
+        );
+        injector.provide::<Database>(
+            Provider::root(|_| Shared::new(Database))
+                .mark::<dyn HealthCheck, _>(|database| database as Shared<dyn HealthCheck>),
+        );
+        injector.provide::<Mailer>(
+            Provider::root(|_| Shared::new(Mailer))
+                .mark::<dyn HealthCheck, _>(|mailer| mailer as Shared<dyn HealthCheck>),
+        );
+        injector
+    }
+
+    #[test]
+    fn test_health_check_all_reports_every_cached_marked_singleton() {
+        let injector = checked_injector();
+        injector.resolve::<Cache>();
+        injector.resolve::<Database>();
+
+        let report = futures::executor::block_on(injector.health_check_all());
+
+        assert_eq!(
+            report,
+            [
+                (std::any::type_name::<Cache>(), HealthStatus::Healthy),
+                (
+                    std::any::type_name::<Database>(),
+                    HealthStatus::unhealthy("connection refused")
+                ),
+            ]
+        );
+        assert!(!report.iter().all(|(_, status)| status.is_healthy()));
+    }
+
+    #[test]
+    fn test_health_check_all_skips_uncached_services() {
+        let injector = checked_injector();
+
+        assert!(futures::executor::block_on(injector.health_check_all()).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! Services whose type is inferred rather than named.
+//!
+//! A factory returning `impl Fn(u32) -> u32`, or any other closure, produces a
+//! type that cannot be written down, so it cannot be passed to
+//! [`resolve`](crate::Injector::resolve) as `resolve::<T>()`.
+//! [`Injector::provide_inferred`](crate::Injector::provide_inferred) infers
+//! the type from the factory instead and returns an [`Inferred<T>`] key that
+//! carries it. The key is a zero-sized, copyable value: keep it wherever the
+//! service is needed and resolve through it.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! let injector = Injector::root();
+//! injector.provide::<u32>(Provider::root(|_| Shared::new(40)));
+//!
+//! let add_base = injector.provide_inferred(|inj| {
+//!     let base = *inj.resolve::<u32>();
+//!     move |value: u32| value + base
+//! });
+//!
+//! let add = add_base.resolve(&injector);
+//! assert_eq!(add(2), 42);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::error::Error;
+use crate::injector::Injector;
+use crate::runtime::Shared;
+
+/// A key naming the service registered by [`Injector::provide_inferred`](crate::Injector::provide_inferred).
+pub struct Inferred<T: 'static> {
+    _service: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Inferred<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            _service: PhantomData,
+        }
+    }
+
+    /// Returns the inferred type's name, as reported by [`std::any::type_name`].
+    pub fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+impl<T: 'static> Clone for Inferred<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for Inferred<T> {}
+
+#[cfg(feature = "debug")]
+impl<T: 'static> std::fmt::Debug for Inferred<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Inferred").field(&self.type_name()).finish()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: 'static> Inferred<T> {
+    /// Resolves the service from `injector`, like [`Injector::try_resolve`].
+    pub fn try_resolve(&self, injector: &Injector) -> Result<Shared<T>, Error> {
+        injector.try_resolve::<T>()
+    }
+
+    /// Resolves the service from `injector`, like [`Injector::resolve`].
+    #[track_caller]
+    pub fn resolve(&self, injector: &Injector) -> Shared<T> {
+        injector.resolve::<T>()
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: Send + Sync + 'static> Inferred<T> {
+    /// Resolves the service from `injector`, like [`Injector::try_resolve`].
+    pub fn try_resolve(&self, injector: &Injector) -> Result<Shared<T>, Error> {
+        injector.try_resolve::<T>()
+    }
+
+    /// Resolves the service from `injector`, like [`Injector::resolve`].
+    #[track_caller]
+    pub fn resolve(&self, injector: &Injector) -> Shared<T> {
+        injector.resolve::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{ErrorKind, Provider};
+
+    fn greeter(greeting: &'static str) -> impl Fn(&str) -> String + Send + Sync {
+        move |name| format!("{greeting}, {name}!")
+    }
+
+    #[test]
+    fn test_closure_factory_is_registered_and_resolved_by_inference() {
+        let built = Shared::new(AtomicUsize::new(0));
+        let injector = Injector::root();
+        injector.provide::<&'static str>(Provider::root(|_| Shared::new("Hello")));
+
+        let counter = built.clone();
+        let greet = injector.provide_inferred(move |inj| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            greeter(*inj.resolve::<&'static str>())
+        });
+
+        let first = greet.resolve(&injector);
+        assert_eq!(first("sadi"), "Hello, sadi!");
+        assert!(Shared::ptr_eq(&first, &greet.resolve(&injector)));
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+
+        let child = Injector::child(Shared::new(injector.clone()));
+        assert!(Shared::ptr_eq(&first, &greet.try_resolve(&child).unwrap()));
+    }
+
+    #[test]
+    fn test_inferred_key_fails_on_an_injector_without_the_service() {
+        let greet = Injector::root().provide_inferred(|_| greeter("Hi"));
+
+        let Err(err) = greet.try_resolve(&Injector::root()) else {
+            panic!("resolved a service registered on another injector");
+        };
+
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+        assert!(greet.type_name().contains("greeter"));
+    }
+}
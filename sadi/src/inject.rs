@@ -0,0 +1,126 @@
+//! Resolution by dependency shape.
+//!
+//! [`Injector::resolve`](crate::Injector::resolve) is keyed by the registered
+//! type `T` and always returns a `Shared<T>`. The [`Inject`] trait instead
+//! describes how a whole field type is obtained from an injector, so a
+//! dependency declared as `Option<Shared<dyn Metrics>>` can be filled with
+//! `Some` when a provider exists and `None` when it does not, through the same
+//! [`Injector::inject`](crate::Injector::inject) call used for required ones.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! struct Metrics;
+//! struct Tracer;
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Metrics>(Provider::singleton(|_| Shared::new(Metrics)));
+//!
+//! let metrics = injector.inject::<Option<Shared<Metrics>>>().unwrap();
+//! let tracer = injector.inject::<Option<Shared<Tracer>>>().unwrap();
+//!
+//! assert!(metrics.is_some());
+//! assert!(tracer.is_none());
+//! ```
+
+use crate::error::Error;
+use crate::injector::Injector;
+use crate::runtime::Shared;
+
+/// A value that can be obtained from an [`Injector`].
+///
+/// Implemented for `Shared<T>`, which requires a provider for `T`, and for
+/// `Option<Shared<T>>`, which resolves to `None` when no provider is
+/// registered.
+pub trait Inject: Sized {
+    /// Obtains the value from `injector`.
+    fn inject(injector: &Injector) -> Result<Self, Error>;
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: ?Sized + 'static> Inject for Shared<T> {
+    fn inject(injector: &Injector) -> Result<Self, Error> {
+        injector.try_resolve::<T>()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: ?Sized + 'static> Inject for Option<Shared<T>> {
+    fn inject(injector: &Injector) -> Result<Self, Error> {
+        if !injector.contains::<T>() {
+            return Ok(None);
+        }
+
+        injector.try_resolve::<T>().map(Some)
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + Send + Sync + 'static> Inject for Shared<T> {
+    fn inject(injector: &Injector) -> Result<Self, Error> {
+        injector.try_resolve::<T>()
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + Send + Sync + 'static> Inject for Option<Shared<T>> {
+    fn inject(injector: &Injector) -> Result<Self, Error> {
+        if !injector.contains::<T>() {
+            return Ok(None);
+        }
+
+        injector.try_resolve::<T>().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::provider::Provider;
+
+    trait Metrics: Send + Sync {
+        fn prefix(&self) -> &'static str;
+    }
+
+    struct StatsdMetrics;
+    impl Metrics for StatsdMetrics {
+        fn prefix(&self) -> &'static str {
+            "statsd"
+        }
+    }
+
+    #[test]
+    fn test_optional_dependency_when_registered() {
+        let injector = Injector::root();
+        injector.provide::<dyn Metrics>(Provider::singleton(|_| {
+            Shared::new(StatsdMetrics) as Shared<dyn Metrics>
+        }));
+
+        let metrics = injector
+            .inject::<Option<Shared<dyn Metrics>>>()
+            .unwrap()
+            .unwrap();
+        assert_eq!(metrics.prefix(), "statsd");
+    }
+
+    #[test]
+    fn test_optional_dependency_when_absent() {
+        let injector = Injector::root();
+
+        let metrics = injector.inject::<Option<Shared<dyn Metrics>>>().unwrap();
+        assert!(metrics.is_none());
+    }
+
+    #[test]
+    fn test_required_dependency_when_absent_errors() {
+        let injector = Injector::root();
+
+        let Err(err) = injector.inject::<Shared<dyn Metrics>>() else {
+            panic!("missing dependency was injected");
+        };
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+    }
+}
@@ -1,53 +1,773 @@
 use std::{
     any::{Any, TypeId},
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
+    marker::PhantomData,
+    panic::Location,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Instant,
 };
 
-use crate::error::Error;
+#[cfg(feature = "async")]
+use crate::async_provider::AsyncProvider;
+use crate::boxed::BoxedFactory;
+use crate::diagnostics::{self, Note, TreeNode};
+use crate::eager::InitReport;
+use crate::erased::{ErasedInstance, MatchingDecorator};
+use crate::error::{Error, ErrorKind};
+use crate::fallback::FallbackChain;
+#[cfg(feature = "ffi")]
+use crate::ffi::{HandleTable, HandleValue};
+use crate::generic::GenericFactory;
+use crate::health::{HealthCheck, HealthStatus};
+use crate::inferred::Inferred;
+use crate::inject::Inject;
 use crate::instance::Instance;
-use crate::provider::Provider;
+use crate::manifest::Manifest;
+use crate::meta::ProviderMeta;
+use crate::module::{DynAsyncModule, LazyModule, Module};
+use crate::params::ParamFactory;
+use crate::pool::{Pool, PooledGuard};
+use crate::profile::Profiles;
+use crate::provider::{Gate, Mark, Provider};
 use crate::resolve_guard::ResolveGuard;
 use crate::runtime::{Shared, Store};
 use crate::scope::Scope;
+use crate::scoped::ScopedShared;
+use crate::stats::InjectorStats;
+#[cfg(feature = "tracing")]
+use crate::store::trace_eviction;
+use crate::store::{CachedInstance, EvictionReason, HashMapStore, SingletonStore};
+use crate::tagged::Tagged;
+use crate::tenant::TenantScope;
+#[cfg(feature = "timing")]
+use crate::timing::ResolveTiming;
+use crate::trace::ResolutionTrace;
+
+#[cfg(feature = "async")]
+use tokio::sync::OnceCell;
+#[cfg(all(feature = "tracing", feature = "async"))]
+use tracing::Instrument;
+#[cfg(feature = "tracing")]
+use tracing::debug;
+
+#[cfg(not(feature = "thread-safe"))]
+use std::rc::Weak;
+#[cfg(feature = "thread-safe")]
+use std::sync::Weak;
+#[cfg(feature = "thread-safe")]
+use std::{sync::mpsc::RecvTimeoutError, time::Duration};
+
+thread_local! {
+    /// Set while this thread loads a lazy module, which was declared before any freeze.
+    static LOADING_LAZY: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Marks the current thread as loading a lazy module until dropped.
+struct LazyLoading(bool);
+
+impl LazyLoading {
+    fn enter() -> Self {
+        Self(LOADING_LAZY.replace(true))
+    }
+}
+
+impl Drop for LazyLoading {
+    fn drop(&mut self) {
+        LOADING_LAZY.set(self.0);
+    }
+}
 
 pub struct Injector {
     inner: Shared<InjectorInner>,
 }
 
+/// A handle to an [`Injector`] that does not keep it alive.
+///
+/// A service that needs to resolve lazily, long after its own factory ran,
+/// should keep one of these rather than a clone of the injector. A strong
+/// handle stored in a singleton forms a cycle, since the injector caches the
+/// singleton, and neither is ever dropped. Obtain one with
+/// [`Injector::weak`] or by resolving `WeakInjector`, which every injector
+/// serves without a registration, and call [`upgrade`](WeakInjector::upgrade)
+/// when the injector is needed.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{Injector, Provider, Shared, WeakInjector};
+///
+/// struct Config {
+///     name: String,
+/// }
+///
+/// struct Reporter {
+///     injector: WeakInjector,
+/// }
+///
+/// impl Reporter {
+///     fn config_name(&self) -> Option<String> {
+///         let injector = self.injector.upgrade()?;
+///         Some(injector.resolve::<Config>().name.clone())
+///     }
+/// }
+///
+/// let injector = Injector::root();
+/// injector.provide::<Config>(Provider::root(|_| {
+///     Shared::new(Config { name: "app".to_string() })
+/// }));
+/// injector.provide::<Reporter>(Provider::root(|inj| {
+///     Shared::new(Reporter {
+///         injector: (*inj.resolve::<WeakInjector>()).clone(),
+///     })
+/// }));
+///
+/// let reporter = injector.resolve::<Reporter>();
+/// assert_eq!(reporter.config_name().as_deref(), Some("app"));
+///
+/// drop(injector);
+/// assert_eq!(reporter.config_name(), None);
+/// ```
+#[derive(Clone)]
+pub struct WeakInjector {
+    inner: Weak<InjectorInner>,
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for WeakInjector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakInjector")
+            .field("alive", &(self.inner.strong_count() > 0))
+            .finish()
+    }
+}
+
+impl WeakInjector {
+    /// Returns the injector if it is still alive.
+    pub fn upgrade(&self) -> Option<Injector> {
+        self.inner.upgrade().map(|inner| Injector { inner })
+    }
+}
+
 struct InjectorInner {
     pub(crate) parent: Option<Shared<InjectorInner>>,
 
+    pub(crate) diagnostics: AtomicBool,
+
+    /// Set on the root once any gated provider is registered in the tree.
+    pub(crate) gated: AtomicBool,
+
+    /// Set on the root once any interceptor is installed in the tree.
+    pub(crate) intercepting: AtomicBool,
+
+    /// Set on the root once any matching decorator is added in the tree.
+    pub(crate) decorating: AtomicBool,
+
+    /// Set on the root once any lazy module is registered in the tree.
+    pub(crate) deferring: AtomicBool,
+
+    /// Set by [`Injector::freeze`]; rejects further registrations here.
+    pub(crate) frozen: AtomicBool,
+
+    /// Set on the root by [`Injector::shutdown`]; rejects further resolves in the tree.
+    pub(crate) shutting_down: AtomicBool,
+
+    pub(crate) track_resolutions: AtomicBool,
+
+    pub(crate) resolution_stats: Store<HashMap<&'static str, usize>>,
+
+    /// Receives every resolve once [`Injector::record_resolutions`] is called.
+    pub(crate) trace: Store<Option<ResolutionTrace>>,
+
+    #[cfg(feature = "timing")]
+    pub(crate) timings: Store<HashMap<&'static str, ResolveTiming>>,
+
+    pub(crate) profile: Store<Option<String>>,
+
+    pub(crate) loaded_modules: Store<Vec<&'static str>>,
+
+    /// The module this injector was created to load; see [`Injector::resolve_with_meta`].
+    pub(crate) module: Option<&'static str>,
+
+    /// Bumped on the root whenever a provider is registered anywhere in the tree.
+    pub(crate) registrations: AtomicUsize,
+
+    /// Types [`Injector::contains`] found unregistered, tagged with the root's
+    /// `registrations` count at the time. Stale once that count moves on.
+    pub(crate) unregistered: Store<(usize, HashSet<TypeId>)>,
+
+    pub(crate) providers: Store<HashMap<TypeId, ProviderEntry>>,
+
+    pub(crate) keyed: Store<HashMap<TypeId, KeyedRegistry>>,
+
+    #[cfg(all(feature = "async", not(feature = "thread-safe")))]
+    pub(crate) async_providers: Store<HashMap<TypeId, Shared<dyn Any>>>,
+
+    #[cfg(all(feature = "async", feature = "thread-safe"))]
+    pub(crate) async_providers: Store<HashMap<TypeId, Shared<dyn Any + Send + Sync>>>,
+
+    /// Cells that let concurrent async resolves share one factory run; see [`Injector::resolve_async`].
+    #[cfg(all(feature = "async", not(feature = "thread-safe")))]
+    pub(crate) async_inits: Store<HashMap<TypeId, Shared<dyn Any>>>,
+
+    #[cfg(all(feature = "async", feature = "thread-safe"))]
+    pub(crate) async_inits: Store<HashMap<TypeId, Shared<dyn Any + Send + Sync>>>,
+
+    /// Plain `Copy` values registered with [`Injector::provide_copy`].
+    #[cfg(not(feature = "thread-safe"))]
+    pub(crate) copies: Store<HashMap<TypeId, Shared<dyn Any>>>,
+
+    #[cfg(feature = "thread-safe")]
+    pub(crate) copies: Store<HashMap<TypeId, Shared<dyn Any + Send + Sync>>>,
+
+    /// Resolve-time interceptors, kept on the root; see [`Injector::set_interceptor`].
     #[cfg(not(feature = "thread-safe"))]
-    pub(crate) providers: Store<HashMap<TypeId, Shared<dyn Any>>>,
+    pub(crate) interceptors: Store<HashMap<TypeId, Shared<dyn Any>>>,
+
+    #[cfg(feature = "thread-safe")]
+    pub(crate) interceptors: Store<HashMap<TypeId, Shared<dyn Any + Send + Sync>>>,
+
+    /// Decorators selected by type name, kept on the root; see [`Injector::decorate_matching`].
+    pub(crate) matching_decorators: Store<Vec<Shared<MatchingDecorator>>>,
+
+    /// Modules waiting for their first resolve; see [`Injector::provide_lazy_module`].
+    pub(crate) lazy_modules: Store<Vec<Shared<LazyModule>>>,
+
+    /// Handles issued by [`Injector::resolve_handle`] and not yet released.
+    #[cfg(feature = "ffi")]
+    pub(crate) handles: HandleTable,
+
+    /// Factories for whole generic families, in registration order; see [`Injector::provide_generic_fn`].
+    pub(crate) generic_factories: Store<Vec<(TypeId, Shared<GenericFactory>)>>,
+
+    /// Cached singletons; see [`Injector::with_store`].
+    ///
+    /// Shared so every scope of one tenant can use the same cache; see [`Injector::tenant_scope`].
+    pub(crate) instances: SingletonCache,
+
+    /// Singleton caches by tenant id, kept on the root; see [`Injector::tenant_scope`].
+    pub(crate) tenants: Store<HashMap<String, SingletonCache>>,
+}
+
+/// An injector's cache of singletons.
+pub(crate) type SingletonCache = Shared<Store<Box<dyn SingletonStore>>>;
+
+/// A dependency declared with [`Provider::depends_on`]: its type id and type name.
+type Dependency = (TypeId, &'static str);
+
+/// A type-erased provider together with the metadata needed to describe it.
+#[derive(Clone)]
+pub(crate) struct ProviderEntry {
+    pub(crate) type_name: &'static str,
+    pub(crate) scope: Scope,
+
     #[cfg(not(feature = "thread-safe"))]
-    pub(crate) instances: Store<HashMap<TypeId, Shared<dyn Any>>>,
+    pub(crate) provider: Shared<dyn Any>,
 
     #[cfg(feature = "thread-safe")]
-    pub(crate) providers: Store<HashMap<TypeId, Shared<dyn Any + Send + Sync>>>,
+    pub(crate) provider: Shared<dyn Any + Send + Sync>,
+
+    pub(crate) gate: Option<Gate>,
+    pub(crate) priority: i32,
+    pub(crate) size: Option<usize>,
+    pub(crate) eager: bool,
+
+    /// Declared with [`Provider::depends_on`]; see [`Injector::check_cycles`].
+    pub(crate) dependencies: Vec<Dependency>,
+
+    /// Where the provider was registered; see [`Injector::registration_sites`].
+    pub(crate) site: &'static Location<'static>,
+
+    /// The module that registered the provider; see [`Injector::resolve_with_meta`].
+    pub(crate) module: Option<&'static str>,
+
+    /// Resolves the registered type without naming it; see [`Injector::resolve_erased`].
+    pub(crate) resolve_erased: ErasedResolver,
+
+    /// Declared with [`Provider::mark`]; see [`Injector::resolve_marked`].
+    pub(crate) marks: Vec<Mark>,
+
+    /// Reads the strong count of a cached instance; see [`Injector::shared_counts`].
+    pub(crate) strong_count: fn(&dyn Any) -> Option<usize>,
+}
+
+/// The span a factory for `T` runs in, so the events it logs nest under the resolve.
+#[cfg(feature = "tracing")]
+fn resolve_span<T: ?Sized>() -> tracing::Span {
+    tracing::debug_span!("resolve", service = std::any::type_name::<T>())
+}
+
+/// A resolve-time hook registered with [`Injector::set_interceptor`].
+#[cfg(not(feature = "thread-safe"))]
+type Interceptor<T> = Box<dyn Fn(Shared<T>) -> Shared<T>>;
+
+#[cfg(feature = "thread-safe")]
+type Interceptor<T> = Box<dyn Fn(Shared<T>) -> Shared<T> + Send + Sync>;
+
+#[cfg(not(feature = "thread-safe"))]
+type ErasedResolver = fn(&Injector) -> Result<Shared<dyn Any>, Error>;
+
+#[cfg(feature = "thread-safe")]
+type ErasedResolver = fn(&Injector) -> Result<Shared<dyn Any + Send + Sync>, Error>;
+
+#[cfg(not(feature = "thread-safe"))]
+impl ProviderEntry {
+    #[track_caller]
+    fn new<T: ?Sized + 'static>(provider: Provider<T>) -> Self {
+        Self {
+            site: Location::caller(),
+            module: None,
+            type_name: std::any::type_name::<T>(),
+            scope: provider.scope,
+            gate: provider.gate.clone(),
+            priority: provider.priority,
+            size: provider.size,
+            eager: provider.eager,
+            dependencies: provider.dependencies.clone(),
+            marks: provider.marks.clone(),
+            provider: Shared::new(provider),
+            strong_count: |instance| {
+                instance
+                    .downcast_ref::<Instance<T>>()
+                    .map(Instance::strong_count)
+            },
+            resolve_erased: |injector| {
+                let value = injector.try_resolve::<T>()?;
+                Ok(Shared::new(Instance::new(value)) as Shared<dyn Any>)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl ProviderEntry {
+    #[track_caller]
+    fn new<T: ?Sized + Send + Sync + 'static>(provider: Provider<T>) -> Self {
+        Self {
+            site: Location::caller(),
+            module: None,
+            type_name: std::any::type_name::<T>(),
+            scope: provider.scope,
+            gate: provider.gate.clone(),
+            priority: provider.priority,
+            size: provider.size,
+            eager: provider.eager,
+            dependencies: provider.dependencies.clone(),
+            marks: provider.marks.clone(),
+            provider: Shared::new(provider),
+            strong_count: |instance| {
+                instance
+                    .downcast_ref::<Instance<T>>()
+                    .map(Instance::strong_count)
+            },
+            resolve_erased: |injector| {
+                let value = injector.try_resolve::<T>()?;
+                Ok(Shared::new(Instance::new(value)) as Shared<dyn Any + Send + Sync>)
+            },
+        }
+    }
+}
+
+/// Providers of `T` registered under runtime keys of type `K`, ordered by key.
+type KeyedMap<T, K> = BTreeMap<K, Shared<KeyedEntry<T>>>;
+
+/// One provider registered with [`Injector::provide_keyed`].
+pub(crate) struct KeyedEntry<T: ?Sized + 'static> {
+    scope: Scope,
+    provider: Shared<Provider<T>>,
+    instance: Store<Option<Shared<T>>>,
+}
+
+/// The keyed providers for one `(T, K)` pair, with both types erased.
+#[derive(Clone)]
+pub(crate) struct KeyedRegistry {
+    #[cfg(not(feature = "thread-safe"))]
+    map: Shared<dyn Any>,
+
     #[cfg(feature = "thread-safe")]
-    pub(crate) instances: Store<HashMap<TypeId, Shared<dyn Any + Send + Sync>>>,
+    map: Shared<dyn Any + Send + Sync>,
+
+    /// Copies the registrations with empty instance caches; see [`Injector::deep_copy`].
+    fresh: fn(&KeyedRegistry) -> KeyedRegistry,
 }
 
-#[cfg(feature = "debug")]
-impl std::fmt::Debug for InjectorInner {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("InjectorInner")
-            .field("parent", &self.parent.is_some())
-            .field("providers", &self.providers)
-            .field("instances", &self.instances)
-            .finish()
+#[cfg(not(feature = "thread-safe"))]
+impl<T: ?Sized + 'static> KeyedEntry<T> {
+    /// Returns the instance, or `None` if the provider's gate is closed.
+    fn resolve(&self, injector: &Injector) -> Option<Shared<T>> {
+        if let Some(gate) = &self.provider.gate
+            && !gate(injector)
+        {
+            return None;
+        }
+
+        if let Some(instance) = self.instance.borrow().clone() {
+            return Some(instance);
+        }
+
+        let value = (self.provider.factory)(injector).value();
+        if self.scope != Scope::Transient {
+            *self.instance.borrow_mut() = Some(value.clone());
+        }
+
+        Some(value)
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl KeyedRegistry {
+    fn new<T: ?Sized + 'static, K: Ord + Clone + 'static>() -> Self {
+        Self {
+            map: Shared::new(Store::new(KeyedMap::<T, K>::new())),
+            fresh: |registry| {
+                let Some(map) = registry.map::<T, K>() else {
+                    return registry.clone();
+                };
+
+                let copy: KeyedMap<T, K> = map
+                    .borrow()
+                    .iter()
+                    .map(|(key, entry)| {
+                        let entry = KeyedEntry {
+                            scope: entry.scope,
+                            provider: entry.provider.clone(),
+                            instance: Store::new(None),
+                        };
+                        (key.clone(), Shared::new(entry))
+                    })
+                    .collect();
+
+                Self {
+                    map: Shared::new(Store::new(copy)),
+                    fresh: registry.fresh,
+                }
+            },
+        }
+    }
+
+    fn map<T: ?Sized + 'static, K: Ord + 'static>(&self) -> Option<Shared<Store<KeyedMap<T, K>>>> {
+        self.map.clone().downcast::<Store<KeyedMap<T, K>>>().ok()
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + Send + Sync + 'static> KeyedEntry<T> {
+    /// Returns the instance, or `None` if the provider's gate is closed.
+    fn resolve(&self, injector: &Injector) -> Option<Shared<T>> {
+        if let Some(gate) = &self.provider.gate
+            && !gate(injector)
+        {
+            return None;
+        }
+
+        if let Some(instance) = self.instance.read().unwrap().clone() {
+            return Some(instance);
+        }
+
+        let value = (self.provider.factory)(injector).value();
+        if self.scope != Scope::Transient {
+            *self.instance.write().unwrap() = Some(value.clone());
+        }
+
+        Some(value)
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl KeyedRegistry {
+    fn new<T: ?Sized + Send + Sync + 'static, K: Ord + Clone + Send + Sync + 'static>() -> Self {
+        Self {
+            map: Shared::new(Store::new(KeyedMap::<T, K>::new())),
+            fresh: |registry| {
+                let Some(map) = registry.map::<T, K>() else {
+                    return registry.clone();
+                };
+
+                let copy: KeyedMap<T, K> = map
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|(key, entry)| {
+                        let entry = KeyedEntry {
+                            scope: entry.scope,
+                            provider: entry.provider.clone(),
+                            instance: Store::new(None),
+                        };
+                        (key.clone(), Shared::new(entry))
+                    })
+                    .collect();
+
+                Self {
+                    map: Shared::new(Store::new(copy)),
+                    fresh: registry.fresh,
+                }
+            },
+        }
+    }
+
+    fn map<T: ?Sized + Send + Sync + 'static, K: Ord + Send + Sync + 'static>(
+        &self,
+    ) -> Option<Shared<Store<KeyedMap<T, K>>>> {
+        self.map.clone().downcast::<Store<KeyedMap<T, K>>>().ok()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl InjectorInner {
+    fn deep_copy(&self) -> Self {
+        Self {
+            parent: self
+                .parent
+                .as_ref()
+                .map(|parent| Shared::new(parent.deep_copy())),
+            diagnostics: AtomicBool::new(self.diagnostics.load(Ordering::Relaxed)),
+            gated: AtomicBool::new(self.gated.load(Ordering::Relaxed)),
+            intercepting: AtomicBool::new(self.intercepting.load(Ordering::Relaxed)),
+            decorating: AtomicBool::new(self.decorating.load(Ordering::Relaxed)),
+            deferring: AtomicBool::new(self.deferring.load(Ordering::Relaxed)),
+            frozen: AtomicBool::new(self.frozen.load(Ordering::Acquire)),
+            shutting_down: AtomicBool::new(false),
+            track_resolutions: AtomicBool::new(self.track_resolutions.load(Ordering::Relaxed)),
+            resolution_stats: Store::new(HashMap::new()),
+            trace: Store::new(None),
+            #[cfg(feature = "timing")]
+            timings: Store::new(HashMap::new()),
+            profile: Store::new(self.profile.borrow().clone()),
+            loaded_modules: Store::new(self.loaded_modules.borrow().clone()),
+            module: self.module,
+            registrations: AtomicUsize::new(self.registrations.load(Ordering::Acquire)),
+            unregistered: Store::new((0, HashSet::new())),
+            providers: Store::new(self.providers.borrow().clone()),
+            keyed: Store::new(
+                self.keyed
+                    .borrow()
+                    .iter()
+                    .map(|(type_id, registry)| (*type_id, (registry.fresh)(registry)))
+                    .collect(),
+            ),
+            #[cfg(feature = "async")]
+            async_providers: Store::new(self.async_providers.borrow().clone()),
+            #[cfg(feature = "async")]
+            async_inits: Store::new(HashMap::new()),
+            copies: Store::new(self.copies.borrow().clone()),
+            interceptors: Store::new(self.interceptors.borrow().clone()),
+            matching_decorators: Store::new(self.matching_decorators.borrow().clone()),
+            // A module not loaded yet here must still load into the copy.
+            lazy_modules: Store::new(
+                self.lazy_modules
+                    .borrow()
+                    .iter()
+                    .map(|lazy| Shared::new(LazyModule::clone(lazy)))
+                    .collect(),
+            ),
+            generic_factories: Store::new(self.generic_factories.borrow().clone()),
+            #[cfg(feature = "ffi")]
+            handles: HandleTable::default(),
+            instances: Shared::new(Store::new(Box::<HashMapStore>::default())),
+            tenants: Store::new(HashMap::new()),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl InjectorInner {
+    fn deep_copy(&self) -> Self {
+        Self {
+            parent: self
+                .parent
+                .as_ref()
+                .map(|parent| Shared::new(parent.deep_copy())),
+            diagnostics: AtomicBool::new(self.diagnostics.load(Ordering::Relaxed)),
+            gated: AtomicBool::new(self.gated.load(Ordering::Relaxed)),
+            intercepting: AtomicBool::new(self.intercepting.load(Ordering::Relaxed)),
+            decorating: AtomicBool::new(self.decorating.load(Ordering::Relaxed)),
+            deferring: AtomicBool::new(self.deferring.load(Ordering::Relaxed)),
+            frozen: AtomicBool::new(self.frozen.load(Ordering::Acquire)),
+            shutting_down: AtomicBool::new(false),
+            track_resolutions: AtomicBool::new(self.track_resolutions.load(Ordering::Relaxed)),
+            resolution_stats: Store::new(HashMap::new()),
+            trace: Store::new(None),
+            #[cfg(feature = "timing")]
+            timings: Store::new(HashMap::new()),
+            profile: Store::new(self.profile.read().unwrap().clone()),
+            loaded_modules: Store::new(self.loaded_modules.read().unwrap().clone()),
+            module: self.module,
+            registrations: AtomicUsize::new(self.registrations.load(Ordering::Acquire)),
+            unregistered: Store::new((0, HashSet::new())),
+            providers: Store::new(self.providers.read().unwrap().clone()),
+            keyed: Store::new(
+                self.keyed
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|(type_id, registry)| (*type_id, (registry.fresh)(registry)))
+                    .collect(),
+            ),
+            #[cfg(feature = "async")]
+            async_providers: Store::new(self.async_providers.read().unwrap().clone()),
+            #[cfg(feature = "async")]
+            async_inits: Store::new(HashMap::new()),
+            copies: Store::new(self.copies.read().unwrap().clone()),
+            interceptors: Store::new(self.interceptors.read().unwrap().clone()),
+            matching_decorators: Store::new(self.matching_decorators.read().unwrap().clone()),
+            // A module not loaded yet here must still load into the copy.
+            lazy_modules: Store::new(
+                self.lazy_modules
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|lazy| Shared::new(LazyModule::clone(lazy)))
+                    .collect(),
+            ),
+            generic_factories: Store::new(self.generic_factories.read().unwrap().clone()),
+            #[cfg(feature = "ffi")]
+            handles: HandleTable::default(),
+            instances: Shared::new(Store::new(Box::<HashMapStore>::default())),
+            tenants: Store::new(HashMap::new()),
+        }
+    }
+}
+
+impl InjectorInner {
+    fn ensure_unfrozen(&self) -> Result<(), Error> {
+        if self.frozen.load(Ordering::Acquire) && !LOADING_LAZY.get() {
+            return Err(Error::invalid_scope("injector is frozen"));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the registered name of `type_id`, as seen from here, for eviction events.
+    #[cfg(feature = "tracing")]
+    fn type_name_of(&self, type_id: TypeId) -> String {
+        let mut current = Some(self);
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let providers = inner.providers.borrow();
+            #[cfg(feature = "thread-safe")]
+            let providers = inner.providers.read().unwrap();
+
+            if let Some(entry) = providers.get(&type_id) {
+                return entry.type_name.to_string();
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        // Generic families cache types that have no registration.
+        format!("{:?}", type_id)
+    }
+}
+
+#[cfg(feature = "ffi")]
+impl Injector {
+    /// Resolves the registration named `type_name` and returns a handle to the instance.
+    ///
+    /// `type_name` is matched against the names [`std::any::type_name`]
+    /// reports, such as `"my_app::Config"`, the nearest registration winning.
+    /// The handle keeps a strong reference to the instance until it is passed
+    /// to [`release_handle`](Injector::release_handle). See the
+    /// [`ffi`](crate::ffi) module for the ownership rules.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided)
+    /// if no registration has that name, plus any error from resolving it.
+    pub fn try_resolve_handle(&self, type_name: &str) -> Result<u64, Error> {
+        let type_id = self
+            .type_id_named(type_name)
+            .ok_or_else(|| Error::service_not_provided(type_name))?;
+        let value = self.resolve_erased(type_id)?;
+
+        Ok(self.inner.handles.insert(value))
+    }
+
+    /// Resolves the registration named `type_name` into a handle, returning `0` on failure.
+    ///
+    /// Unlike most non-`try` methods this does not panic, so it is safe to
+    /// call from a function exposed over a C ABI, where unwinding must not
+    /// cross the boundary. See [`try_resolve_handle`](Injector::try_resolve_handle).
+    pub fn resolve_handle(&self, type_name: &str) -> u64 {
+        self.try_resolve_handle(type_name).unwrap_or(0)
+    }
+
+    /// Returns the instance behind `handle`, wrapped in an [`Instance`].
+    ///
+    /// Downcast it as with [`resolve_erased`](Injector::resolve_erased).
+    /// Returns `None` if `handle` was not issued by this injector or has been
+    /// released.
+    pub fn handle(&self, handle: u64) -> Option<HandleValue> {
+        self.inner.handles.get(handle)
+    }
+
+    /// Releases `handle`, dropping the reference it held.
+    ///
+    /// Returns `false` if `handle` was not issued by this injector or has
+    /// already been released.
+    pub fn release_handle(&self, handle: u64) -> bool {
+        self.inner.handles.remove(handle).is_some()
+    }
+
+    /// Finds the nearest registration whose type name is `type_name`.
+    fn type_id_named(&self, type_name: &str) -> Option<TypeId> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let providers = inner.providers.borrow();
+            #[cfg(feature = "thread-safe")]
+            let providers = inner.providers.read().unwrap();
+
+            let found = providers
+                .iter()
+                .find(|(_, entry)| entry.type_name == type_name)
+                .map(|(type_id, _)| *type_id);
+            if found.is_some() {
+                return found;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
     }
 }
 
 #[cfg(feature = "debug")]
 impl std::fmt::Debug for Injector {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        #[cfg(not(feature = "thread-safe"))]
+        let (providers, instances) = (self.inner.providers.borrow(), self.inner.instances.borrow());
+        #[cfg(feature = "thread-safe")]
+        let (providers, instances) = (
+            self.inner.providers.read().unwrap(),
+            self.inner.instances.read().unwrap(),
+        );
+
+        let count = |scope: Scope| {
+            providers
+                .values()
+                .filter(|entry| entry.scope == scope)
+                .count()
+        };
+
+        let mut types: Vec<&str> = providers.values().map(|entry| entry.type_name).collect();
+        types.sort_unstable();
+
         f.debug_struct(std::any::type_name::<Self>())
-            .field("inner", &self.inner)
+            .field("parent", &self.inner.parent.is_some())
+            .field("diagnostics", &self.inner.diagnostics)
+            .field("registrations", &providers.len())
+            .field("root", &count(Scope::Root))
+            .field("module", &count(Scope::Module))
+            .field("transient", &count(Scope::Transient))
+            .field("instances", &instances.len())
+            .field("types", &types)
             .finish()
     }
 }
 
+/// Cloning an injector is cheap and shares its state; see [`Injector::share`].
 impl Clone for Injector {
     fn clone(&self) -> Self {
         Self {
@@ -61,22 +781,257 @@ impl Injector {
         Self {
             inner: Shared::new(InjectorInner {
                 parent: None,
+                diagnostics: AtomicBool::new(false),
+                gated: AtomicBool::new(false),
+                intercepting: AtomicBool::new(false),
+                decorating: AtomicBool::new(false),
+                deferring: AtomicBool::new(false),
+                frozen: AtomicBool::new(false),
+                shutting_down: AtomicBool::new(false),
+                track_resolutions: AtomicBool::new(false),
+                resolution_stats: Store::new(HashMap::new()),
+                trace: Store::new(None),
+                #[cfg(feature = "timing")]
+                timings: Store::new(HashMap::new()),
+                profile: Store::new(None),
+                loaded_modules: Store::new(Vec::new()),
+                module: None,
+                registrations: AtomicUsize::new(0),
+                unregistered: Store::new((0, HashSet::new())),
                 providers: Store::new(HashMap::new()),
-                instances: Store::new(HashMap::new()),
+                keyed: Store::new(HashMap::new()),
+                #[cfg(feature = "async")]
+                async_providers: Store::new(HashMap::new()),
+                #[cfg(feature = "async")]
+                async_inits: Store::new(HashMap::new()),
+                copies: Store::new(HashMap::new()),
+                interceptors: Store::new(HashMap::new()),
+                matching_decorators: Store::new(Vec::new()),
+                lazy_modules: Store::new(Vec::new()),
+                generic_factories: Store::new(Vec::new()),
+                #[cfg(feature = "ffi")]
+                handles: HandleTable::default(),
+                instances: Shared::new(Store::new(Box::<HashMapStore>::default())),
+                tenants: Store::new(HashMap::new()),
             }),
         }
     }
 
     pub fn child(parent: Shared<Injector>) -> Self {
+        Self::child_in(parent, None)
+    }
+
+    /// Creates a child injector, recording the module it loads if any.
+    fn child_in(parent: Shared<Injector>, module: Option<&'static str>) -> Self {
         Self {
             inner: Shared::new(InjectorInner {
                 parent: Some(parent.inner.clone()),
+                diagnostics: AtomicBool::new(false),
+                gated: AtomicBool::new(false),
+                intercepting: AtomicBool::new(false),
+                decorating: AtomicBool::new(false),
+                deferring: AtomicBool::new(false),
+                frozen: AtomicBool::new(false),
+                shutting_down: AtomicBool::new(false),
+                track_resolutions: AtomicBool::new(false),
+                resolution_stats: Store::new(HashMap::new()),
+                trace: Store::new(None),
+                #[cfg(feature = "timing")]
+                timings: Store::new(HashMap::new()),
+                profile: Store::new(None),
+                loaded_modules: Store::new(Vec::new()),
+                module,
+                registrations: AtomicUsize::new(0),
+                unregistered: Store::new((0, HashSet::new())),
                 providers: Store::new(HashMap::new()),
-                instances: Store::new(HashMap::new()),
+                keyed: Store::new(HashMap::new()),
+                #[cfg(feature = "async")]
+                async_providers: Store::new(HashMap::new()),
+                #[cfg(feature = "async")]
+                async_inits: Store::new(HashMap::new()),
+                copies: Store::new(HashMap::new()),
+                interceptors: Store::new(HashMap::new()),
+                matching_decorators: Store::new(Vec::new()),
+                lazy_modules: Store::new(Vec::new()),
+                generic_factories: Store::new(Vec::new()),
+                #[cfg(feature = "ffi")]
+                handles: HandleTable::default(),
+                instances: Shared::new(Store::new(Box::<HashMapStore>::default())),
+                tenants: Store::new(HashMap::new()),
             }),
         }
     }
 
+    /// Returns a handle to the same injector.
+    ///
+    /// The handle shares registrations, cached instances and settings with
+    /// `self`: a provider registered through either one is visible to both.
+    /// This is what [`Clone`] does, spelled out for call sites where an
+    /// independent copy might otherwise be assumed.
+    pub fn share(&self) -> Injector {
+        self.clone()
+    }
+
+    /// Returns an independent copy of this injector and its ancestors.
+    ///
+    /// The copy starts with the same registrations and settings but an empty
+    /// instance cache, so every singleton is created anew on first resolve.
+    /// Registrations made on either injector afterwards are not seen by the
+    /// other. Providers themselves are shared, so factories that capture state
+    /// still see the same captured values.
+    pub fn deep_copy(&self) -> Injector {
+        Injector {
+            inner: Shared::new(self.inner.deep_copy()),
+        }
+    }
+
+    /// Moves this injector's singleton cache into `store`, which keeps it from then on.
+    ///
+    /// Instances already cached are carried over. Only this injector is
+    /// affected: its children, including [tenant scopes](Injector::tenant_scope),
+    /// and copies made with [`deep_copy`](Injector::deep_copy) start with a
+    /// [`HashMapStore`]. See the [`store`](crate::store) module.
+    pub fn with_store(self, store: impl SingletonStore + 'static) -> Self {
+        let mut store: Box<dyn SingletonStore> = Box::new(store);
+
+        #[cfg(not(feature = "thread-safe"))]
+        let mut instances = self.inner.instances.borrow_mut();
+        #[cfg(feature = "thread-safe")]
+        let mut instances = self.inner.instances.write().unwrap();
+
+        for type_id in instances.type_ids() {
+            if let Some(instance) = instances.remove(type_id) {
+                store.insert(type_id, instance);
+            }
+        }
+        *instances = store;
+        drop(instances);
+
+        self
+    }
+
+    /// Returns an injector whose singletons are cached for `tenant` alone.
+    ///
+    /// The scope is a child of the root, so it sees every provider registered
+    /// there. [`singleton`](Provider::singleton) instances it creates are
+    /// cached per tenant and shared by every scope returned for the same id,
+    /// until [`drop_tenant`](Injector::drop_tenant) evicts them; root-scoped
+    /// instances stay shared by all tenants. Each tenant's cache is a
+    /// [`HashMapStore`], whatever store the root was given with
+    /// [`with_store`](Injector::with_store). See the [`tenant`](crate::tenant)
+    /// module.
+    pub fn tenant_scope(&self, tenant: impl Into<String>) -> TenantScope {
+        let tenant = tenant.into();
+        let root = self.root_injector();
+
+        #[cfg(not(feature = "thread-safe"))]
+        let mut tenants = root.inner.tenants.borrow_mut();
+        #[cfg(feature = "thread-safe")]
+        let mut tenants = root.inner.tenants.write().unwrap();
+
+        let cache = tenants
+            .entry(tenant.clone())
+            .or_insert_with(|| Shared::new(Store::new(Box::<HashMapStore>::default())))
+            .clone();
+        drop(tenants);
+
+        let mut injector = Injector::child(Shared::new(root));
+        Shared::get_mut(&mut injector.inner)
+            .expect("a new injector is not shared")
+            .instances = cache;
+
+        TenantScope::new(tenant, injector)
+    }
+
+    /// Evicts the singletons cached for `tenant`, returning `false` if it had none.
+    ///
+    /// The next [`tenant_scope`](Injector::tenant_scope) for `tenant` starts
+    /// with an empty cache. Scopes obtained before keep the evicted cache, and
+    /// handles to its instances stay valid.
+    pub fn drop_tenant(&self, tenant: &str) -> bool {
+        let root = self.root_inner();
+
+        #[cfg(not(feature = "thread-safe"))]
+        let removed = root.tenants.borrow_mut().remove(tenant);
+        #[cfg(feature = "thread-safe")]
+        let removed = root.tenants.write().unwrap().remove(tenant);
+
+        #[cfg(feature = "tracing")]
+        if let Some(cache) = &removed {
+            #[cfg(not(feature = "thread-safe"))]
+            let cache = cache.borrow();
+            #[cfg(feature = "thread-safe")]
+            let cache = cache.read().unwrap();
+
+            for type_id in cache.type_ids() {
+                trace_eviction(&root.type_name_of(type_id), EvictionReason::Manual);
+            }
+        }
+
+        removed.is_some()
+    }
+
+    /// Reports the eviction of `T`'s cached instance, returning whether there was one.
+    #[cfg_attr(
+        not(feature = "tracing"),
+        allow(unused_variables, clippy::extra_unused_type_parameters)
+    )]
+    fn evicted<T: ?Sized>(instance: Option<CachedInstance>, reason: EvictionReason) -> bool {
+        #[cfg(feature = "tracing")]
+        if instance.is_some() {
+            trace_eviction(std::any::type_name::<T>(), reason);
+        }
+
+        instance.is_some()
+    }
+
+    /// Returns a handle to this injector that does not keep it alive.
+    ///
+    /// See [`WeakInjector`] for why services should store this instead of a
+    /// clone of the injector.
+    pub fn weak(&self) -> WeakInjector {
+        WeakInjector {
+            inner: Shared::downgrade(&self.inner),
+        }
+    }
+
+    /// Serves `resolve::<WeakInjector>()` without a registration.
+    ///
+    /// Returns `None` unless `T` is [`WeakInjector`].
+    fn resolve_weak<T: ?Sized + 'static>(&self) -> Option<Shared<T>> {
+        if TypeId::of::<T>() != TypeId::of::<WeakInjector>() {
+            return None;
+        }
+
+        let handle: Box<dyn Any> = Box::new(Shared::new(self.weak()));
+        handle.downcast::<Shared<T>>().ok().map(|handle| *handle)
+    }
+
+    fn keyed_not_provided<T: ?Sized, K>() -> Error {
+        Error::service_not_provided(&format!(
+            "{} keyed by {}",
+            std::any::type_name::<T>(),
+            std::any::type_name::<K>()
+        ))
+    }
+
+    /// Records that the tree has a gated provider, so resolves start checking gates.
+    fn track_gate<T: ?Sized + 'static>(&self, provider: &Provider<T>) {
+        if provider.gate.is_some() {
+            self.root_inner().gated.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn root_inner(&self) -> &InjectorInner {
+        let mut current = &*self.inner;
+
+        while let Some(parent) = current.parent.as_deref() {
+            current = parent;
+        }
+
+        current
+    }
+
     pub(crate) fn root_injector(&self) -> Injector {
         let mut current = self.clone();
 
@@ -88,354 +1043,8795 @@ impl Injector {
 
         current
     }
-}
 
-#[cfg(not(feature = "thread-safe"))]
-impl Injector {
-    pub fn try_provide<T>(&self, provider: Provider<T>) -> Result<(), Error>
-    where
-        T: ?Sized + 'static,
-    {
-        match provider.scope {
-            Scope::Root => {
-                let root = self.root_injector();
-                root.store_provider::<T>(provider)
-            }
+    /// Enables or disables resolution tree diagnostics for this injector.
+    ///
+    /// While enabled, each top-level resolve emits a single indented tree of
+    /// every type it resolved transitively, annotated with whether the value
+    /// came from the cache or was created. Diagnostics also apply to resolves
+    /// made through child injectors. The tree is logged at debug level, so it
+    /// needs the `tracing` feature; without it nothing is emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.set_diagnostics(true);
+    /// injector.provide::<u32>(Provider::singleton(|_| Shared::new(7)));
+    ///
+    /// // Emits:
+    /// // resolution tree:
+    /// //   u32 [created, Module]
+    /// injector.resolve::<u32>();
+    /// ```
+    pub fn set_diagnostics(&self, enabled: bool) -> &Self {
+        self.inner.diagnostics.store(enabled, Ordering::Relaxed);
+        self
+    }
+
+    /// Enables or disables resolution counting on this injector.
+    ///
+    /// While enabled, every resolve made through this injector, including the
+    /// nested resolves its factories make, increments a per-type counter read
+    /// with [`resolution_stats`](Injector::resolution_stats). Counting is local:
+    /// a child injector used as a request scope only sees its own resolves,
+    /// which makes it easy to flag a type resolved dozens of times per request.
+    /// Disabling keeps the counts collected so far.
+    pub fn set_resolution_stats(&self, enabled: bool) -> &Self {
+        self.inner
+            .track_resolutions
+            .store(enabled, Ordering::Relaxed);
+        self
+    }
+
+    /// Returns `true` if diagnostics are enabled on this injector or any ancestor.
+    pub fn diagnostics_enabled(&self) -> bool {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if inner.diagnostics.load(Ordering::Relaxed) {
+                return true;
+            }
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Locks the registrations of this injector; resolving keeps working.
+    ///
+    /// Once frozen, registering on this injector fails with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope): `provide`, keyed and
+    /// async providers, overrides and decorators of its providers alike.
+    /// Resolves are unaffected and still create and cache singletons. Since
+    /// root-scoped providers are stored on the root, freezing the root rejects
+    /// them even when registered through a child. Children are not frozen
+    /// with their parent, so request scopes can still register overrides.
+    /// A [lazy module](Injector::provide_lazy_module) declared before the
+    /// freeze still registers its providers when it loads.
+    ///
+    /// There is no way to unfreeze an injector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{ErrorKind, Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+    /// injector.freeze();
+    ///
+    /// let err = injector
+    ///     .try_provide::<String>(Provider::root(|_| Shared::new("late".to_string())))
+    ///     .unwrap_err();
+    /// assert!(err.kind == ErrorKind::InvalidScope);
+    /// assert_eq!(*injector.resolve::<u32>(), 7);
+    /// ```
+    pub fn freeze(&self) -> &Self {
+        self.inner.frozen.store(true, Ordering::Release);
+        self
+    }
+
+    /// Returns `true` once [`freeze`](Injector::freeze) was called on this injector.
+    pub fn is_frozen(&self) -> bool {
+        self.inner.frozen.load(Ordering::Acquire)
+    }
+
+    /// Shuts the container down, dropping the singletons cached on this injector and its ancestors.
+    ///
+    /// The per-tenant caches of [tenant scopes](Injector::tenant_scope) are
+    /// dropped as well, including those of scopes still held.
+    ///
+    /// Shutdown applies to the whole tree: from then on every resolve through
+    /// any injector sharing this root fails with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope), saying the container
+    /// is shutting down. The cached instances are taken out of their maps
+    /// before they are dropped, so a `Drop` impl that resolves from a stored
+    /// [`WeakInjector`] gets that error instead of deadlocking on, or panicking
+    /// over, a lock the shutdown still holds. Singletons that are still
+    /// referenced elsewhere live on until their last handle is dropped.
+    ///
+    /// There is no way to undo a shutdown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{ErrorKind, Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+    /// assert_eq!(*injector.resolve::<u32>(), 7);
+    ///
+    /// injector.shutdown();
+    ///
+    /// let err = injector.try_resolve::<u32>().unwrap_err();
+    /// assert!(err.kind == ErrorKind::InvalidScope);
+    /// ```
+    pub fn shutdown(&self) {
+        self.root_inner()
+            .shutting_down
+            .store(true, Ordering::Release);
+
+        let mut current = Some(&*self.inner);
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let mut cache = inner.instances.borrow_mut();
+            #[cfg(feature = "thread-safe")]
+            let mut cache = inner.instances.write().unwrap();
+
+            #[cfg(feature = "tracing")]
+            for type_id in cache.type_ids() {
+                trace_eviction(&inner.type_name_of(type_id), EvictionReason::Shutdown);
+            }
+            let instances = cache.drain();
+            drop(cache);
+
+            // The lock is already released, so the instances' `Drop` impls may resolve.
+            drop(instances);
+            current = inner.parent.as_deref();
+        }
+
+        let root = self.root_inner();
+        #[cfg(not(feature = "thread-safe"))]
+        let tenants = std::mem::take(&mut *root.tenants.borrow_mut());
+        #[cfg(feature = "thread-safe")]
+        let tenants = std::mem::take(&mut *root.tenants.write().unwrap());
+
+        for cache in tenants.into_values() {
+            #[cfg(not(feature = "thread-safe"))]
+            let mut cache = cache.borrow_mut();
+            #[cfg(feature = "thread-safe")]
+            let mut cache = cache.write().unwrap();
+
+            #[cfg(feature = "tracing")]
+            for type_id in cache.type_ids() {
+                trace_eviction(&root.type_name_of(type_id), EvictionReason::Shutdown);
+            }
+            let instances = cache.drain();
+            drop(cache);
+            drop(instances);
+        }
+    }
+
+    /// Returns `true` once [`shutdown`](Injector::shutdown) was called anywhere in this tree.
+    pub fn is_shutting_down(&self) -> bool {
+        self.root_inner().shutting_down.load(Ordering::Acquire)
+    }
+
+    fn ensure_running(&self) -> Result<(), Error> {
+        if self.is_shutting_down() {
+            return Err(Error::invalid_scope("container is shutting down"));
+        }
+
+        Ok(())
+    }
+
+    /// Creates an isolated child injector with overrides applied.
+    ///
+    /// The child shares every provider and cached singleton of `self`, while the
+    /// registrations made through [`override_provider`](Injector::override_provider)
+    /// inside `configure` apply only to resolutions made through the child. This
+    /// makes it cheap to give each test its own fakes on top of a shared,
+    /// fully-built application injector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<String>(Provider::root(|_| Shared::new("real".to_string())));
+    ///
+    /// let test_injector = injector.child_with_overrides(|child| {
+    ///     child.override_provider::<String>(Provider::root(|_| Shared::new("fake".to_string())));
+    /// });
+    ///
+    /// assert_eq!(*test_injector.resolve::<String>(), "fake");
+    /// assert_eq!(*injector.resolve::<String>(), "real");
+    /// ```
+    pub fn child_with_overrides<F>(&self, configure: F) -> Injector
+    where
+        F: FnOnce(&Injector),
+    {
+        let child = Injector::child(Shared::new(self.clone()));
+        configure(&child);
+        child
+    }
+
+    /// Loads a list of modules into this injector.
+    ///
+    /// Each module gets its own child injector, exactly as the root module does
+    /// during [`Application::bootstrap`](crate::Application::bootstrap): imports are
+    /// loaded first, then the module's own providers. Because the list is a plain
+    /// `Vec<Box<dyn Module>>`, it can be assembled at runtime, for example to
+    /// include a debugging module only in development builds.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from a module's
+    /// [`try_providers`](Module::try_providers); modules after it are not loaded.
+    /// Registrations made with [`Injector::provide`] still panic on conflict.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Module};
+    ///
+    /// struct CoreModule;
+    /// impl Module for CoreModule {}
+    ///
+    /// struct DebugModule;
+    /// impl Module for DebugModule {}
+    ///
+    /// let verbose = false;
+    ///
+    /// let mut modules: Vec<Box<dyn Module>> = vec![Box::new(CoreModule)];
+    /// if verbose {
+    ///     modules.push(Box::new(DebugModule));
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.load_modules(modules).unwrap();
+    /// ```
+    pub fn load_modules(&self, modules: Vec<Box<dyn Module>>) -> Result<(), Error> {
+        let parent = Shared::new(self.clone());
+
+        for module in modules {
+            Injector::load_module(parent.clone(), module.as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Registers `module` to be loaded the first time one of `type_ids` is resolved.
+    ///
+    /// Until then none of its providers, nor those of its imports, are
+    /// registered, which keeps rarely used modules out of startup. The first
+    /// resolve of a declared type through this injector or a descendant loads
+    /// the module as if it were passed to [`load_modules`](Injector::load_modules)
+    /// here, then resolves the type as usual; as there, only root-scoped
+    /// providers are visible outside the module. Until it is loaded, the
+    /// module's types are unknown to [`contains`](Injector::contains) and to
+    /// async resolves.
+    ///
+    /// The module loads exactly once, even when several threads resolve its
+    /// types at the same time: the others wait for the first load to finish.
+    /// A failed load is not retried, and every later trigger returns its error.
+    /// The module must not resolve its own declared types while registering.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if this injector is
+    /// [frozen](Injector::freeze).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    ///
+    /// use sadi::{Injector, Module, Provider, Shared};
+    ///
+    /// struct Reports(&'static str);
+    ///
+    /// struct ReportsModule;
+    /// impl Module for ReportsModule {
+    ///     fn providers(&self, injector: &Injector) {
+    ///         injector.provide::<Reports>(Provider::root(|_| Shared::new(Reports("monthly"))));
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector
+    ///     .try_provide_lazy_module(&[TypeId::of::<Reports>()], Box::new(ReportsModule))
+    ///     .unwrap();
+    /// assert!(!injector.contains::<Reports>());
+    ///
+    /// assert_eq!(injector.resolve::<Reports>().0, "monthly");
+    /// assert!(injector.assert_loaded::<ReportsModule>().is_ok());
+    /// ```
+    pub fn try_provide_lazy_module(
+        &self,
+        type_ids: &[TypeId],
+        module: Box<dyn Module>,
+    ) -> Result<(), Error> {
+        self.inner.ensure_unfrozen()?;
+
+        let lazy = Shared::new(LazyModule {
+            type_ids: type_ids.to_vec(),
+            module: Shared::from(module),
+            loaded: std::sync::OnceLock::new(),
+        });
+
+        #[cfg(not(feature = "thread-safe"))]
+        self.inner.lazy_modules.borrow_mut().push(lazy);
+        #[cfg(feature = "thread-safe")]
+        self.inner.lazy_modules.write().unwrap().push(lazy);
+
+        self.root_inner().deferring.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Registers `module` to be loaded on first use, panicking if this injector is frozen.
+    ///
+    /// See [`try_provide_lazy_module`](Injector::try_provide_lazy_module).
+    #[track_caller]
+    pub fn provide_lazy_module(&self, type_ids: &[TypeId], module: Box<dyn Module>) -> &Self {
+        self.try_provide_lazy_module(type_ids, module).unwrap();
+        self
+    }
+
+    /// Loads the nearest lazy module declaring `type_id`, unless it is loaded already.
+    fn load_lazy_module(&self, type_id: TypeId) -> Result<(), Error> {
+        let mut current = Some(self.inner.clone());
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let lazy_modules = inner.lazy_modules.borrow();
+            #[cfg(feature = "thread-safe")]
+            let lazy_modules = inner.lazy_modules.read().unwrap();
+
+            let lazy = lazy_modules
+                .iter()
+                .find(|lazy| lazy.type_ids.contains(&type_id))
+                .cloned();
+            drop(lazy_modules);
+
+            if let Some(lazy) = lazy {
+                let holder = Shared::new(Injector { inner });
+                return lazy
+                    .loaded
+                    .get_or_init(|| {
+                        let _loading = LazyLoading::enter();
+                        Injector::load_module(holder, lazy.module.as_ref())
+                    })
+                    .clone();
+            }
+
+            current = inner.parent.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Loads a list of [`AsyncModule`](crate::AsyncModule)s, awaiting each in turn.
+    ///
+    /// Each module gets its own child injector, as with
+    /// [`load_modules`](Injector::load_modules), and is recorded as loaded once
+    /// its providers are registered. Loading stops at the first module that
+    /// returns an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by a module's `providers`.
+    pub async fn load_async_modules(
+        &self,
+        modules: Vec<Box<dyn DynAsyncModule>>,
+    ) -> Result<(), Error> {
+        let parent = Shared::new(self.clone());
+
+        for module in modules {
+            let module_injector = Injector::child_in(parent.clone(), Some(module.name()));
+            module.providers_boxed(&module_injector).await?;
+            self.record_loaded_module(module.name());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that module `M` was loaded into this injector's hierarchy.
+    ///
+    /// Useful right after bootstrapping to catch a module that was declared but
+    /// never wired in. Modules are matched by [`Module::name`], which defaults to
+    /// the type name of `M`. Async modules are always recorded under theirs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModuleNotLoaded`](crate::ErrorKind::ModuleNotLoaded) if `M` has
+    /// not been loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Module};
+    ///
+    /// struct CoreModule;
+    /// impl Module for CoreModule {}
+    ///
+    /// struct AdminModule;
+    /// impl Module for AdminModule {}
+    ///
+    /// let injector = Injector::root();
+    /// injector.load_modules(vec![Box::new(CoreModule)]).unwrap();
+    ///
+    /// assert!(injector.assert_loaded::<CoreModule>().is_ok());
+    /// assert!(injector.assert_loaded::<AdminModule>().is_err());
+    /// ```
+    pub fn assert_loaded<M: ?Sized + 'static>(&self) -> Result<(), Error> {
+        let name = std::any::type_name::<M>();
+
+        if self.loaded_modules().contains(&name) {
+            Ok(())
+        } else {
+            Err(Error::module_not_loaded(name))
+        }
+    }
+
+    /// Checks the declared dependencies visible from here for cycles, without running any factory.
+    ///
+    /// Each registration contributes the edges declared with
+    /// [`Provider::depends_on`] (and implied by
+    /// [`provide_factory_with`](Injector::provide_factory_with)). Undeclared
+    /// resolves are invisible to this check, so it complements rather than
+    /// replaces the cycle detection done while resolving, but it needs no
+    /// instance and can run at startup in release builds. Dependencies on
+    /// unregistered types are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CircularDependency`](crate::ErrorKind::CircularDependency)
+    /// naming the chain of the first cycle found, in type-name order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{ErrorKind, Injector, Provider, Shared};
+    ///
+    /// struct Orders;
+    /// struct Billing;
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Orders>(Provider::root(|_| Shared::new(Orders)).depends_on::<Billing>());
+    /// injector.provide::<Billing>(Provider::root(|_| Shared::new(Billing)).depends_on::<Orders>());
+    ///
+    /// let err = injector.check_cycles().unwrap_err();
+    /// assert!(err.kind == ErrorKind::CircularDependency);
+    /// ```
+    pub fn check_cycles(&self) -> Result<(), Error> {
+        let mut graph = self.declared_dependencies();
+        graph.sort_by_key(|(_, type_name, _)| *type_name);
+
+        let edges: HashMap<TypeId, &[Dependency]> = graph
+            .iter()
+            .map(|(type_id, _, dependencies)| (*type_id, dependencies.as_slice()))
+            .collect();
+
+        let mut path = Vec::new();
+        let mut checked = HashSet::new();
+
+        for (type_id, type_name, _) in &graph {
+            Self::find_cycle((*type_id, *type_name), &edges, &mut path, &mut checked)?;
+        }
+
+        Ok(())
+    }
+
+    /// Depth-first walk from `node`, failing on an edge back into `path`.
+    fn find_cycle(
+        node: Dependency,
+        edges: &HashMap<TypeId, &[Dependency]>,
+        path: &mut Vec<Dependency>,
+        checked: &mut HashSet<TypeId>,
+    ) -> Result<(), Error> {
+        if let Some(start) = path.iter().position(|(type_id, _)| *type_id == node.0) {
+            let chain: Vec<&str> = path[start..]
+                .iter()
+                .map(|(_, type_name)| *type_name)
+                .chain([node.1])
+                .collect();
+            return Err(Error::circular_dependency(&chain));
+        }
+
+        if checked.contains(&node.0) {
+            return Ok(());
+        }
+
+        path.push(node);
+        for dependency in edges.get(&node.0).copied().unwrap_or_default() {
+            Self::find_cycle(*dependency, edges, path, checked)?;
+        }
+        path.pop();
+
+        checked.insert(node.0);
+        Ok(())
+    }
+
+    /// Reports what this injector holds, for leak diagnosis. See [`InjectorStats`].
+    ///
+    /// Only this injector is counted: root-scoped singletons are cached on the
+    /// root, so a child's stats do not include them.
+    pub fn stats(&self) -> InjectorStats {
+        #[cfg(not(feature = "thread-safe"))]
+        let (providers, instances) = (self.inner.providers.borrow(), self.inner.instances.borrow());
+        #[cfg(feature = "thread-safe")]
+        let (providers, instances) = (
+            self.inner.providers.read().unwrap(),
+            self.inner.instances.read().unwrap(),
+        );
+
+        let count = |scope: Scope| {
+            providers
+                .values()
+                .filter(|entry| entry.scope == scope)
+                .count()
+        };
+
+        // The size is recorded on the provider, which may live in an ancestor.
+        let size_of = |type_id: &TypeId| {
+            if let Some(entry) = providers.get(type_id) {
+                return entry.size.unwrap_or(0);
+            }
+
+            let mut current = self.inner.parent.as_deref();
+            while let Some(inner) = current {
+                #[cfg(not(feature = "thread-safe"))]
+                let providers = inner.providers.borrow();
+                #[cfg(feature = "thread-safe")]
+                let providers = inner.providers.read().unwrap();
+
+                if let Some(entry) = providers.get(type_id) {
+                    return entry.size.unwrap_or(0);
+                }
+                current = inner.parent.as_deref();
+            }
+
+            0
+        };
+
+        InjectorStats {
+            root: count(Scope::Root),
+            module: count(Scope::Module),
+            transient: count(Scope::Transient),
+            cached_singletons: instances.len(),
+            retained_bytes: instances.type_ids().iter().map(size_of).sum(),
+        }
+    }
+
+    /// Reports how many [`Shared`] handles exist to each singleton cached on this injector.
+    ///
+    /// Each entry pairs a type name with the instance's current strong count,
+    /// which includes the injector's own reference: an instance nothing else
+    /// holds reports 1. Polled over time, a count that keeps growing points at
+    /// a service that clones handles and never drops them. As with
+    /// [`stats`](Injector::stats), only this injector's cache is covered, and
+    /// instances cached by async providers (see `Injector::provide_async`) are
+    /// not reported. Entries are sorted by type name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u64>(Provider::root(|_| Shared::new(7)));
+    ///
+    /// let held = injector.resolve::<u64>();
+    /// assert_eq!(injector.shared_counts(), [("u64", 2)]);
+    ///
+    /// drop(held);
+    /// assert_eq!(injector.shared_counts(), [("u64", 1)]);
+    /// ```
+    pub fn shared_counts(&self) -> Vec<(&'static str, usize)> {
+        #[cfg(not(feature = "thread-safe"))]
+        let instances = self.inner.instances.borrow();
+        #[cfg(feature = "thread-safe")]
+        let instances = self.inner.instances.read().unwrap();
+
+        let mut counts = Vec::with_capacity(instances.len());
+        for type_id in instances.type_ids() {
+            let Some(instance) = instances.get(type_id) else {
+                continue;
+            };
+
+            // The provider that filled this cache may be registered in an ancestor.
+            let mut current = Some(&*self.inner);
+            while let Some(inner) = current {
+                #[cfg(not(feature = "thread-safe"))]
+                let providers = inner.providers.borrow();
+                #[cfg(feature = "thread-safe")]
+                let providers = inner.providers.read().unwrap();
+
+                if let Some(entry) = providers.get(&type_id) {
+                    if let Some(count) = (entry.strong_count)(&**instance) {
+                        counts.push((entry.type_name, count));
+                    }
+                    break;
+                }
+                current = inner.parent.as_deref();
+            }
+        }
+
+        counts.sort_unstable();
+        counts
+    }
+
+    /// Creates every instance whose provider is marked [`eager`](Provider::eager).
+    ///
+    /// Eager providers registered on this injector and its ancestors are
+    /// resolved in type-name order; a type registered at several levels is only
+    /// created from the nearest registration. Providers whose gate is closed are
+    /// skipped. Call it on the root once the application is wired, or on a
+    /// module's injector to include that module's own providers.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first resolve error; the types before it stay cached.
+    pub fn try_init_eager(&self) -> Result<InitReport, Error> {
+        let started = Instant::now();
+
+        let mut seen = HashSet::new();
+        let mut pending = Vec::new();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let providers = inner.providers.borrow();
+            #[cfg(feature = "thread-safe")]
+            let providers = inner.providers.read().unwrap();
+
+            for (type_id, entry) in providers.iter() {
+                if seen.insert(*type_id) && entry.eager && entry.scope != Scope::Transient {
+                    pending.push(entry.clone());
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        pending.sort_by_key(|entry| entry.type_name);
+
+        let mut per_type = Vec::with_capacity(pending.len());
+        for entry in pending {
+            if let Some(gate) = &entry.gate
+                && !gate(self)
+            {
+                continue;
+            }
+
+            let resolve_started = Instant::now();
+            (entry.resolve_erased)(self)?;
+            per_type.push((entry.type_name, resolve_started.elapsed()));
+        }
+
+        Ok(InitReport {
+            per_type,
+            total: started.elapsed(),
+        })
+    }
+
+    /// Like [`try_init_eager`](Injector::try_init_eager), but panics on error.
+    #[track_caller]
+    pub fn init_eager(&self) -> InitReport {
+        self.try_init_eager().unwrap()
+    }
+
+    /// Resolves the instance of every provider [marked](Provider::mark) with `M`.
+    ///
+    /// Providers registered on this injector and its ancestors are considered,
+    /// in type-name order; as with [`init_eager`](Injector::init_eager), a type
+    /// registered at several levels only counts its nearest registration, and
+    /// providers whose gate is closed are skipped. Each instance is resolved as
+    /// its registered type, so singletons come from the usual cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first resolve error.
+    pub fn try_resolve_marked<M>(&self) -> Result<Vec<Shared<M>>, Error>
+    where
+        M: ?Sized + 'static,
+    {
+        let pending = self.marked_with(TypeId::of::<M>());
+
+        let mut marked = Vec::with_capacity(pending.len());
+        for (_, type_name, gate, (_, resolve)) in pending {
+            if let Some(gate) = &gate
+                && !gate(self)
+            {
+                continue;
+            }
+
+            let value = resolve(self)?;
+            let value = value
+                .downcast_ref::<Shared<M>>()
+                .cloned()
+                .ok_or_else(|| Error::type_mismatch_found(std::any::type_name::<M>(), type_name))?;
+            marked.push(value);
+        }
+
+        Ok(marked)
+    }
+
+    /// Like [`try_resolve_marked`](Injector::try_resolve_marked), but panics on error.
+    #[track_caller]
+    pub fn resolve_marked<M>(&self) -> Vec<Shared<M>>
+    where
+        M: ?Sized + 'static,
+    {
+        self.try_resolve_marked::<M>().unwrap()
+    }
+
+    /// Runs the [`HealthCheck`] of every cached singleton marked with `dyn HealthCheck`.
+    ///
+    /// Singletons are considered as in [`resolve_marked`](Injector::resolve_marked),
+    /// but only those already cached are checked, so a probe never constructs a
+    /// service. The checks run one after another and are reported in type-name
+    /// order. See the [`health`](crate::health) module.
+    pub async fn health_check_all(&self) -> Vec<(&'static str, HealthStatus)> {
+        let mut checks = Vec::new();
+        for (type_id, type_name, _, (_, resolve)) in
+            self.marked_with(TypeId::of::<dyn HealthCheck>())
+        {
+            if !self.is_cached(type_id) {
+                continue;
+            }
+
+            let check = match resolve(self) {
+                Ok(value) => value
+                    .downcast_ref::<Shared<dyn HealthCheck>>()
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::type_mismatch_found(
+                            std::any::type_name::<dyn HealthCheck>(),
+                            type_name,
+                        )
+                    }),
+                Err(err) => Err(err),
+            };
+            checks.push((type_name, check));
+        }
+
+        let mut report = Vec::with_capacity(checks.len());
+        for (type_name, check) in checks {
+            let status = match check {
+                Ok(check) => check.check().await,
+                Err(err) => HealthStatus::Unhealthy(err.message),
+            };
+            report.push((type_name, status));
+        }
+
+        report
+    }
+
+    /// Collects the [marks](Provider::mark) for `marker` visible from this
+    /// injector, with the type, name, and gate of the provider carrying each,
+    /// sorted by type name. A type registered at several levels only counts its
+    /// nearest registration.
+    #[allow(clippy::type_complexity)]
+    fn marked_with(&self, marker: TypeId) -> Vec<(TypeId, &'static str, Option<Gate>, Mark)> {
+        let mut seen = HashSet::new();
+        let mut pending = Vec::new();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let providers = inner.providers.borrow();
+            #[cfg(feature = "thread-safe")]
+            let providers = inner.providers.read().unwrap();
+
+            for (type_id, entry) in providers.iter() {
+                if !seen.insert(*type_id) {
+                    continue;
+                }
+
+                for mark in &entry.marks {
+                    if mark.0 == marker {
+                        pending.push((*type_id, entry.type_name, entry.gate.clone(), mark.clone()));
+                    }
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        pending.sort_by_key(|(_, type_name, _, _)| *type_name);
+        pending
+    }
+
+    /// Inserts `entry` into `providers`, which belongs to this injector or an
+    /// ancestor, returning the entry it replaces.
+    ///
+    /// Every provider entry enters the tree through here, so that each one
+    /// invalidates the misses [`contains`](Injector::contains) remembers.
+    fn insert_provider_entry(
+        &self,
+        providers: &mut HashMap<TypeId, ProviderEntry>,
+        type_id: TypeId,
+        entry: ProviderEntry,
+    ) -> Option<ProviderEntry> {
+        let replaced = providers.insert(type_id, entry);
+
+        // Only after the insert, with `providers` still locked, so a probe that
+        // reads the new count also finds the entry, and one that read the old
+        // count cannot cache `T` as unregistered under the new one.
+        self.root_inner()
+            .registrations
+            .fetch_add(1, Ordering::Release);
+
+        replaced
+    }
+
+    /// Returns the module this injector, or its nearest ancestor, was created to load.
+    fn module_name(&self) -> Option<&'static str> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if inner.module.is_some() {
+                return inner.module;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Describes the nearest registration of `type_id`.
+    fn provider_meta(&self, type_id: TypeId, was_cached: bool) -> Option<ProviderMeta> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let providers = inner.providers.borrow();
+            #[cfg(feature = "thread-safe")]
+            let providers = inner.providers.read().unwrap();
+
+            if let Some(entry) = providers.get(&type_id) {
+                return Some(ProviderMeta {
+                    scope: entry.scope,
+                    registered_at: entry.site,
+                    was_cached,
+                    module: entry.module,
+                });
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Returns `true` if the nearest cache for `type_id` holds an instance,
+    /// walking up the chain like [`get_instance`](Injector::get_instance).
+    fn is_cached(&self, type_id: TypeId) -> bool {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            #[cfg(not(feature = "thread-safe"))]
+            let (cached, registered) = (
+                inner.instances.borrow().get(type_id).is_some(),
+                inner.providers.borrow().contains_key(&type_id),
+            );
+            #[cfg(feature = "thread-safe")]
+            let (cached, registered) = (
+                inner.instances.read().unwrap().get(type_id).is_some(),
+                inner.providers.read().unwrap().contains_key(&type_id),
+            );
+
+            if cached {
+                return true;
+            }
+            if registered {
+                return false;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Captures the registrations visible from this injector. See [`Manifest`].
+    pub fn manifest(&self) -> Manifest {
+        Manifest::of(self)
+    }
+
+    /// Lists the type name of every registration visible from here.
+    ///
+    /// The nearest registration of a type shadows those of its ancestors. The
+    /// list is sorted by type name, so it is the same on every run regardless
+    /// of registration order, which keeps snapshot tests stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+    /// injector.provide::<bool>(Provider::root(|_| Shared::new(true)));
+    ///
+    /// assert_eq!(injector.registered_types(), ["bool", "u32"]);
+    /// ```
+    pub fn registered_types(&self) -> Vec<&'static str> {
+        self.registrations()
+            .into_iter()
+            .map(|(type_name, _)| type_name)
+            .collect()
+    }
+
+    /// Obtains a dependency of shape `D`, such as `Shared<T>` or `Option<Shared<T>>`.
+    ///
+    /// Unlike [`try_resolve`](Injector::try_resolve), the requested type is the
+    /// dependency as a consumer declares it, so an optional dependency resolves
+    /// to `None` rather than failing when nothing is registered. See [`Inject`].
+    pub fn inject<D: Inject>(&self) -> Result<D, Error> {
+        D::inject(self)
+    }
+
+    /// Recursively loads a module and its imports into the injector hierarchy.
+    ///
+    /// Creates a child injector for the module, loads all imported modules first,
+    /// then registers the module's own providers. This ensures proper dependency
+    /// resolution order.
+    ///
+    /// # Parameters
+    ///
+    /// - `parent`: The parent injector to create a child from
+    /// - `module`: The module to load
+    ///
+    /// Stops at the first import or provider registration that fails.
+    pub(crate) fn load_module(parent: Shared<Injector>, module: &dyn Module) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        debug!("Loading module into injector hierarchy");
+
+        let module_injector = Shared::new(Injector::child_in(parent.clone(), Some(module.name())));
+
+        #[cfg(feature = "tracing")]
+        debug!("Created child injector for module");
+
+        let imports = module.imports();
+        #[cfg(feature = "tracing")]
+        if !imports.is_empty() {
+            debug!("Module has {} imports, loading them first", imports.len());
+        }
+
+        #[allow(unused_variables)]
+        for (index, import) in imports.into_iter().enumerate() {
+            #[cfg(feature = "tracing")]
+            debug!("Loading import {}", index + 1);
+
+            Injector::load_module(module_injector.clone(), import.as_ref())?;
+        }
+
+        #[cfg(feature = "tracing")]
+        debug!("Registering module providers");
+
+        module.try_providers(&module_injector)?;
+        parent.record_loaded_module(module.name());
+
+        #[cfg(feature = "tracing")]
+        debug!("Module loaded successfully");
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl Injector {
+    /// Registers `provider` for `T` under `key`, alongside any other keyed providers of `T`.
+    ///
+    /// Unlike [`try_provide`](Injector::try_provide), several providers of the
+    /// same `T` can coexist as long as their keys differ. They are resolved
+    /// together, in key order, with [`resolve_ordered`](Injector::resolve_ordered).
+    /// Scopes, profiles and gates apply to each provider as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if `key` is already taken for `T` on the target injector.
+    pub fn try_provide_keyed<T, K>(&self, key: K, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        if !provider.profiles.is_active(self.profile().as_deref()) {
+            return Ok(());
+        }
+
+        match provider.scope {
+            Scope::Root => self.root_injector().store_keyed(key, provider),
+            Scope::Module | Scope::Transient => self.store_keyed(key, provider),
+        }
+    }
+
+    #[track_caller]
+    pub fn provide_keyed<T, K>(&self, key: K, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        self.try_provide_keyed::<T, K>(key, provider).unwrap();
+        self
+    }
+
+    /// Registers a root-scoped keyed provider whose factory receives its key.
+    ///
+    /// Useful when the key parameterizes the instance, such as a database URL
+    /// for a pool: the factory is called with the key it was registered under,
+    /// so one closure can serve every key. See [`try_provide_keyed`](Injector::try_provide_keyed)
+    /// for how keyed providers are resolved and when registration fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Shared};
+    ///
+    /// struct Pool {
+    ///     url: String,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// for url in ["postgres://primary", "postgres://replica"] {
+    ///     injector.provide_keyed_with::<Pool, String, _>(url.to_string(), |_, url| {
+    ///         Shared::new(Pool { url: url.clone() })
+    ///     });
+    /// }
+    ///
+    /// let replica = injector.resolve_keyed::<Pool, String>(&"postgres://replica".to_string());
+    /// assert_eq!(replica.url, "postgres://replica");
+    /// ```
+    pub fn try_provide_keyed_with<T, K, F>(&self, key: K, factory: F) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+        F: Fn(&Injector, &K) -> Shared<T> + 'static,
+    {
+        let captured = key.clone();
+        self.try_provide_keyed::<T, K>(
+            key,
+            Provider::root(move |injector| factory(injector, &captured)),
+        )
+    }
+
+    #[track_caller]
+    pub fn provide_keyed_with<T, K, F>(&self, key: K, factory: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+        F: Fn(&Injector, &K) -> Shared<T> + 'static,
+    {
+        self.try_provide_keyed_with::<T, K, F>(key, factory)
+            .unwrap();
+        self
+    }
+
+    /// Resolves the keyed provider of `T` registered under `key`.
+    ///
+    /// The nearest registration of `key` wins, as with
+    /// [`resolve_ordered`](Injector::resolve_ordered).
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+    /// nothing is registered under `key`, or its gate is closed.
+    pub fn try_resolve_keyed<T, K>(&self, key: &K) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        let type_id = TypeId::of::<(K, PhantomData<T>)>();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let entry = inner
+                .keyed
+                .borrow()
+                .get(&type_id)
+                .and_then(|registry| registry.map::<T, K>())
+                .and_then(|map| map.borrow().get(key).cloned());
+
+            // Resolved with no lock held, so the factory may resolve freely.
+            if let Some(entry) = entry {
+                return entry
+                    .resolve(self)
+                    .ok_or_else(|| Self::keyed_not_provided::<T, K>());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Self::keyed_not_provided::<T, K>())
+    }
+
+    #[track_caller]
+    pub fn resolve_keyed<T, K>(&self, key: &K) -> Shared<T>
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        self.try_resolve_keyed::<T, K>(key).unwrap()
+    }
+
+    /// Returns `true` if a keyed provider of `T` is registered under `key` here or in an ancestor.
+    #[cfg(feature = "config")]
+    pub(crate) fn contains_keyed<T, K>(&self, key: &K) -> bool
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        let type_id = TypeId::of::<(K, PhantomData<T>)>();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let registered = inner
+                .keyed
+                .borrow()
+                .get(&type_id)
+                .and_then(|registry| registry.map::<T, K>())
+                .is_some_and(|map| map.borrow().contains_key(key));
+
+            if registered {
+                return true;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Resolves every provider registered with [`provide_keyed`](Injector::provide_keyed)
+    /// for `T` and `K`, ordered by key.
+    ///
+    /// Keyed providers of this injector and its ancestors are combined; for a key
+    /// registered at several levels, the nearest one wins. Providers whose gate is
+    /// closed are left out, and an unregistered `T` yields an empty list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Middleware {
+    ///     fn name(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Auth;
+    /// impl Middleware for Auth {
+    ///     fn name(&self) -> &'static str {
+    ///         "auth"
+    ///     }
+    /// }
+    ///
+    /// struct Logging;
+    /// impl Middleware for Logging {
+    ///     fn name(&self) -> &'static str {
+    ///         "logging"
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide_keyed::<dyn Middleware, u8>(20, Provider::root(|_| {
+    ///     Shared::new(Auth) as Shared<dyn Middleware>
+    /// }));
+    /// injector.provide_keyed::<dyn Middleware, u8>(10, Provider::root(|_| {
+    ///     Shared::new(Logging) as Shared<dyn Middleware>
+    /// }));
+    ///
+    /// let pipeline: Vec<_> = injector
+    ///     .resolve_ordered::<dyn Middleware, u8>()
+    ///     .iter()
+    ///     .map(|middleware| middleware.name())
+    ///     .collect();
+    /// assert_eq!(pipeline, ["logging", "auth"]);
+    /// ```
+    pub fn resolve_ordered<T, K>(&self) -> Vec<Shared<T>>
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        let type_id = TypeId::of::<(K, PhantomData<T>)>();
+        let mut entries = KeyedMap::<T, K>::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let map = inner
+                .keyed
+                .borrow()
+                .get(&type_id)
+                .and_then(|registry| registry.map::<T, K>());
+
+            if let Some(map) = map {
+                for (key, entry) in map.borrow().iter() {
+                    if !entries.contains_key(key) {
+                        entries.insert(key.clone(), entry.clone());
+                    }
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        // No lock is held here, so factories may resolve or register freely.
+        entries
+            .into_values()
+            .filter_map(|entry| entry.resolve(self))
+            .collect()
+    }
+
+    fn store_keyed<T, K>(&self, key: K, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        K: Ord + Clone + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+
+        self.inner.ensure_unfrozen()?;
+        self.track_gate(&provider);
+
+        let map = self
+            .inner
+            .keyed
+            .borrow_mut()
+            .entry(TypeId::of::<(K, PhantomData<T>)>())
+            .or_insert_with(KeyedRegistry::new::<T, K>)
+            .map::<T, K>()
+            .ok_or_else(|| Error::type_mismatch(type_name))?;
+
+        let mut map = map.borrow_mut();
+        if map.contains_key(&key) {
+            return Err(Error::provider_already_registered(
+                &format!("{} keyed by {}", type_name, std::any::type_name::<K>()),
+                provider.scope.to_string().as_str(),
+            ));
+        }
+
+        let entry = KeyedEntry {
+            scope: provider.scope,
+            provider: Shared::new(provider),
+            instance: Store::new(None),
+        };
+        map.insert(key, Shared::new(entry));
+
+        Ok(())
+    }
+
+    /// Fails with `ServiceNotProvided` if the nearest registration of `T` is gated shut.
+    fn check_gate<T: ?Sized + 'static>(&self) -> Result<(), Error> {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let gate = inner
+                .providers
+                .borrow()
+                .get(&type_id)
+                .map(|entry| entry.gate.clone());
+
+            match gate {
+                Some(Some(gate)) if !gate(self) => {
+                    return Err(Error::service_not_provided(std::any::type_name::<T>()));
+                }
+                Some(_) => return Ok(()),
+                None => current = inner.parent.as_deref(),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the factory timings collected across the whole injector tree.
+    ///
+    /// Entries are sorted by type name. See [`ResolveTiming`].
+    #[cfg(feature = "timing")]
+    pub fn timing_report(&self) -> Vec<(&'static str, ResolveTiming)> {
+        let mut report: Vec<_> = self
+            .root_injector()
+            .inner
+            .timings
+            .borrow()
+            .iter()
+            .map(|(name, timing)| (*name, *timing))
+            .collect();
+        report.sort_unstable_by_key(|(name, _)| *name);
+        report
+    }
+
+    #[cfg(feature = "timing")]
+    fn record_timing<T: ?Sized + 'static>(&self, elapsed: std::time::Duration) {
+        self.root_injector()
+            .inner
+            .timings
+            .borrow_mut()
+            .entry(std::any::type_name::<T>())
+            .or_default()
+            .record(elapsed);
+    }
+
+    /// Resolves the type identified by `type_id` without naming it statically.
+    ///
+    /// This is the reflective counterpart of [`try_resolve`](Injector::try_resolve)
+    /// for callers that only hold a [`TypeId`], such as plugin dispatchers.
+    /// Scopes and caching behave exactly as for the generic call. Because the
+    /// registered type may be a trait object, the value is returned wrapped in
+    /// an [`Instance`]: downcast to `Instance<T>` and call
+    /// [`value`](Instance::value) to get the `Shared<T>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if no
+    /// provider is registered for `type_id`, plus any error from resolving it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    ///
+    /// use sadi::{Injector, Instance, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::singleton(|_| Shared::new(7)));
+    ///
+    /// let erased = injector.resolve_erased(TypeId::of::<u32>()).unwrap();
+    /// let value = erased.downcast_ref::<Instance<u32>>().unwrap().value();
+    ///
+    /// assert_eq!(*value, 7);
+    /// ```
+    pub fn resolve_erased(&self, type_id: TypeId) -> Result<Shared<dyn Any>, Error> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let resolver = inner
+                .providers
+                .borrow()
+                .get(&type_id)
+                .map(|entry| entry.resolve_erased);
+
+            if let Some(resolve) = resolver {
+                return resolve(self);
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Error::service_not_provided(&format!("{:?}", type_id)))
+    }
+
+    /// Lists the type name and scope of every registration visible from here.
+    ///
+    /// The nearest registration of a type shadows those of its ancestors, and
+    /// the list is sorted by type name.
+    pub(crate) fn registrations(&self) -> Vec<(&'static str, Scope)> {
+        let mut seen = std::collections::HashSet::new();
+        let mut registrations = Vec::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            for (type_id, entry) in inner.providers.borrow().iter() {
+                if seen.insert(*type_id) {
+                    registrations.push((entry.type_name, entry.scope));
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        registrations.sort_unstable_by_key(|(type_name, _)| *type_name);
+        registrations
+    }
+
+    /// Lists the declared dependencies of every registration visible from here.
+    ///
+    /// The nearest registration of a type shadows those of its ancestors.
+    fn declared_dependencies(&self) -> Vec<(TypeId, &'static str, Vec<Dependency>)> {
+        let mut seen = std::collections::HashSet::new();
+        let mut graph = Vec::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            for (type_id, entry) in inner.providers.borrow().iter() {
+                if seen.insert(*type_id) {
+                    graph.push((*type_id, entry.type_name, entry.dependencies.clone()));
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        graph
+    }
+
+    /// Lists where each registration visible from here was made, by type name.
+    ///
+    /// Registering functions such as [`provide`](Injector::provide) and
+    /// [`override_provider`](Injector::override_provider) are
+    /// `#[track_caller]`, so the location is the user code that called them,
+    /// such as the body of a module's `providers`. The nearest registration of
+    /// a type shadows those of its ancestors, and the list is sorted by type
+    /// name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// let line = line!() + 1;
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+    ///
+    /// let sites = injector.registration_sites();
+    /// assert_eq!(sites[0].0, "u32");
+    /// assert_eq!(sites[0].1.line(), line);
+    /// ```
+    pub fn registration_sites(&self) -> Vec<(&'static str, &'static Location<'static>)> {
+        let mut seen = HashSet::new();
+        let mut sites = Vec::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            for (type_id, entry) in inner.providers.borrow().iter() {
+                if seen.insert(*type_id) {
+                    sites.push((entry.type_name, entry.site));
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        sites.sort_by_key(|(type_name, _)| *type_name);
+        sites
+    }
+
+    /// Returns how many times each type was resolved through this injector.
+    ///
+    /// Empty unless counting was turned on with
+    /// [`set_resolution_stats`](Injector::set_resolution_stats).
+    pub fn resolution_stats(&self) -> HashMap<&'static str, usize> {
+        self.inner.resolution_stats.borrow().clone()
+    }
+
+    /// Starts recording, in order, every type resolved through this injector.
+    ///
+    /// Like [`set_resolution_stats`](Injector::set_resolution_stats), this sees
+    /// the nested resolves made by factories run through this injector, but
+    /// keeps their order. Calling it again starts a new trace; handles to the
+    /// previous one stop growing. See [`ResolutionTrace`].
+    pub fn record_resolutions(&self) -> ResolutionTrace {
+        let trace = ResolutionTrace::default();
+        *self.inner.trace.borrow_mut() = Some(trace.clone());
+        trace
+    }
+
+    fn record_resolution<T: ?Sized + 'static>(&self) {
+        if let Some(trace) = self.inner.trace.borrow().as_ref() {
+            trace.push::<T>();
+        }
+
+        if self.inner.track_resolutions.load(Ordering::Relaxed) {
+            *self
+                .inner
+                .resolution_stats
+                .borrow_mut()
+                .entry(std::any::type_name::<T>())
+                .or_default() += 1;
+        }
+    }
+
+    /// Returns the names of every module loaded into this injector's hierarchy.
+    ///
+    /// Modules are listed in the order they finished loading, so imports come
+    /// before the modules that import them.
+    pub fn loaded_modules(&self) -> Vec<&'static str> {
+        self.root_injector().inner.loaded_modules.borrow().clone()
+    }
+
+    pub(crate) fn record_loaded_module(&self, name: &'static str) {
+        self.root_injector()
+            .inner
+            .loaded_modules
+            .borrow_mut()
+            .push(name);
+    }
+
+    /// Sets the active profile of this injector and its descendants.
+    ///
+    /// Providers restricted with [`Provider::only_in`] or [`Provider::except_in`]
+    /// are filtered when they are registered, so set the profile before loading
+    /// the modules that register them. Changing it afterwards does not add or
+    /// remove existing registrations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.set_profile("prod");
+    ///
+    /// assert_eq!(injector.profile().as_deref(), Some("prod"));
+    /// ```
+    pub fn set_profile(&self, profile: impl Into<String>) -> &Self {
+        *self.inner.profile.borrow_mut() = Some(profile.into());
+        self
+    }
+
+    /// Returns the active profile, set on this injector or the nearest ancestor.
+    pub fn profile(&self) -> Option<String> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(profile) = inner.profile.borrow().as_ref() {
+                return Some(profile.clone());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Registers `provider` for `T`, failing if `T` already has a local registration.
+    ///
+    /// An existing registration with a different [`priority`](Provider::priority)
+    /// is not a conflict: the higher priority provider is kept.
+    ///
+    /// [`Scope::Root`] providers are registered on the root injector; the others
+    /// on this one. Registering from inside a factory is allowed: no map is
+    /// borrowed while a factory runs, so the new provider is visible to every
+    /// resolve that starts afterwards, though not to one already past its
+    /// provider lookup.
+    #[track_caller]
+    pub fn try_provide<T>(&self, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        if !provider.profiles.is_active(self.profile().as_deref()) {
+            #[cfg(feature = "tracing")]
+            debug!(
+                "Skipping provider for {} not active in the current profile",
+                std::any::type_name::<T>()
+            );
+
+            return Ok(());
+        }
+
+        match provider.scope {
+            Scope::Root => {
+                let root = self.root_injector();
+                root.store_provider::<T>(provider, self.module_name())
+            }
+
+            Scope::Module | Scope::Transient => {
+                self.store_provider::<T>(provider, self.module_name())
+            }
+        }
+    }
+
+    /// Registers `provider` for `T`, panicking if `T` already has a local registration.
+    ///
+    /// Every instantiation of a generic type is a distinct `T`, so
+    /// `Cache<String, u32>` and `Cache<u32, String>` are registered and resolved
+    /// independently:
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Cache<K, V> {
+    ///     entries: Vec<(K, V)>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Cache<String, u32>>(Provider::root(|_| {
+    ///     Shared::new(Cache { entries: vec![("answer".to_string(), 42)] })
+    /// }));
+    /// injector.provide::<Cache<u32, String>>(Provider::root(|_| {
+    ///     Shared::new(Cache { entries: vec![] })
+    /// }));
+    ///
+    /// assert_eq!(injector.resolve::<Cache<String, u32>>().entries.len(), 1);
+    /// assert!(injector.resolve::<Cache<u32, String>>().entries.is_empty());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`try_provide`](Self::try_provide) fails.
+    #[track_caller]
+    pub fn provide<T>(&self, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_provide::<T>(provider).unwrap();
+        self
+    }
+
+    /// Registers a singleton `T` built from one resolved dependency `D`.
+    ///
+    /// Shorthand for a [`Provider::singleton`] whose factory resolves `D` and
+    /// hands it to `factory`. `D` is resolved through the injector that first
+    /// resolves `T`, so it may be registered in an ancestor.
+    ///
+    /// Like every factory, `factory` must be `'static`: it cannot borrow
+    /// locals. Shared configuration should either be registered and declared as
+    /// `D`, or captured by value as a `Shared<Config>` with a `move` closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a provider for `T` is already registered on this injector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Config {
+    ///     url: String,
+    /// }
+    ///
+    /// struct Database {
+    ///     config: Shared<Config>,
+    /// }
+    ///
+    /// impl Database {
+    ///     fn new(config: Shared<Config>) -> Self {
+    ///         Self { config }
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| {
+    ///     Shared::new(Config { url: "sqlite::memory:".to_string() })
+    /// }));
+    /// injector.provide_factory_with::<Database, Config, _>(Database::new);
+    ///
+    /// assert_eq!(injector.resolve::<Database>().config.url, "sqlite::memory:");
+    /// ```
+    #[track_caller]
+    pub fn provide_factory_with<T, D, F>(&self, factory: F) -> &Self
+    where
+        T: 'static,
+        D: ?Sized + 'static,
+        F: Fn(Shared<D>) -> T + 'static,
+    {
+        self.provide::<T>(
+            Provider::singleton(move |injector| Shared::new(factory(injector.resolve::<D>())))
+                .depends_on::<D>(),
+        )
+    }
+
+    /// Registers a provider for `T` that `choose` picks on first resolve.
+    ///
+    /// `choose` runs the first time `T` is resolved and may resolve other
+    /// services, typically a configuration, to decide which implementation to
+    /// use and with which scope. The decision is memoized: the chosen provider
+    /// replaces this registration, so `choose` never runs again and later
+    /// resolves behave exactly as if the chosen provider had been registered
+    /// directly. Values that `choose` looked at may change afterwards without
+    /// affecting the decision.
+    ///
+    /// The registration lives on this injector. Until the first resolve, it is
+    /// reported as [`Scope::Transient`] by [`manifest`](Injector::manifest) and
+    /// [`stats`](Injector::stats), and it is skipped by
+    /// [`init_eager`](Injector::init_eager). On a child injector, such as the
+    /// one a module registers on, a chosen [`Scope::Root`] provider stays local
+    /// like an [override](Injector::override_provider): it becomes
+    /// [`Scope::Module`] and its instance is cached below the root.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already
+    /// registered on this injector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Cache: Send + Sync {
+    ///     fn backend(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Redis;
+    /// impl Cache for Redis {
+    ///     fn backend(&self) -> &'static str {
+    ///         "redis"
+    ///     }
+    /// }
+    ///
+    /// struct InMemory;
+    /// impl Cache for InMemory {
+    ///     fn backend(&self) -> &'static str {
+    ///         "in-memory"
+    ///     }
+    /// }
+    ///
+    /// struct Config {
+    ///     use_redis: bool,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| Shared::new(Config { use_redis: true })));
+    /// injector.provide_conditional::<dyn Cache, _>(|inj| {
+    ///     if inj.resolve::<Config>().use_redis {
+    ///         Provider::root(|_| Shared::new(Redis) as Shared<dyn Cache>)
+    ///     } else {
+    ///         Provider::transient(|_| Shared::new(InMemory) as Shared<dyn Cache>)
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(injector.resolve::<dyn Cache>().backend(), "redis");
+    /// ```
+    #[track_caller]
+    pub fn try_provide_conditional<T, F>(&self, choose: F) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Provider<T> + 'static,
+    {
+        self.store_provider::<T>(
+            Provider {
+                scope: Scope::Transient,
+                profiles: Profiles::default(),
+                gate: None,
+                priority: 0,
+                size: None,
+                eager: false,
+                dependencies: Vec::new(),
+                deadline: None,
+                after_init: None,
+                marks: Vec::new(),
+                fallbacks: None,
+                factory: Box::new(move |injector| injector.adopt_provider(choose(injector))),
+            },
+            self.module_name(),
+        )
+    }
+
+    /// Registers a provider for `T` that `choose` picks on first resolve.
+    ///
+    /// See [`try_provide_conditional`](Injector::try_provide_conditional).
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`try_provide_conditional`](Injector::try_provide_conditional) fails.
+    #[track_caller]
+    pub fn provide_conditional<T, F>(&self, choose: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Provider<T> + 'static,
+    {
+        self.try_provide_conditional::<T, F>(choose).unwrap();
+        self
+    }
+
+    /// Registers a root singleton built in two phases, to break a genuine cycle.
+    ///
+    /// Two services that each hold the other cannot both receive their
+    /// dependency at construction: resolving either fails with
+    /// [`CircularDependency`](crate::ErrorKind::CircularDependency). This is
+    /// the escape hatch. `construct` builds `T` without the dependency that
+    /// closes the cycle, and the instance is cached at once. `wire` then runs
+    /// on the cached instance and supplies the dependency through a setter,
+    /// typically into a `OnceLock` field. By then `T` resolves to the cached
+    /// instance, so the other side of the cycle can be built, even with a
+    /// plain provider that takes `T` in its factory, and `T` is fully wired
+    /// before the resolve that created it returns.
+    ///
+    /// Prefer restructuring the services, or a [`WeakInjector`] that resolves
+    /// the other side on demand, where possible. Until `wire` has run, whatever is built
+    /// during it sees `T` without its dependency. With the `thread-safe`
+    /// feature, other threads may also resolve `T` in that window. Services
+    /// that keep strong references to each other are never freed.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::OnceLock;
+    ///
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Parent {
+    ///     child: OnceLock<Shared<Child>>,
+    /// }
+    ///
+    /// struct Child {
+    ///     parent: Shared<Parent>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Child>(Provider::root(|inj| {
+    ///     Shared::new(Child {
+    ///         parent: inj.resolve::<Parent>(),
+    ///     })
+    /// }));
+    /// injector.provide_two_phase::<Parent, _, _>(
+    ///     |_| Parent {
+    ///         child: OnceLock::new(),
+    ///     },
+    ///     |parent, inj| {
+    ///         let _ = parent.child.set(inj.resolve::<Child>());
+    ///     },
+    /// );
+    ///
+    /// let parent = injector.resolve::<Parent>();
+    /// let child = parent.child.get().unwrap();
+    /// assert!(Shared::ptr_eq(&child.parent, &parent));
+    /// ```
+    #[track_caller]
+    pub fn try_provide_two_phase<T, C, W>(&self, construct: C, wire: W) -> Result<(), Error>
+    where
+        T: 'static,
+        C: Fn(&Injector) -> T + 'static,
+        W: Fn(&T, &Injector) + 'static,
+    {
+        self.try_provide::<T>(
+            Provider::root(move |injector| Shared::new(construct(injector))).after_init(
+                move |instance, injector| {
+                    wire(instance, injector);
+                    Ok(())
+                },
+            ),
+        )
+    }
+
+    /// Registers a two-phase root singleton, panicking on a duplicate.
+    ///
+    /// See [`try_provide_two_phase`](Injector::try_provide_two_phase).
+    #[track_caller]
+    pub fn provide_two_phase<T, C, W>(&self, construct: C, wire: W) -> &Self
+    where
+        T: 'static,
+        C: Fn(&Injector) -> T + 'static,
+        W: Fn(&T, &Injector) + 'static,
+    {
+        self.try_provide_two_phase::<T, C, W>(construct, wire)
+            .unwrap();
+        self
+    }
+
+    /// Registers a root singleton whose type is inferred from `factory`, returning a key to resolve it.
+    ///
+    /// Meant for factories returning a type that cannot be named, such as a
+    /// closure: `T` is taken from `factory`'s return type, so no turbofish is
+    /// needed, and the returned [`Inferred<T>`] resolves the service without
+    /// naming it either. See the [`inferred`](crate::inferred) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already registered.
+    #[track_caller]
+    pub fn try_provide_inferred<T, F>(&self, factory: F) -> Result<Inferred<T>, Error>
+    where
+        T: 'static,
+        F: Fn(&Injector) -> T + 'static,
+    {
+        self.try_provide::<T>(Provider::root(move |injector| {
+            Shared::new(factory(injector))
+        }))?;
+        Ok(Inferred::new())
+    }
+
+    /// Registers a root singleton of an inferred type, panicking on a duplicate.
+    ///
+    /// See [`try_provide_inferred`](Injector::try_provide_inferred).
+    #[track_caller]
+    pub fn provide_inferred<T, F>(&self, factory: F) -> Inferred<T>
+    where
+        T: 'static,
+        F: Fn(&Injector) -> T + 'static,
+    {
+        self.try_provide_inferred::<T, F>(factory).unwrap()
+    }
+
+    /// Registers `providers` for `T`, to be tried in order until one succeeds.
+    ///
+    /// Resolving `T` creates an instance from the first provider; if it fails
+    /// with [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed)
+    /// or [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided), the next
+    /// one is tried, and so on. The first success is cached according to the
+    /// first provider's scope, which also decides where the chain is
+    /// registered. See the [`fallback`](crate::fallback) module for which
+    /// failures move on and which abort.
+    ///
+    /// Providers not active in the current [profile](Injector::set_profile) are
+    /// left out; if none is left, nothing is registered.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already registered.
+    #[track_caller]
+    pub fn try_provide_fallback_chain<T>(
+        &self,
+        mut providers: Vec<Provider<T>>,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let profile = self.profile();
+        providers.retain(|provider| provider.profiles.is_active(profile.as_deref()));
+
+        let Some(scope) = providers.first().map(|provider| provider.scope) else {
+            return Ok(());
+        };
+        let dependencies = providers
+            .iter()
+            .flat_map(|provider| provider.dependencies.iter().copied())
+            .collect();
+
+        let fallbacks = FallbackChain::new(providers);
+
+        self.try_provide::<T>(Provider {
+            scope,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies,
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: Some(fallbacks),
+            // `map` and `decorate` carry the chain along, so every resolve goes through it.
+            factory: Box::new(|_| {
+                unreachable!("a fallback chain is created through its fallbacks")
+            }),
+        })
+    }
+
+    /// Registers `providers` for `T`, to be tried in order, panicking on conflict.
+    ///
+    /// See [`try_provide_fallback_chain`](Injector::try_provide_fallback_chain).
+    #[track_caller]
+    pub fn provide_fallback_chain<T>(&self, providers: Vec<Provider<T>>) -> &Self
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_provide_fallback_chain::<T>(providers).unwrap();
+        self
+    }
+
+    /// Creates `T` from the first of `providers` that succeeds.
+    pub(crate) fn resolve_chain<T>(
+        &self,
+        providers: &[Shared<Provider<T>>],
+    ) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+        let mut last = Error::service_not_provided(type_name);
+
+        for provider in providers {
+            let created = match &provider.gate {
+                Some(gate) if !gate(self) => Err(Error::service_not_provided(type_name)),
+                _ => self.create_instance(provider.clone()).and_then(|instance| {
+                    self.run_after_init(provider, instance.get())?;
+                    Ok(instance.value())
+                }),
+            };
+
+            match created {
+                Ok(value) => return Ok(value),
+                Err(err)
+                    if err.kind == ErrorKind::FactoryExecutionFailed
+                        || err.kind == ErrorKind::ServiceNotProvided =>
+                {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Falling back from a provider for {}: {}",
+                        type_name, err.message
+                    );
+
+                    last = err;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(last)
+    }
+
+    /// Builds the first instance from `provider` and makes it the nearest registration of `T`.
+    ///
+    /// The instance is cached according to the provider's scope, because the
+    /// conditional registration it replaces is transient and is not cached by
+    /// the resolve that called it.
+    fn adopt_provider<T>(&self, mut provider: Provider<T>) -> Instance<T>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        // The placeholder may be registered on an ancestor of this injector.
+        let mut holder = None;
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if inner.providers.borrow().contains_key(&type_id) {
+                holder = Some(inner);
+                break;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        // A root-scoped choice below the root would be cached on the root, past
+        // the registration that shadows it, and never be found; as with
+        // `override_provider`, it is cached locally instead.
+        if provider.scope == Scope::Root && holder.is_some_and(|inner| inner.parent.is_some()) {
+            provider.scope = Scope::Module;
+        }
+        let scope = provider.scope;
+
+        self.track_gate(&provider);
+        let value = (provider.factory)(self).value();
+
+        if let Some(inner) = holder {
+            let mut providers = inner.providers.borrow_mut();
+            if let Some(entry) = providers.get(&type_id) {
+                let (site, module) = (entry.site, entry.module);
+                self.insert_provider_entry(
+                    &mut providers,
+                    type_id,
+                    ProviderEntry {
+                        site,
+                        module,
+                        ..ProviderEntry::new(provider)
+                    },
+                );
+            }
+        }
+
+        match scope {
+            Scope::Root => self
+                .root_injector()
+                .store_instance::<T>(Shared::new(Instance::new(value.clone()))),
+            Scope::Module => self.store_instance::<T>(Shared::new(Instance::new(value.clone()))),
+            Scope::Transient => {}
+        }
+
+        Instance::new(value)
+    }
+
+    /// Registers `provider` under the marker type `Tag`.
+    ///
+    /// The provider is stored as a [`Tagged<T, Tag>`] registration, so several
+    /// providers of `T` can coexist as long as their tags differ. Resolve it
+    /// with [`resolve_tagged`](Injector::resolve_tagged).
+    #[track_caller]
+    pub fn try_provide_tagged<T, Tag>(&self, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        Tag: 'static,
+    {
+        self.try_provide::<Tagged<T, Tag>>(provider.map(|value| Shared::new(Tagged::new(value))))
+    }
+
+    #[track_caller]
+    pub fn provide_tagged<T, Tag>(&self, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + 'static,
+        Tag: 'static,
+    {
+        self.try_provide_tagged::<T, Tag>(provider).unwrap();
+        self
+    }
+
+    /// Resolves the `T` registered under `Tag` with [`provide_tagged`](Injector::provide_tagged).
+    pub fn try_resolve_tagged<T, Tag>(&self) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+        Tag: 'static,
+    {
+        self.try_resolve::<Tagged<T, Tag>>()
+            .map(|tagged| tagged.value())
+    }
+
+    #[track_caller]
+    pub fn resolve_tagged<T, Tag>(&self) -> Shared<T>
+    where
+        T: ?Sized + 'static,
+        Tag: 'static,
+    {
+        self.try_resolve_tagged::<T, Tag>().unwrap()
+    }
+
+    /// Registers a factory of owned `Box<T>` values, typically a boxed trait object.
+    ///
+    /// Unlike a [`Provider::transient`], which still hands out a shared handle,
+    /// each [`resolve_boxed`](Injector::resolve_boxed) runs `factory` with the
+    /// resolving injector and returns the new box to the caller alone. The
+    /// factory is registered on this injector under [`BoxedFactory<T>`], so a
+    /// shared registration of `T` does not conflict with it. See the
+    /// [`boxed`](crate::boxed) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if a boxed factory
+    /// for `T` is already registered here.
+    #[track_caller]
+    pub fn try_provide_transient_dyn<T, F>(&self, factory: F) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Box<T> + 'static,
+    {
+        let factory = Shared::new(BoxedFactory::new(factory));
+        self.try_provide::<BoxedFactory<T>>(Provider::singleton(move |_| factory.clone()))
+    }
+
+    /// Registers a factory of owned `Box<T>` values, panicking on a duplicate.
+    ///
+    /// See [`try_provide_transient_dyn`](Injector::try_provide_transient_dyn).
+    #[track_caller]
+    pub fn provide_transient_dyn<T, F>(&self, factory: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Box<T> + 'static,
+    {
+        self.try_provide_transient_dyn::<T, F>(factory).unwrap();
+        self
+    }
+
+    /// Creates a new `Box<T>` with the factory registered by
+    /// [`provide_transient_dyn`](Injector::provide_transient_dyn).
+    pub fn try_resolve_boxed<T>(&self) -> Result<Box<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let factory = self.try_resolve::<BoxedFactory<T>>()?;
+        Ok(factory.create(self))
+    }
+
+    /// Creates a new `Box<T>`, panicking if no boxed factory is registered.
+    ///
+    /// See [`try_resolve_boxed`](Injector::try_resolve_boxed).
+    #[track_caller]
+    pub fn resolve_boxed<T>(&self) -> Box<T>
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_resolve_boxed::<T>().unwrap()
+    }
+
+    /// Checks an object out of the [`Pool<T>`] registered with [`Provider::pooled`].
+    ///
+    /// The returned guard gives exclusive access to the object and puts it
+    /// back into the pool when dropped. See the [`pool`](crate::pool) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve) if no `Pool<T>` is registered.
+    pub fn try_resolve_pooled<T>(&self) -> Result<PooledGuard<T>, Error>
+    where
+        T: 'static,
+    {
+        Ok(self.try_resolve::<Pool<T>>()?.acquire())
+    }
+
+    /// Checks an object out of the pool of `T`, panicking if none is registered.
+    ///
+    /// See [`try_resolve_pooled`](Injector::try_resolve_pooled).
+    #[track_caller]
+    pub fn resolve_pooled<T>(&self) -> PooledGuard<T>
+    where
+        T: 'static,
+    {
+        self.try_resolve_pooled::<T>().unwrap()
+    }
+
+    /// Registers a factory that receives a `P` supplied at resolve time.
+    ///
+    /// Each [`resolve_with_params`](Injector::resolve_with_params) runs
+    /// `factory` with the resolving injector and the given parameters; the
+    /// result is never cached. The factory is registered on this injector under
+    /// [`ParamFactory<T, P>`], so a plain registration of `T` does not conflict
+    /// with it. See the [`params`](crate::params) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if a factory for `T`
+    /// taking `P` is already registered here.
+    #[track_caller]
+    pub fn try_provide_with_params<T, P, F>(&self, factory: F) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        P: 'static,
+        F: Fn(&Injector, P) -> Shared<T> + 'static,
+    {
+        let factory = Shared::new(ParamFactory::new(factory));
+        self.try_provide::<ParamFactory<T, P>>(Provider::singleton(move |_| factory.clone()))
+    }
+
+    /// Registers a parameterized factory, panicking on a duplicate.
+    ///
+    /// See [`try_provide_with_params`](Injector::try_provide_with_params).
+    #[track_caller]
+    pub fn provide_with_params<T, P, F>(&self, factory: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        P: 'static,
+        F: Fn(&Injector, P) -> Shared<T> + 'static,
+    {
+        self.try_provide_with_params::<T, P, F>(factory).unwrap();
+        self
+    }
+
+    /// Creates a new `T` from `params` with the factory registered by
+    /// [`provide_with_params`](Injector::provide_with_params).
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve) if no factory for `T`
+    /// taking `P` is visible from this injector.
+    pub fn try_resolve_with_params<T, P>(&self, params: P) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+        P: 'static,
+    {
+        let factory = self.try_resolve::<ParamFactory<T, P>>()?;
+        Ok(factory.create(self, params))
+    }
+
+    /// Creates a new `T` from `params`, panicking if no factory is registered.
+    ///
+    /// See [`try_resolve_with_params`](Injector::try_resolve_with_params).
+    #[track_caller]
+    pub fn resolve_with_params<T, P>(&self, params: P) -> Shared<T>
+    where
+        T: ?Sized + 'static,
+        P: 'static,
+    {
+        self.try_resolve_with_params::<T, P>(params).unwrap()
+    }
+
+    /// Registers `provider` on this injector, replacing any local registration for `T`.
+    ///
+    /// Unlike [`provide`](Injector::provide), an override never errors on an
+    /// existing registration and always stays local to this injector: a
+    /// [`Scope::Root`] provider is cached here rather than in the root, so the
+    /// override is invisible to ancestors and siblings. Any instance of `T`
+    /// already cached on this injector is dropped.
+    ///
+    /// Root-scoped services that depend on `T` and are resolved through this
+    /// injector are still cached in the root, so override them as well if they
+    /// must see the replacement.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if this
+    /// injector is [frozen](Injector::freeze).
+    #[track_caller]
+    pub fn try_override_provider<T>(&self, mut provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        if provider.scope == Scope::Root {
+            provider.scope = Scope::Module;
+        }
+
+        self.track_gate(&provider);
+
+        let type_id = TypeId::of::<T>();
+
+        Self::evicted::<T>(
+            self.inner.instances.borrow_mut().remove(type_id),
+            EvictionReason::Replace,
+        );
+        self.insert_provider_entry(
+            &mut self.inner.providers.borrow_mut(),
+            type_id,
+            ProviderEntry::new(provider),
+        );
+
+        Ok(())
+    }
+
+    /// Replaces the local registration for `T`, panicking if this injector is frozen.
+    ///
+    /// See [`try_override_provider`](Injector::try_override_provider).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this injector is [frozen](Injector::freeze).
+    #[track_caller]
+    pub fn override_provider<T>(&self, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_override_provider::<T>(provider).unwrap();
+        self
+    }
+
+    /// Removes the local registration for `T`, returning whether there was one.
+    ///
+    /// Only this injector's own registration is removed: a provider inherited
+    /// from an ancestor keeps serving `T`, and [`Scope::Root`] providers live
+    /// on the root, so remove those through the root. Any instance of `T`
+    /// cached on this injector is dropped with it, so registering `T` again
+    /// starts from a fresh instance. Handles already resolved stay valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::singleton(|_| Shared::new(1)));
+    /// assert_eq!(*injector.resolve::<u32>(), 1);
+    ///
+    /// assert!(injector.remove_provider::<u32>());
+    /// assert!(injector.try_resolve::<u32>().is_err());
+    /// assert!(!injector.remove_provider::<u32>());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if this
+    /// injector is [frozen](Injector::freeze).
+    pub fn try_remove_provider<T>(&self) -> Result<bool, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        let type_id = TypeId::of::<T>();
+
+        Self::evicted::<T>(
+            self.inner.instances.borrow_mut().remove(type_id),
+            EvictionReason::Replace,
+        );
+        let removed = self.inner.providers.borrow_mut().remove(&type_id).is_some();
+
+        Ok(removed)
+    }
+
+    /// Removes the local registration for `T`, panicking if this injector is frozen.
+    ///
+    /// See [`try_remove_provider`](Injector::try_remove_provider).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this injector is [frozen](Injector::freeze).
+    #[track_caller]
+    pub fn remove_provider<T>(&self) -> bool
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_remove_provider::<T>().unwrap()
+    }
+
+    /// Wraps the registered provider for `T` so every new instance passes through `decorator`.
+    ///
+    /// The decorator receives the instance produced by the existing provider
+    /// together with the resolving injector, and returns the value that is
+    /// handed out instead. The registration keeps its scope and stays on the
+    /// injector that owns it, so a decorated singleton is decorated once and
+    /// then served from the cache. Instances cached before the decorator was
+    /// registered are not re-decorated.
+    ///
+    /// Decorators stack in registration order: the first one wraps the
+    /// original instance and each later one wraps the result of the previous
+    /// one, so the last decorator registered is the outermost layer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if no
+    /// provider for `T` is registered in this injector or any ancestor, and
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) if the injector owning
+    /// it is [frozen](Injector::freeze).
+    pub fn try_decorate<T, F>(&self, decorator: F) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        F: Fn(Shared<T>, &Injector) -> Shared<T> + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let type_name = std::any::type_name::<T>();
+        let decorator = Shared::new(decorator);
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(entry) = inner.providers.borrow_mut().get_mut(&type_id) {
+                inner.ensure_unfrozen()?;
+
+                let previous = entry
+                    .provider
+                    .clone()
+                    .downcast::<Provider<T>>()
+                    .map_err(|_| Error::type_mismatch_found(type_name, entry.type_name))?;
+
+                #[cfg(feature = "tracing")]
+                debug!("Decorating provider for {}", type_name);
+
+                entry.provider = Shared::new(Provider::<T> {
+                    scope: entry.scope,
+                    profiles: Profiles::default(),
+                    gate: None,
+                    priority: entry.priority,
+                    size: entry.size,
+                    eager: entry.eager,
+                    dependencies: entry.dependencies.clone(),
+                    deadline: previous.deadline,
+                    after_init: previous.after_init.clone(),
+                    marks: previous.marks.clone(),
+                    // The winner of a fallback chain is decorated too.
+                    fallbacks: previous.fallbacks.as_ref().map(|chain| {
+                        let decorator = decorator.clone();
+                        chain.then(move |inner, injector| (*decorator)(inner, injector))
+                    }),
+                    factory: Box::new(move |injector| {
+                        let inner = (previous.factory)(injector).value();
+                        Instance::new((*decorator)(inner, injector))
+                    }),
+                });
+
+                return Ok(());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Error::service_not_provided(type_name))
+    }
+
+    /// Wraps the registered provider for `T` with `decorator`.
+    ///
+    /// See [`try_decorate`](Injector::try_decorate) for ordering and caching.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no provider for `T` is registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Greeter {
+    ///     fn greet(&self) -> String;
+    /// }
+    ///
+    /// struct Plain;
+    /// impl Greeter for Plain {
+    ///     fn greet(&self) -> String {
+    ///         "hello".to_string()
+    ///     }
+    /// }
+    ///
+    /// struct Loud(Shared<dyn Greeter>);
+    /// impl Greeter for Loud {
+    ///     fn greet(&self) -> String {
+    ///         self.0.greet().to_uppercase()
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<dyn Greeter>(Provider::singleton(|_| Shared::new(Plain) as Shared<dyn Greeter>));
+    /// injector.decorate::<dyn Greeter, _>(|inner, _| Shared::new(Loud(inner)) as Shared<dyn Greeter>);
+    ///
+    /// assert_eq!(injector.resolve::<dyn Greeter>().greet(), "HELLO");
+    /// ```
+    #[track_caller]
+    pub fn decorate<T, F>(&self, decorator: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        F: Fn(Shared<T>, &Injector) -> Shared<T> + 'static,
+    {
+        self.try_decorate::<T, F>(decorator).unwrap();
+        self
+    }
+
+    /// Returns the nearest provider for `T`, with the type it was registered as.
+    pub(crate) fn get_provider<T>(&self) -> Option<(Shared<dyn Any>, &'static str)>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let local = self
+            .inner
+            .providers
+            .borrow()
+            .get(&type_id)
+            .map(|entry| (entry.provider.clone(), entry.type_name));
+
+        if local.is_some() {
+            return local;
+        }
+
+        if let Some(parent) = &self.inner.parent {
+            let parent_injector = Injector {
+                inner: parent.clone(),
+            };
+            return parent_injector.get_provider::<T>();
+        }
+
+        None
+    }
+
+    pub(crate) fn resolve_provider<T>(&self) -> Result<Shared<Provider<T>>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+
+        let (any_provider, registered_as) = self
+            .get_provider::<T>()
+            .ok_or_else(|| Error::service_not_provided(type_name))?;
+
+        let provider = any_provider
+            .downcast::<Provider<T>>()
+            .map_err(|_| Error::type_mismatch_found(type_name, registered_as))?;
+
+        Ok(provider)
+    }
+
+    pub(crate) fn resolve_instance<T>(&self) -> Result<Shared<Instance<T>>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        self.create_instance(self.resolve_provider::<T>()?)
+    }
+
+    /// Runs the factory of `provider_ref`, passing the result through any matching decorators.
+    fn create_instance<T>(
+        &self,
+        provider_ref: Shared<Provider<T>>,
+    ) -> Result<Shared<Instance<T>>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = resolve_span::<T>().entered();
+
+        // The provider is an owned handle, so the factory runs without any map
+        // borrowed and may itself resolve or register providers.
+        let instance = (provider_ref.factory)(self);
+
+        if !self.root_inner().decorating.load(Ordering::Relaxed) {
+            return Ok(Shared::new(instance));
+        }
+
+        let value = self.decorate_matching_instance(instance.value())?;
+        Ok(Shared::new(Instance::new(value)))
+    }
+
+    /// Runs the [`after_init`](Provider::after_init) hook of `provider` on a new instance.
+    fn run_after_init<T>(&self, provider: &Provider<T>, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let Some(hook) = &provider.after_init else {
+            return Ok(());
+        };
+
+        hook(value, self)
+            .map_err(|reason| Error::factory_execution_failed(std::any::type_name::<T>(), &reason))
+    }
+
+    pub(crate) fn store_instance<T>(&self, instance: Shared<Instance<T>>)
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        self.inner.instances.borrow_mut().insert(type_id, instance);
+    }
+
+    #[track_caller]
+    pub(crate) fn store_provider<T>(
+        &self,
+        provider: Provider<T>,
+        module: Option<&'static str>,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let type_name = std::any::type_name::<T>();
+
+        self.inner.ensure_unfrozen()?;
+        self.track_gate(&provider);
+
+        let mut providers = self.inner.providers.borrow_mut();
+        if let Some(existing) = providers.get(&type_id) {
+            match provider.priority.cmp(&existing.priority) {
+                std::cmp::Ordering::Less => {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Ignoring provider for {} outranked by the registered one",
+                        type_name
+                    );
+
+                    return Ok(());
+                }
+                std::cmp::Ordering::Equal => {
+                    return Err(Error::provider_already_registered(
+                        type_name,
+                        provider.scope.to_string().as_str(),
+                    ));
+                }
+                std::cmp::Ordering::Greater => {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Replacing provider for {} with a higher priority one",
+                        type_name
+                    );
+                }
+            }
+        }
+        let replaced = self
+            .insert_provider_entry(
+                &mut providers,
+                type_id,
+                ProviderEntry {
+                    module,
+                    ..ProviderEntry::new(provider)
+                },
+            )
+            .is_some();
+        drop(providers);
+
+        // An instance built by the outranked provider must not outlive it.
+        if replaced {
+            Self::evicted::<T>(
+                self.inner.instances.borrow_mut().remove(type_id),
+                EvictionReason::Replace,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Drops the cached instance of `T`, so the next resolve creates a new one.
+    ///
+    /// The cache entry nearest to this injector is removed, which for a
+    /// root-scoped provider is the one on the root. Handles to the old instance
+    /// held elsewhere stay valid. Returns `false` if nothing was cached.
+    pub fn clear_singleton<T>(&self) -> bool
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if Self::evicted::<T>(
+                inner.instances.borrow_mut().remove(type_id),
+                EvictionReason::Manual,
+            ) {
+                return true;
+            }
+
+            // Mirrors `get_instance`: nothing above the registration is visible.
+            if inner.providers.borrow().contains_key(&type_id) {
+                return false;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Looks up a cached instance of `T` in this injector and its ancestors.
+    ///
+    /// The cache is walked by reference and the stored instance is borrowed via
+    /// `downcast_ref`, so a hit costs a single `Shared::clone` of the value.
+    pub(crate) fn get_instance<T>(&self) -> Option<Shared<T>>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+
+        // The cache holds the `Shared<T>` the factory returned, already unsized
+        // for a trait object, so a hit is a downcast and a reference count bump.
+        while let Some(inner) = current {
+            if let Some(instance) = inner
+                .instances
+                .borrow()
+                .get(type_id)
+                .and_then(|instance| instance.downcast_ref::<Instance<T>>())
+            {
+                return Some(instance.value());
+            }
+
+            // The nearest registration shadows anything cached further up,
+            // which is what lets `override_provider` win over a parent's cache.
+            if inner.providers.borrow().contains_key(&type_id) {
+                return None;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Resolves an instance of `T`, returning an [`Error`] instead of panicking.
+    ///
+    /// `T` may be a concrete type or a trait object. A trait object is resolved
+    /// under the exact type it was registered with, so a provider registered as
+    /// `Provider<dyn Trait>` yields a `Shared<dyn Trait>`; the unsizing happens
+    /// once, inside the factory.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+    /// nothing is registered for `T`, and with
+    /// [`CircularDependency`](crate::ErrorKind::CircularDependency) if resolving
+    /// `T` requires `T` itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Clock {
+    ///     fn now(&self) -> u64;
+    /// }
+    ///
+    /// struct FixedClock(u64);
+    /// impl Clock for FixedClock {
+    ///     fn now(&self) -> u64 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<FixedClock>(Provider::singleton(|_| Shared::new(FixedClock(1))));
+    /// injector.provide::<dyn Clock>(Provider::singleton(|_| {
+    ///     Shared::new(FixedClock(2)) as Shared<dyn Clock>
+    /// }));
+    ///
+    /// let concrete: Shared<FixedClock> = injector.try_resolve::<FixedClock>().unwrap();
+    /// let clock: Shared<dyn Clock> = injector.try_resolve::<dyn Clock>().unwrap();
+    ///
+    /// assert_eq!(concrete.now(), 1);
+    /// assert_eq!(clock.now(), 2);
+    /// ```
+    pub fn try_resolve<T>(&self) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        self.record_resolution::<T>();
+
+        if let Some(weak) = self.resolve_weak::<T>() {
+            return Ok(weak);
+        }
+
+        self.ensure_running()?;
+
+        let node = if self.diagnostics_enabled() {
+            TreeNode::enter::<T>()
+        } else {
+            None
+        };
+
+        if self.root_inner().gated.load(Ordering::Relaxed) {
+            self.check_gate::<T>().inspect_err(|err| {
+                diagnostics::note(&node, || Note::Failed(err.message.clone()))
+            })?;
+        }
+
+        // A cached instance cannot be part of a cycle, so the hit path skips
+        // the resolve guard entirely.
+        if let Some(value) = self.get_instance::<T>() {
+            diagnostics::note(&node, || Note::Cached);
+            return Ok(self.intercept(value));
+        }
+
+        if self.root_inner().deferring.load(Ordering::Relaxed) {
+            self.load_lazy_module(TypeId::of::<T>())
+                .inspect_err(|err| {
+                    diagnostics::note(&node, || Note::Failed(err.message.clone()))
+                })?;
+        }
+
+        let _guard = ResolveGuard::push(TypeId::of::<T>())
+            .inspect_err(|err| diagnostics::note(&node, || Note::Failed(err.message.clone())))?;
+
+        let provider = match self.resolve_provider::<T>() {
+            Err(err) if err.kind == ErrorKind::ServiceNotProvided => {
+                match self.resolve_generic::<T>() {
+                    Some(value) => {
+                        diagnostics::note(&node, || Note::Created(Scope::Root));
+                        return value.map(|value| self.intercept(value));
+                    }
+                    None => Err(err),
+                }
+            }
+            resolved => resolved,
+        }
+        .inspect_err(|err| diagnostics::note(&node, || Note::Failed(err.message.clone())))?;
+
+        diagnostics::note(&node, || Note::Created(provider.scope));
+
+        #[cfg(feature = "timing")]
+        let started = std::time::Instant::now();
+
+        let instance = match &provider.fallbacks {
+            Some(chain) => Shared::new(Instance::new(chain.resolve(self)?)),
+            None => self.resolve_instance::<T>()?,
+        };
+
+        #[cfg(feature = "timing")]
+        self.record_timing::<T>(started.elapsed());
+
+        if provider.scope == Scope::Transient {
+            self.run_after_init(&provider, instance.get())?;
+            return Ok(self.intercept(instance.value()));
+        }
+
+        match provider.scope {
+            Scope::Root => {
+                let root = self.root_injector();
+                root.store_instance::<T>(instance.clone());
+            }
+
+            Scope::Module => {
+                self.store_instance::<T>(instance.clone());
+            }
+
+            Scope::Transient => unreachable!(),
+        }
+
+        // A failed hook must not leave its instance behind for the next resolve.
+        self.run_after_init(&provider, instance.get())
+            .inspect_err(|_| {
+                self.clear_singleton::<T>();
+            })?;
+
+        Ok(self.intercept(instance.value()))
+    }
+
+    #[track_caller]
+    pub fn resolve<T>(&self) -> Shared<T>
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_resolve::<T>().unwrap()
+    }
+
+    /// Resolves `T` like [`try_resolve`](Injector::try_resolve), together with
+    /// the [`ProviderMeta`] of the provider that produced it.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve), and with
+    /// [`ServiceNotProvided`](ErrorKind::ServiceNotProvided) if `T` was not
+    /// registered through a provider, such as a member of a
+    /// [generic family](Injector::provide_generic_fn).
+    pub fn resolve_with_meta<T>(&self) -> Result<(Shared<T>, ProviderMeta), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let was_cached = self.get_instance::<T>().is_some();
+        let value = self.try_resolve::<T>()?;
+
+        let meta = self
+            .provider_meta(TypeId::of::<T>(), was_cached)
+            .ok_or_else(|| Error::service_not_provided(std::any::type_name::<T>()))?;
+
+        Ok((value, meta))
+    }
+
+    /// Registers `factory` as the provider of every type in the generic family `F`.
+    ///
+    /// Whenever a resolve finds no provider for a type, `factory` is called
+    /// with that type's [`TypeId`] and may return an instance of exactly that
+    /// type, or `None` if it does not belong to the family. The instance is
+    /// cached on this injector, so each concrete type is created once. `F`
+    /// only names the registration; see the [`generic`](crate::generic) module
+    /// for the limits of this approach.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if this injector already has a factory for `F`.
+    pub fn try_provide_generic_fn<F, G>(&self, factory: G) -> Result<(), Error>
+    where
+        F: ?Sized + 'static,
+        G: Fn(&Injector, TypeId) -> Option<ErasedInstance> + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        let family = TypeId::of::<F>();
+        let mut factories = self.inner.generic_factories.borrow_mut();
+        if factories
+            .iter()
+            .any(|(registered, _)| *registered == family)
+        {
+            return Err(Error::provider_already_registered(
+                std::any::type_name::<F>(),
+                "generic",
+            ));
+        }
+        factories.push((
+            family,
+            Shared::new(GenericFactory {
+                create: Box::new(factory),
+            }),
+        ));
+
+        Ok(())
+    }
+
+    /// Registers `factory` for the generic family `F`, panicking on conflict.
+    ///
+    /// See [`try_provide_generic_fn`](Injector::try_provide_generic_fn).
+    #[track_caller]
+    pub fn provide_generic_fn<F, G>(&self, factory: G) -> &Self
+    where
+        F: ?Sized + 'static,
+        G: Fn(&Injector, TypeId) -> Option<ErasedInstance> + 'static,
+    {
+        self.try_provide_generic_fn::<F, G>(factory).unwrap();
+        self
+    }
+
+    /// Returns `true` if a type [`contains`](Injector::contains) does not see may
+    /// still resolve, through a lazy module or a generic factory on the path.
+    fn may_provide_unregistered(&self) -> bool {
+        if self.root_inner().deferring.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let mut current = Some(&*self.inner);
+        while let Some(inner) = current {
+            if !inner.generic_factories.borrow().is_empty() {
+                return true;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Creates `T` with the nearest generic factory that recognizes it.
+    ///
+    /// The instance is cached beside that factory, where `get_instance` finds
+    /// it on later resolves. Returns `None` if no factory claims `T`.
+    fn resolve_generic<T>(&self) -> Option<Result<Shared<T>, Error>>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+        while let Some(inner) = current {
+            // Cloned out so a factory may itself resolve without a held lock.
+            let factories = inner.generic_factories.borrow().clone();
+
+            for (_, factory) in &factories {
+                let Some(instance) = (factory.create)(self, type_id) else {
+                    continue;
+                };
+                let Some(value) = instance.downcast::<T>() else {
+                    return Some(Err(Error::type_mismatch_found(
+                        std::any::type_name::<T>(),
+                        instance.type_name(),
+                    )));
+                };
+
+                // A concurrent resolve may have cached one first; keep that one.
+                let mut instances = inner.instances.borrow_mut();
+                if let Some(existing) = instances
+                    .get(type_id)
+                    .and_then(|instance| instance.downcast_ref::<Instance<T>>())
+                {
+                    return Some(Ok(existing.value()));
+                }
+                instances.insert(type_id, Shared::new(Instance::new(value.clone())));
+                return Some(Ok(value));
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Resolves `T` as a handle that cannot outlive this injector.
+    ///
+    /// The returned [`ScopedShared`] borrows `self`, so keeping it after a
+    /// request-scoped child injector is dropped fails to compile. See the
+    /// [`scoped`](crate::scoped) module for the trade-offs against
+    /// [`resolve`](Injector::resolve).
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve).
+    pub fn try_resolve_scoped<T>(&self) -> Result<ScopedShared<'_, T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        Ok(ScopedShared::new(self.try_resolve::<T>()?, self))
+    }
+
+    /// Resolves `T` as a handle tied to this injector, panicking on failure.
+    ///
+    /// See [`try_resolve_scoped`](Injector::try_resolve_scoped).
+    #[track_caller]
+    pub fn resolve_scoped<T>(&self) -> ScopedShared<'_, T>
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_resolve_scoped::<T>().unwrap()
+    }
+
+    /// Registers a plain `Copy` value on this injector, such as a port or a flag.
+    ///
+    /// The value is stored once and read back by value with
+    /// [`resolve_copy`](Injector::resolve_copy), which skips the provider,
+    /// caching and interception machinery of [`resolve`](Injector::resolve)
+    /// and hands out no [`Shared`] handle. Copy values live apart from
+    /// providers: they are not visible to `resolve`, and a provider of the
+    /// same `T` does not conflict with them.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if a copy value of `T` is already registered here, and with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) if this injector is
+    /// frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::Injector;
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Port(u16);
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide_copy(Port(8080));
+    ///
+    /// let Port(port) = injector.resolve_copy::<Port>();
+    /// assert_eq!(port, 8080);
+    /// ```
+    #[track_caller]
+    pub fn try_provide_copy<T>(&self, value: T) -> Result<(), Error>
+    where
+        T: Copy + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        let mut copies = self.inner.copies.borrow_mut();
+        if copies.contains_key(&TypeId::of::<T>()) {
+            return Err(Error::provider_already_registered(
+                std::any::type_name::<T>(),
+                "copy",
+            ));
+        }
+        copies.insert(TypeId::of::<T>(), Shared::new(value));
+
+        Ok(())
+    }
+
+    /// Registers a plain `Copy` value, panicking on a duplicate.
+    ///
+    /// See [`try_provide_copy`](Injector::try_provide_copy).
+    #[track_caller]
+    pub fn provide_copy<T>(&self, value: T) -> &Self
+    where
+        T: Copy + 'static,
+    {
+        self.try_provide_copy::<T>(value).unwrap();
+        self
+    }
+
+    /// Returns the `Copy` value registered with [`provide_copy`](Injector::provide_copy).
+    ///
+    /// The nearest registration, starting from this injector and walking up
+    /// through its parents, wins.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided)
+    /// if no copy value of `T` is visible from this injector, and with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) once the container is
+    /// [shutting down](Injector::shutdown).
+    pub fn try_resolve_copy<T>(&self) -> Result<T, Error>
+    where
+        T: Copy + 'static,
+    {
+        self.ensure_running()?;
+
+        let type_id = TypeId::of::<T>();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(value) = inner
+                .copies
+                .borrow()
+                .get(&type_id)
+                .and_then(|value| value.downcast_ref::<T>())
+            {
+                return Ok(*value);
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Error::service_not_provided(std::any::type_name::<T>()))
+    }
+
+    /// Returns the registered `Copy` value of `T`, panicking if there is none.
+    ///
+    /// See [`try_resolve_copy`](Injector::try_resolve_copy).
+    #[track_caller]
+    pub fn resolve_copy<T>(&self) -> T
+    where
+        T: Copy + 'static,
+    {
+        self.try_resolve_copy::<T>().unwrap()
+    }
+
+    /// Resolves `T` and returns an owned clone of it.
+    ///
+    /// Prefer [`resolve`](Injector::resolve), which hands out the shared
+    /// instance, or [`with`](Injector::with), which lends it: both are a
+    /// reference count bump at most. Reach for this only when the caller must
+    /// own and mutate its copy, since it clones `T` on every call; for a large
+    /// configuration that is an allocation per field on every request.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve).
+    pub fn try_resolve_cloned<T>(&self) -> Result<T, Error>
+    where
+        T: Clone + 'static,
+    {
+        Ok(T::clone(&*self.try_resolve::<T>()?))
+    }
+
+    /// Resolves `T` and returns an owned clone of it, panicking on failure.
+    ///
+    /// See [`try_resolve_cloned`](Injector::try_resolve_cloned).
+    #[track_caller]
+    pub fn resolve_cloned<T>(&self) -> T
+    where
+        T: Clone + 'static,
+    {
+        self.try_resolve_cloned::<T>().unwrap()
+    }
+
+    /// Resolves `T` and passes a reference to it to `f`, returning what `f` returns.
+    ///
+    /// This costs the same as [`resolve`](Injector::resolve): `T` itself is
+    /// never cloned, and the handle to it is dropped when `f` returns. It reads
+    /// well when only a few fields of a large shared value, such as a
+    /// configuration, are needed:
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// #[derive(Clone)]
+    /// struct Config {
+    ///     port: u16,
+    ///     allowed_origins: Vec<String>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| {
+    ///     Shared::new(Config {
+    ///         port: 8080,
+    ///         allowed_origins: vec!["https://example.com".to_string()],
+    ///     })
+    /// }));
+    ///
+    /// let port = injector.with::<Config, _>(|config| config.port);
+    /// assert_eq!(port, 8080);
+    ///
+    /// // An owned copy, for when the caller must mutate it.
+    /// let mut config = injector.resolve_cloned::<Config>();
+    /// config.allowed_origins.clear();
+    /// assert_eq!(injector.resolve::<Config>().allowed_origins.len(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve); `f` is not called.
+    pub fn try_with<T, R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        Ok(f(&*self.try_resolve::<T>()?))
+    }
+
+    /// Resolves `T` and passes a reference to it to `f`, panicking on failure.
+    ///
+    /// See [`try_with`](Injector::try_with).
+    #[track_caller]
+    pub fn with<T, R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_with::<T, R>(f).unwrap()
+    }
+
+    /// Resolves the cached instance of `T` and leaks a handle to it, returning a `&'static T`.
+    ///
+    /// Hot paths can then read the singleton without resolving it or touching
+    /// a reference count. The leaked handle keeps the instance alive for the
+    /// rest of the program, so it is intentionally never dropped: not when the
+    /// injector is dropped, nor when [`clear_singleton`](Injector::clear_singleton)
+    /// or an override replaces it in the cache, in which case later resolves
+    /// return the new instance while the `&'static T` keeps pointing at the old
+    /// one. Each call leaks one more handle, so call it once at startup and keep
+    /// the reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Config {
+    ///     port: u16,
+    /// }
+    ///
+    /// fn port(config: &'static Config) -> u16 {
+    ///     config.port
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+    ///
+    /// let config: &'static Config = injector.leak_singleton::<Config>();
+    /// drop(injector);
+    /// assert_eq!(port(config), 8080);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve), and with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) if `T` is not cached,
+    /// as with a transient provider, since every call would leak a new instance.
+    pub fn try_leak_singleton<T>(&self) -> Result<&'static T, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let instance = self.try_resolve::<T>()?;
+
+        if self.get_instance::<T>().is_none() {
+            return Err(Error::invalid_scope(&format!(
+                "{} is not cached, so leaking it would leak an instance per call",
+                std::any::type_name::<T>()
+            )));
+        }
+
+        let leaked: &'static Shared<T> = Box::leak(Box::new(instance));
+        Ok(&**leaked)
+    }
+
+    /// Resolves and leaks the cached instance of `T`, panicking on failure.
+    ///
+    /// See [`try_leak_singleton`](Injector::try_leak_singleton).
+    #[track_caller]
+    pub fn leak_singleton<T>(&self) -> &'static T
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_leak_singleton::<T>().unwrap()
+    }
+
+    /// Returns `true` if a provider for `T` is registered in this injector or any ancestor.
+    ///
+    /// The lookup never constructs an [`Error`], which keeps probing for
+    /// unregistered types free of formatting and logging. On a child injector a
+    /// miss is also remembered until the next registration anywhere in the tree,
+    /// so repeated probes for an absent type skip walking the ancestors.
+    ///
+    /// [`WeakInjector`] is always available, so it is always contained. A member
+    /// of a [generic family](Injector::provide_generic_fn) or a type declared by
+    /// a [lazy module](Injector::provide_lazy_module) not loaded yet is not a
+    /// registration and is not contained, although
+    /// [`optional_resolve`](Injector::optional_resolve) still finds it.
+    pub fn contains<T>(&self) -> bool
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        if type_id == TypeId::of::<WeakInjector>() {
+            return true;
+        }
+
+        // The root answers with one lookup; only walking ancestors is worth caching.
+        if self.inner.parent.is_none() {
+            return self.inner.providers.borrow().contains_key(&type_id);
+        }
+
+        let registrations = self.root_inner().registrations.load(Ordering::Acquire);
+
+        {
+            let (seen_at, unregistered) = &*self.inner.unregistered.borrow();
+            if *seen_at == registrations && unregistered.contains(&type_id) {
+                return false;
+            }
+        }
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if inner.providers.borrow().contains_key(&type_id) {
+                return true;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        let mut cache = self.inner.unregistered.borrow_mut();
+        if cache.0 != registrations {
+            *cache = (registrations, HashSet::new());
+        }
+        cache.1.insert(type_id);
+
+        false
+    }
+
+    pub fn optional_resolve<T>(&self) -> Option<Shared<T>>
+    where
+        T: ?Sized + 'static,
+    {
+        // Bail out before `try_resolve` would build a `ServiceNotProvided` error,
+        // unless a generic factory or a lazy module may still provide `T`.
+        if !self.contains::<T>() && !self.may_provide_unregistered() {
+            return None;
+        }
+
+        self.try_resolve::<T>().ok()
+    }
+
+    /// Resolves `T`, or returns `T::default()` if no provider for `T` is available.
+    ///
+    /// This is a fallback at the call site, for optional settings whose absence
+    /// just means defaults; nothing is registered or cached, so each miss
+    /// builds a new default. A provider that is gated shut counts as absent.
+    /// Unlike [`optional_resolve`](Injector::optional_resolve), other failures
+    /// are not swallowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// #[derive(Default)]
+    /// struct RetryTuning {
+    ///     attempts: u32,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// assert_eq!(injector.resolve_or_default::<RetryTuning>().attempts, 0);
+    ///
+    /// injector.provide::<RetryTuning>(Provider::root(|_| Shared::new(RetryTuning { attempts: 3 })));
+    /// assert_eq!(injector.resolve_or_default::<RetryTuning>().attempts, 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`try_resolve`](Injector::try_resolve) other than
+    /// [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) for `T`,
+    /// such as a failed factory.
+    pub fn try_resolve_or_default<T>(&self) -> Result<Shared<T>, Error>
+    where
+        T: Default + 'static,
+    {
+        if !self.contains::<T>() && !self.may_provide_unregistered() {
+            return Ok(Shared::new(T::default()));
+        }
+
+        match self.try_resolve::<T>() {
+            Err(err) if err.kind == ErrorKind::ServiceNotProvided => Ok(Shared::new(T::default())),
+            result => result,
+        }
+    }
+
+    /// Resolves `T` or falls back to `T::default()`, panicking on other failures.
+    ///
+    /// See [`try_resolve_or_default`](Injector::try_resolve_or_default).
+    #[track_caller]
+    pub fn resolve_or_default<T>(&self) -> Shared<T>
+    where
+        T: Default + 'static,
+    {
+        self.try_resolve_or_default::<T>().unwrap()
+    }
+
+    /// Passes every resolved `T` through `interceptor` before it is returned.
+    ///
+    /// Unlike [`decorate`](Injector::decorate), which wraps the provider once at
+    /// registration, an interceptor runs on every resolve, including cache hits
+    /// and resolves made by other factories, and can be swapped or removed at
+    /// any time. That makes it a fit for end-to-end tests that substitute a
+    /// recording spy. Interceptors are installed on the root, so they apply to
+    /// the whole tree; setting one for a type that has one replaces it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(1)));
+    ///
+    /// injector.set_interceptor::<u32, _>(|original| Shared::new(*original + 1));
+    /// assert_eq!(*injector.resolve::<u32>(), 2);
+    ///
+    /// injector.remove_interceptor::<u32>();
+    /// assert_eq!(*injector.resolve::<u32>(), 1);
+    /// ```
+    pub fn set_interceptor<T, F>(&self, interceptor: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        F: Fn(Shared<T>) -> Shared<T> + 'static,
+    {
+        let interceptor: Interceptor<T> = Box::new(interceptor);
+
+        let root = self.root_inner();
+        root.interceptors
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), Shared::new(interceptor));
+        root.intercepting.store(true, Ordering::Relaxed);
+
+        self
+    }
+
+    /// Removes the interceptor for `T`, if any. Returns `true` if one was removed.
+    pub fn remove_interceptor<T>(&self) -> bool
+    where
+        T: ?Sized + 'static,
+    {
+        self.root_inner()
+            .interceptors
+            .borrow_mut()
+            .remove(&TypeId::of::<T>())
+            .is_some()
+    }
+
+    fn intercept<T>(&self, value: Shared<T>) -> Shared<T>
+    where
+        T: ?Sized + 'static,
+    {
+        let root = self.root_inner();
+        if !root.intercepting.load(Ordering::Relaxed) {
+            return value;
+        }
+
+        // Cloned out so the interceptor may itself resolve without a held lock.
+        let interceptor = root.interceptors.borrow().get(&TypeId::of::<T>()).cloned();
+
+        match interceptor
+            .as_deref()
+            .and_then(|interceptor| interceptor.downcast_ref::<Interceptor<T>>())
+        {
+            Some(interceptor) => interceptor(value),
+            None => value,
+        }
+    }
+
+    /// Wraps every new instance whose type name satisfies `matches` with `wrap`.
+    ///
+    /// Unlike [`decorate`](Injector::decorate), which targets one type, this
+    /// selects types at runtime by their [`std::any::type_name`], so a single
+    /// decorator can wrap, say, every repository. `wrap` receives each instance
+    /// as an [`ErasedInstance`] and must return one of the same type; see the
+    /// [`erased`](crate::erased) module for the constraints this implies.
+    ///
+    /// Matching decorators are kept on the root and apply to the whole tree, to
+    /// providers registered both before and after them. They run in the order
+    /// they were added, after any [`decorate`](Injector::decorate) layers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if the root is
+    /// [frozen](Injector::freeze).
+    pub fn try_decorate_matching<P, W>(&self, matches: P, wrap: W) -> Result<(), Error>
+    where
+        P: Fn(&str) -> bool + 'static,
+        W: Fn(ErasedInstance, &Injector) -> ErasedInstance + 'static,
+    {
+        let root = self.root_inner();
+        root.ensure_unfrozen()?;
+
+        root.matching_decorators
+            .borrow_mut()
+            .push(Shared::new(MatchingDecorator {
+                matches: Box::new(matches),
+                wrap: Box::new(wrap),
+            }));
+        root.decorating.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Like [`try_decorate_matching`](Injector::try_decorate_matching), but panics on error.
+    #[track_caller]
+    pub fn decorate_matching<P, W>(&self, matches: P, wrap: W) -> &Self
+    where
+        P: Fn(&str) -> bool + 'static,
+        W: Fn(ErasedInstance, &Injector) -> ErasedInstance + 'static,
+    {
+        self.try_decorate_matching(matches, wrap).unwrap();
+        self
+    }
+
+    /// Passes a new instance through every matching decorator whose predicate accepts `T`.
+    fn decorate_matching_instance<T>(&self, value: Shared<T>) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+
+        // Cloned out so a decorator may itself resolve without a held lock.
+        let decorators = self.root_inner().matching_decorators.borrow().clone();
+
+        let mut instance = ErasedInstance::new(value);
+        for decorator in decorators.iter().filter(|d| (d.matches)(type_name)) {
+            instance = (decorator.wrap)(instance, self);
+        }
+
+        instance
+            .downcast::<T>()
+            .ok_or_else(|| Error::type_mismatch_found(type_name, instance.type_name()))
+    }
+
+    /// Registers an [`AsyncProvider`] for `T`, returning an [`Error`] on conflict.
+    ///
+    /// Root-scoped providers, the only kind [`Provider::async_root`] creates, are
+    /// registered on the root injector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if `T` already has a synchronous or asynchronous provider there.
+    #[cfg(feature = "async")]
+    pub fn try_provide_async<T>(&self, provider: AsyncProvider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let target = match provider.scope {
+            Scope::Root => self.root_injector(),
+            Scope::Module | Scope::Transient => self.clone(),
+        };
+        target.inner.ensure_unfrozen()?;
+
+        let type_id = TypeId::of::<T>();
+        let mut providers = target.inner.async_providers.borrow_mut();
+        if providers.contains_key(&type_id)
+            || target.inner.providers.borrow().contains_key(&type_id)
+        {
+            return Err(Error::provider_already_registered(
+                std::any::type_name::<T>(),
+                provider.scope.to_string().as_str(),
+            ));
+        }
+
+        providers.insert(type_id, Shared::new(provider));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[track_caller]
+    pub fn provide_async<T>(&self, provider: AsyncProvider<T>) -> &Self
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_provide_async::<T>(provider).unwrap();
+        self
+    }
+
+    /// Resolves `T` from its [`AsyncProvider`], awaiting the factory on first use.
+    ///
+    /// A cached instance is returned without awaiting anything. Otherwise the
+    /// factory runs, retried according to its [`RetryPolicy`](crate::RetryPolicy),
+    /// and a successful result is cached. Once cached, the instance is also
+    /// returned by the synchronous [`resolve`](Injector::resolve).
+    ///
+    /// Concurrent resolves of a singleton share a single factory run and all
+    /// return its instance. Initialization is cancel-safe: if the resolve
+    /// running the factory is dropped mid-way, or its factory fails, one of
+    /// the waiting resolves runs the factory again in its place, and the first
+    /// success is cached for everyone.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+    /// `T` has no async provider, and otherwise with the factory's last error.
+    #[cfg(feature = "async")]
+    pub async fn resolve_async<T>(&self) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        self.ensure_running()?;
+
+        if let Some(value) = self.get_instance::<T>() {
+            return Ok(self.intercept(value));
+        }
+
+        let type_id = TypeId::of::<T>();
+        let type_name = std::any::type_name::<T>();
+
+        let provider = self
+            .get_async_provider(type_id)
+            .ok_or_else(|| Error::service_not_provided(type_name))?
+            .downcast::<AsyncProvider<T>>()
+            .map_err(|_| Error::type_mismatch(type_name))?;
+
+        let holder = match provider.scope {
+            Scope::Root => self.root_injector(),
+            Scope::Module => self.clone(),
+            Scope::Transient => return Ok(self.intercept(self.create_async(&provider).await?)),
+        };
+
+        // The cell admits one factory run at a time. Dropping the future that
+        // holds it hands the run to the next waiter rather than poisoning it.
+        let cell = holder.async_init_cell::<T>();
+        let value = cell
+            .get_or_try_init(|| async {
+                let value = self.create_async(&provider).await?;
+                Ok::<_, Error>(holder.cache_async_instance(value))
+            })
+            .await?
+            .clone();
+
+        Ok(self.intercept(value))
+    }
+
+    /// Runs the factory of `provider`, retrying as configured, inside a resolve span.
+    #[cfg(feature = "async")]
+    async fn create_async<T>(&self, provider: &AsyncProvider<T>) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let create = provider.create(self);
+        // Instrumented rather than entered, so the span follows the factory
+        // across its `.await` points, including retries.
+        #[cfg(feature = "tracing")]
+        let create = create.instrument(resolve_span::<T>());
+
+        create.await
+    }
+
+    /// Returns the cell through which async resolves initialize `T` on this injector.
+    ///
+    /// A set cell whose instance is no longer cached here belongs to a cleared
+    /// singleton, so it is replaced by a fresh one.
+    #[cfg(feature = "async")]
+    fn async_init_cell<T>(&self) -> Shared<OnceCell<Shared<T>>>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let mut cells = self.inner.async_inits.borrow_mut();
+
+        let current = cells
+            .get(&type_id)
+            .and_then(|cell| cell.clone().downcast::<OnceCell<Shared<T>>>().ok());
+        if let Some(cell) = current
+            && (!cell.initialized() || self.inner.instances.borrow().get(type_id).is_some())
+        {
+            return cell;
+        }
+
+        let cell = Shared::new(OnceCell::new());
+        cells.insert(type_id, cell.clone());
+        cell
+    }
+
+    /// Caches `value` as this injector's instance of `T`, unless one is cached already.
+    ///
+    /// Returns whichever instance ends up cached.
+    #[cfg(feature = "async")]
+    fn cache_async_instance<T>(&self, value: Shared<T>) -> Shared<T>
+    where
+        T: ?Sized + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let mut instances = self.inner.instances.borrow_mut();
+
+        if let Some(existing) = instances
+            .get(type_id)
+            .and_then(|instance| instance.downcast_ref::<Instance<T>>())
+        {
+            return existing.value();
+        }
+        instances.insert(type_id, Shared::new(Instance::new(value.clone())));
+
+        value
+    }
+
+    #[cfg(feature = "async")]
+    fn get_async_provider(&self, type_id: TypeId) -> Option<Shared<dyn Any>> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(provider) = inner.async_providers.borrow().get(&type_id) {
+                return Some(provider.clone());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl Injector {
+    /// Registers `provider` for `T` under `key`, alongside any other keyed providers of `T`.
+    ///
+    /// Unlike [`try_provide`](Injector::try_provide), several providers of the
+    /// same `T` can coexist as long as their keys differ. They are resolved
+    /// together, in key order, with [`resolve_ordered`](Injector::resolve_ordered).
+    /// Scopes, profiles and gates apply to each provider as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if `key` is already taken for `T` on the target injector.
+    pub fn try_provide_keyed<T, K>(&self, key: K, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        if !provider.profiles.is_active(self.profile().as_deref()) {
+            return Ok(());
+        }
+
+        match provider.scope {
+            Scope::Root => self.root_injector().store_keyed(key, provider),
+            Scope::Module | Scope::Transient => self.store_keyed(key, provider),
+        }
+    }
+
+    #[track_caller]
+    pub fn provide_keyed<T, K>(&self, key: K, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        self.try_provide_keyed::<T, K>(key, provider).unwrap();
+        self
+    }
+
+    /// Registers a root-scoped keyed provider whose factory receives its key.
+    ///
+    /// Useful when the key parameterizes the instance, such as a database URL
+    /// for a pool: the factory is called with the key it was registered under,
+    /// so one closure can serve every key. See [`try_provide_keyed`](Injector::try_provide_keyed)
+    /// for how keyed providers are resolved and when registration fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Shared};
+    ///
+    /// struct Pool {
+    ///     url: String,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// for url in ["postgres://primary", "postgres://replica"] {
+    ///     injector.provide_keyed_with::<Pool, String, _>(url.to_string(), |_, url| {
+    ///         Shared::new(Pool { url: url.clone() })
+    ///     });
+    /// }
+    ///
+    /// let replica = injector.resolve_keyed::<Pool, String>(&"postgres://replica".to_string());
+    /// assert_eq!(replica.url, "postgres://replica");
+    /// ```
+    pub fn try_provide_keyed_with<T, K, F>(&self, key: K, factory: F) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+        F: Fn(&Injector, &K) -> Shared<T> + Send + Sync + 'static,
+    {
+        let captured = key.clone();
+        self.try_provide_keyed::<T, K>(
+            key,
+            Provider::root(move |injector| factory(injector, &captured)),
+        )
+    }
+
+    #[track_caller]
+    pub fn provide_keyed_with<T, K, F>(&self, key: K, factory: F) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+        F: Fn(&Injector, &K) -> Shared<T> + Send + Sync + 'static,
+    {
+        self.try_provide_keyed_with::<T, K, F>(key, factory)
+            .unwrap();
+        self
+    }
+
+    /// Resolves the keyed provider of `T` registered under `key`.
+    ///
+    /// The nearest registration of `key` wins, as with
+    /// [`resolve_ordered`](Injector::resolve_ordered).
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+    /// nothing is registered under `key`, or its gate is closed.
+    pub fn try_resolve_keyed<T, K>(&self, key: &K) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<(K, PhantomData<T>)>();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let entry = inner
+                .keyed
+                .read()
+                .unwrap()
+                .get(&type_id)
+                .and_then(|registry| registry.map::<T, K>())
+                .and_then(|map| map.read().unwrap().get(key).cloned());
+
+            // Resolved with no lock held, so the factory may resolve freely.
+            if let Some(entry) = entry {
+                return entry
+                    .resolve(self)
+                    .ok_or_else(|| Self::keyed_not_provided::<T, K>());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Self::keyed_not_provided::<T, K>())
+    }
+
+    #[track_caller]
+    pub fn resolve_keyed<T, K>(&self, key: &K) -> Shared<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        self.try_resolve_keyed::<T, K>(key).unwrap()
+    }
+
+    /// Returns `true` if a keyed provider of `T` is registered under `key` here or in an ancestor.
+    #[cfg(feature = "config")]
+    pub(crate) fn contains_keyed<T, K>(&self, key: &K) -> bool
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<(K, PhantomData<T>)>();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let registered = inner
+                .keyed
+                .read()
+                .unwrap()
+                .get(&type_id)
+                .and_then(|registry| registry.map::<T, K>())
+                .is_some_and(|map| map.read().unwrap().contains_key(key));
+
+            if registered {
+                return true;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Resolves every provider registered with [`provide_keyed`](Injector::provide_keyed)
+    /// for `T` and `K`, ordered by key.
+    ///
+    /// Keyed providers of this injector and its ancestors are combined; for a key
+    /// registered at several levels, the nearest one wins. Providers whose gate is
+    /// closed are left out, and an unregistered `T` yields an empty list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Middleware: Send + Sync {
+    ///     fn name(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Auth;
+    /// impl Middleware for Auth {
+    ///     fn name(&self) -> &'static str {
+    ///         "auth"
+    ///     }
+    /// }
+    ///
+    /// struct Logging;
+    /// impl Middleware for Logging {
+    ///     fn name(&self) -> &'static str {
+    ///         "logging"
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide_keyed::<dyn Middleware, u8>(20, Provider::root(|_| {
+    ///     Shared::new(Auth) as Shared<dyn Middleware>
+    /// }));
+    /// injector.provide_keyed::<dyn Middleware, u8>(10, Provider::root(|_| {
+    ///     Shared::new(Logging) as Shared<dyn Middleware>
+    /// }));
+    ///
+    /// let pipeline: Vec<_> = injector
+    ///     .resolve_ordered::<dyn Middleware, u8>()
+    ///     .iter()
+    ///     .map(|middleware| middleware.name())
+    ///     .collect();
+    /// assert_eq!(pipeline, ["logging", "auth"]);
+    /// ```
+    pub fn resolve_ordered<T, K>(&self) -> Vec<Shared<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<(K, PhantomData<T>)>();
+        let mut entries = KeyedMap::<T, K>::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let map = inner
+                .keyed
+                .read()
+                .unwrap()
+                .get(&type_id)
+                .and_then(|registry| registry.map::<T, K>());
+
+            if let Some(map) = map {
+                for (key, entry) in map.read().unwrap().iter() {
+                    if !entries.contains_key(key) {
+                        entries.insert(key.clone(), entry.clone());
+                    }
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        // No lock is held here, so factories may resolve or register freely.
+        entries
+            .into_values()
+            .filter_map(|entry| entry.resolve(self))
+            .collect()
+    }
+
+    fn store_keyed<T, K>(&self, key: K, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        K: Ord + Clone + Send + Sync + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+
+        self.inner.ensure_unfrozen()?;
+        self.track_gate(&provider);
+
+        let map = self
+            .inner
+            .keyed
+            .write()
+            .unwrap()
+            .entry(TypeId::of::<(K, PhantomData<T>)>())
+            .or_insert_with(KeyedRegistry::new::<T, K>)
+            .map::<T, K>()
+            .ok_or_else(|| Error::type_mismatch(type_name))?;
+
+        let mut map = map.write().unwrap();
+        if map.contains_key(&key) {
+            return Err(Error::provider_already_registered(
+                &format!("{} keyed by {}", type_name, std::any::type_name::<K>()),
+                provider.scope.to_string().as_str(),
+            ));
+        }
+
+        let entry = KeyedEntry {
+            scope: provider.scope,
+            provider: Shared::new(provider),
+            instance: Store::new(None),
+        };
+        map.insert(key, Shared::new(entry));
+
+        Ok(())
+    }
+
+    /// Fails with `ServiceNotProvided` if the nearest registration of `T` is gated shut.
+    fn check_gate<T: ?Sized + 'static>(&self) -> Result<(), Error> {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let gate = inner
+                .providers
+                .read()
+                .unwrap()
+                .get(&type_id)
+                .map(|entry| entry.gate.clone());
+
+            match gate {
+                Some(Some(gate)) if !gate(self) => {
+                    return Err(Error::service_not_provided(std::any::type_name::<T>()));
+                }
+                Some(_) => return Ok(()),
+                None => current = inner.parent.as_deref(),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the factory timings collected across the whole injector tree.
+    ///
+    /// Entries are sorted by type name. See [`ResolveTiming`].
+    #[cfg(feature = "timing")]
+    pub fn timing_report(&self) -> Vec<(&'static str, ResolveTiming)> {
+        let mut report: Vec<_> = self
+            .root_injector()
+            .inner
+            .timings
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, timing)| (*name, *timing))
+            .collect();
+        report.sort_unstable_by_key(|(name, _)| *name);
+        report
+    }
+
+    #[cfg(feature = "timing")]
+    fn record_timing<T: ?Sized + 'static>(&self, elapsed: std::time::Duration) {
+        self.root_injector()
+            .inner
+            .timings
+            .write()
+            .unwrap()
+            .entry(std::any::type_name::<T>())
+            .or_default()
+            .record(elapsed);
+    }
+
+    /// Resolves the type identified by `type_id` without naming it statically.
+    ///
+    /// This is the reflective counterpart of [`try_resolve`](Injector::try_resolve)
+    /// for callers that only hold a [`TypeId`], such as plugin dispatchers.
+    /// Scopes and caching behave exactly as for the generic call. Because the
+    /// registered type may be a trait object, the value is returned wrapped in
+    /// an [`Instance`]: downcast to `Instance<T>` and call
+    /// [`value`](Instance::value) to get the `Shared<T>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if no
+    /// provider is registered for `type_id`, plus any error from resolving it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    ///
+    /// use sadi::{Injector, Instance, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::singleton(|_| Shared::new(7)));
+    ///
+    /// let erased = injector.resolve_erased(TypeId::of::<u32>()).unwrap();
+    /// let value = erased.downcast_ref::<Instance<u32>>().unwrap().value();
+    ///
+    /// assert_eq!(*value, 7);
+    /// ```
+    pub fn resolve_erased(&self, type_id: TypeId) -> Result<Shared<dyn Any + Send + Sync>, Error> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            let resolver = inner
+                .providers
+                .read()
+                .unwrap()
+                .get(&type_id)
+                .map(|entry| entry.resolve_erased);
+
+            if let Some(resolve) = resolver {
+                return resolve(self);
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Error::service_not_provided(&format!("{:?}", type_id)))
+    }
+
+    /// Lists the type name and scope of every registration visible from here.
+    ///
+    /// The nearest registration of a type shadows those of its ancestors, and
+    /// the list is sorted by type name.
+    pub(crate) fn registrations(&self) -> Vec<(&'static str, Scope)> {
+        let mut seen = std::collections::HashSet::new();
+        let mut registrations = Vec::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            for (type_id, entry) in inner.providers.read().unwrap().iter() {
+                if seen.insert(*type_id) {
+                    registrations.push((entry.type_name, entry.scope));
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        registrations.sort_unstable_by_key(|(type_name, _)| *type_name);
+        registrations
+    }
+
+    /// Lists the declared dependencies of every registration visible from here.
+    ///
+    /// The nearest registration of a type shadows those of its ancestors.
+    fn declared_dependencies(&self) -> Vec<(TypeId, &'static str, Vec<Dependency>)> {
+        let mut seen = std::collections::HashSet::new();
+        let mut graph = Vec::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            for (type_id, entry) in inner.providers.read().unwrap().iter() {
+                if seen.insert(*type_id) {
+                    graph.push((*type_id, entry.type_name, entry.dependencies.clone()));
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        graph
+    }
+
+    /// Lists where each registration visible from here was made, by type name.
+    ///
+    /// Registering functions such as [`provide`](Injector::provide) and
+    /// [`override_provider`](Injector::override_provider) are
+    /// `#[track_caller]`, so the location is the user code that called them,
+    /// such as the body of a module's `providers`. The nearest registration of
+    /// a type shadows those of its ancestors, and the list is sorted by type
+    /// name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// let line = line!() + 1;
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+    ///
+    /// let sites = injector.registration_sites();
+    /// assert_eq!(sites[0].0, "u32");
+    /// assert_eq!(sites[0].1.line(), line);
+    /// ```
+    pub fn registration_sites(&self) -> Vec<(&'static str, &'static Location<'static>)> {
+        let mut seen = HashSet::new();
+        let mut sites = Vec::new();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            for (type_id, entry) in inner.providers.read().unwrap().iter() {
+                if seen.insert(*type_id) {
+                    sites.push((entry.type_name, entry.site));
+                }
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        sites.sort_by_key(|(type_name, _)| *type_name);
+        sites
+    }
+
+    /// Returns how many times each type was resolved through this injector.
+    ///
+    /// Empty unless counting was turned on with
+    /// [`set_resolution_stats`](Injector::set_resolution_stats).
+    pub fn resolution_stats(&self) -> HashMap<&'static str, usize> {
+        self.inner.resolution_stats.read().unwrap().clone()
+    }
+
+    /// Starts recording, in order, every type resolved through this injector.
+    ///
+    /// Like [`set_resolution_stats`](Injector::set_resolution_stats), this sees
+    /// the nested resolves made by factories run through this injector, but
+    /// keeps their order. Calling it again starts a new trace; handles to the
+    /// previous one stop growing. See [`ResolutionTrace`].
+    pub fn record_resolutions(&self) -> ResolutionTrace {
+        let trace = ResolutionTrace::default();
+        *self.inner.trace.write().unwrap() = Some(trace.clone());
+        trace
+    }
+
+    fn record_resolution<T: ?Sized + 'static>(&self) {
+        if let Some(trace) = self.inner.trace.read().unwrap().as_ref() {
+            trace.push::<T>();
+        }
+
+        if self.inner.track_resolutions.load(Ordering::Relaxed) {
+            *self
+                .inner
+                .resolution_stats
+                .write()
+                .unwrap()
+                .entry(std::any::type_name::<T>())
+                .or_default() += 1;
+        }
+    }
+
+    /// Returns the names of every module loaded into this injector's hierarchy.
+    ///
+    /// Modules are listed in the order they finished loading, so imports come
+    /// before the modules that import them.
+    pub fn loaded_modules(&self) -> Vec<&'static str> {
+        self.root_injector()
+            .inner
+            .loaded_modules
+            .read()
+            .unwrap()
+            .clone()
+    }
+
+    pub(crate) fn record_loaded_module(&self, name: &'static str) {
+        self.root_injector()
+            .inner
+            .loaded_modules
+            .write()
+            .unwrap()
+            .push(name);
+    }
+
+    /// Sets the active profile of this injector and its descendants.
+    ///
+    /// Providers restricted with [`Provider::only_in`] or [`Provider::except_in`]
+    /// are filtered when they are registered, so set the profile before loading
+    /// the modules that register them. Changing it afterwards does not add or
+    /// remove existing registrations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.set_profile("prod");
+    ///
+    /// assert_eq!(injector.profile().as_deref(), Some("prod"));
+    /// ```
+    pub fn set_profile(&self, profile: impl Into<String>) -> &Self {
+        *self.inner.profile.write().unwrap() = Some(profile.into());
+        self
+    }
+
+    /// Returns the active profile, set on this injector or the nearest ancestor.
+    pub fn profile(&self) -> Option<String> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(profile) = inner.profile.read().unwrap().as_ref() {
+                return Some(profile.clone());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Registers `provider` for `T`, failing if `T` already has a local registration.
+    ///
+    /// An existing registration with a different [`priority`](Provider::priority)
+    /// is not a conflict: the higher priority provider is kept.
+    ///
+    /// [`Scope::Root`] providers are registered on the root injector; the others
+    /// on this one. Registering from inside a factory is allowed: no map is
+    /// borrowed while a factory runs, so the new provider is visible to every
+    /// resolve that starts afterwards, though not to one already past its
+    /// provider lookup.
+    #[track_caller]
+    pub fn try_provide<T>(&self, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        if !provider.profiles.is_active(self.profile().as_deref()) {
+            #[cfg(feature = "tracing")]
+            debug!(
+                "Skipping provider for {} not active in the current profile",
+                std::any::type_name::<T>()
+            );
+
+            return Ok(());
+        }
+
+        match provider.scope {
+            Scope::Root => {
+                let root = self.root_injector();
+                root.store_provider::<T>(provider, self.module_name())
+            }
+
+            Scope::Module | Scope::Transient => {
+                self.store_provider::<T>(provider, self.module_name())
+            }
+        }
+    }
+
+    /// Registers `provider` for `T`, panicking if `T` already has a local registration.
+    ///
+    /// Every instantiation of a generic type is a distinct `T`, so
+    /// `Cache<String, u32>` and `Cache<u32, String>` are registered and resolved
+    /// independently:
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Cache<K, V> {
+    ///     entries: Vec<(K, V)>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Cache<String, u32>>(Provider::root(|_| {
+    ///     Shared::new(Cache { entries: vec![("answer".to_string(), 42)] })
+    /// }));
+    /// injector.provide::<Cache<u32, String>>(Provider::root(|_| {
+    ///     Shared::new(Cache { entries: vec![] })
+    /// }));
+    ///
+    /// assert_eq!(injector.resolve::<Cache<String, u32>>().entries.len(), 1);
+    /// assert!(injector.resolve::<Cache<u32, String>>().entries.is_empty());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`try_provide`](Self::try_provide) fails.
+    #[track_caller]
+    pub fn provide<T>(&self, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_provide::<T>(provider).unwrap();
+        self
+    }
+
+    /// Registers a singleton `T` built from one resolved dependency `D`.
+    ///
+    /// Shorthand for a [`Provider::singleton`] whose factory resolves `D` and
+    /// hands it to `factory`. `D` is resolved through the injector that first
+    /// resolves `T`, so it may be registered in an ancestor.
+    ///
+    /// Like every factory, `factory` must be `'static` and `Send + Sync`: it cannot borrow
+    /// locals. Shared configuration should either be registered and declared as
+    /// `D`, or captured by value as a `Shared<Config>` with a `move` closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a provider for `T` is already registered on this injector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Config {
+    ///     url: String,
+    /// }
+    ///
+    /// struct Database {
+    ///     config: Shared<Config>,
+    /// }
+    ///
+    /// impl Database {
+    ///     fn new(config: Shared<Config>) -> Self {
+    ///         Self { config }
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| {
+    ///     Shared::new(Config { url: "sqlite::memory:".to_string() })
+    /// }));
+    /// injector.provide_factory_with::<Database, Config, _>(Database::new);
+    ///
+    /// assert_eq!(injector.resolve::<Database>().config.url, "sqlite::memory:");
+    /// ```
+    #[track_caller]
+    pub fn provide_factory_with<T, D, F>(&self, factory: F) -> &Self
+    where
+        T: Send + Sync + 'static,
+        D: ?Sized + Send + Sync + 'static,
+        F: Fn(Shared<D>) -> T + Send + Sync + 'static,
+    {
+        self.provide::<T>(
+            Provider::singleton(move |injector| Shared::new(factory(injector.resolve::<D>())))
+                .depends_on::<D>(),
+        )
+    }
+
+    /// Registers a provider for `T` that `choose` picks on first resolve.
+    ///
+    /// `choose` runs the first time `T` is resolved and may resolve other
+    /// services, typically a configuration, to decide which implementation to
+    /// use and with which scope. The decision is memoized: the chosen provider
+    /// replaces this registration, so `choose` never runs again and later
+    /// resolves behave exactly as if the chosen provider had been registered
+    /// directly. Values that `choose` looked at may change afterwards without
+    /// affecting the decision.
+    ///
+    /// The registration lives on this injector. Until the first resolve, it is
+    /// reported as [`Scope::Transient`] by [`manifest`](Injector::manifest) and
+    /// [`stats`](Injector::stats), and it is skipped by
+    /// [`init_eager`](Injector::init_eager). On a child injector, such as the
+    /// one a module registers on, a chosen [`Scope::Root`] provider stays local
+    /// like an [override](Injector::override_provider): it becomes
+    /// [`Scope::Module`] and its instance is cached below the root.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already
+    /// registered on this injector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Cache: Send + Sync {
+    ///     fn backend(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Redis;
+    /// impl Cache for Redis {
+    ///     fn backend(&self) -> &'static str {
+    ///         "redis"
+    ///     }
+    /// }
+    ///
+    /// struct InMemory;
+    /// impl Cache for InMemory {
+    ///     fn backend(&self) -> &'static str {
+    ///         "in-memory"
+    ///     }
+    /// }
+    ///
+    /// struct Config {
+    ///     use_redis: bool,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| Shared::new(Config { use_redis: true })));
+    /// injector.provide_conditional::<dyn Cache, _>(|inj| {
+    ///     if inj.resolve::<Config>().use_redis {
+    ///         Provider::root(|_| Shared::new(Redis) as Shared<dyn Cache>)
+    ///     } else {
+    ///         Provider::transient(|_| Shared::new(InMemory) as Shared<dyn Cache>)
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(injector.resolve::<dyn Cache>().backend(), "redis");
+    /// ```
+    #[track_caller]
+    pub fn try_provide_conditional<T, F>(&self, choose: F) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(&Injector) -> Provider<T> + Send + Sync + 'static,
+    {
+        self.store_provider::<T>(
+            Provider {
+                scope: Scope::Transient,
+                profiles: Profiles::default(),
+                gate: None,
+                priority: 0,
+                size: None,
+                eager: false,
+                dependencies: Vec::new(),
+                deadline: None,
+                after_init: None,
+                marks: Vec::new(),
+                fallbacks: None,
+                factory: Box::new(move |injector| injector.adopt_provider(choose(injector))),
+            },
+            self.module_name(),
+        )
+    }
+
+    /// Registers a provider for `T` that `choose` picks on first resolve.
+    ///
+    /// See [`try_provide_conditional`](Injector::try_provide_conditional).
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`try_provide_conditional`](Injector::try_provide_conditional) fails.
+    #[track_caller]
+    pub fn provide_conditional<T, F>(&self, choose: F) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(&Injector) -> Provider<T> + Send + Sync + 'static,
+    {
+        self.try_provide_conditional::<T, F>(choose).unwrap();
+        self
+    }
+
+    /// Registers a root singleton built in two phases, to break a genuine cycle.
+    ///
+    /// Two services that each hold the other cannot both receive their
+    /// dependency at construction: resolving either fails with
+    /// [`CircularDependency`](crate::ErrorKind::CircularDependency). This is
+    /// the escape hatch. `construct` builds `T` without the dependency that
+    /// closes the cycle, and the instance is cached at once. `wire` then runs
+    /// on the cached instance and supplies the dependency through a setter,
+    /// typically into a `OnceLock` field. By then `T` resolves to the cached
+    /// instance, so the other side of the cycle can be built, even with a
+    /// plain provider that takes `T` in its factory, and `T` is fully wired
+    /// before the resolve that created it returns.
+    ///
+    /// Prefer restructuring the services, or a [`WeakInjector`] that resolves
+    /// the other side on demand, where possible. Until `wire` has run, whatever is built
+    /// during it sees `T` without its dependency. With the `thread-safe`
+    /// feature, other threads may also resolve `T` in that window. Services
+    /// that keep strong references to each other are never freed.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::OnceLock;
+    ///
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Parent {
+    ///     child: OnceLock<Shared<Child>>,
+    /// }
+    ///
+    /// struct Child {
+    ///     parent: Shared<Parent>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Child>(Provider::root(|inj| {
+    ///     Shared::new(Child {
+    ///         parent: inj.resolve::<Parent>(),
+    ///     })
+    /// }));
+    /// injector.provide_two_phase::<Parent, _, _>(
+    ///     |_| Parent {
+    ///         child: OnceLock::new(),
+    ///     },
+    ///     |parent, inj| {
+    ///         let _ = parent.child.set(inj.resolve::<Child>());
+    ///     },
+    /// );
+    ///
+    /// let parent = injector.resolve::<Parent>();
+    /// let child = parent.child.get().unwrap();
+    /// assert!(Shared::ptr_eq(&child.parent, &parent));
+    /// ```
+    #[track_caller]
+    pub fn try_provide_two_phase<T, C, W>(&self, construct: C, wire: W) -> Result<(), Error>
+    where
+        T: Send + Sync + 'static,
+        C: Fn(&Injector) -> T + Send + Sync + 'static,
+        W: Fn(&T, &Injector) + Send + Sync + 'static,
+    {
+        self.try_provide::<T>(
+            Provider::root(move |injector| Shared::new(construct(injector))).after_init(
+                move |instance, injector| {
+                    wire(instance, injector);
+                    Ok(())
+                },
+            ),
+        )
+    }
+
+    /// Registers a two-phase root singleton, panicking on a duplicate.
+    ///
+    /// See [`try_provide_two_phase`](Injector::try_provide_two_phase).
+    #[track_caller]
+    pub fn provide_two_phase<T, C, W>(&self, construct: C, wire: W) -> &Self
+    where
+        T: Send + Sync + 'static,
+        C: Fn(&Injector) -> T + Send + Sync + 'static,
+        W: Fn(&T, &Injector) + Send + Sync + 'static,
+    {
+        self.try_provide_two_phase::<T, C, W>(construct, wire)
+            .unwrap();
+        self
+    }
+
+    /// Registers a root singleton whose type is inferred from `factory`, returning a key to resolve it.
+    ///
+    /// Meant for factories returning a type that cannot be named, such as a
+    /// closure: `T` is taken from `factory`'s return type, so no turbofish is
+    /// needed, and the returned [`Inferred<T>`] resolves the service without
+    /// naming it either. See the [`inferred`](crate::inferred) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already registered.
+    #[track_caller]
+    pub fn try_provide_inferred<T, F>(&self, factory: F) -> Result<Inferred<T>, Error>
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&Injector) -> T + Send + Sync + 'static,
+    {
+        self.try_provide::<T>(Provider::root(move |injector| {
+            Shared::new(factory(injector))
+        }))?;
+        Ok(Inferred::new())
+    }
+
+    /// Registers a root singleton of an inferred type, panicking on a duplicate.
+    ///
+    /// See [`try_provide_inferred`](Injector::try_provide_inferred).
+    #[track_caller]
+    pub fn provide_inferred<T, F>(&self, factory: F) -> Inferred<T>
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&Injector) -> T + Send + Sync + 'static,
+    {
+        self.try_provide_inferred::<T, F>(factory).unwrap()
+    }
+
+    /// Registers `providers` for `T`, to be tried in order until one succeeds.
+    ///
+    /// Resolving `T` creates an instance from the first provider; if it fails
+    /// with [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed)
+    /// or [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided), the next
+    /// one is tried, and so on. The first success is cached according to the
+    /// first provider's scope, which also decides where the chain is
+    /// registered. See the [`fallback`](crate::fallback) module for which
+    /// failures move on and which abort.
+    ///
+    /// Providers not active in the current [profile](Injector::set_profile) are
+    /// left out; if none is left, nothing is registered.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if `T` is already registered.
+    #[track_caller]
+    pub fn try_provide_fallback_chain<T>(
+        &self,
+        mut providers: Vec<Provider<T>>,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let profile = self.profile();
+        providers.retain(|provider| provider.profiles.is_active(profile.as_deref()));
+
+        let Some(scope) = providers.first().map(|provider| provider.scope) else {
+            return Ok(());
+        };
+        let dependencies = providers
+            .iter()
+            .flat_map(|provider| provider.dependencies.iter().copied())
+            .collect();
+
+        let fallbacks = FallbackChain::new(providers);
+
+        self.try_provide::<T>(Provider {
+            scope,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies,
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: Some(fallbacks),
+            // `map` and `decorate` carry the chain along, so every resolve goes through it.
+            factory: Box::new(|_| {
+                unreachable!("a fallback chain is created through its fallbacks")
+            }),
+        })
+    }
+
+    /// Registers `providers` for `T`, to be tried in order, panicking on conflict.
+    ///
+    /// See [`try_provide_fallback_chain`](Injector::try_provide_fallback_chain).
+    #[track_caller]
+    pub fn provide_fallback_chain<T>(&self, providers: Vec<Provider<T>>) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_provide_fallback_chain::<T>(providers).unwrap();
+        self
+    }
+
+    /// Creates `T` from the first of `providers` that succeeds.
+    pub(crate) fn resolve_chain<T>(
+        &self,
+        providers: &[Shared<Provider<T>>],
+    ) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+        let mut last = Error::service_not_provided(type_name);
+
+        for provider in providers {
+            let created = match &provider.gate {
+                Some(gate) if !gate(self) => Err(Error::service_not_provided(type_name)),
+                _ => self.create_instance(provider.clone()).and_then(|instance| {
+                    self.run_after_init(provider, instance.get())?;
+                    Ok(instance.value())
+                }),
+            };
+
+            match created {
+                Ok(value) => return Ok(value),
+                Err(err)
+                    if err.kind == ErrorKind::FactoryExecutionFailed
+                        || err.kind == ErrorKind::ServiceNotProvided =>
+                {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Falling back from a provider for {}: {}",
+                        type_name, err.message
+                    );
+
+                    last = err;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(last)
+    }
+
+    /// Builds the first instance from `provider` and makes it the nearest registration of `T`.
+    ///
+    /// The instance is cached according to the provider's scope, because the
+    /// conditional registration it replaces is transient and is not cached by
+    /// the resolve that called it.
+    fn adopt_provider<T>(&self, mut provider: Provider<T>) -> Instance<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        // The placeholder may be registered on an ancestor of this injector.
+        let mut holder = None;
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if inner.providers.read().unwrap().contains_key(&type_id) {
+                holder = Some(inner);
+                break;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        // A root-scoped choice below the root would be cached on the root, past
+        // the registration that shadows it, and never be found; as with
+        // `override_provider`, it is cached locally instead.
+        if provider.scope == Scope::Root && holder.is_some_and(|inner| inner.parent.is_some()) {
+            provider.scope = Scope::Module;
+        }
+        let scope = provider.scope;
+
+        self.track_gate(&provider);
+        let value = (provider.factory)(self).value();
+
+        if let Some(inner) = holder {
+            let mut providers = inner.providers.write().unwrap();
+            if let Some(entry) = providers.get(&type_id) {
+                let (site, module) = (entry.site, entry.module);
+                self.insert_provider_entry(
+                    &mut providers,
+                    type_id,
+                    ProviderEntry {
+                        site,
+                        module,
+                        ..ProviderEntry::new(provider)
+                    },
+                );
+            }
+        }
+
+        match scope {
+            Scope::Root => self
+                .root_injector()
+                .store_instance::<T>(Shared::new(Instance::new(value.clone()))),
+            Scope::Module => self.store_instance::<T>(Shared::new(Instance::new(value.clone()))),
+            Scope::Transient => {}
+        }
+
+        Instance::new(value)
+    }
+
+    /// Registers `provider` under the marker type `Tag`.
+    ///
+    /// The provider is stored as a [`Tagged<T, Tag>`] registration, so several
+    /// providers of `T` can coexist as long as their tags differ. Resolve it
+    /// with [`resolve_tagged`](Injector::resolve_tagged).
+    #[track_caller]
+    pub fn try_provide_tagged<T, Tag>(&self, provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        Tag: 'static,
+    {
+        self.try_provide::<Tagged<T, Tag>>(provider.map(|value| Shared::new(Tagged::new(value))))
+    }
+
+    #[track_caller]
+    pub fn provide_tagged<T, Tag>(&self, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        Tag: 'static,
+    {
+        self.try_provide_tagged::<T, Tag>(provider).unwrap();
+        self
+    }
+
+    /// Resolves the `T` registered under `Tag` with [`provide_tagged`](Injector::provide_tagged).
+    pub fn try_resolve_tagged<T, Tag>(&self) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        Tag: 'static,
+    {
+        self.try_resolve::<Tagged<T, Tag>>()
+            .map(|tagged| tagged.value())
+    }
+
+    #[track_caller]
+    pub fn resolve_tagged<T, Tag>(&self) -> Shared<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        Tag: 'static,
+    {
+        self.try_resolve_tagged::<T, Tag>().unwrap()
+    }
+
+    /// Registers a factory of owned `Box<T>` values, typically a boxed trait object.
+    ///
+    /// Unlike a [`Provider::transient`], which still hands out a shared handle,
+    /// each [`resolve_boxed`](Injector::resolve_boxed) runs `factory` with the
+    /// resolving injector and returns the new box to the caller alone. The
+    /// factory is registered on this injector under [`BoxedFactory<T>`], so a
+    /// shared registration of `T` does not conflict with it. See the
+    /// [`boxed`](crate::boxed) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if a boxed factory
+    /// for `T` is already registered here.
+    #[track_caller]
+    pub fn try_provide_transient_dyn<T, F>(&self, factory: F) -> Result<(), Error>
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Box<T> + Send + Sync + 'static,
+    {
+        let factory = Shared::new(BoxedFactory::new(factory));
+        self.try_provide::<BoxedFactory<T>>(Provider::singleton(move |_| factory.clone()))
+    }
+
+    /// Registers a factory of owned `Box<T>` values, panicking on a duplicate.
+    ///
+    /// See [`try_provide_transient_dyn`](Injector::try_provide_transient_dyn).
+    #[track_caller]
+    pub fn provide_transient_dyn<T, F>(&self, factory: F) -> &Self
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Box<T> + Send + Sync + 'static,
+    {
+        self.try_provide_transient_dyn::<T, F>(factory).unwrap();
+        self
+    }
+
+    /// Creates a new `Box<T>` with the factory registered by
+    /// [`provide_transient_dyn`](Injector::provide_transient_dyn).
+    pub fn try_resolve_boxed<T>(&self) -> Result<Box<T>, Error>
+    where
+        T: ?Sized + 'static,
+    {
+        let factory = self.try_resolve::<BoxedFactory<T>>()?;
+        Ok(factory.create(self))
+    }
+
+    /// Creates a new `Box<T>`, panicking if no boxed factory is registered.
+    ///
+    /// See [`try_resolve_boxed`](Injector::try_resolve_boxed).
+    #[track_caller]
+    pub fn resolve_boxed<T>(&self) -> Box<T>
+    where
+        T: ?Sized + 'static,
+    {
+        self.try_resolve_boxed::<T>().unwrap()
+    }
+
+    /// Checks an object out of the [`Pool<T>`] registered with [`Provider::pooled`].
+    ///
+    /// The returned guard gives exclusive access to the object and puts it
+    /// back into the pool when dropped. See the [`pool`](crate::pool) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve) if no `Pool<T>` is registered.
+    pub fn try_resolve_pooled<T>(&self) -> Result<PooledGuard<T>, Error>
+    where
+        T: Send + Sync + 'static,
+    {
+        Ok(self.try_resolve::<Pool<T>>()?.acquire())
+    }
+
+    /// Checks an object out of the pool of `T`, panicking if none is registered.
+    ///
+    /// See [`try_resolve_pooled`](Injector::try_resolve_pooled).
+    #[track_caller]
+    pub fn resolve_pooled<T>(&self) -> PooledGuard<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.try_resolve_pooled::<T>().unwrap()
+    }
+
+    /// Registers a factory that receives a `P` supplied at resolve time.
+    ///
+    /// Each [`resolve_with_params`](Injector::resolve_with_params) runs
+    /// `factory` with the resolving injector and the given parameters; the
+    /// result is never cached. The factory is registered on this injector under
+    /// [`ParamFactory<T, P>`], so a plain registration of `T` does not conflict
+    /// with it. See the [`params`](crate::params) module.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_provide`](Injector::try_provide) if a factory for `T`
+    /// taking `P` is already registered here.
+    #[track_caller]
+    pub fn try_provide_with_params<T, P, F>(&self, factory: F) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        P: 'static,
+        F: Fn(&Injector, P) -> Shared<T> + Send + Sync + 'static,
+    {
+        let factory = Shared::new(ParamFactory::new(factory));
+        self.try_provide::<ParamFactory<T, P>>(Provider::singleton(move |_| factory.clone()))
+    }
+
+    /// Registers a parameterized factory, panicking on a duplicate.
+    ///
+    /// See [`try_provide_with_params`](Injector::try_provide_with_params).
+    #[track_caller]
+    pub fn provide_with_params<T, P, F>(&self, factory: F) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        P: 'static,
+        F: Fn(&Injector, P) -> Shared<T> + Send + Sync + 'static,
+    {
+        self.try_provide_with_params::<T, P, F>(factory).unwrap();
+        self
+    }
+
+    /// Creates a new `T` from `params` with the factory registered by
+    /// [`provide_with_params`](Injector::provide_with_params).
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve) if no factory for `T`
+    /// taking `P` is visible from this injector.
+    pub fn try_resolve_with_params<T, P>(&self, params: P) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        P: 'static,
+    {
+        let factory = self.try_resolve::<ParamFactory<T, P>>()?;
+        Ok(factory.create(self, params))
+    }
+
+    /// Creates a new `T` from `params`, panicking if no factory is registered.
+    ///
+    /// See [`try_resolve_with_params`](Injector::try_resolve_with_params).
+    #[track_caller]
+    pub fn resolve_with_params<T, P>(&self, params: P) -> Shared<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        P: 'static,
+    {
+        self.try_resolve_with_params::<T, P>(params).unwrap()
+    }
+
+    /// Registers `provider` on this injector, replacing any local registration for `T`.
+    ///
+    /// Unlike [`provide`](Injector::provide), an override never errors on an
+    /// existing registration and always stays local to this injector: a
+    /// [`Scope::Root`] provider is cached here rather than in the root, so the
+    /// override is invisible to ancestors and siblings. Any instance of `T`
+    /// already cached on this injector is dropped.
+    ///
+    /// Root-scoped services that depend on `T` and are resolved through this
+    /// injector are still cached in the root, so override them as well if they
+    /// must see the replacement.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if this
+    /// injector is [frozen](Injector::freeze).
+    #[track_caller]
+    pub fn try_override_provider<T>(&self, mut provider: Provider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        if provider.scope == Scope::Root {
+            provider.scope = Scope::Module;
+        }
+
+        self.track_gate(&provider);
+
+        let type_id = TypeId::of::<T>();
+
+        Self::evicted::<T>(
+            self.inner.instances.write().unwrap().remove(type_id),
+            EvictionReason::Replace,
+        );
+        self.insert_provider_entry(
+            &mut self.inner.providers.write().unwrap(),
+            type_id,
+            ProviderEntry::new(provider),
+        );
+
+        Ok(())
+    }
+
+    /// Replaces the local registration for `T`, panicking if this injector is frozen.
+    ///
+    /// See [`try_override_provider`](Injector::try_override_provider).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this injector is [frozen](Injector::freeze).
+    #[track_caller]
+    pub fn override_provider<T>(&self, provider: Provider<T>) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_override_provider::<T>(provider).unwrap();
+        self
+    }
+
+    /// Removes the local registration for `T`, returning whether there was one.
+    ///
+    /// Only this injector's own registration is removed: a provider inherited
+    /// from an ancestor keeps serving `T`, and [`Scope::Root`] providers live
+    /// on the root, so remove those through the root. Any instance of `T`
+    /// cached on this injector is dropped with it, so registering `T` again
+    /// starts from a fresh instance. Handles already resolved stay valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::singleton(|_| Shared::new(1)));
+    /// assert_eq!(*injector.resolve::<u32>(), 1);
+    ///
+    /// assert!(injector.remove_provider::<u32>());
+    /// assert!(injector.try_resolve::<u32>().is_err());
+    /// assert!(!injector.remove_provider::<u32>());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if this
+    /// injector is [frozen](Injector::freeze).
+    pub fn try_remove_provider<T>(&self) -> Result<bool, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        let type_id = TypeId::of::<T>();
+
+        Self::evicted::<T>(
+            self.inner.instances.write().unwrap().remove(type_id),
+            EvictionReason::Replace,
+        );
+        let removed = self
+            .inner
+            .providers
+            .write()
+            .unwrap()
+            .remove(&type_id)
+            .is_some();
+
+        Ok(removed)
+    }
+
+    /// Removes the local registration for `T`, panicking if this injector is frozen.
+    ///
+    /// See [`try_remove_provider`](Injector::try_remove_provider).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this injector is [frozen](Injector::freeze).
+    #[track_caller]
+    pub fn remove_provider<T>(&self) -> bool
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_remove_provider::<T>().unwrap()
+    }
+
+    /// Wraps the registered provider for `T` so every new instance passes through `decorator`.
+    ///
+    /// The decorator receives the instance produced by the existing provider
+    /// together with the resolving injector, and returns the value that is
+    /// handed out instead. The registration keeps its scope and stays on the
+    /// injector that owns it, so a decorated singleton is decorated once and
+    /// then served from the cache. Instances cached before the decorator was
+    /// registered are not re-decorated.
+    ///
+    /// Decorators stack in registration order: the first one wraps the
+    /// original instance and each later one wraps the result of the previous
+    /// one, so the last decorator registered is the outermost layer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if no
+    /// provider for `T` is registered in this injector or any ancestor, and
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) if the injector owning
+    /// it is [frozen](Injector::freeze).
+    pub fn try_decorate<T, F>(&self, decorator: F) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(Shared<T>, &Injector) -> Shared<T> + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let type_name = std::any::type_name::<T>();
+        let decorator = Shared::new(decorator);
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(entry) = inner.providers.write().unwrap().get_mut(&type_id) {
+                inner.ensure_unfrozen()?;
+
+                let previous = entry
+                    .provider
+                    .clone()
+                    .downcast::<Provider<T>>()
+                    .map_err(|_| Error::type_mismatch_found(type_name, entry.type_name))?;
+
+                #[cfg(feature = "tracing")]
+                debug!("Decorating provider for {}", type_name);
+
+                entry.provider = Shared::new(Provider::<T> {
+                    scope: entry.scope,
+                    profiles: Profiles::default(),
+                    gate: None,
+                    priority: entry.priority,
+                    size: entry.size,
+                    eager: entry.eager,
+                    dependencies: entry.dependencies.clone(),
+                    deadline: previous.deadline,
+                    after_init: previous.after_init.clone(),
+                    marks: previous.marks.clone(),
+                    // The winner of a fallback chain is decorated too.
+                    fallbacks: previous.fallbacks.as_ref().map(|chain| {
+                        let decorator = decorator.clone();
+                        chain.then(move |inner, injector| (*decorator)(inner, injector))
+                    }),
+                    factory: Box::new(move |injector| {
+                        let inner = (previous.factory)(injector).value();
+                        Instance::new((*decorator)(inner, injector))
+                    }),
+                });
+
+                return Ok(());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Error::service_not_provided(type_name))
+    }
+
+    /// Wraps the registered provider for `T` with `decorator`.
+    ///
+    /// See [`try_decorate`](Injector::try_decorate) for ordering and caching.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no provider for `T` is registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Greeter: Send + Sync {
+    ///     fn greet(&self) -> String;
+    /// }
+    ///
+    /// struct Plain;
+    /// impl Greeter for Plain {
+    ///     fn greet(&self) -> String {
+    ///         "hello".to_string()
+    ///     }
+    /// }
+    ///
+    /// struct Loud(Shared<dyn Greeter>);
+    /// impl Greeter for Loud {
+    ///     fn greet(&self) -> String {
+    ///         self.0.greet().to_uppercase()
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<dyn Greeter>(Provider::singleton(|_| Shared::new(Plain) as Shared<dyn Greeter>));
+    /// injector.decorate::<dyn Greeter, _>(|inner, _| Shared::new(Loud(inner)) as Shared<dyn Greeter>);
+    ///
+    /// assert_eq!(injector.resolve::<dyn Greeter>().greet(), "HELLO");
+    /// ```
+    #[track_caller]
+    pub fn decorate<T, F>(&self, decorator: F) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(Shared<T>, &Injector) -> Shared<T> + Send + Sync + 'static,
+    {
+        self.try_decorate::<T, F>(decorator).unwrap();
+        self
+    }
+
+    /// Returns the nearest provider for `T`, with the type it was registered as.
+    pub(crate) fn get_provider<T>(&self) -> Option<(Shared<dyn Any + Send + Sync>, &'static str)>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let local = self
+            .inner
+            .providers
+            .read()
+            .unwrap()
+            .get(&type_id)
+            .map(|entry| (entry.provider.clone(), entry.type_name));
+
+        if local.is_some() {
+            return local;
+        }
+
+        if let Some(parent) = &self.inner.parent {
+            let parent_injector = Injector {
+                inner: parent.clone(),
+            };
+            return parent_injector.get_provider::<T>();
+        }
+
+        None
+    }
+
+    pub(crate) fn resolve_provider<T>(&self) -> Result<Shared<Provider<T>>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+
+        let (any_provider, registered_as) = self
+            .get_provider::<T>()
+            .ok_or_else(|| Error::service_not_provided(type_name))?;
+
+        let provider = any_provider
+            .downcast::<Provider<T>>()
+            .map_err(|_| Error::type_mismatch_found(type_name, registered_as))?;
+
+        Ok(provider)
+    }
+
+    pub(crate) fn resolve_instance<T>(&self) -> Result<Shared<Instance<T>>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.create_instance(self.resolve_provider::<T>()?)
+    }
+
+    /// Runs the factory of `provider_ref`, passing the result through any matching decorators.
+    fn create_instance<T>(
+        &self,
+        provider_ref: Shared<Provider<T>>,
+    ) -> Result<Shared<Instance<T>>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = resolve_span::<T>().entered();
+
+        // The provider is an owned handle, so the factory runs without any map
+        // borrowed and may itself resolve or register providers.
+        let instance = match provider_ref.deadline {
+            Some(deadline) => self.run_with_deadline(provider_ref, deadline)?,
+            None => (provider_ref.factory)(self),
+        };
+
+        if !self.root_inner().decorating.load(Ordering::Relaxed) {
+            return Ok(Shared::new(instance));
+        }
+
+        let value = self.decorate_matching_instance(instance.value())?;
+        Ok(Shared::new(Instance::new(value)))
+    }
+
+    /// Runs the factory of `provider` on its own thread, giving up after `deadline`.
+    fn run_with_deadline<T>(
+        &self,
+        provider: Shared<Provider<T>>,
+        deadline: Duration,
+    ) -> Result<Instance<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let injector = self.clone();
+        let stack = ResolveGuard::stack();
+        #[cfg(feature = "tracing")]
+        let span = tracing::Span::current();
+
+        let factory = std::thread::spawn(move || {
+            #[cfg(feature = "tracing")]
+            let _span = span.entered();
+
+            // Carried over so a cycle through this factory is still detected.
+            ResolveGuard::inherit(stack);
+
+            // The receiver is gone if the deadline passed; the instance is dropped.
+            let _ = sender.send((provider.factory)(&injector));
+        });
+
+        match receiver.recv_timeout(deadline) {
+            Ok(instance) => Ok(instance),
+            Err(RecvTimeoutError::Timeout) => Err(Error::factory_execution_failed(
+                std::any::type_name::<T>(),
+                &format!("exceeded deadline of {:?}", deadline),
+            )),
+            Err(RecvTimeoutError::Disconnected) => match factory.join() {
+                Err(panic) => std::panic::resume_unwind(panic),
+                Ok(()) => unreachable!("the factory thread exited without sending"),
+            },
+        }
+    }
+
+    /// Runs the [`after_init`](Provider::after_init) hook of `provider` on a new instance.
+    fn run_after_init<T>(&self, provider: &Provider<T>, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let Some(hook) = &provider.after_init else {
+            return Ok(());
+        };
+
+        hook(value, self)
+            .map_err(|reason| Error::factory_execution_failed(std::any::type_name::<T>(), &reason))
+    }
+
+    pub(crate) fn store_instance<T>(&self, instance: Shared<Instance<T>>)
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        self.inner
+            .instances
+            .write()
+            .unwrap()
+            .insert(type_id, instance);
+    }
+
+    #[track_caller]
+    pub(crate) fn store_provider<T>(
+        &self,
+        provider: Provider<T>,
+        module: Option<&'static str>,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let type_name = std::any::type_name::<T>();
+
+        self.inner.ensure_unfrozen()?;
+        self.track_gate(&provider);
+
+        let mut providers = self.inner.providers.write().unwrap();
+        if let Some(existing) = providers.get(&type_id) {
+            match provider.priority.cmp(&existing.priority) {
+                std::cmp::Ordering::Less => {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Ignoring provider for {} outranked by the registered one",
+                        type_name
+                    );
+
+                    return Ok(());
+                }
+                std::cmp::Ordering::Equal => {
+                    return Err(Error::provider_already_registered(
+                        type_name,
+                        provider.scope.to_string().as_str(),
+                    ));
+                }
+                std::cmp::Ordering::Greater => {
+                    #[cfg(feature = "tracing")]
+                    debug!(
+                        "Replacing provider for {} with a higher priority one",
+                        type_name
+                    );
+                }
+            }
+        }
+        let replaced = self
+            .insert_provider_entry(
+                &mut providers,
+                type_id,
+                ProviderEntry {
+                    module,
+                    ..ProviderEntry::new(provider)
+                },
+            )
+            .is_some();
+        drop(providers);
+
+        // An instance built by the outranked provider must not outlive it.
+        if replaced {
+            Self::evicted::<T>(
+                self.inner.instances.write().unwrap().remove(type_id),
+                EvictionReason::Replace,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Drops the cached instance of `T`, so the next resolve creates a new one.
+    ///
+    /// The cache entry nearest to this injector is removed, which for a
+    /// root-scoped provider is the one on the root. Handles to the old instance
+    /// held elsewhere stay valid. Returns `false` if nothing was cached.
+    pub fn clear_singleton<T>(&self) -> bool
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if Self::evicted::<T>(
+                inner.instances.write().unwrap().remove(type_id),
+                EvictionReason::Manual,
+            ) {
+                return true;
+            }
+
+            // Mirrors `get_instance`: nothing above the registration is visible.
+            if inner.providers.read().unwrap().contains_key(&type_id) {
+                return false;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Looks up a cached instance of `T` in this injector and its ancestors.
+    ///
+    /// The cache is walked by reference and the stored instance is borrowed via
+    /// `downcast_ref`, so a hit costs a single `Shared::clone` of the value.
+    pub(crate) fn get_instance<T>(&self) -> Option<Shared<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+
+        // The cache holds the `Shared<T>` the factory returned, already unsized
+        // for a trait object, so a hit is a downcast and a reference count bump.
+        while let Some(inner) = current {
+            if let Some(instance) = inner
+                .instances
+                .read()
+                .unwrap()
+                .get(type_id)
+                .and_then(|instance| instance.downcast_ref::<Instance<T>>())
+            {
+                return Some(instance.value());
+            }
+
+            // The nearest registration shadows anything cached further up,
+            // which is what lets `override_provider` win over a parent's cache.
+            if inner.providers.read().unwrap().contains_key(&type_id) {
+                return None;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Resolves an instance of `T`, returning an [`Error`] instead of panicking.
+    ///
+    /// `T` may be a concrete type or a trait object. A trait object is resolved
+    /// under the exact type it was registered with, so a provider registered as
+    /// `Provider<dyn Trait>` yields a `Shared<dyn Trait>`; the unsizing happens
+    /// once, inside the factory.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+    /// nothing is registered for `T`, and with
+    /// [`CircularDependency`](crate::ErrorKind::CircularDependency) if resolving
+    /// `T` requires `T` itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Clock: Send + Sync {
+    ///     fn now(&self) -> u64;
+    /// }
+    ///
+    /// struct FixedClock(u64);
+    /// impl Clock for FixedClock {
+    ///     fn now(&self) -> u64 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<FixedClock>(Provider::singleton(|_| Shared::new(FixedClock(1))));
+    /// injector.provide::<dyn Clock>(Provider::singleton(|_| {
+    ///     Shared::new(FixedClock(2)) as Shared<dyn Clock>
+    /// }));
+    ///
+    /// let concrete: Shared<FixedClock> = injector.try_resolve::<FixedClock>().unwrap();
+    /// let clock: Shared<dyn Clock> = injector.try_resolve::<dyn Clock>().unwrap();
+    ///
+    /// assert_eq!(concrete.now(), 1);
+    /// assert_eq!(clock.now(), 2);
+    /// ```
+    pub fn try_resolve<T>(&self) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.record_resolution::<T>();
+
+        if let Some(weak) = self.resolve_weak::<T>() {
+            return Ok(weak);
+        }
+
+        self.ensure_running()?;
+
+        let node = if self.diagnostics_enabled() {
+            TreeNode::enter::<T>()
+        } else {
+            None
+        };
+
+        if self.root_inner().gated.load(Ordering::Relaxed) {
+            self.check_gate::<T>().inspect_err(|err| {
+                diagnostics::note(&node, || Note::Failed(err.message.clone()))
+            })?;
+        }
+
+        // A cached instance cannot be part of a cycle, so the hit path skips
+        // the resolve guard entirely.
+        if let Some(value) = self.get_instance::<T>() {
+            diagnostics::note(&node, || Note::Cached);
+            return Ok(self.intercept(value));
+        }
+
+        if self.root_inner().deferring.load(Ordering::Relaxed) {
+            self.load_lazy_module(TypeId::of::<T>())
+                .inspect_err(|err| {
+                    diagnostics::note(&node, || Note::Failed(err.message.clone()))
+                })?;
+        }
+
+        let _guard = ResolveGuard::push(TypeId::of::<T>())
+            .inspect_err(|err| diagnostics::note(&node, || Note::Failed(err.message.clone())))?;
+
+        let provider = match self.resolve_provider::<T>() {
+            Err(err) if err.kind == ErrorKind::ServiceNotProvided => {
+                match self.resolve_generic::<T>() {
+                    Some(value) => {
+                        diagnostics::note(&node, || Note::Created(Scope::Root));
+                        return value.map(|value| self.intercept(value));
+                    }
+                    None => Err(err),
+                }
+            }
+            resolved => resolved,
+        }
+        .inspect_err(|err| diagnostics::note(&node, || Note::Failed(err.message.clone())))?;
+
+        diagnostics::note(&node, || Note::Created(provider.scope));
+
+        #[cfg(feature = "timing")]
+        let started = std::time::Instant::now();
+
+        let instance = match &provider.fallbacks {
+            Some(chain) => Shared::new(Instance::new(chain.resolve(self)?)),
+            None => self.resolve_instance::<T>()?,
+        };
+
+        #[cfg(feature = "timing")]
+        self.record_timing::<T>(started.elapsed());
+
+        if provider.scope == Scope::Transient {
+            self.run_after_init(&provider, instance.get())?;
+            return Ok(self.intercept(instance.value()));
+        }
+
+        match provider.scope {
+            Scope::Root => {
+                let root = self.root_injector();
+                root.store_instance::<T>(instance.clone());
+            }
+
+            Scope::Module => {
+                self.store_instance::<T>(instance.clone());
+            }
+
+            Scope::Transient => unreachable!(),
+        }
+
+        // A failed hook must not leave its instance behind for the next resolve.
+        self.run_after_init(&provider, instance.get())
+            .inspect_err(|_| {
+                self.clear_singleton::<T>();
+            })?;
+
+        Ok(self.intercept(instance.value()))
+    }
+
+    #[track_caller]
+    pub fn resolve<T>(&self) -> Shared<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_resolve::<T>().unwrap()
+    }
+
+    /// Resolves `T` like [`try_resolve`](Injector::try_resolve), together with
+    /// the [`ProviderMeta`] of the provider that produced it.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve), and with
+    /// [`ServiceNotProvided`](ErrorKind::ServiceNotProvided) if `T` was not
+    /// registered through a provider, such as a member of a
+    /// [generic family](Injector::provide_generic_fn).
+    pub fn resolve_with_meta<T>(&self) -> Result<(Shared<T>, ProviderMeta), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let was_cached = self.get_instance::<T>().is_some();
+        let value = self.try_resolve::<T>()?;
+
+        let meta = self
+            .provider_meta(TypeId::of::<T>(), was_cached)
+            .ok_or_else(|| Error::service_not_provided(std::any::type_name::<T>()))?;
+
+        Ok((value, meta))
+    }
+
+    /// Registers `factory` as the provider of every type in the generic family `F`.
+    ///
+    /// Whenever a resolve finds no provider for a type, `factory` is called
+    /// with that type's [`TypeId`] and may return an instance of exactly that
+    /// type, or `None` if it does not belong to the family. The instance is
+    /// cached on this injector, so each concrete type is created once. `F`
+    /// only names the registration; see the [`generic`](crate::generic) module
+    /// for the limits of this approach.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if this injector already has a factory for `F`.
+    pub fn try_provide_generic_fn<F, G>(&self, factory: G) -> Result<(), Error>
+    where
+        F: ?Sized + 'static,
+        G: Fn(&Injector, TypeId) -> Option<ErasedInstance> + Send + Sync + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        let family = TypeId::of::<F>();
+        let mut factories = self.inner.generic_factories.write().unwrap();
+        if factories
+            .iter()
+            .any(|(registered, _)| *registered == family)
+        {
+            return Err(Error::provider_already_registered(
+                std::any::type_name::<F>(),
+                "generic",
+            ));
+        }
+        factories.push((
+            family,
+            Shared::new(GenericFactory {
+                create: Box::new(factory),
+            }),
+        ));
+
+        Ok(())
+    }
+
+    /// Registers `factory` for the generic family `F`, panicking on conflict.
+    ///
+    /// See [`try_provide_generic_fn`](Injector::try_provide_generic_fn).
+    #[track_caller]
+    pub fn provide_generic_fn<F, G>(&self, factory: G) -> &Self
+    where
+        F: ?Sized + 'static,
+        G: Fn(&Injector, TypeId) -> Option<ErasedInstance> + Send + Sync + 'static,
+    {
+        self.try_provide_generic_fn::<F, G>(factory).unwrap();
+        self
+    }
+
+    /// Returns `true` if a type [`contains`](Injector::contains) does not see may
+    /// still resolve, through a lazy module or a generic factory on the path.
+    fn may_provide_unregistered(&self) -> bool {
+        if self.root_inner().deferring.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let mut current = Some(&*self.inner);
+        while let Some(inner) = current {
+            if !inner.generic_factories.read().unwrap().is_empty() {
+                return true;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        false
+    }
+
+    /// Creates `T` with the nearest generic factory that recognizes it.
+    ///
+    /// The instance is cached beside that factory, where `get_instance` finds
+    /// it on later resolves. Returns `None` if no factory claims `T`.
+    fn resolve_generic<T>(&self) -> Option<Result<Shared<T>, Error>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        let mut current = Some(&*self.inner);
+        while let Some(inner) = current {
+            // Cloned out so a factory may itself resolve without a held lock.
+            let factories = inner.generic_factories.read().unwrap().clone();
+
+            for (_, factory) in &factories {
+                let Some(instance) = (factory.create)(self, type_id) else {
+                    continue;
+                };
+                let Some(value) = instance.downcast::<T>() else {
+                    return Some(Err(Error::type_mismatch_found(
+                        std::any::type_name::<T>(),
+                        instance.type_name(),
+                    )));
+                };
+
+                // A concurrent resolve may have cached one first; keep that one.
+                let mut instances = inner.instances.write().unwrap();
+                if let Some(existing) = instances
+                    .get(type_id)
+                    .and_then(|instance| instance.downcast_ref::<Instance<T>>())
+                {
+                    return Some(Ok(existing.value()));
+                }
+                instances.insert(type_id, Shared::new(Instance::new(value.clone())));
+                return Some(Ok(value));
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+
+    /// Resolves `T` as a handle that cannot outlive this injector.
+    ///
+    /// The returned [`ScopedShared`] borrows `self`, so keeping it after a
+    /// request-scoped child injector is dropped fails to compile. See the
+    /// [`scoped`](crate::scoped) module for the trade-offs against
+    /// [`resolve`](Injector::resolve).
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve).
+    pub fn try_resolve_scoped<T>(&self) -> Result<ScopedShared<'_, T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        Ok(ScopedShared::new(self.try_resolve::<T>()?, self))
+    }
+
+    /// Resolves `T` as a handle tied to this injector, panicking on failure.
+    ///
+    /// See [`try_resolve_scoped`](Injector::try_resolve_scoped).
+    #[track_caller]
+    pub fn resolve_scoped<T>(&self) -> ScopedShared<'_, T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_resolve_scoped::<T>().unwrap()
+    }
+
+    /// Registers a plain `Copy` value on this injector, such as a port or a flag.
+    ///
+    /// The value is stored once and read back by value with
+    /// [`resolve_copy`](Injector::resolve_copy), which skips the provider,
+    /// caching and interception machinery of [`resolve`](Injector::resolve)
+    /// and hands out no [`Shared`] handle. Copy values live apart from
+    /// providers: they are not visible to `resolve`, and a provider of the
+    /// same `T` does not conflict with them.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if a copy value of `T` is already registered here, and with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) if this injector is
+    /// frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::Injector;
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Port(u16);
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide_copy(Port(8080));
+    ///
+    /// let Port(port) = injector.resolve_copy::<Port>();
+    /// assert_eq!(port, 8080);
+    /// ```
+    #[track_caller]
+    pub fn try_provide_copy<T>(&self, value: T) -> Result<(), Error>
+    where
+        T: Copy + Send + Sync + 'static,
+    {
+        self.inner.ensure_unfrozen()?;
+
+        let mut copies = self.inner.copies.write().unwrap();
+        if copies.contains_key(&TypeId::of::<T>()) {
+            return Err(Error::provider_already_registered(
+                std::any::type_name::<T>(),
+                "copy",
+            ));
+        }
+        copies.insert(TypeId::of::<T>(), Shared::new(value));
+
+        Ok(())
+    }
+
+    /// Registers a plain `Copy` value, panicking on a duplicate.
+    ///
+    /// See [`try_provide_copy`](Injector::try_provide_copy).
+    #[track_caller]
+    pub fn provide_copy<T>(&self, value: T) -> &Self
+    where
+        T: Copy + Send + Sync + 'static,
+    {
+        self.try_provide_copy::<T>(value).unwrap();
+        self
+    }
+
+    /// Returns the `Copy` value registered with [`provide_copy`](Injector::provide_copy).
+    ///
+    /// The nearest registration, starting from this injector and walking up
+    /// through its parents, wins.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided)
+    /// if no copy value of `T` is visible from this injector, and with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) once the container is
+    /// [shutting down](Injector::shutdown).
+    pub fn try_resolve_copy<T>(&self) -> Result<T, Error>
+    where
+        T: Copy + Send + Sync + 'static,
+    {
+        self.ensure_running()?;
+
+        let type_id = TypeId::of::<T>();
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(value) = inner
+                .copies
+                .read()
+                .unwrap()
+                .get(&type_id)
+                .and_then(|value| value.downcast_ref::<T>())
+            {
+                return Ok(*value);
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        Err(Error::service_not_provided(std::any::type_name::<T>()))
+    }
+
+    /// Returns the registered `Copy` value of `T`, panicking if there is none.
+    ///
+    /// See [`try_resolve_copy`](Injector::try_resolve_copy).
+    #[track_caller]
+    pub fn resolve_copy<T>(&self) -> T
+    where
+        T: Copy + Send + Sync + 'static,
+    {
+        self.try_resolve_copy::<T>().unwrap()
+    }
+
+    /// Resolves `T` and returns an owned clone of it.
+    ///
+    /// Prefer [`resolve`](Injector::resolve), which hands out the shared
+    /// instance, or [`with`](Injector::with), which lends it: both are a
+    /// reference count bump at most. Reach for this only when the caller must
+    /// own and mutate its copy, since it clones `T` on every call; for a large
+    /// configuration that is an allocation per field on every request.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve).
+    pub fn try_resolve_cloned<T>(&self) -> Result<T, Error>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        Ok(T::clone(&*self.try_resolve::<T>()?))
+    }
+
+    /// Resolves `T` and returns an owned clone of it, panicking on failure.
+    ///
+    /// See [`try_resolve_cloned`](Injector::try_resolve_cloned).
+    #[track_caller]
+    pub fn resolve_cloned<T>(&self) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.try_resolve_cloned::<T>().unwrap()
+    }
+
+    /// Resolves `T` and passes a reference to it to `f`, returning what `f` returns.
+    ///
+    /// This costs the same as [`resolve`](Injector::resolve): `T` itself is
+    /// never cloned, and the handle to it is dropped when `f` returns. It reads
+    /// well when only a few fields of a large shared value, such as a
+    /// configuration, are needed:
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// #[derive(Clone)]
+    /// struct Config {
+    ///     port: u16,
+    ///     allowed_origins: Vec<String>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| {
+    ///     Shared::new(Config {
+    ///         port: 8080,
+    ///         allowed_origins: vec!["https://example.com".to_string()],
+    ///     })
+    /// }));
+    ///
+    /// let port = injector.with::<Config, _>(|config| config.port);
+    /// assert_eq!(port, 8080);
+    ///
+    /// // An owned copy, for when the caller must mutate it.
+    /// let mut config = injector.resolve_cloned::<Config>();
+    /// config.allowed_origins.clear();
+    /// assert_eq!(injector.resolve::<Config>().allowed_origins.len(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve); `f` is not called.
+    pub fn try_with<T, R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        Ok(f(&*self.try_resolve::<T>()?))
+    }
+
+    /// Resolves `T` and passes a reference to it to `f`, panicking on failure.
+    ///
+    /// See [`try_with`](Injector::try_with).
+    #[track_caller]
+    pub fn with<T, R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_with::<T, R>(f).unwrap()
+    }
+
+    /// Resolves the cached instance of `T` and leaks a handle to it, returning a `&'static T`.
+    ///
+    /// Hot paths can then read the singleton without resolving it or touching
+    /// a reference count. The leaked handle keeps the instance alive for the
+    /// rest of the program, so it is intentionally never dropped: not when the
+    /// injector is dropped, nor when [`clear_singleton`](Injector::clear_singleton)
+    /// or an override replaces it in the cache, in which case later resolves
+    /// return the new instance while the `&'static T` keeps pointing at the old
+    /// one. Each call leaks one more handle, so call it once at startup and keep
+    /// the reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Config {
+    ///     port: u16,
+    /// }
+    ///
+    /// fn port(config: &'static Config) -> u16 {
+    ///     config.port
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Config>(Provider::root(|_| Shared::new(Config { port: 8080 })));
+    ///
+    /// let config: &'static Config = injector.leak_singleton::<Config>();
+    /// drop(injector);
+    /// assert_eq!(port(config), 8080);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`try_resolve`](Injector::try_resolve), and with
+    /// [`InvalidScope`](crate::ErrorKind::InvalidScope) if `T` is not cached,
+    /// as with a transient provider, since every call would leak a new instance.
+    pub fn try_leak_singleton<T>(&self) -> Result<&'static T, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let instance = self.try_resolve::<T>()?;
+
+        if self.get_instance::<T>().is_none() {
+            return Err(Error::invalid_scope(&format!(
+                "{} is not cached, so leaking it would leak an instance per call",
+                std::any::type_name::<T>()
+            )));
+        }
+
+        let leaked: &'static Shared<T> = Box::leak(Box::new(instance));
+        Ok(&**leaked)
+    }
+
+    /// Resolves and leaks the cached instance of `T`, panicking on failure.
+    ///
+    /// See [`try_leak_singleton`](Injector::try_leak_singleton).
+    #[track_caller]
+    pub fn leak_singleton<T>(&self) -> &'static T
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_leak_singleton::<T>().unwrap()
+    }
+
+    /// Returns `true` if a provider for `T` is registered in this injector or any ancestor.
+    ///
+    /// The lookup never constructs an [`Error`], which keeps probing for
+    /// unregistered types free of formatting and logging. On a child injector a
+    /// miss is also remembered until the next registration anywhere in the tree,
+    /// so repeated probes for an absent type skip walking the ancestors.
+    ///
+    /// [`WeakInjector`] is always available, so it is always contained. A member
+    /// of a [generic family](Injector::provide_generic_fn) or a type declared by
+    /// a [lazy module](Injector::provide_lazy_module) not loaded yet is not a
+    /// registration and is not contained, although
+    /// [`optional_resolve`](Injector::optional_resolve) still finds it.
+    pub fn contains<T>(&self) -> bool
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+
+        if type_id == TypeId::of::<WeakInjector>() {
+            return true;
+        }
+
+        // The root answers with one lookup; only walking ancestors is worth caching.
+        if self.inner.parent.is_none() {
+            return self.inner.providers.read().unwrap().contains_key(&type_id);
+        }
+
+        let registrations = self.root_inner().registrations.load(Ordering::Acquire);
+
+        {
+            let (seen_at, unregistered) = &*self.inner.unregistered.read().unwrap();
+            if *seen_at == registrations && unregistered.contains(&type_id) {
+                return false;
+            }
+        }
+
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if inner.providers.read().unwrap().contains_key(&type_id) {
+                return true;
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        let mut cache = self.inner.unregistered.write().unwrap();
+        if cache.0 != registrations {
+            *cache = (registrations, HashSet::new());
+        }
+        cache.1.insert(type_id);
+
+        false
+    }
+
+    pub fn optional_resolve<T>(&self) -> Option<Shared<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        // Bail out before `try_resolve` would build a `ServiceNotProvided` error,
+        // unless a generic factory or a lazy module may still provide `T`.
+        if !self.contains::<T>() && !self.may_provide_unregistered() {
+            return None;
+        }
+
+        self.try_resolve::<T>().ok()
+    }
+
+    /// Resolves `T`, or returns `T::default()` if no provider for `T` is available.
+    ///
+    /// This is a fallback at the call site, for optional settings whose absence
+    /// just means defaults; nothing is registered or cached, so each miss
+    /// builds a new default. A provider that is gated shut counts as absent.
+    /// Unlike [`optional_resolve`](Injector::optional_resolve), other failures
+    /// are not swallowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// #[derive(Default)]
+    /// struct RetryTuning {
+    ///     attempts: u32,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// assert_eq!(injector.resolve_or_default::<RetryTuning>().attempts, 0);
+    ///
+    /// injector.provide::<RetryTuning>(Provider::root(|_| Shared::new(RetryTuning { attempts: 3 })));
+    /// assert_eq!(injector.resolve_or_default::<RetryTuning>().attempts, 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`try_resolve`](Injector::try_resolve) other than
+    /// [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) for `T`,
+    /// such as a failed factory.
+    pub fn try_resolve_or_default<T>(&self) -> Result<Shared<T>, Error>
+    where
+        T: Default + Send + Sync + 'static,
+    {
+        if !self.contains::<T>() && !self.may_provide_unregistered() {
+            return Ok(Shared::new(T::default()));
+        }
+
+        match self.try_resolve::<T>() {
+            Err(err) if err.kind == ErrorKind::ServiceNotProvided => Ok(Shared::new(T::default())),
+            result => result,
+        }
+    }
+
+    /// Resolves `T` or falls back to `T::default()`, panicking on other failures.
+    ///
+    /// See [`try_resolve_or_default`](Injector::try_resolve_or_default).
+    #[track_caller]
+    pub fn resolve_or_default<T>(&self) -> Shared<T>
+    where
+        T: Default + Send + Sync + 'static,
+    {
+        self.try_resolve_or_default::<T>().unwrap()
+    }
+
+    /// Passes every resolved `T` through `interceptor` before it is returned.
+    ///
+    /// Unlike [`decorate`](Injector::decorate), which wraps the provider once at
+    /// registration, an interceptor runs on every resolve, including cache hits
+    /// and resolves made by other factories, and can be swapped or removed at
+    /// any time. That makes it a fit for end-to-end tests that substitute a
+    /// recording spy. Interceptors are installed on the root, so they apply to
+    /// the whole tree; setting one for a type that has one replaces it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<u32>(Provider::root(|_| Shared::new(1)));
+    ///
+    /// injector.set_interceptor::<u32, _>(|original| Shared::new(*original + 1));
+    /// assert_eq!(*injector.resolve::<u32>(), 2);
+    ///
+    /// injector.remove_interceptor::<u32>();
+    /// assert_eq!(*injector.resolve::<u32>(), 1);
+    /// ```
+    pub fn set_interceptor<T, F>(&self, interceptor: F) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(Shared<T>) -> Shared<T> + Send + Sync + 'static,
+    {
+        let interceptor: Interceptor<T> = Box::new(interceptor);
+
+        let root = self.root_inner();
+        root.interceptors
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Shared::new(interceptor));
+        root.intercepting.store(true, Ordering::Relaxed);
+
+        self
+    }
+
+    /// Removes the interceptor for `T`, if any. Returns `true` if one was removed.
+    pub fn remove_interceptor<T>(&self) -> bool
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.root_inner()
+            .interceptors
+            .write()
+            .unwrap()
+            .remove(&TypeId::of::<T>())
+            .is_some()
+    }
+
+    fn intercept<T>(&self, value: Shared<T>) -> Shared<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let root = self.root_inner();
+        if !root.intercepting.load(Ordering::Relaxed) {
+            return value;
+        }
+
+        // Cloned out so the interceptor may itself resolve without a held lock.
+        let interceptor = root
+            .interceptors
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .cloned();
+
+        match interceptor
+            .as_deref()
+            .and_then(|interceptor| interceptor.downcast_ref::<Interceptor<T>>())
+        {
+            Some(interceptor) => interceptor(value),
+            None => value,
+        }
+    }
+
+    /// Wraps every new instance whose type name satisfies `matches` with `wrap`.
+    ///
+    /// Unlike [`decorate`](Injector::decorate), which targets one type, this
+    /// selects types at runtime by their [`std::any::type_name`], so a single
+    /// decorator can wrap, say, every repository. `wrap` receives each instance
+    /// as an [`ErasedInstance`] and must return one of the same type; see the
+    /// [`erased`](crate::erased) module for the constraints this implies.
+    ///
+    /// Matching decorators are kept on the root and apply to the whole tree, to
+    /// providers registered both before and after them. They run in the order
+    /// they were added, after any [`decorate`](Injector::decorate) layers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidScope`](crate::ErrorKind::InvalidScope) if the root is
+    /// [frozen](Injector::freeze).
+    pub fn try_decorate_matching<P, W>(&self, matches: P, wrap: W) -> Result<(), Error>
+    where
+        P: Fn(&str) -> bool + Send + Sync + 'static,
+        W: Fn(ErasedInstance, &Injector) -> ErasedInstance + Send + Sync + 'static,
+    {
+        let root = self.root_inner();
+        root.ensure_unfrozen()?;
+
+        root.matching_decorators
+            .write()
+            .unwrap()
+            .push(Shared::new(MatchingDecorator {
+                matches: Box::new(matches),
+                wrap: Box::new(wrap),
+            }));
+        root.decorating.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Like [`try_decorate_matching`](Injector::try_decorate_matching), but panics on error.
+    #[track_caller]
+    pub fn decorate_matching<P, W>(&self, matches: P, wrap: W) -> &Self
+    where
+        P: Fn(&str) -> bool + Send + Sync + 'static,
+        W: Fn(ErasedInstance, &Injector) -> ErasedInstance + Send + Sync + 'static,
+    {
+        self.try_decorate_matching(matches, wrap).unwrap();
+        self
+    }
+
+    /// Passes a new instance through every matching decorator whose predicate accepts `T`.
+    fn decorate_matching_instance<T>(&self, value: Shared<T>) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_name = std::any::type_name::<T>();
+
+        // Cloned out so a decorator may itself resolve without a held lock.
+        let decorators = self
+            .root_inner()
+            .matching_decorators
+            .read()
+            .unwrap()
+            .clone();
+
+        let mut instance = ErasedInstance::new(value);
+        for decorator in decorators.iter().filter(|d| (d.matches)(type_name)) {
+            instance = (decorator.wrap)(instance, self);
+        }
+
+        instance
+            .downcast::<T>()
+            .ok_or_else(|| Error::type_mismatch_found(type_name, instance.type_name()))
+    }
+
+    /// Registers an [`AsyncProvider`] for `T`, returning an [`Error`] on conflict.
+    ///
+    /// Root-scoped providers, the only kind [`Provider::async_root`] creates, are
+    /// registered on the root injector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered)
+    /// if `T` already has a synchronous or asynchronous provider there.
+    #[cfg(feature = "async")]
+    pub fn try_provide_async<T>(&self, provider: AsyncProvider<T>) -> Result<(), Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let target = match provider.scope {
+            Scope::Root => self.root_injector(),
+            Scope::Module | Scope::Transient => self.clone(),
+        };
+        target.inner.ensure_unfrozen()?;
+
+        let type_id = TypeId::of::<T>();
+        let mut providers = target.inner.async_providers.write().unwrap();
+        if providers.contains_key(&type_id)
+            || target
+                .inner
+                .providers
+                .read()
+                .unwrap()
+                .contains_key(&type_id)
+        {
+            return Err(Error::provider_already_registered(
+                std::any::type_name::<T>(),
+                provider.scope.to_string().as_str(),
+            ));
+        }
+
+        providers.insert(type_id, Shared::new(provider));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[track_caller]
+    pub fn provide_async<T>(&self, provider: AsyncProvider<T>) -> &Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.try_provide_async::<T>(provider).unwrap();
+        self
+    }
+
+    /// Resolves `T` from its [`AsyncProvider`], awaiting the factory on first use.
+    ///
+    /// A cached instance is returned without awaiting anything. Otherwise the
+    /// factory runs, retried according to its [`RetryPolicy`](crate::RetryPolicy),
+    /// and a successful result is cached. Once cached, the instance is also
+    /// returned by the synchronous [`resolve`](Injector::resolve).
+    ///
+    /// Concurrent resolves of a singleton share a single factory run and all
+    /// return its instance. Initialization is cancel-safe: if the resolve
+    /// running the factory is dropped mid-way, or its factory fails, one of
+    /// the waiting resolves runs the factory again in its place, and the first
+    /// success is cached for everyone.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) if
+    /// `T` has no async provider, and otherwise with the factory's last error.
+    #[cfg(feature = "async")]
+    pub async fn resolve_async<T>(&self) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.ensure_running()?;
+
+        if let Some(value) = self.get_instance::<T>() {
+            return Ok(self.intercept(value));
+        }
+
+        let type_id = TypeId::of::<T>();
+        let type_name = std::any::type_name::<T>();
+
+        let provider = self
+            .get_async_provider(type_id)
+            .ok_or_else(|| Error::service_not_provided(type_name))?
+            .downcast::<AsyncProvider<T>>()
+            .map_err(|_| Error::type_mismatch(type_name))?;
+
+        let holder = match provider.scope {
+            Scope::Root => self.root_injector(),
+            Scope::Module => self.clone(),
+            Scope::Transient => return Ok(self.intercept(self.create_async(&provider).await?)),
+        };
+
+        // The cell admits one factory run at a time. Dropping the future that
+        // holds it hands the run to the next waiter rather than poisoning it.
+        let cell = holder.async_init_cell::<T>();
+        let value = cell
+            .get_or_try_init(|| async {
+                let value = self.create_async(&provider).await?;
+                Ok::<_, Error>(holder.cache_async_instance(value))
+            })
+            .await?
+            .clone();
+
+        Ok(self.intercept(value))
+    }
+
+    /// Runs the factory of `provider`, retrying as configured, inside a resolve span.
+    #[cfg(feature = "async")]
+    async fn create_async<T>(&self, provider: &AsyncProvider<T>) -> Result<Shared<T>, Error>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let create = provider.create(self);
+        // Instrumented rather than entered, so the span follows the factory
+        // across its `.await` points, including retries.
+        #[cfg(feature = "tracing")]
+        let create = create.instrument(resolve_span::<T>());
+
+        create.await
+    }
+
+    /// Returns the cell through which async resolves initialize `T` on this injector.
+    ///
+    /// A set cell whose instance is no longer cached here belongs to a cleared
+    /// singleton, so it is replaced by a fresh one.
+    #[cfg(feature = "async")]
+    fn async_init_cell<T>(&self) -> Shared<OnceCell<Shared<T>>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let mut cells = self.inner.async_inits.write().unwrap();
+
+        let current = cells
+            .get(&type_id)
+            .and_then(|cell| cell.clone().downcast::<OnceCell<Shared<T>>>().ok());
+        if let Some(cell) = current
+            && (!cell.initialized() || self.inner.instances.read().unwrap().get(type_id).is_some())
+        {
+            return cell;
+        }
+
+        let cell = Shared::new(OnceCell::new());
+        cells.insert(type_id, cell.clone());
+        cell
+    }
+
+    /// Caches `value` as this injector's instance of `T`, unless one is cached already.
+    ///
+    /// Returns whichever instance ends up cached.
+    #[cfg(feature = "async")]
+    fn cache_async_instance<T>(&self, value: Shared<T>) -> Shared<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        let mut instances = self.inner.instances.write().unwrap();
+
+        if let Some(existing) = instances
+            .get(type_id)
+            .and_then(|instance| instance.downcast_ref::<Instance<T>>())
+        {
+            return existing.value();
+        }
+        instances.insert(type_id, Shared::new(Instance::new(value.clone())));
+
+        value
+    }
+
+    #[cfg(feature = "async")]
+    fn get_async_provider(&self, type_id: TypeId) -> Option<Shared<dyn Any + Send + Sync>> {
+        let mut current = Some(&*self.inner);
+
+        while let Some(inner) = current {
+            if let Some(provider) = inner.async_providers.read().unwrap().get(&type_id) {
+                return Some(provider.clone());
+            }
+
+            current = inner.parent.as_deref();
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Config {
+        name: String,
+    }
+
+    #[test]
+    fn test_cached_singleton_returns_same_instance() {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::singleton(|_| {
+            Shared::new(Config {
+                name: "cached".to_string(),
+            })
+        }));
+
+        let first = injector.resolve::<Config>();
+        let second = injector.resolve::<Config>();
+
+        assert!(Shared::ptr_eq(&first, &second));
+        assert_eq!(second.name, "cached");
+    }
+
+    #[test]
+    fn test_child_reads_instance_cached_in_root() {
+        let root = Shared::new(Injector::root());
+        root.provide::<Config>(Provider::root(|_| {
+            Shared::new(Config {
+                name: "root".to_string(),
+            })
+        }));
+
+        let from_root = root.resolve::<Config>();
+        let child = Injector::child(root.clone());
+        let from_child = child.resolve::<Config>();
+
+        assert!(Shared::ptr_eq(&from_root, &from_child));
+    }
+
+    #[test]
+    fn test_cache_hit_does_not_hold_extra_references() {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::singleton(|_| {
+            Shared::new(Config {
+                name: "counted".to_string(),
+            })
+        }));
+
+        let first = injector.resolve::<Config>();
+        // One reference held by the cache and one by `first`.
+        assert_eq!(Shared::strong_count(&first), 2);
+
+        let second = injector.resolve::<Config>();
+        assert_eq!(Shared::strong_count(&first), 3);
+
+        drop(second);
+        assert_eq!(Shared::strong_count(&first), 2);
+    }
+
+    struct Level1;
+    struct Level2;
+    struct Level3;
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_diagnostics_logs_resolution_tree() {
+        let recorder = SpanRecorder::default();
+        let injector = Injector::root();
+        injector.set_diagnostics(true);
+
+        injector.provide::<Level3>(Provider::singleton(|_| Shared::new(Level3)));
+        injector.provide::<Level2>(Provider::singleton(|injector| {
+            injector.resolve::<Level3>();
+            Shared::new(Level2)
+        }));
+        injector.provide::<Level1>(Provider::transient(|injector| {
+            injector.resolve::<Level2>();
+            injector.resolve::<Level3>();
+            Shared::new(Level1)
+        }));
+
+        tracing::subscriber::with_default(recorder.clone(), || injector.resolve::<Level1>());
+
+        let trees = recorder.events_with("resolution tree:");
+        assert_eq!(trees.len(), 1, "one tree per top-level resolve");
+
+        let expected = [
+            "resolution tree:",
+            "  sadi::injector::tests::Level1 [created, Transient]",
+            "    sadi::injector::tests::Level2 [created, Module]",
+            "      sadi::injector::tests::Level3 [created, Module]",
+            "    sadi::injector::tests::Level3 [cached]",
+        ]
+        .join("\n");
+        assert_eq!(trees[0], format!("message={} ", expected));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_diagnostics_disabled_by_default() {
+        let recorder = SpanRecorder::default();
+        let injector = Injector::root();
+        injector.provide::<Level3>(Provider::singleton(|_| Shared::new(Level3)));
+
+        tracing::subscriber::with_default(recorder.clone(), || injector.resolve::<Level3>());
+
+        assert!(!injector.diagnostics_enabled());
+        assert!(recorder.events_with("resolution tree:").is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics_enabled_on_root_applies_to_child() {
+        let root = Shared::new(Injector::root());
+        root.set_diagnostics(true);
+        let child = Injector::child(root.clone());
+
+        assert!(child.diagnostics_enabled());
+    }
+
+    #[test]
+    fn test_contains_reports_registered_providers() {
+        let root = Shared::new(Injector::root());
+        root.provide::<Config>(Provider::root(|_| {
+            Shared::new(Config {
+                name: "root".to_string(),
+            })
+        }));
+        let child = Injector::child(root.clone());
+
+        assert!(root.contains::<Config>());
+        assert!(child.contains::<Config>());
+        assert!(!child.contains::<Level1>());
+    }
+
+    #[test]
+    fn test_override_invalidates_a_remembered_miss() {
+        let root = Shared::new(Injector::root());
+        let child = Injector::child(root.clone());
+        let grandchild = Injector::child(Shared::new(child.clone()));
+
+        assert!(!child.contains::<Level1>());
+        assert!(!grandchild.contains::<Level1>());
+        assert!(child.optional_resolve::<Level1>().is_none());
+
+        child.override_provider::<Level1>(Provider::singleton(|_| Shared::new(Level1)));
+
+        assert!(child.contains::<Level1>());
+        assert!(grandchild.contains::<Level1>());
+        assert!(child.optional_resolve::<Level1>().is_some());
+        assert!(grandchild.optional_resolve::<Level1>().is_some());
+    }
+
+    #[test]
+    fn test_optional_resolve_misses_and_hits() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(Provider::singleton(|_| Shared::new(Level1)));
+
+        assert!(injector.optional_resolve::<Level1>().is_some());
+        assert!(injector.optional_resolve::<Level2>().is_none());
+    }
+
+    struct LevelModule;
+
+    impl Module for LevelModule {
+        fn providers(&self, injector: &Injector) {
+            injector.provide::<Level1>(Provider::root(|_| Shared::new(Level1)));
+        }
+    }
+
+    struct SkippedModule;
+
+    impl Module for SkippedModule {
+        fn providers(&self, injector: &Injector) {
+            injector.provide::<Level2>(Provider::root(|_| Shared::new(Level2)));
+        }
+    }
+
+    #[test]
+    fn test_load_modules_from_dynamic_list() {
+        let include_skipped = false;
+
+        let mut modules: Vec<Box<dyn Module>> = vec![Box::new(LevelModule)];
+        if include_skipped {
+            modules.push(Box::new(SkippedModule));
+        }
+
+        let injector = Injector::root();
+        injector.load_modules(modules).unwrap();
+
+        assert!(injector.contains::<Level1>());
+        assert!(!injector.contains::<Level2>());
+    }
+
+    struct ParentModule;
+
+    impl Module for ParentModule {
+        fn imports(&self) -> Vec<Box<dyn Module>> {
+            vec![Box::new(LevelModule)]
+        }
+    }
+
+    #[test]
+    fn test_loaded_modules_are_tracked() {
+        let injector = Injector::root();
+        injector.load_modules(vec![Box::new(ParentModule)]).unwrap();
+
+        assert_eq!(
+            injector.loaded_modules(),
+            vec![
+                std::any::type_name::<LevelModule>(),
+                std::any::type_name::<ParentModule>(),
+            ]
+        );
+        assert!(injector.assert_loaded::<LevelModule>().is_ok());
+        assert!(injector.assert_loaded::<ParentModule>().is_ok());
+
+        let err = injector.assert_loaded::<SkippedModule>().unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::ModuleNotLoaded);
+    }
+
+    trait Repo: Send + Sync {
+        fn name(&self) -> &'static str;
+    }
+
+    struct RealRepo;
+    impl Repo for RealRepo {
+        fn name(&self) -> &'static str {
+            "real"
+        }
+    }
+
+    struct FakeRepo(&'static str);
+    impl Repo for FakeRepo {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    fn app_injector() -> Injector {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::root(|_| {
+            Shared::new(Config {
+                name: "expensive".to_string(),
+            })
+        }));
+        injector.provide::<dyn Repo>(Provider::root(|_| {
+            Shared::new(RealRepo) as Shared<dyn Repo>
+        }));
+        injector
+    }
+
+    fn fake(name: &'static str) -> Provider<dyn Repo> {
+        Provider::root(move |_| Shared::new(FakeRepo(name)) as Shared<dyn Repo>)
+    }
+
+    #[test]
+    fn test_child_with_overrides_is_isolated() {
+        let injector = app_injector();
+        let config = injector.resolve::<Config>();
+
+        let first = injector.child_with_overrides(|child| {
+            child.override_provider::<dyn Repo>(fake("first"));
+        });
+        let second = injector.child_with_overrides(|child| {
+            child.override_provider::<dyn Repo>(fake("second"));
+        });
+
+        // The parent has already cached the real repository.
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "real");
+
+        assert_eq!(first.resolve::<dyn Repo>().name(), "first");
+        assert_eq!(second.resolve::<dyn Repo>().name(), "second");
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "real");
+
+        // Non-overridden singletons are shared with the parent.
+        assert!(Shared::ptr_eq(&config, &first.resolve::<Config>()));
+        assert!(Shared::ptr_eq(&config, &second.resolve::<Config>()));
+    }
+
+    #[test]
+    fn test_override_provider_replaces_local_registration() {
+        let injector = Injector::root();
+        injector.provide::<dyn Repo>(Provider::singleton(|_| {
+            Shared::new(RealRepo) as Shared<dyn Repo>
+        }));
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "real");
+
+        injector.override_provider::<dyn Repo>(fake("fake"));
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "fake");
+    }
+
+    #[test]
+    fn test_try_resolve_concrete_and_trait_object() {
+        let injector = app_injector();
+        injector.provide::<RealRepo>(Provider::singleton(|_| Shared::new(RealRepo)));
+
+        let concrete: Shared<RealRepo> = injector.try_resolve::<RealRepo>().unwrap();
+        let repo: Shared<dyn Repo> = injector.try_resolve::<dyn Repo>().unwrap();
+
+        assert_eq!(concrete.name(), "real");
+        assert_eq!(repo.name(), "real");
+
+        // The two registrations are independent entries with their own caches.
+        assert!(Shared::ptr_eq(&repo, &injector.resolve::<dyn Repo>()));
+        assert!(Shared::ptr_eq(&concrete, &injector.resolve::<RealRepo>()));
+    }
+
+    #[test]
+    fn test_try_resolve_unregistered_trait_object_errors() {
+        let Err(err) = Injector::root().try_resolve::<dyn Repo>() else {
+            panic!("unregistered trait object resolved");
+        };
+
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+        assert!(err.message.contains("dyn"));
+    }
+
+    #[test]
+    fn test_decorators_stack_in_registration_order() {
+        let calls = Shared::new(std::sync::Mutex::new(Vec::new()));
+        let injector = app_injector();
+
+        for tag in ["first", "second"] {
+            let calls = calls.clone();
+            injector.decorate::<dyn Repo, _>(move |inner, _| {
+                calls.lock().unwrap().push((tag, inner.name()));
+                Shared::new(FakeRepo(tag)) as Shared<dyn Repo>
+            });
+        }
+
+        let repo = injector.resolve::<dyn Repo>();
+        assert_eq!(repo.name(), "second");
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![("first", "real"), ("second", "first")]
+        );
+
+        // The decorated singleton is cached; decorators do not run again.
+        assert!(Shared::ptr_eq(&repo, &injector.resolve::<dyn Repo>()));
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_decorate_unregistered_type_errors() {
+        let injector = Injector::root();
+        let err = injector
+            .try_decorate::<dyn Repo, _>(|inner, _| inner)
+            .unwrap_err();
+
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+    }
+
+    #[test]
+    fn test_resolution_stats_count_per_scope() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(Provider::transient(|_| Shared::new(Level1)));
+
+        let scope = Injector::child(Shared::new(injector.clone()));
+        scope.set_resolution_stats(true);
+
+        for _ in 0..10 {
+            scope.resolve::<Level1>();
+        }
+        injector.resolve::<Level1>();
+
+        let stats = scope.resolution_stats();
+        assert_eq!(stats.get(std::any::type_name::<Level1>()), Some(&10));
+        assert!(injector.resolution_stats().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_erased_by_type_id() {
+        let injector = app_injector();
+        let config = injector.resolve::<Config>();
+
+        let erased = injector.resolve_erased(TypeId::of::<Config>()).unwrap();
+        let resolved = erased.downcast_ref::<Instance<Config>>().unwrap().value();
+        assert!(Shared::ptr_eq(&config, &resolved));
+
+        let erased = injector.resolve_erased(TypeId::of::<dyn Repo>()).unwrap();
+        let repo = erased.downcast_ref::<Instance<dyn Repo>>().unwrap().value();
+        assert_eq!(repo.name(), "real");
+
+        let err = injector
+            .resolve_erased(TypeId::of::<Level1>())
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+    }
+
+    struct FeatureFlags {
+        enabled: AtomicBool,
+    }
+
+    #[test]
+    fn test_gated_provider_follows_flag() {
+        let injector = Injector::root();
+        injector.provide::<FeatureFlags>(Provider::root(|_| {
+            Shared::new(FeatureFlags {
+                enabled: AtomicBool::new(false),
+            })
+        }));
+        injector.provide::<Level1>(Provider::singleton(|_| Shared::new(Level1)).gated_by(
+            |injector| {
+                injector
+                    .resolve::<FeatureFlags>()
+                    .enabled
+                    .load(Ordering::Relaxed)
+            },
+        ));
+        let flags = injector.resolve::<FeatureFlags>();
+
+        let err = injector.try_resolve::<Level1>().map(|_| ()).unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+        assert!(injector.optional_resolve::<Level1>().is_none());
+
+        flags.enabled.store(true, Ordering::Relaxed);
+        let first = injector.resolve::<Level1>();
+
+        // The gate also applies to the cached instance.
+        flags.enabled.store(false, Ordering::Relaxed);
+        assert!(injector.optional_resolve::<Level1>().is_none());
+
+        flags.enabled.store(true, Ordering::Relaxed);
+        assert!(Shared::ptr_eq(&first, &injector.resolve::<Level1>()));
+    }
+
+    #[test]
+    fn test_factory_can_register_providers() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(Provider::singleton(|injector| {
+            injector.provide::<Level2>(Provider::singleton(|_| Shared::new(Level2)));
+            injector.provide::<Level3>(Provider::root(|_| Shared::new(Level3)));
+            Shared::new(Level1)
+        }));
+
+        assert!(!injector.contains::<Level2>());
+
+        injector.resolve::<Level1>();
+
+        assert!(injector.try_resolve::<Level2>().is_ok());
+        assert!(injector.try_resolve::<Level3>().is_ok());
+    }
+
+    #[test]
+    fn test_higher_priority_provider_wins() {
+        let injector = Injector::root();
+        injector.provide::<dyn Repo>(
+            Provider::root(|_| Shared::new(FakeRepo("app")) as Shared<dyn Repo>).priority(100),
+        );
+        injector.provide::<dyn Repo>(Provider::root(|_| {
+            Shared::new(RealRepo) as Shared<dyn Repo>
+        }));
+
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "app");
+
+        let injector = Injector::root();
+        injector.provide::<dyn Repo>(Provider::root(|_| {
+            Shared::new(RealRepo) as Shared<dyn Repo>
+        }));
+        injector.resolve::<dyn Repo>();
+        injector.provide::<dyn Repo>(
+            Provider::root(|_| Shared::new(FakeRepo("app")) as Shared<dyn Repo>).priority(100),
+        );
+
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "app");
+    }
+
+    #[test]
+    fn test_equal_priority_providers_conflict() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(Provider::root(|_| Shared::new(Level1)).priority(5));
+
+        let err = injector
+            .try_provide::<Level1>(Provider::root(|_| Shared::new(Level1)).priority(5))
+            .unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::ProviderAlreadyRegistered);
+    }
+
+    struct Cache<K, V> {
+        entries: Vec<(K, V)>,
+    }
+
+    #[test]
+    fn test_cached_miss_is_cleared_by_registration() {
+        let root = Shared::new(Injector::root());
+        let child = Injector::child(root.clone());
+
+        assert!(child.optional_resolve::<Level1>().is_none());
+        assert!(child.optional_resolve::<Level1>().is_none());
+
+        root.provide::<Level1>(Provider::root(|_| Shared::new(Level1)));
+        assert!(child.optional_resolve::<Level1>().is_some());
+
+        assert!(!root.contains::<Level2>());
+        child.provide::<Level2>(Provider::singleton(|_| Shared::new(Level2)));
+        assert!(child.contains::<Level2>());
+        assert!(!root.contains::<Level2>());
+    }
+
+    #[test]
+    fn test_generic_instantiations_are_distinct() {
+        let injector = Injector::root();
+        injector.provide::<Cache<String, u32>>(Provider::root(|_| {
+            Shared::new(Cache {
+                entries: vec![("answer".to_string(), 42)],
+            })
+        }));
+        injector.provide::<Cache<u32, String>>(Provider::singleton(|_| {
+            Shared::new(Cache {
+                entries: vec![(7, "seven".to_string())],
+            })
+        }));
+
+        assert_eq!(
+            injector.resolve::<Cache<String, u32>>().entries[0],
+            ("answer".to_string(), 42)
+        );
+        assert_eq!(
+            injector.resolve::<Cache<u32, String>>().entries[0],
+            (7, "seven".to_string())
+        );
+    }
+
+    #[test]
+    fn test_generic_error_names_type_arguments() {
+        let injector = Injector::root();
+        injector.provide::<Cache<String, u32>>(Provider::root(|_| {
+            Shared::new(Cache { entries: vec![] })
+        }));
+
+        let Err(err) = injector.try_resolve::<Cache<u32, u32>>() else {
+            panic!("unregistered instantiation was resolved");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+        assert!(
+            err.message
+                .contains("sadi::injector::tests::Cache<u32, u32>")
+        );
+    }
+
+    #[test]
+    fn test_share_sees_writes_from_original() {
+        let injector = Injector::root();
+        let shared = injector.share();
+
+        injector.provide::<Level1>(Provider::singleton(|_| Shared::new(Level1)));
+
+        assert!(shared.contains::<Level1>());
+        assert!(Shared::ptr_eq(
+            &injector.resolve::<Level1>(),
+            &shared.resolve::<Level1>()
+        ));
+    }
+
+    #[test]
+    fn test_deep_copy_is_independent() {
+        let injector = app_injector();
+        let config = injector.resolve::<Config>();
+
+        let copy = injector.deep_copy();
+        injector.provide::<Level1>(Provider::singleton(|_| Shared::new(Level1)));
+
+        assert!(!copy.contains::<Level1>());
+        assert!(copy.contains::<Config>());
+
+        // The copy starts with an empty cache.
+        assert!(!Shared::ptr_eq(&config, &copy.resolve::<Config>()));
+    }
+
+    struct Service {
+        config: Shared<Config>,
+    }
+
+    #[test]
+    fn test_provide_factory_with_injects_dependency() {
+        let injector = app_injector();
+        injector.provide_factory_with::<Service, Config, _>(|config| Service { config });
+
+        let child = Injector::child(Shared::new(injector.clone()));
+        let service = child.resolve::<Service>();
+
+        assert!(Shared::ptr_eq(
+            &service.config,
+            &injector.resolve::<Config>()
+        ));
+        assert!(Shared::ptr_eq(&service, &child.resolve::<Service>()));
+    }
+
+    struct Primary;
+    struct Replica;
+
+    #[test]
+    fn test_tagged_providers_resolve_independently() {
+        let injector = Injector::root();
+        injector.provide_tagged::<Config, Primary>(Provider::root(|_| {
+            Shared::new(Config {
+                name: "primary".to_string(),
+            })
+        }));
+        injector.provide_tagged::<Config, Replica>(Provider::singleton(|_| {
+            Shared::new(Config {
+                name: "replica".to_string(),
+            })
+        }));
+
+        let primary = injector.resolve_tagged::<Config, Primary>();
+        let replica = injector.resolve_tagged::<Config, Replica>();
+
+        assert_eq!(primary.name, "primary");
+        assert_eq!(replica.name, "replica");
+        assert!(Shared::ptr_eq(
+            &primary,
+            &injector.resolve_tagged::<Config, Primary>()
+        ));
+
+        // Tagged registrations do not occupy the untagged slot.
+        assert!(!injector.contains::<Config>());
+    }
+
+    fn profiled_injector(profile: Option<&str>) -> Injector {
+        let injector = Injector::root();
+        if let Some(profile) = profile {
+            injector.set_profile(profile);
+        }
+
+        let child = Injector::child(Shared::new(injector.clone()));
+        child.provide::<dyn Repo>(
+            Provider::root(|_| Shared::new(FakeRepo("memory")) as Shared<dyn Repo>).only_in("dev"),
+        );
+        child.provide::<dyn Repo>(
+            Provider::root(|_| Shared::new(RealRepo) as Shared<dyn Repo>).except_in("dev"),
+        );
+        injector
+    }
+
+    #[test]
+    fn test_profile_selects_provider() {
+        assert_eq!(
+            profiled_injector(Some("dev")).resolve::<dyn Repo>().name(),
+            "memory"
+        );
+        assert_eq!(
+            profiled_injector(Some("prod")).resolve::<dyn Repo>().name(),
+            "real"
+        );
+        assert_eq!(profiled_injector(None).resolve::<dyn Repo>().name(), "real");
+    }
+
+    #[test]
+    fn test_profile_inherited_from_parent() {
+        let injector = Injector::root();
+        injector.set_profile("prod");
+        let child = Injector::child(Shared::new(injector.clone()));
+
+        assert_eq!(child.profile().as_deref(), Some("prod"));
+
+        child.set_profile("dev");
+        assert_eq!(child.profile().as_deref(), Some("dev"));
+        assert_eq!(injector.profile().as_deref(), Some("prod"));
+    }
+
+    #[cfg(feature = "thread-safe")]
+    #[test]
+    fn test_child_with_overrides_across_threads() {
+        let injector = Shared::new(app_injector());
+
+        let handles: Vec<_> = ["first", "second", "third"]
+            .into_iter()
+            .map(|name| {
+                let injector = injector.clone();
+                std::thread::spawn(move || {
+                    let child = injector.child_with_overrides(|child| {
+                        child.override_provider::<dyn Repo>(fake(name));
+                    });
+                    (name, child.resolve::<dyn Repo>().name())
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (expected, resolved) = handle.join().unwrap();
+            assert_eq!(expected, resolved);
+        }
+
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "real");
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_debug_summarizes_registrations() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(Provider::root(|_| Shared::new(Level1)));
+        injector.provide::<Level2>(Provider::singleton(|_| Shared::new(Level2)));
+        injector.provide::<Level3>(Provider::transient(|_| Shared::new(Level3)));
+        injector.resolve::<Level2>();
+
+        let debug_str = format!("{:?}", injector);
+
+        assert!(debug_str.contains("registrations: 3"));
+        assert!(debug_str.contains("root: 1"));
+        assert!(debug_str.contains("module: 1"));
+        assert!(debug_str.contains("transient: 1"));
+        assert!(debug_str.contains("instances: 1"));
+        assert!(debug_str.contains("sadi::injector::tests::Level1"));
+        assert!(!debug_str.contains("factory"));
+    }
+
+    trait Middleware: Send + Sync {
+        fn name(&self) -> &'static str;
+    }
+
+    struct Named(&'static str);
+    impl Middleware for Named {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_resolve_ordered_sorts_by_key() {
+        let injector = Injector::root();
+        for (key, name) in [(30, "compress"), (10, "auth"), (20, "logging")] {
+            injector.provide_keyed::<dyn Middleware, u8>(
+                key,
+                Provider::root(move |_| Shared::new(Named(name)) as Shared<dyn Middleware>),
+            );
+        }
+
+        let names: Vec<_> = injector
+            .resolve_ordered::<dyn Middleware, u8>()
+            .iter()
+            .map(|middleware| middleware.name())
+            .collect();
+        assert_eq!(names, ["auth", "logging", "compress"]);
+    }
+
+    #[test]
+    fn test_duplicate_key_conflicts() {
+        let injector = Injector::root();
+        injector.provide_keyed::<dyn Middleware, u8>(
+            10,
+            Provider::root(|_| Shared::new(Named("auth")) as Shared<dyn Middleware>),
+        );
+
+        let err = injector
+            .try_provide_keyed::<dyn Middleware, u8>(
+                10,
+                Provider::root(|_| Shared::new(Named("other")) as Shared<dyn Middleware>),
+            )
+            .unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::ProviderAlreadyRegistered);
+    }
+
+    #[test]
+    fn test_stats_track_cached_singletons() {
+        let injector = Injector::root();
+        injector.provide::<Config>(
+            Provider::root(|_| {
+                Shared::new(Config {
+                    name: "app".to_string(),
+                })
+            })
+            .track_size(),
+        );
+        injector.provide::<Level1>(Provider::transient(|_| Shared::new(Level1)));
+        assert_eq!(injector.stats().cached_singletons, 0);
+
+        injector.resolve::<Config>();
+        injector.resolve::<Level1>();
+
+        let stats = injector.stats();
+        assert_eq!((stats.root, stats.module, stats.transient), (1, 0, 1));
+        assert_eq!(stats.cached_singletons, 1);
+        assert_eq!(stats.retained_bytes, std::mem::size_of::<Config>());
+
+        assert!(injector.clear_singleton::<Config>());
+        assert!(!injector.clear_singleton::<Config>());
+
+        let stats = injector.stats();
+        assert_eq!(stats.cached_singletons, 0);
+        assert_eq!(stats.retained_bytes, 0);
+    }
+
+    struct SpyRepo {
+        name: &'static str,
+        calls: std::sync::Arc<AtomicUsize>,
+    }
+    impl Repo for SpyRepo {
+        fn name(&self) -> &'static str {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.name
+        }
+    }
+
+    #[test]
+    fn test_interceptor_wraps_every_resolve_until_removed() {
+        let injector = app_injector();
+        let calls = std::sync::Arc::new(AtomicUsize::new(0));
+
+        let spied = calls.clone();
+        injector.set_interceptor::<dyn Repo, _>(move |original| {
+            Shared::new(SpyRepo {
+                name: original.name(),
+                calls: spied.clone(),
+            }) as Shared<dyn Repo>
+        });
+
+        let child = Injector::child(Shared::new(injector.clone()));
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "real");
+        assert_eq!(child.resolve::<dyn Repo>().name(), "real");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        assert!(injector.remove_interceptor::<dyn Repo>());
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "real");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    struct SlowPool;
+
+    #[test]
+    fn test_init_eager_reports_time_per_type() {
+        let injector = Injector::root();
+        injector.provide::<SlowPool>(
+            Provider::root(|_| {
+                std::thread::sleep(std::time::Duration::from_millis(30));
+                Shared::new(SlowPool)
+            })
+            .eager(),
+        );
+        injector.provide::<Level1>(Provider::root(|_| Shared::new(Level1)).eager());
+        injector.provide::<Level2>(Provider::root(|_| Shared::new(Level2)));
+
+        let report = injector.init_eager();
+
+        let types: Vec<_> = report.per_type.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            types,
+            [
+                std::any::type_name::<Level1>(),
+                std::any::type_name::<SlowPool>()
+            ]
+        );
+        assert_eq!(
+            report.slowest().map(|(name, _)| name),
+            Some(std::any::type_name::<SlowPool>())
+        );
+        assert!(report.per_type[1].1 >= std::time::Duration::from_millis(30));
+        assert!(report.total >= report.per_type[1].1);
+        assert_eq!(injector.stats().cached_singletons, 2);
+    }
+
+    #[test]
+    fn test_type_mismatch_names_expected_and_registered_types() {
+        let injector = Injector::root();
+        {
+            // Only reachable by bypassing `provide`, which keys entries by `T`.
+            #[cfg(not(feature = "thread-safe"))]
+            let mut providers = injector.inner.providers.borrow_mut();
+            #[cfg(feature = "thread-safe")]
+            let mut providers = injector.inner.providers.write().unwrap();
+
+            providers.insert(
+                TypeId::of::<dyn Repo>(),
+                ProviderEntry::new(Provider::root(|_| Shared::new(Level1))),
+            );
+        }
+
+        let Err(err) = injector.try_resolve::<dyn Repo>() else {
+            panic!("expected a type mismatch");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::TypeMismatch);
+        assert!(
+            err.message
+                .contains(&format!("expected {}", std::any::type_name::<dyn Repo>()))
+        );
+        assert!(err.message.contains(std::any::type_name::<Level1>()));
+    }
+
+    #[test]
+    fn test_keyed_factory_observes_its_key() {
+        let injector = Injector::root();
+        for name in ["primary", "replica"] {
+            injector.provide_keyed_with::<String, &'static str, _>(name, |_, key| {
+                Shared::new(format!("postgres://{}", key))
+            });
+        }
+
+        assert_eq!(
+            *injector.resolve_keyed::<String, &'static str>(&"replica"),
+            "postgres://replica"
+        );
+        assert_eq!(
+            injector
+                .resolve_ordered::<String, &'static str>()
+                .iter()
+                .map(|url| url.as_str())
+                .collect::<Vec<_>>(),
+            ["postgres://primary", "postgres://replica"]
+        );
+
+        let err = injector
+            .try_resolve_keyed::<String, &'static str>(&"missing")
+            .unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+    }
+
+    #[test]
+    fn test_frozen_injector_rejects_registrations_but_resolves() {
+        let injector = app_injector();
+        assert!(!injector.is_frozen());
+        injector.freeze();
+        assert!(injector.is_frozen());
+
+        let errors = [
+            injector
+                .try_provide::<Level1>(Provider::singleton(|_| Shared::new(Level1)))
+                .err(),
+            injector
+                .try_override_provider::<dyn Repo>(fake("fake"))
+                .err(),
+            injector.try_decorate::<Config, _>(|config, _| config).err(),
+            injector
+                .try_decorate_matching(|_| true, |instance, _| instance)
+                .err(),
+            injector
+                .try_provide_keyed::<Config, u8>(
+                    1,
+                    Provider::root(|_| {
+                        Shared::new(Config {
+                            name: "keyed".to_string(),
+                        })
+                    }),
+                )
+                .err(),
+        ];
+        for err in errors {
+            assert!(err.unwrap().kind == crate::ErrorKind::InvalidScope);
+        }
+
+        // Resolving still builds and caches the root singletons.
+        let repo = injector.resolve::<dyn Repo>();
+        assert_eq!(repo.name(), "real");
+        assert!(Shared::ptr_eq(&repo, &injector.resolve::<dyn Repo>()));
+        assert_eq!(injector.stats().cached_singletons, 1);
+
+        // Root-scoped registrations through a child land on the frozen root.
+        let child = Injector::child(Shared::new(injector.clone()));
+        assert!(!child.is_frozen());
+        assert!(
+            child
+                .try_provide::<Level1>(Provider::root(|_| Shared::new(Level1)))
+                .is_err()
+        );
+        assert!(
+            child
+                .try_provide::<Level1>(Provider::singleton(|_| Shared::new(Level1)))
+                .is_ok()
+        );
+
+        // Matching decorators are kept on the root, so they are rejected too.
+        assert!(
+            child
+                .try_decorate_matching(|_| true, |instance, _| instance)
+                .is_err()
+        );
+    }
+
+    trait CacheBackend: Send + Sync {
+        fn backend(&self) -> &'static str;
+    }
+
+    struct RedisCache;
+    impl CacheBackend for RedisCache {
+        fn backend(&self) -> &'static str {
+            "redis"
+        }
+    }
+
+    struct MemoryCache;
+    impl CacheBackend for MemoryCache {
+        fn backend(&self) -> &'static str {
+            "memory"
+        }
+    }
+
+    struct CacheConfig {
+        use_redis: bool,
+    }
+
+    fn conditional_cache(use_redis: bool, choices: std::sync::Arc<AtomicUsize>) -> Injector {
+        let injector = Injector::root();
+        injector.provide::<CacheConfig>(Provider::root(move |_| {
+            Shared::new(CacheConfig { use_redis })
+        }));
+        injector.provide_conditional::<dyn CacheBackend, _>(move |inj| {
+            choices.fetch_add(1, Ordering::SeqCst);
+            if inj.resolve::<CacheConfig>().use_redis {
+                Provider::root(|_| Shared::new(RedisCache) as Shared<dyn CacheBackend>)
+            } else {
+                Provider::transient(|_| Shared::new(MemoryCache) as Shared<dyn CacheBackend>)
+            }
+        });
+        injector
+    }
+
+    #[test]
+    fn test_conditional_provider_follows_config() {
+        let choices = std::sync::Arc::new(AtomicUsize::new(0));
+
+        let redis = conditional_cache(true, choices.clone());
+        let first = redis.resolve::<dyn CacheBackend>();
+        assert_eq!(first.backend(), "redis");
+        assert!(Shared::ptr_eq(&first, &redis.resolve::<dyn CacheBackend>()));
+
+        let memory = conditional_cache(false, choices.clone());
+        let first = memory.resolve::<dyn CacheBackend>();
+        assert_eq!(first.backend(), "memory");
+        assert!(!Shared::ptr_eq(
+            &first,
+            &memory.resolve::<dyn CacheBackend>()
+        ));
+
+        // Each injector chose once, and then kept its choice.
+        assert_eq!(choices.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_conditional_choice_is_memoized() {
+        let choices = std::sync::Arc::new(AtomicUsize::new(0));
+        let injector = conditional_cache(true, choices.clone());
+        let child = Injector::child(Shared::new(injector.clone()));
+
+        let cache = child.resolve::<dyn CacheBackend>();
+        injector.override_provider::<CacheConfig>(Provider::root(|_| {
+            Shared::new(CacheConfig { use_redis: false })
+        }));
+
+        assert!(Shared::ptr_eq(
+            &cache,
+            &injector.resolve::<dyn CacheBackend>()
+        ));
+        // The chosen root provider replaced the transient placeholder.
+        let stats = injector.stats();
+        assert_eq!((stats.root, stats.transient), (1, 0));
+        assert_eq!(choices.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_conditional_root_choice_on_a_child_is_cached() {
+        let built = std::sync::Arc::new(AtomicUsize::new(0));
+        let root = Shared::new(Injector::root());
+        let child = Injector::child(root.clone());
+
+        let counter = built.clone();
+        child.provide_conditional::<dyn CacheBackend, _>(move |_| {
+            let counter = counter.clone();
+            Provider::root(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Shared::new(RedisCache) as Shared<dyn CacheBackend>
+            })
+        });
+
+        let first = child.resolve::<dyn CacheBackend>();
+        for _ in 0..2 {
+            assert!(Shared::ptr_eq(&first, &child.resolve::<dyn CacheBackend>()));
+        }
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+
+        // The choice stays local to the child, like an override.
+        assert!(!root.contains::<dyn CacheBackend>());
+        assert_eq!(child.stats().module, 1);
+    }
+
+    struct LazyRepo {
+        injector: WeakInjector,
+    }
+
+    #[test]
+    fn test_injector_drops_when_only_weak_handles_remain() {
+        let injector = app_injector();
+        injector.provide::<LazyRepo>(Provider::root(|inj| {
+            Shared::new(LazyRepo {
+                injector: (*inj.resolve::<WeakInjector>()).clone(),
+            })
+        }));
+        assert!(injector.contains::<WeakInjector>());
+
+        let lazy = injector.resolve::<LazyRepo>();
+        let repo = lazy.injector.upgrade().unwrap().resolve::<dyn Repo>();
+        assert_eq!(repo.name(), "real");
+
+        // The cached `LazyRepo` only points back weakly, so dropping the last
+        // strong handle frees the injector and everything it caches.
+        drop(injector);
+        assert!(lazy.injector.upgrade().is_none());
+        assert_eq!(Shared::strong_count(&lazy), 1);
+    }
+
+    #[test]
+    fn test_registration_sites_point_at_the_call_site() {
+        let injector = Injector::root();
+        let child = Injector::child(Shared::new(injector.clone()));
+
+        let provide_line = line!() + 1;
+        child.provide::<dyn Repo>(fake("fake"));
+        let override_line = line!() + 1;
+        child.override_provider::<Level1>(Provider::singleton(|_| Shared::new(Level1)));
+
+        let sites = child.registration_sites();
+        let lines: Vec<_> = sites
+            .iter()
+            .map(|(type_name, site)| (*type_name, site.file(), site.line()))
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                (std::any::type_name::<dyn Repo>(), file!(), provide_line),
+                (std::any::type_name::<Level1>(), file!(), override_line),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registered_types_are_sorted_by_type_name() {
+        let registered = |order: &[usize]| {
+            let root = Shared::new(Injector::root());
+            let child = Injector::child(root.clone());
+            for &index in order {
+                match index {
+                    0 => root.provide::<u64>(Provider::root(|_| Shared::new(0))),
+                    1 => root.provide::<Config>(Provider::root(|_| {
+                        Shared::new(Config {
+                            name: "app".to_string(),
+                        })
+                    })),
+                    2 => child.provide::<bool>(Provider::transient(|_| Shared::new(true))),
+                    _ => child.provide::<u64>(Provider::singleton(|_| Shared::new(1))),
+                };
+            }
+            child.registered_types()
+        };
+
+        let expected = vec!["bool", std::any::type_name::<Config>(), "u64"];
+        assert_eq!(registered(&[0, 1, 2, 3]), expected);
+        assert_eq!(registered(&[3, 2, 1, 0]), expected);
+        assert_eq!(registered(&[2, 0, 3, 1]), expected);
+    }
+
+    trait UserRepository: Send + Sync {
+        fn users(&self) -> Vec<String>;
+    }
+
+    trait OrderRepository: Send + Sync {
+        fn orders(&self) -> Vec<String>;
+    }
+
+    struct Users;
+    impl UserRepository for Users {
+        fn users(&self) -> Vec<String> {
+            vec!["ada".to_string()]
+        }
+    }
+
+    struct Orders;
+    impl OrderRepository for Orders {
+        fn orders(&self) -> Vec<String> {
+            vec!["#1".to_string()]
+        }
+    }
+
+    /// Serves a snapshot of the wrapped repository's rows, marked as traced.
+    struct Traced(Vec<String>);
+
+    impl Traced {
+        fn new(rows: Vec<String>) -> Self {
+            Self(
+                rows.into_iter()
+                    .map(|row| format!("traced {}", row))
+                    .collect(),
+            )
+        }
+    }
+
+    impl UserRepository for Traced {
+        fn users(&self) -> Vec<String> {
+            self.0.clone()
+        }
+    }
+
+    impl OrderRepository for Traced {
+        fn orders(&self) -> Vec<String> {
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn test_decorate_matching_wraps_every_matching_type() {
+        let injector = app_injector();
+        injector.provide::<dyn UserRepository>(Provider::root(|_| {
+            Shared::new(Users) as Shared<dyn UserRepository>
+        }));
+
+        injector.decorate_matching(
+            |type_name| type_name.ends_with("Repository"),
+            |instance, _| {
+                if let Some(users) = instance.downcast::<dyn UserRepository>() {
+                    let traced = Shared::new(Traced::new(users.users()));
+                    ErasedInstance::new(traced as Shared<dyn UserRepository>)
+                } else if let Some(orders) = instance.downcast::<dyn OrderRepository>() {
+                    let traced = Shared::new(Traced::new(orders.orders()));
+                    ErasedInstance::new(traced as Shared<dyn OrderRepository>)
+                } else {
+                    instance
+                }
+            },
+        );
+        // Registered after the decorator, and still wrapped.
+        injector.provide::<dyn OrderRepository>(Provider::root(|_| {
+            Shared::new(Orders) as Shared<dyn OrderRepository>
+        }));
+
+        assert_eq!(
+            injector.resolve::<dyn UserRepository>().users(),
+            ["traced ada"]
+        );
+        assert_eq!(
+            injector.resolve::<dyn OrderRepository>().orders(),
+            ["traced #1"]
+        );
+        assert_eq!(injector.resolve::<Config>().name, "expensive");
+    }
+
+    #[test]
+    fn test_decorate_matching_rejects_a_changed_type() {
+        let injector = app_injector();
+        injector.decorate_matching(
+            |type_name| type_name.ends_with("Config"),
+            |_, _| ErasedInstance::new(Shared::new(Level1)),
+        );
+
+        let Err(err) = injector.try_resolve::<Config>() else {
+            panic!("expected the decorator's result to be rejected");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::TypeMismatch);
+        assert!(err.message.contains("Level1"));
+    }
+
+    #[test]
+    fn test_cached_trait_object_keeps_identity_across_resolves() {
+        let injector = app_injector();
+        let child = Injector::child(Shared::new(injector.clone()));
+        let grandchild = Injector::child(Shared::new(child));
+
+        let first = injector.resolve::<dyn Repo>();
+        for resolved in [
+            injector.resolve::<dyn Repo>(),
+            grandchild.resolve::<dyn Repo>(),
+            grandchild.resolve::<dyn Repo>(),
+        ] {
+            // Same allocation and same vtable: the stored fat pointer is reused.
+            assert!(std::ptr::eq(&*first, &*resolved));
+            assert_eq!(resolved.name(), "real");
+        }
+        assert_eq!(Shared::strong_count(&first), 2);
+    }
+
+    #[test]
+    fn test_resolve_cloned_copies_while_with_borrows() {
+        #[derive(Clone)]
+        struct Settings {
+            origins: Vec<String>,
+        }
+
+        let injector = app_injector();
+        injector.provide::<Settings>(Provider::root(|_| {
+            Shared::new(Settings {
+                origins: vec!["https://example.com".to_string()],
+            })
+        }));
+
+        let mut owned = injector.resolve_cloned::<Settings>();
+        owned.origins.clear();
+
+        assert_eq!(injector.with::<Settings, _>(|s| s.origins.len()), 1);
+        assert_eq!(injector.with::<dyn Repo, _>(|repo| repo.name()), "real");
+
+        let Err(err) = injector.try_with::<Level1, _>(|_| ()) else {
+            panic!("Level1 is not registered");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+    }
+
+    #[test]
+    fn test_remove_provider_unregisters_and_evicts_cached_instance() {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::singleton(|_| {
+            Shared::new(Config {
+                name: "first".to_string(),
+            })
+        }));
+        let first = injector.resolve::<Config>();
+
+        assert!(injector.remove_provider::<Config>());
+        let Err(err) = injector.try_resolve::<Config>() else {
+            panic!("Config was removed");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+        assert!(!injector.remove_provider::<Config>());
+
+        injector.provide::<Config>(Provider::singleton(|_| {
+            Shared::new(Config {
+                name: "second".to_string(),
+            })
+        }));
+        assert_eq!(injector.resolve::<Config>().name, "second");
+        assert_eq!(first.name, "first");
+    }
+
+    #[test]
+    fn test_remove_provider_is_local_and_rejected_when_frozen() {
+        let root = Shared::new(app_injector());
+        let child = Injector::child(root.clone());
+        child.override_provider::<dyn Repo>(fake("fake"));
+
+        assert!(!child.remove_provider::<Config>());
+        assert!(child.remove_provider::<dyn Repo>());
+        assert_eq!(child.resolve::<dyn Repo>().name(), "real");
+
+        root.freeze();
+        let Err(err) = root.try_remove_provider::<Config>() else {
+            panic!("frozen injectors keep their registrations");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::InvalidScope);
+        assert_eq!(root.resolve::<Config>().name, "expensive");
+    }
+
+    fn config_name(config: &'static Config) -> &'static str {
+        &config.name
+    }
+
+    #[test]
+    fn test_leaked_singleton_outlives_injector_without_clones() {
+        let injector = app_injector();
+        let config = injector.leak_singleton::<Config>();
+        let repo: &'static dyn Repo = injector.leak_singleton::<dyn Repo>();
+
+        assert!(std::ptr::eq(config, &*injector.resolve::<Config>()));
+
+        injector.clear_singleton::<Config>();
+        drop(injector);
+
+        let handle = std::thread::spawn(move || (config_name(config).len(), repo.name()));
+        assert_eq!(handle.join().unwrap(), ("expensive".len(), "real"));
+    }
+
+    #[test]
+    fn test_leak_singleton_rejects_transient_providers() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(Provider::transient(|_| Shared::new(Level1)));
+
+        let Err(err) = injector.try_leak_singleton::<Level1>() else {
+            panic!("a transient instance was leaked");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::InvalidScope);
+    }
+
+    #[test]
+    fn test_check_cycles_finds_declared_cycle_without_instantiating() {
+        let built = Shared::new(AtomicUsize::new(0));
+        let injector = Injector::root();
+
+        let counter = built.clone();
+        injector.provide::<Level1>(
+            Provider::root(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Shared::new(Level1)
+            })
+            .depends_on::<Level2>(),
+        );
+        injector.provide::<Level2>(Provider::root(|_| Shared::new(Level2)).depends_on::<Level3>());
+        injector.provide::<Level3>(
+            Provider::root(|_| Shared::new(Level3))
+                .depends_on::<Config>()
+                .depends_on::<Level1>(),
+        );
+
+        let err = injector.check_cycles().unwrap_err();
+        assert_eq!(err.kind, crate::ErrorKind::CircularDependency);
+        assert!(err.message.contains(&format!(
+            "{} -> {} -> {} -> {}",
+            std::any::type_name::<Level1>(),
+            std::any::type_name::<Level2>(),
+            std::any::type_name::<Level3>(),
+            std::any::type_name::<Level1>(),
+        )));
+        assert_eq!(built.load(Ordering::Relaxed), 0);
+
+        let child = Injector::child(Shared::new(injector));
+        child.override_provider::<Level3>(Provider::root(|_| Shared::new(Level3)));
+        assert!(child.check_cycles().is_ok());
+    }
+
+    #[test]
+    fn test_check_cycles_accepts_shared_dependencies() {
+        let injector = app_injector();
+        injector.provide_factory_with::<Level2, Config, _>(|_| Level2);
+        injector.provide_factory_with::<Level3, Config, _>(|_| Level3);
+        injector.provide::<Level1>(
+            Provider::root(|_| Shared::new(Level1))
+                .depends_on::<Level2>()
+                .depends_on::<Level3>()
+                .depends_on::<dyn Repo>(),
+        );
+
+        assert!(injector.check_cycles().is_ok());
+
+        injector.override_provider::<Config>(
+            Provider::root(|_| {
+                Shared::new(Config {
+                    name: "cyclic".to_string(),
+                })
+            })
+            .depends_on::<Level1>(),
+        );
+        assert_eq!(
+            injector.check_cycles().unwrap_err().kind,
+            crate::ErrorKind::CircularDependency
+        );
+    }
+
+    #[cfg(feature = "thread-safe")]
+    #[test]
+    fn test_deadline_fails_a_hanging_factory_without_caching() {
+        let calls = Shared::new(AtomicUsize::new(0));
+        let injector = app_injector();
+
+        let counter = calls.clone();
+        injector.provide::<Level1>(
+            Provider::root(move |_| {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    std::thread::sleep(Duration::from_millis(500));
+                }
+                Shared::new(Level1)
+            })
+            .with_deadline(Duration::from_millis(20)),
+        );
+
+        let started = Instant::now();
+        let Err(err) = injector.try_resolve::<Level1>() else {
+            panic!("the first factory call hangs past the deadline");
+        };
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(err.kind, crate::ErrorKind::FactoryExecutionFailed);
+        assert!(err.message.contains("exceeded deadline"));
+
+        let level1 = injector.resolve::<Level1>();
+        assert!(Shared::ptr_eq(&level1, &injector.resolve::<Level1>()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "thread-safe")]
+    #[test]
+    fn test_deadline_factory_resolves_dependencies_off_thread() {
+        let injector = app_injector();
+        injector.provide::<String>(
+            Provider::transient(|inj| Shared::new(inj.resolve::<Config>().name.clone()))
+                .with_deadline(Duration::from_secs(5)),
+        );
+
+        assert_eq!(*injector.resolve::<String>(), "expensive");
+    }
+
+    #[cfg(feature = "thread-safe")]
+    #[test]
+    #[should_panic(expected = "factory exploded")]
+    fn test_deadline_factory_panic_reaches_the_caller() {
+        let injector = Injector::root();
+        injector.provide::<Level1>(
+            Provider::root(|_| panic!("factory exploded")).with_deadline(Duration::from_secs(5)),
+        );
+
+        injector.resolve::<Level1>();
+    }
+
+    #[cfg(feature = "thread-safe")]
+    #[test]
+    fn test_deadline_factory_still_detects_cycles() {
+        let calls = Shared::new(AtomicUsize::new(0));
+        let cycle = Shared::new(Store::new(None));
+        let injector = Injector::root();
+
+        let counter = calls.clone();
+        injector.provide::<Level1>(
+            Provider::transient(move |inj| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                inj.resolve::<Level2>();
+                Shared::new(Level1)
+            })
+            .with_deadline(Duration::from_secs(5)),
+        );
+        let seen = cycle.clone();
+        injector.provide::<Level2>(Provider::transient(move |inj| {
+            *seen.write().unwrap() = inj.try_resolve::<Level1>().err().map(|err| err.kind);
+            Shared::new(Level2)
+        }));
+
+        injector.resolve::<Level1>();
+
+        assert_eq!(
+            *cycle.read().unwrap(),
+            Some(crate::ErrorKind::CircularDependency)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_after_init_runs_once_and_observes_the_cached_instance() {
+        let seen = Shared::new(Store::new(Vec::new()));
+        let injector = app_injector();
+
+        let log = seen.clone();
+        injector.provide::<Level1>(Provider::root(|_| Shared::new(Level1)).after_init(
+            move |level1: &Level1, inj: &Injector| {
+                let cached = inj.resolve::<Level1>();
+                let name = inj.resolve::<Config>().name.clone();
+
+                #[cfg(not(feature = "thread-safe"))]
+                log.borrow_mut()
+                    .push((std::ptr::eq(level1, &*cached), name));
+                #[cfg(feature = "thread-safe")]
+                log.write()
+                    .unwrap()
+                    .push((std::ptr::eq(level1, &*cached), name));
+
+                Ok(())
+            },
+        ));
+
+        let first = injector.resolve::<Level1>();
+        assert!(Shared::ptr_eq(&first, &injector.resolve::<Level1>()));
 
-            Scope::Module | Scope::Transient => self.store_provider::<T>(provider),
-        }
-    }
+        #[cfg(not(feature = "thread-safe"))]
+        let seen = seen.borrow().clone();
+        #[cfg(feature = "thread-safe")]
+        let seen = seen.read().unwrap().clone();
 
-    pub fn provide<T>(&self, provider: Provider<T>) -> &Self
-    where
-        T: ?Sized + 'static,
-    {
-        self.try_provide::<T>(provider).unwrap();
-        self
+        assert_eq!(seen, [(true, "expensive".to_string())]);
     }
 
-    pub(crate) fn get_provider<T>(&self) -> Option<Shared<dyn Any>>
-    where
-        T: ?Sized + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+    #[test]
+    fn test_failed_after_init_evicts_the_instance() {
+        let attempts = Shared::new(AtomicUsize::new(0));
+        let injector = Injector::root();
 
-        let local = self.inner.providers.borrow().get(&type_id).cloned();
-
-        if local.is_some() {
-            return local;
-        }
+        let counter = attempts.clone();
+        injector.provide::<Level1>(Provider::root(|_| Shared::new(Level1)).after_init(
+            move |_: &Level1, _: &Injector| match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("seed data rejected".to_string()),
+                _ => Ok(()),
+            },
+        ));
 
-        if let Some(parent) = &self.inner.parent {
-            let parent_injector = Injector {
-                inner: parent.clone(),
-            };
-            return parent_injector.get_provider::<T>();
-        }
+        let Err(err) = injector.try_resolve::<Level1>() else {
+            panic!("the first hook call fails");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::FactoryExecutionFailed);
+        assert!(err.message.contains("seed data rejected"));
+        assert!(injector.get_instance::<Level1>().is_none());
 
-        None
+        injector.resolve::<Level1>();
+        injector.resolve::<Level1>();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
     }
 
-    pub(crate) fn resolve_provider<T>(&self) -> Result<Shared<Provider<T>>, Error>
-    where
-        T: ?Sized + 'static,
-    {
-        let type_name = std::any::type_name::<T>();
+    #[test]
+    fn test_resolve_or_default_falls_back_only_when_unprovided() {
+        #[derive(Default, PartialEq, Debug)]
+        struct Tuning {
+            batch_size: usize,
+        }
 
-        let any_provider = self
-            .get_provider::<T>()
-            .ok_or_else(|| Error::service_not_provided(type_name))?;
+        let injector = Injector::root();
+        assert_eq!(*injector.resolve_or_default::<Tuning>(), Tuning::default());
 
-        let provider = any_provider
-            .downcast::<Provider<T>>()
-            .map_err(|_| Error::type_mismatch(type_name))?;
+        let child = Injector::child(Shared::new(injector.clone()));
+        injector.provide::<Tuning>(Provider::root(|_| Shared::new(Tuning { batch_size: 64 })));
+        assert_eq!(child.resolve_or_default::<Tuning>().batch_size, 64);
 
-        Ok(provider)
+        injector.provide::<String>(
+            Provider::root(|_| Shared::new("unused".to_string()))
+                .after_init(|_: &String, _: &Injector| Err("warm-up failed".to_string())),
+        );
+        let Err(err) = child.try_resolve_or_default::<String>() else {
+            panic!("a failing factory must not fall back to the default");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::FactoryExecutionFailed);
     }
 
-    pub(crate) fn resolve_instance<T>(&self) -> Result<Shared<Instance<T>>, Error>
-    where
-        T: ?Sized + 'static,
-    {
-        let provider_ref = self.resolve_provider::<T>()?;
+    struct FlushOnDrop {
+        injector: WeakInjector,
+        flushed: std::sync::Arc<std::sync::Mutex<Option<Result<(), Error>>>>,
+    }
 
-        Ok(Shared::new((provider_ref.factory)(self)))
+    impl Drop for FlushOnDrop {
+        fn drop(&mut self) {
+            let injector = self.injector.upgrade().unwrap();
+            let result = injector.try_resolve::<dyn Repo>().map(|_| ());
+            *self.flushed.lock().unwrap() = Some(result);
+        }
     }
 
-    pub(crate) fn store_instance<T>(&self, instance: Shared<Instance<T>>)
-    where
-        T: ?Sized + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+    #[test]
+    fn test_drop_resolving_during_shutdown_gets_an_error() {
+        let flushed = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let injector = app_injector();
+        let outcome = flushed.clone();
+        injector.provide::<FlushOnDrop>(Provider::root(move |inj| {
+            Shared::new(FlushOnDrop {
+                injector: (*inj.resolve::<WeakInjector>()).clone(),
+                flushed: outcome.clone(),
+            })
+        }));
+        injector.resolve::<FlushOnDrop>();
 
-        self.inner.instances.borrow_mut().insert(type_id, instance);
+        let child = Injector::child(Shared::new(injector.clone()));
+        child.shutdown();
+
+        let Some(Err(err)) = flushed.lock().unwrap().take() else {
+            panic!("expected the disposed singleton to fail its resolve");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::InvalidScope);
+        assert!(err.message.contains("shutting down"));
+        assert!(injector.is_shutting_down());
+        assert!(injector.try_resolve::<Config>().is_err());
     }
 
-    pub(crate) fn store_provider<T>(&self, provider: Provider<T>) -> Result<(), Error>
-    where
-        T: ?Sized + 'static,
-    {
-        let type_id = TypeId::of::<T>();
-        let type_name = std::any::type_name::<T>();
+    #[test]
+    fn test_copy_values_resolve_repeatedly_by_value() {
+        let root = Shared::new(app_injector());
+        root.provide_copy(8080_u16).provide_copy(true);
 
-        let mut providers = self.inner.providers.borrow_mut();
-        if providers.contains_key(&type_id) {
-            return Err(Error::provider_already_registered(
-                type_name,
-                provider.scope.to_string().as_str(),
-            ));
+        let child = Injector::child(root.clone());
+        child.provide_copy(false);
+
+        for _ in 0..3 {
+            assert_eq!(child.resolve_copy::<u16>(), 8080);
+            assert!(!child.resolve_copy::<bool>());
+            assert!(root.resolve_copy::<bool>());
         }
-        providers.insert(type_id, Shared::new(provider));
 
-        Ok(())
-    }
+        assert!(!root.contains::<u16>());
+        let Err(err) = root.try_provide_copy(443_u16) else {
+            panic!("expected a duplicate copy value to be rejected");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ProviderAlreadyRegistered);
+        assert_eq!(root.resolve_copy::<u16>(), 8080);
 
-    pub(crate) fn get_instance<T>(&self) -> Option<Shared<Instance<T>>>
-    where
-        T: ?Sized + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+        let Err(err) = child.try_resolve_copy::<u32>() else {
+            panic!("expected an unregistered copy value to be missing");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+    }
 
-        let local = self.inner.instances.borrow().get(&type_id).cloned();
+    #[test]
+    fn test_resolve_marked_collects_services_of_different_types() {
+        type Log = std::sync::Mutex<Vec<&'static str>>;
 
-        if local.is_some() {
-            return local.and_then(|instance| instance.downcast::<Instance<T>>().ok());
+        trait Startable: Send + Sync {
+            fn start(&self, log: &Log);
         }
 
-        if let Some(parent) = &self.inner.parent {
-            let parent_injector = Injector {
-                inner: parent.clone(),
-            };
-            return parent_injector.get_instance::<T>();
+        struct Database;
+        impl Startable for Database {
+            fn start(&self, log: &Log) {
+                log.lock().unwrap().push("database");
+            }
         }
 
-        None
-    }
+        struct Mailer;
+        impl Startable for Mailer {
+            fn start(&self, log: &Log) {
+                log.lock().unwrap().push("mailer");
+            }
+        }
 
-    pub fn try_resolve<T>(&self) -> Result<Shared<T>, Error>
-    where
-        T: ?Sized + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+        let root = Shared::new(app_injector());
+        root.provide::<Database>(
+            Provider::root(|_| Shared::new(Database))
+                .mark::<dyn Startable, _>(|database| database as Shared<dyn Startable>),
+        );
 
-        let _guard = ResolveGuard::push(type_id)?;
+        let child = Injector::child(root.clone());
+        child.provide::<Mailer>(
+            Provider::singleton(|_| Shared::new(Mailer))
+                .mark::<dyn Startable, _>(|mailer| mailer as Shared<dyn Startable>),
+        );
 
-        if let Some(instance) = self.get_instance::<T>() {
-            return Ok(instance.value());
+        let log = Log::default();
+        for service in child.resolve_marked::<dyn Startable>() {
+            service.start(&log);
         }
 
-        let provider = self.resolve_provider::<T>()?;
+        // Type-name order puts `Database` before `Mailer`.
+        assert_eq!(*log.lock().unwrap(), ["database", "mailer"]);
+        assert!(std::ptr::addr_eq(
+            &*child.resolve_marked::<dyn Startable>()[0],
+            &*root.resolve::<Database>()
+        ));
+        assert_eq!(root.resolve_marked::<dyn Startable>().len(), 1);
+        assert!(child.resolve_marked::<dyn Repo>().is_empty());
+    }
 
-        let instance = self.resolve_instance::<T>()?;
+    #[test]
+    fn test_root_override_in_one_scope_leaves_root_and_siblings_alone() {
+        trait Clock: Send + Sync {
+            fn now(&self) -> u64;
+        }
 
-        if provider.scope == Scope::Transient {
-            return Ok(instance.value());
+        struct SystemClock;
+        impl Clock for SystemClock {
+            fn now(&self) -> u64 {
+                1_000
+            }
         }
 
-        match provider.scope {
-            Scope::Root => {
-                let root = self.root_injector();
-                root.store_instance::<T>(instance.clone());
+        struct FrozenClock(u64);
+        impl Clock for FrozenClock {
+            fn now(&self) -> u64 {
+                self.0
             }
+        }
 
-            Scope::Module => {
-                self.store_instance::<T>(instance.clone());
+        let root = Shared::new(Injector::root());
+        root.provide::<dyn Clock>(Provider::root(|_| {
+            Shared::new(SystemClock) as Shared<dyn Clock>
+        }));
+
+        let frozen = Injector::child(root.clone());
+        let sibling = Injector::child(root.clone());
+        frozen.override_provider::<dyn Clock>(Provider::root(|_| {
+            Shared::new(FrozenClock(42)) as Shared<dyn Clock>
+        }));
+
+        assert_eq!(frozen.resolve::<dyn Clock>().now(), 42);
+        assert_eq!(sibling.resolve::<dyn Clock>().now(), 1_000);
+        assert_eq!(root.resolve::<dyn Clock>().now(), 1_000);
+
+        drop(frozen);
+        let next = Injector::child(root.clone());
+        assert_eq!(next.resolve::<dyn Clock>().now(), 1_000);
+        assert!(Shared::ptr_eq(
+            &next.resolve::<dyn Clock>(),
+            &sibling.resolve::<dyn Clock>()
+        ));
+    }
+
+    #[test]
+    fn test_panicking_resolves_report_the_call_site() {
+        thread_local! {
+            static PANIC_LOCATION: std::cell::RefCell<Option<(String, u32)>> =
+                const { std::cell::RefCell::new(None) };
+        }
+
+        // Other tests may panic concurrently, so the location is kept per thread.
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                PANIC_LOCATION.with(|cell| {
+                    *cell.borrow_mut() = Some((location.file().to_string(), location.line()));
+                });
             }
+            previous(info);
+        }));
 
-            Scope::Transient => unreachable!(),
+        fn panic_location<R>(call: impl FnOnce() -> R) -> Option<(String, u32)> {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(call));
+            assert!(result.is_err());
+            PANIC_LOCATION.with(|cell| cell.borrow_mut().take())
         }
 
-        Ok(instance.value())
-    }
+        let injector = Injector::root();
+        let expected = |line| Some((file!().to_string(), line));
 
-    pub fn resolve<T>(&self) -> Shared<T>
-    where
-        T: ?Sized + 'static,
-    {
-        self.try_resolve::<T>().unwrap()
+        let line = line!() + 1;
+        let location = panic_location(|| injector.resolve::<Config>());
+        assert_eq!(location, expected(line));
+
+        let line = line!() + 1;
+        let location = panic_location(|| injector.with::<Config, _>(|_| ()));
+        assert_eq!(location, expected(line));
+
+        let line = line!() + 1;
+        let location = panic_location(|| injector.resolve_copy::<u16>());
+        assert_eq!(location, expected(line));
     }
 
-    pub fn optional_resolve<T>(&self) -> Option<Shared<T>>
-    where
-        T: ?Sized + 'static,
-    {
-        self.try_resolve::<T>().ok()
+    #[test]
+    fn test_shared_counts_follow_held_handles() {
+        let injector = app_injector();
+        let count_of = |injector: &Injector, type_name: &str| {
+            injector
+                .shared_counts()
+                .into_iter()
+                .find(|(name, _)| *name == type_name)
+                .map(|(_, count)| count)
+        };
+        let config = std::any::type_name::<Config>();
+
+        assert_eq!(count_of(&injector, config), None);
+        let first = injector.resolve::<Config>();
+        assert_eq!(count_of(&injector, config), Some(2));
+
+        let held = vec![first.clone(), injector.resolve::<Config>()];
+        assert_eq!(count_of(&injector, config), Some(4));
+
+        drop(held);
+        assert_eq!(count_of(&injector, config), Some(2));
+        drop(first);
+        assert_eq!(count_of(&injector, config), Some(1));
     }
-}
 
-#[cfg(feature = "thread-safe")]
-impl Injector {
-    pub fn try_provide<T>(&self, provider: Provider<T>) -> Result<(), Error>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        match provider.scope {
-            Scope::Root => {
-                let root = self.root_injector();
-                root.store_provider::<T>(provider)
-            }
+    struct GenericRepository<T>(PhantomData<T>);
 
-            Scope::Module | Scope::Transient => self.store_provider::<T>(provider),
+    impl<T> Default for GenericRepository<T> {
+        fn default() -> Self {
+            Self(PhantomData)
         }
     }
+    struct GenericUser;
+    struct GenericTodo;
 
-    pub fn provide<T>(&self, provider: Provider<T>) -> &Self
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        self.try_provide::<T>(provider).unwrap();
-        self
+    fn generic_repository<T: Send + Sync + 'static>(type_id: TypeId) -> Option<ErasedInstance> {
+        (type_id == TypeId::of::<GenericRepository<T>>())
+            .then(|| ErasedInstance::new(Shared::new(GenericRepository::<T>(PhantomData))))
     }
 
-    pub(crate) fn get_provider<T>(&self) -> Option<Shared<dyn Any + Send + Sync>>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+    #[test]
+    fn test_one_generic_registration_serves_each_instantiation_once() {
+        let calls = Shared::new(AtomicUsize::new(0));
+        let root = Shared::new(Injector::root());
 
-        let local = self.inner.providers.read().unwrap().get(&type_id).cloned();
+        let counter = calls.clone();
+        root.provide_generic_fn::<GenericRepository<()>, _>(move |_, type_id| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            generic_repository::<GenericUser>(type_id)
+                .or_else(|| generic_repository::<GenericTodo>(type_id))
+        });
 
-        if local.is_some() {
-            return local;
-        }
+        let child = Injector::child(root.clone());
+        let users = child.resolve::<GenericRepository<GenericUser>>();
+        let todos = child.resolve::<GenericRepository<GenericTodo>>();
 
-        if let Some(parent) = &self.inner.parent {
-            let parent_injector = Injector {
-                inner: parent.clone(),
-            };
-            return parent_injector.get_provider::<T>();
-        }
+        assert!(Shared::ptr_eq(
+            &users,
+            &root.resolve::<GenericRepository<GenericUser>>()
+        ));
+        assert!(Shared::ptr_eq(
+            &todos,
+            &child.resolve::<GenericRepository<GenericTodo>>()
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
 
-        None
+        let Err(err) = child.try_resolve::<GenericRepository<u8>>() else {
+            panic!("the factory does not build GenericRepository<u8>");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ServiceNotProvided);
+
+        let Err(err) = root.try_provide_generic_fn::<GenericRepository<()>, _>(|_, _| None) else {
+            panic!("the family already has a factory");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::ProviderAlreadyRegistered);
     }
 
-    pub(crate) fn resolve_provider<T>(&self) -> Result<Shared<Provider<T>>, Error>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let type_name = std::any::type_name::<T>();
+    #[test]
+    fn test_generic_factory_returning_the_wrong_type_is_a_mismatch() {
+        let injector = Injector::root();
+        injector.provide_generic_fn::<GenericRepository<()>, _>(|_, _| {
+            Some(ErasedInstance::new(Shared::new(GenericRepository::<
+                GenericTodo,
+            >(PhantomData))))
+        });
 
-        let any_provider = self
-            .get_provider::<T>()
-            .ok_or_else(|| Error::service_not_provided(type_name))?;
+        let Err(err) = injector.try_resolve::<GenericRepository<GenericUser>>() else {
+            panic!("resolved a repository of the wrong type");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::TypeMismatch);
+    }
 
-        let provider = any_provider
-            .downcast::<Provider<T>>()
-            .map_err(|_| Error::type_mismatch(type_name))?;
+    #[test]
+    fn test_optional_resolves_see_generic_families() {
+        let root = Shared::new(Injector::root());
+        root.provide_generic_fn::<GenericRepository<()>, _>(|_, type_id| {
+            generic_repository::<GenericUser>(type_id)
+        });
+        let child = Injector::child(root.clone());
 
-        Ok(provider)
+        assert!(!child.contains::<GenericRepository<GenericUser>>());
+        let users = child
+            .optional_resolve::<GenericRepository<GenericUser>>()
+            .unwrap();
+        assert!(Shared::ptr_eq(
+            &users,
+            &child.resolve_or_default::<GenericRepository<GenericUser>>()
+        ));
+        assert!(
+            child
+                .optional_resolve::<GenericRepository<GenericTodo>>()
+                .is_none()
+        );
     }
 
-    pub(crate) fn resolve_instance<T>(&self) -> Result<Shared<Instance<T>>, Error>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let provider_ref = self.resolve_provider::<T>()?;
+    #[test]
+    fn test_fallback_chain_caches_the_first_provider_that_succeeds() {
+        let attempts = Shared::new(AtomicUsize::new(0));
+        let injector = Injector::root();
 
-        Ok(Shared::new((provider_ref.factory)(self)))
+        let redis_attempts = attempts.clone();
+        injector.provide_fallback_chain::<dyn Repo>(vec![
+            fake("closed").gated_by(|_| false),
+            Provider::root(move |_| {
+                redis_attempts.fetch_add(1, Ordering::SeqCst);
+                Shared::new(FakeRepo("redis")) as Shared<dyn Repo>
+            })
+            .after_init(|_, _| Err("connection refused".to_string())),
+            fake("in-memory"),
+        ]);
+
+        let first = injector.resolve::<dyn Repo>();
+        assert_eq!(first.name(), "in-memory");
+        assert!(std::ptr::addr_eq(&*first, &*injector.resolve::<dyn Repo>()));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
     }
 
-    pub(crate) fn store_instance<T>(&self, instance: Shared<Instance<T>>)
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+    #[test]
+    fn test_fallback_chain_reports_the_last_failure() {
+        let injector = Injector::root();
+        injector.provide_fallback_chain::<dyn Repo>(vec![
+            Provider::transient(|_| Shared::new(FakeRepo("redis")) as Shared<dyn Repo>)
+                .after_init(|_, _| Err("connection refused".to_string())),
+            Provider::transient(|_| Shared::new(FakeRepo("memcached")) as Shared<dyn Repo>)
+                .after_init(|_, _| Err("out of memory".to_string())),
+        ]);
 
-        self.inner
-            .instances
-            .write()
-            .unwrap()
-            .insert(type_id, instance);
+        let Err(err) = injector.try_resolve::<dyn Repo>() else {
+            panic!("every provider in the chain failed");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::FactoryExecutionFailed);
+        assert!(err.message.contains("out of memory"));
     }
 
-    pub(crate) fn store_provider<T>(&self, provider: Provider<T>) -> Result<(), Error>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let type_id = TypeId::of::<T>();
-        let type_name = std::any::type_name::<T>();
+    #[test]
+    fn test_decorated_fallback_chain_still_fails_over() {
+        let decorated = Shared::new(AtomicUsize::new(0));
+        let injector = Injector::root();
+        injector.provide_fallback_chain::<dyn Repo>(vec![
+            fake("redis").after_init(|_, _| Err("connection refused".to_string())),
+            fake("in-memory"),
+        ]);
 
-        let mut providers = self.inner.providers.write().unwrap();
-        if providers.contains_key(&type_id) {
-            return Err(Error::provider_already_registered(
-                type_name,
-                provider.scope.to_string().as_str(),
-            ));
-        }
-        providers.insert(type_id, Shared::new(provider));
+        let counter = decorated.clone();
+        injector.decorate::<dyn Repo, _>(move |inner, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            inner
+        });
 
-        Ok(())
+        assert_eq!(injector.resolve::<dyn Repo>().name(), "in-memory");
+        assert_eq!(decorated.load(Ordering::SeqCst), 1);
+
+        let injector = Injector::root();
+        injector.provide_fallback_chain::<dyn Repo>(vec![
+            fake("redis").after_init(|_, _| Err("connection refused".to_string())),
+        ]);
+        injector.decorate::<dyn Repo, _>(|inner, _| inner);
+
+        let Err(err) = injector.try_resolve::<dyn Repo>() else {
+            panic!("every provider in the chain failed");
+        };
+        assert_eq!(err.kind, crate::ErrorKind::FactoryExecutionFailed);
     }
 
-    pub(crate) fn get_instance<T>(&self) -> Option<Shared<Instance<T>>>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+    struct Left {
+        right: std::sync::OnceLock<Shared<Right>>,
+    }
 
-        let local = self.inner.instances.read().unwrap().get(&type_id).cloned();
+    struct Right {
+        left: std::sync::OnceLock<Shared<Left>>,
+    }
 
-        if local.is_some() {
-            return local.and_then(|instance| instance.downcast::<Instance<T>>().ok());
+    fn two_phase_pair() -> Injector {
+        let injector = Injector::root();
+        injector.provide_two_phase::<Left, _, _>(
+            |_| Left {
+                right: std::sync::OnceLock::new(),
+            },
+            |left, inj| {
+                let _ = left.right.set(inj.resolve::<Right>());
+            },
+        );
+        injector.provide_two_phase::<Right, _, _>(
+            |_| Right {
+                left: std::sync::OnceLock::new(),
+            },
+            |right, inj| {
+                let _ = right.left.set(inj.resolve::<Left>());
+            },
+        );
+        injector
+    }
+
+    #[test]
+    fn test_two_phase_providers_wire_a_mutual_reference() {
+        let injector = two_phase_pair();
+
+        let left = injector.resolve::<Left>();
+        let right = left.right.get().unwrap();
+        assert!(Shared::ptr_eq(right.left.get().unwrap(), &left));
+        assert!(Shared::ptr_eq(right, &injector.resolve::<Right>()));
+
+        let injector = two_phase_pair();
+        let right = injector.resolve::<Right>();
+        let left = right.left.get().unwrap();
+        assert!(Shared::ptr_eq(left.right.get().unwrap(), &right));
+    }
+
+    /// Spans by id and, for each event, its fields and the innermost entered span.
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct Recorded {
+        spans: Vec<(u64, String)>,
+        entered: Vec<u64>,
+        events: Vec<(String, Option<u64>)>,
+    }
+
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct SpanRecorder(std::sync::Arc<std::sync::Mutex<Recorded>>);
+
+    #[cfg(feature = "tracing")]
+    struct FieldText(String);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldText {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0 += &format!("{}={:?} ", field.name(), value);
         }
+    }
 
-        if let Some(parent) = &self.inner.parent {
-            let parent_injector = Injector {
-                inner: parent.clone(),
-            };
-            return parent_injector.get_instance::<T>();
+    #[cfg(feature = "tracing")]
+    impl SpanRecorder {
+        fn span_id(&self, service: &str) -> Option<u64> {
+            let text = format!("service={:?} ", service);
+            self.0
+                .lock()
+                .unwrap()
+                .spans
+                .iter()
+                .find(|(_, fields)| *fields == text)
+                .map(|(id, _)| *id)
         }
 
-        None
+        fn event_span(&self, message: &str) -> Option<u64> {
+            let text = format!("message={} ", message);
+            self.0
+                .lock()
+                .unwrap()
+                .events
+                .iter()
+                .find(|(fields, _)| *fields == text)?
+                .1
+        }
+
+        fn events_with(&self, message: &str) -> Vec<String> {
+            let text = format!("message={}", message);
+            self.0
+                .lock()
+                .unwrap()
+                .events
+                .iter()
+                .filter(|(fields, _)| fields.contains(&text))
+                .map(|(fields, _)| fields.clone())
+                .collect()
+        }
     }
 
-    pub fn try_resolve<T>(&self) -> Result<Shared<T>, Error>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        let type_id = TypeId::of::<T>();
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
 
-        let _guard = ResolveGuard::push(type_id)?;
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = FieldText(String::new());
+            span.record(&mut fields);
 
-        if let Some(instance) = self.get_instance::<T>() {
-            return Ok(instance.value());
+            let spans = &mut self.0.lock().unwrap().spans;
+            let id = spans.len() as u64 + 1;
+            spans.push((id, fields.0));
+            tracing::span::Id::from_u64(id)
         }
 
-        let provider = self.resolve_provider::<T>()?;
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
 
-        let instance = self.resolve_instance::<T>()?;
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
 
-        if provider.scope == Scope::Transient {
-            return Ok(instance.value());
-        }
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = FieldText(String::new());
+            event.record(&mut fields);
 
-        match provider.scope {
-            Scope::Root => {
-                let root = self.root_injector();
-                root.store_instance::<T>(instance.clone());
-            }
+            let mut recorded = self.0.lock().unwrap();
+            let current = recorded.entered.last().copied();
+            recorded.events.push((fields.0, current));
+        }
 
-            Scope::Module => {
-                self.store_instance::<T>(instance.clone());
-            }
+        fn enter(&self, span: &tracing::span::Id) {
+            self.0.lock().unwrap().entered.push(span.into_u64());
+        }
 
-            Scope::Transient => unreachable!(),
+        fn exit(&self, _: &tracing::span::Id) {
+            self.0.lock().unwrap().entered.pop();
         }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_sync_factory_logs_inside_its_resolve_span() {
+        let recorder = SpanRecorder::default();
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::root(|_| {
+            tracing::info!("building config");
+            Shared::new(Config {
+                name: "traced".to_string(),
+            })
+        }));
 
-        Ok(instance.value())
+        tracing::subscriber::with_default(recorder.clone(), || injector.resolve::<Config>());
+
+        let span = recorder.span_id(std::any::type_name::<Config>());
+        assert!(span.is_some());
+        assert_eq!(recorder.event_span("building config"), span);
     }
 
-    pub fn resolve<T>(&self) -> Shared<T>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        self.try_resolve::<T>().unwrap()
+    #[cfg(all(feature = "tracing", feature = "async"))]
+    #[tokio::test]
+    async fn test_async_factory_keeps_its_resolve_span_across_awaits() {
+        let recorder = SpanRecorder::default();
+        let _default = tracing::subscriber::set_default(recorder.clone());
+
+        let injector = Injector::root();
+        injector.provide_async::<Config>(Provider::async_root(|_| async {
+            tokio::task::yield_now().await;
+            tracing::info!("config fetched");
+            Ok(Shared::new(Config {
+                name: "traced".to_string(),
+            }))
+        }));
+
+        injector.resolve_async::<Config>().await.unwrap();
+
+        let span = recorder.span_id(std::any::type_name::<Config>());
+        assert!(span.is_some());
+        assert_eq!(recorder.event_span("config fetched"), span);
     }
 
-    pub fn optional_resolve<T>(&self) -> Option<Shared<T>>
-    where
-        T: ?Sized + Send + Sync + 'static,
-    {
-        self.try_resolve::<T>().ok()
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_clear_singleton_logs_a_manual_eviction() {
+        let recorder = SpanRecorder::default();
+        let injector = app_injector();
+        injector.resolve::<Config>();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            assert!(injector.clear_singleton::<Config>());
+            assert!(!injector.clear_singleton::<Config>());
+        });
+
+        let evictions = recorder.events_with("evicted cached singleton");
+        assert_eq!(evictions.len(), 1);
+        assert!(evictions[0].contains(&format!("service={:?}", std::any::type_name::<Config>())));
+        assert!(evictions[0].contains("reason=\"manual\""));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_replacing_and_shutting_down_log_their_evictions() {
+        let recorder = SpanRecorder::default();
+        let injector = app_injector();
+        injector.resolve::<Config>();
+        injector.resolve::<dyn Repo>();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            injector.remove_provider::<Config>();
+            injector.shutdown();
+        });
+
+        let evictions = recorder.events_with("evicted cached singleton");
+        assert_eq!(evictions.len(), 2);
+        assert!(evictions[0].contains("reason=\"replace\""));
+        assert!(evictions[1].contains(&format!("service={:?}", std::any::type_name::<dyn Repo>())));
+        assert!(evictions[1].contains("reason=\"shutdown\""));
     }
 }
@@ -367,6 +367,11 @@ impl<T: ?Sized + 'static> Instance<T> {
     pub fn value(&self) -> Shared<T> {
         self.value.clone()
     }
+
+    /// The number of handles to the value, including this one.
+    pub(crate) fn strong_count(&self) -> usize {
+        Shared::strong_count(&self.value)
+    }
 }
 
 #[cfg(test)]
@@ -1,18 +1,77 @@
 pub mod application;
+#[cfg(feature = "async")]
+pub mod async_provider;
+pub mod boxed;
+#[cfg(feature = "config")]
+pub mod config;
+mod diagnostics;
+pub mod eager;
+pub mod erased;
 pub mod error;
+pub mod fallback;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generic;
+#[cfg(feature = "thread-safe")]
+pub mod global;
+pub mod health;
+pub mod inferred;
+pub mod inject;
 pub mod injector;
 pub mod instance;
+mod macros;
+pub mod manifest;
+pub mod meta;
 pub mod module;
+pub mod params;
+pub mod pool;
+pub mod profile;
 pub mod provider;
 pub mod resolve_guard;
 pub mod runtime;
 pub mod scope;
+pub mod scoped;
+pub mod stats;
+pub mod store;
+pub mod tagged;
+pub mod tenant;
+#[cfg(feature = "test-util")]
+pub mod test;
+#[cfg(feature = "timing")]
+pub mod timing;
+pub mod trace;
 
 pub use application::*;
+#[cfg(feature = "async")]
+pub use async_provider::*;
+pub use boxed::*;
+#[cfg(feature = "config")]
+pub use config::*;
+pub use eager::*;
+pub use erased::*;
 pub use error::*;
+pub use fallback::*;
+#[cfg(feature = "ffi")]
+pub use ffi::*;
+pub use health::*;
+pub use inferred::*;
+pub use inject::*;
 pub use injector::*;
 pub use instance::*;
+pub use manifest::*;
+pub use meta::*;
 pub use module::*;
+pub use params::*;
+pub use pool::*;
+pub use profile::*;
 pub use provider::*;
 pub use runtime::*;
 pub use scope::*;
+pub use scoped::*;
+pub use stats::*;
+pub use store::*;
+pub use tagged::*;
+pub use tenant::*;
+#[cfg(feature = "timing")]
+pub use timing::*;
+pub use trace::*;
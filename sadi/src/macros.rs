@@ -0,0 +1,197 @@
+/// Registers a concrete type together with a trait-object view of the same instance.
+///
+/// Rust cannot turn a `Shared<Concrete>` into a `Shared<dyn Trait>` generically,
+/// so a provider of a trait object normally spells the coercion out with
+/// `as Shared<dyn Trait>`. `register_as!(injector, Concrete as dyn Trait, provider)`
+/// registers `provider` for `Concrete`, then a provider for `dyn Trait` with
+/// the same scope that resolves `Concrete` and coerces it. Both views are
+/// therefore registered on the same injector, and for a singleton or root
+/// `provider` they are the same instance.
+///
+/// Expands to a `Result<(), Error>`, failing like
+/// [`Injector::try_provide`](crate::Injector::try_provide) if either type is
+/// already registered.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{Injector, Provider, Shared, register_as};
+///
+/// trait UserRepository: Send + Sync {
+///     fn name(&self) -> &'static str;
+/// }
+///
+/// struct SqlUsers;
+/// impl UserRepository for SqlUsers {
+///     fn name(&self) -> &'static str {
+///         "sql"
+///     }
+/// }
+///
+/// let injector = Injector::root();
+/// register_as!(
+///     injector,
+///     SqlUsers as dyn UserRepository,
+///     Provider::root(|_| Shared::new(SqlUsers))
+/// )
+/// .unwrap();
+///
+/// assert_eq!(injector.resolve::<dyn UserRepository>().name(), "sql");
+/// ```
+#[macro_export]
+macro_rules! register_as {
+    ($injector:expr, $concrete:ty as $view:ty, $provider:expr $(,)?) => {{
+        let injector: &$crate::Injector = &$injector;
+        let provider: $crate::Provider<$concrete> = $provider;
+        let scope = provider.scope;
+
+        injector.try_provide::<$concrete>(provider).and_then(|()| {
+            let mut view = $crate::Provider::transient(|injector| {
+                injector.resolve::<$concrete>() as $crate::Shared<$view>
+            })
+            .depends_on::<$concrete>();
+            view.scope = scope;
+
+            injector.try_provide::<$view>(view)
+        })
+    }};
+}
+
+/// Builds a `Vec<Box<dyn Module>>` from a list of modules.
+///
+/// Each expression is boxed and coerced to `Box<dyn Module>`, so the result can
+/// be returned from [`Module::imports`](crate::Module::imports), passed to
+/// [`Injector::load_modules`](crate::Injector::load_modules), or turned into a
+/// [`CompositeModule`](crate::CompositeModule). Modules keep the order they are
+/// listed in.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{Injector, Module, Provider, Shared, modules};
+///
+/// struct PortModule;
+/// impl Module for PortModule {
+///     fn providers(&self, injector: &Injector) {
+///         injector.provide::<u16>(Provider::root(|_| Shared::new(8080)));
+///     }
+/// }
+///
+/// struct AppModule;
+/// impl Module for AppModule {
+///     fn imports(&self) -> Vec<Box<dyn Module>> {
+///         modules![PortModule]
+///     }
+/// }
+///
+/// let injector = Injector::root();
+/// injector.load_modules(modules![AppModule]).unwrap();
+///
+/// assert_eq!(*injector.resolve::<u16>(), 8080);
+/// ```
+#[macro_export]
+macro_rules! modules {
+    ($($module:expr),* $(,)?) => {
+        ::std::vec![$(::std::boxed::Box::new($module) as ::std::boxed::Box<dyn $crate::Module>),*]
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompositeModule, ErrorKind, Injector, Module, Provider, Shared};
+
+    trait UserRepository: Send + Sync {
+        fn name(&self) -> &'static str;
+    }
+
+    struct SqlUsers;
+    impl UserRepository for SqlUsers {
+        fn name(&self) -> &'static str {
+            "sql"
+        }
+    }
+
+    #[test]
+    fn test_register_as_shares_one_instance_between_both_views() {
+        let root = Shared::new(Injector::root());
+        register_as!(
+            root,
+            SqlUsers as dyn UserRepository,
+            Provider::root(|_| Shared::new(SqlUsers)),
+        )
+        .unwrap();
+
+        let child = Injector::child(root.clone());
+        let view = child.resolve::<dyn UserRepository>();
+        let concrete = root.resolve::<SqlUsers>();
+
+        assert_eq!(view.name(), "sql");
+        assert!(std::ptr::addr_eq(&*view, &*concrete));
+        assert!(std::ptr::addr_eq(
+            &*view,
+            &*root.resolve::<dyn UserRepository>()
+        ));
+    }
+
+    #[test]
+    fn test_register_as_rejects_an_existing_registration() {
+        let injector = Injector::root();
+        injector.provide::<dyn UserRepository>(Provider::root(|_| {
+            Shared::new(SqlUsers) as Shared<dyn UserRepository>
+        }));
+
+        let Err(err) = register_as!(
+            injector,
+            SqlUsers as dyn UserRepository,
+            Provider::root(|_| Shared::new(SqlUsers)),
+        ) else {
+            panic!("expected the trait view to be registered already");
+        };
+        assert_eq!(err.kind, ErrorKind::ProviderAlreadyRegistered);
+    }
+
+    struct RepositoriesModule;
+    impl Module for RepositoriesModule {
+        fn providers(&self, injector: &Injector) {
+            register_as!(
+                injector,
+                SqlUsers as dyn UserRepository,
+                Provider::root(|_| Shared::new(SqlUsers)),
+            )
+            .unwrap();
+        }
+    }
+
+    struct UseCasesModule;
+    impl Module for UseCasesModule {
+        fn providers(&self, injector: &Injector) {
+            injector.provide::<String>(Provider::root(|inj| {
+                Shared::new(inj.resolve::<dyn UserRepository>().name().to_string())
+            }));
+        }
+    }
+
+    #[test]
+    fn test_modules_lists_each_module_in_order_and_loads_them_all() {
+        let modules = modules![RepositoriesModule, UseCasesModule];
+        assert_eq!(
+            modules
+                .iter()
+                .map(|module| module.name())
+                .collect::<Vec<_>>(),
+            [
+                std::any::type_name::<RepositoriesModule>(),
+                std::any::type_name::<UseCasesModule>(),
+            ]
+        );
+
+        let injector = Injector::root();
+        injector
+            .load_modules(vec![Box::new(CompositeModule::from(modules))])
+            .unwrap();
+
+        assert_eq!(*injector.resolve::<String>(), "sql");
+        assert!(injector.assert_loaded::<RepositoriesModule>().is_ok());
+        assert!(injector.assert_loaded::<UseCasesModule>().is_ok());
+    }
+}
@@ -0,0 +1,163 @@
+//! Registration manifests.
+//!
+//! A [`Manifest`] is a sorted snapshot of the registrations visible from an
+//! [`Injector`]: each type name together with its [`Scope`](crate::Scope).
+//! With the `serde` feature it can be written to JSON, checked in, and later
+//! compared against the live injector with [`Manifest::assert_matches`] to
+//! catch unintended wiring changes.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! let injector = Injector::root();
+//! injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+//!
+//! let manifest = injector.manifest();
+//! assert_eq!(manifest.entries.len(), 1);
+//! assert!(manifest.assert_matches(&injector).is_ok());
+//!
+//! injector.provide::<String>(Provider::transient(|_| Shared::new(String::new())));
+//! assert!(manifest.assert_matches(&injector).is_err());
+//! ```
+
+use crate::error::Error;
+use crate::injector::Injector;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A single registration in a [`Manifest`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ManifestEntry {
+    /// The registered type, as reported by [`std::any::type_name`].
+    pub type_name: String,
+    /// The provider's scope, as displayed by [`Scope`](crate::Scope).
+    pub scope: String,
+}
+
+/// The registrations visible from an injector, sorted by type name.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Captures the registrations visible from `injector`.
+    ///
+    /// A registration shadowed by a closer one for the same type is left out.
+    pub fn of(injector: &Injector) -> Self {
+        let mut entries: Vec<ManifestEntry> = injector
+            .registrations()
+            .into_iter()
+            .map(|(type_name, scope)| ManifestEntry {
+                type_name: type_name.to_string(),
+                scope: scope.to_string(),
+            })
+            .collect();
+        entries.sort();
+
+        Self { entries }
+    }
+
+    /// Checks that `injector` has exactly the registrations in this manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ManifestMismatch`](crate::ErrorKind::ManifestMismatch) listing the
+    /// entries missing from the injector and those it has in addition.
+    pub fn assert_matches(&self, injector: &Injector) -> Result<(), Error> {
+        let live = Manifest::of(injector);
+
+        let describe = |entries: Vec<&ManifestEntry>| {
+            entries
+                .iter()
+                .map(|entry| format!("{} ({})", entry.type_name, entry.scope))
+                .collect::<Vec<_>>()
+        };
+
+        let missing = describe(
+            self.entries
+                .iter()
+                .filter(|entry| !live.entries.contains(entry))
+                .collect(),
+        );
+        let unexpected = describe(
+            live.entries
+                .iter()
+                .filter(|entry| !self.entries.contains(entry))
+                .collect(),
+        );
+
+        if missing.is_empty() && unexpected.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::manifest_mismatch(&missing, &unexpected))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::provider::Provider;
+    use crate::runtime::Shared;
+
+    struct Database;
+    struct Cache;
+
+    fn injector() -> Injector {
+        let injector = Injector::root();
+        injector.provide::<Database>(Provider::root(|_| Shared::new(Database)));
+        injector.provide::<Cache>(Provider::singleton(|_| Shared::new(Cache)));
+        injector
+    }
+
+    #[test]
+    fn test_manifest_is_sorted_and_includes_ancestors() {
+        let child = Injector::child(Shared::new(injector()));
+        child.provide::<u8>(Provider::transient(|_| Shared::new(1)));
+
+        let manifest = child.manifest();
+        let scopes: Vec<&str> = manifest
+            .entries
+            .iter()
+            .map(|entry| entry.scope.as_str())
+            .collect();
+
+        assert_eq!(manifest.entries.len(), 3);
+        assert!(manifest.entries.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(scopes.contains(&"Transient"));
+    }
+
+    #[test]
+    fn test_manifest_mismatch_lists_differences() {
+        let manifest = injector().manifest();
+
+        let live = Injector::root();
+        live.provide::<Database>(Provider::transient(|_| Shared::new(Database)));
+
+        let err = manifest.assert_matches(&live).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ManifestMismatch);
+        assert!(err.message.contains("Cache (Module)"));
+        assert!(err.message.contains("Database (Transient)"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_manifest_round_trips_through_json() {
+        let injector = injector();
+        let json = serde_json::to_string_pretty(&injector.manifest()).unwrap();
+
+        let manifest: Manifest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(manifest, injector.manifest());
+        assert!(manifest.assert_matches(&injector).is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+//! Registration metadata reported alongside a resolved instance.
+//!
+//! [`Injector::resolve_with_meta`](crate::Injector::resolve_with_meta) returns
+//! the instance together with a [`ProviderMeta`] describing the provider that
+//! produced it: its scope, where it was registered, which module registered
+//! it, and whether this resolve was served from the cache. This is the raw
+//! material for tooling such as an admin page listing the container's state.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Scope, Shared};
+//!
+//! let injector = Injector::root();
+//! injector.provide::<u16>(Provider::root(|_| Shared::new(8080)));
+//!
+//! let (port, meta) = injector.resolve_with_meta::<u16>().unwrap();
+//! assert_eq!(*port, 8080);
+//! assert_eq!(meta.scope, Scope::Root);
+//! assert!(!meta.was_cached);
+//! assert_eq!(meta.module, None);
+//! ```
+
+use std::panic::Location;
+
+use crate::scope::Scope;
+
+/// How the provider behind a resolved instance was registered.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ProviderMeta {
+    /// The provider's scope.
+    pub scope: Scope,
+    /// Where the provider was registered, as in [`Injector::registration_sites`](crate::Injector::registration_sites).
+    pub registered_at: &'static Location<'static>,
+    /// Whether the instance came from the singleton cache rather than the factory.
+    pub was_cached: bool,
+    /// The [`Module::name`](crate::Module::name) of the module that registered
+    /// the provider, or `None` if it was registered outside any module.
+    pub module: Option<&'static str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorKind, Injector, Module, Provider, Scope, Shared};
+
+    struct Clock;
+
+    struct ClockModule;
+
+    impl Module for ClockModule {
+        fn providers(&self, injector: &Injector) {
+            injector.provide::<Clock>(Provider::root(|_| Shared::new(Clock)));
+        }
+    }
+
+    #[test]
+    fn test_was_cached_is_only_set_once_the_singleton_exists() {
+        let injector = Injector::root();
+        injector.provide::<u16>(Provider::root(|_| Shared::new(8080)));
+        let line = line!() - 1;
+
+        let (first, meta) = injector.resolve_with_meta::<u16>().unwrap();
+        assert!(!meta.was_cached);
+        assert_eq!(meta.scope, Scope::Root);
+        assert_eq!(meta.registered_at.file(), file!());
+        assert_eq!(meta.registered_at.line(), line);
+
+        let (second, meta) = injector.resolve_with_meta::<u16>().unwrap();
+        assert!(meta.was_cached);
+        assert!(Shared::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_transients_are_never_cached() {
+        let injector = Injector::root();
+        injector.provide::<u16>(Provider::transient(|_| Shared::new(8080)));
+
+        for _ in 0..2 {
+            let (_, meta) = injector.resolve_with_meta::<u16>().unwrap();
+            assert!(!meta.was_cached);
+            assert_eq!(meta.scope, Scope::Transient);
+        }
+    }
+
+    #[test]
+    fn test_meta_names_the_registering_module() {
+        let injector = Injector::root();
+        injector.load_modules(vec![Box::new(ClockModule)]).unwrap();
+        injector.provide::<u16>(Provider::root(|_| Shared::new(8080)));
+
+        let (_, meta) = injector.resolve_with_meta::<Clock>().unwrap();
+        assert_eq!(meta.module, Some(ClockModule.name()));
+
+        let (_, meta) = injector.resolve_with_meta::<u16>().unwrap();
+        assert_eq!(meta.module, None);
+
+        let Err(err) = injector.resolve_with_meta::<String>() else {
+            panic!("resolved an unregistered type");
+        };
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+    }
+}
@@ -29,7 +29,14 @@
 //!     }
 //! }
 //! ```
+use std::any::TypeId;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+use crate::error::Error;
 use crate::injector::Injector;
+use crate::runtime::Shared;
 
 /// Trait for defining a module in the dependency injection system.
 ///
@@ -50,6 +57,7 @@ use crate::injector::Injector;
 /// # Optional Methods
 ///
 /// - [`imports`](Module::imports): Returns other modules that this module depends on
+/// - [`name`](Module::name): Returns the name the module is recorded under
 ///
 /// # Examples
 ///
@@ -169,6 +177,45 @@ pub trait Module {
     /// }
     /// ```
     fn providers(&self, _injector: &Injector) {}
+
+    /// Registers providers, reporting failures instead of panicking.
+    ///
+    /// Module loading calls this rather than [`providers`](Module::providers),
+    /// and stops at the first error, which is returned from
+    /// [`Application::try_bootstrap`](crate::Application::try_bootstrap) and
+    /// [`Injector::load_modules`]. The default calls `providers` and succeeds;
+    /// override it to register with [`Injector::try_provide`] and `?`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Error, Injector, Module, Provider, Shared};
+    ///
+    /// struct ConfigModule;
+    ///
+    /// impl Module for ConfigModule {
+    ///     fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+    ///         injector.try_provide::<u16>(Provider::root(|_| Shared::new(8080)))?;
+    ///         injector.try_provide::<u16>(Provider::root(|_| Shared::new(8081)))
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// assert!(injector.load_modules(vec![Box::new(ConfigModule)]).is_err());
+    /// ```
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+        self.providers(injector);
+        Ok(())
+    }
+
+    /// Returns the name this module is recorded under once loaded.
+    ///
+    /// Defaults to the module's type name, which is what
+    /// [`Injector::assert_loaded`] compares against. Override it only if that
+    /// name is not stable enough for your diagnostics.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 #[cfg(feature = "thread-safe")]
@@ -236,6 +283,206 @@ pub trait Module: Send + Sync {
     /// }
     /// ```
     fn providers(&self, _injector: &Injector) {}
+
+    /// Registers providers, reporting failures instead of panicking.
+    ///
+    /// Module loading calls this rather than [`providers`](Module::providers),
+    /// and stops at the first error, which is returned from
+    /// [`Application::try_bootstrap`](crate::Application::try_bootstrap) and
+    /// [`Injector::load_modules`]. The default calls `providers` and succeeds;
+    /// override it to register with [`Injector::try_provide`] and `?`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Error, Injector, Module, Provider, Shared};
+    ///
+    /// struct ConfigModule;
+    ///
+    /// impl Module for ConfigModule {
+    ///     fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+    ///         injector.try_provide::<u16>(Provider::root(|_| Shared::new(8080)))?;
+    ///         injector.try_provide::<u16>(Provider::root(|_| Shared::new(8081)))
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// assert!(injector.load_modules(vec![Box::new(ConfigModule)]).is_err());
+    /// ```
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+        self.providers(injector);
+        Ok(())
+    }
+
+    /// Returns the name this module is recorded under once loaded.
+    ///
+    /// Defaults to the module's type name, which is what
+    /// [`Injector::assert_loaded`] compares against. Override it only if that
+    /// name is not stable enough for your diagnostics.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// A module that bundles other modules, so they can be imported as one.
+///
+/// Loading a composite loads each added module in turn, exactly as if it had
+/// been listed in [`imports`](Module::imports): each gets its own child
+/// injector, its own imports are loaded first, and it is recorded as loaded
+/// under its own name. Modules are loaded in the order they were added, and
+/// loading stops at the first error.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{CompositeModule, Injector, Module, Provider, Shared};
+///
+/// struct RepositoriesModule;
+/// impl Module for RepositoriesModule {
+///     fn providers(&self, injector: &Injector) {
+///         injector.provide::<u16>(Provider::root(|_| Shared::new(5432)));
+///     }
+/// }
+///
+/// struct UseCasesModule;
+/// impl Module for UseCasesModule {
+///     fn providers(&self, injector: &Injector) {
+///         injector.provide::<String>(Provider::root(|inj| {
+///             Shared::new(format!("port {}", inj.resolve::<u16>()))
+///         }));
+///     }
+/// }
+///
+/// let app_module = CompositeModule::new()
+///     .add(Box::new(RepositoriesModule))
+///     .add(Box::new(UseCasesModule));
+///
+/// let injector = Injector::root();
+/// injector.load_modules(vec![Box::new(app_module)]).unwrap();
+///
+/// assert_eq!(*injector.resolve::<String>(), "port 5432");
+/// assert!(injector.assert_loaded::<UseCasesModule>().is_ok());
+/// ```
+#[derive(Default)]
+pub struct CompositeModule {
+    modules: Vec<Box<dyn Module>>,
+}
+
+impl CompositeModule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `module`, to be loaded after the modules added before it.
+    // A builder step, not arithmetic: there is nothing for `std::ops::Add` to sum.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, module: Box<dyn Module>) -> Self {
+        self.modules.push(module);
+        self
+    }
+
+    /// Returns the names of the bundled modules, in loading order.
+    pub fn module_names(&self) -> Vec<&'static str> {
+        self.modules.iter().map(|module| module.name()).collect()
+    }
+}
+
+impl From<Vec<Box<dyn Module>>> for CompositeModule {
+    /// Bundles `modules`, as built by [`modules!`](crate::modules), in order.
+    fn from(modules: Vec<Box<dyn Module>>) -> Self {
+        Self { modules }
+    }
+}
+
+impl Module for CompositeModule {
+    fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+        let parent = Shared::new(injector.clone());
+
+        for module in &self.modules {
+            Injector::load_module(parent.clone(), module.as_ref())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A module registered with [`Injector::provide_lazy_module`], loaded on first use.
+#[derive(Clone)]
+pub(crate) struct LazyModule {
+    /// The types whose first resolve loads the module.
+    pub(crate) type_ids: Vec<TypeId>,
+    pub(crate) module: Shared<dyn Module>,
+
+    /// Set once by the first load, so concurrent triggers load it only once.
+    pub(crate) loaded: OnceLock<Result<(), Error>>,
+}
+
+/// A module whose provider registration needs to `.await`.
+///
+/// Use it when the registration itself depends on an asynchronous value, such
+/// as configuration fetched from a remote store: await it in
+/// [`providers`](AsyncModule::providers) and capture the result in the
+/// providers you register. Resolution stays synchronous, so anything that can
+/// be created lazily on first use belongs in a regular factory instead.
+///
+/// Async modules are loaded with [`Injector::load_async_modules`], which only
+/// awaits, so any executor can drive it.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{AsyncModule, DynAsyncModule, Error, Injector, Provider, Shared};
+///
+/// struct ConfigModule;
+///
+/// impl AsyncModule for ConfigModule {
+///     async fn providers(&self, injector: &Injector) -> Result<(), Error> {
+///         let url = std::future::ready("sqlite::memory:".to_string()).await;
+///         injector.provide::<String>(Provider::root(move |_| Shared::new(url.clone())));
+///         Ok(())
+///     }
+/// }
+///
+/// let injector = Injector::root();
+/// let modules: Vec<Box<dyn DynAsyncModule>> = vec![Box::new(ConfigModule)];
+/// futures::executor::block_on(injector.load_async_modules(modules)).unwrap();
+///
+/// assert_eq!(*injector.resolve::<String>(), "sqlite::memory:");
+/// ```
+// The futures are awaited in place and never required to be `Send`, which is
+// what the lint warns about.
+#[allow(async_fn_in_trait)]
+pub trait AsyncModule {
+    /// Registers providers with the given injector.
+    async fn providers(&self, injector: &Injector) -> Result<(), Error>;
+}
+
+/// Object-safe form of [`AsyncModule`], implemented for every async module.
+///
+/// It exists so that modules of different types can be collected in a
+/// `Vec<Box<dyn DynAsyncModule>>`; it is not meant to be implemented directly.
+pub trait DynAsyncModule {
+    /// Registers providers, boxing the future returned by [`AsyncModule::providers`].
+    fn providers_boxed<'a>(
+        &'a self,
+        injector: &'a Injector,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Error>> + 'a>>;
+
+    /// Returns the name this module is recorded under once loaded.
+    fn name(&self) -> &'static str;
+}
+
+impl<M: AsyncModule> DynAsyncModule for M {
+    fn providers_boxed<'a>(
+        &'a self,
+        injector: &'a Injector,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Error>> + 'a>> {
+        Box::pin(self.providers(injector))
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<M>()
+    }
 }
 
 #[cfg(test)]
@@ -262,6 +509,44 @@ mod tests {
         fn providers(&self, _injector: &Injector) {}
     }
 
+    /// A future that is pending once before completing, to make sure loading
+    /// really awaits rather than relying on futures that are ready immediately.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<()> {
+            if self.0 {
+                return std::task::Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+
+    struct RemoteConfigModule;
+
+    impl AsyncModule for RemoteConfigModule {
+        async fn providers(&self, injector: &Injector) -> Result<(), Error> {
+            YieldOnce(false).await;
+            injector.provide::<u16>(crate::Provider::root(|_| crate::Shared::new(8080)));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_async_module_registers_after_await() {
+        let injector = Injector::root();
+        let modules: Vec<Box<dyn DynAsyncModule>> = vec![Box::new(RemoteConfigModule)];
+
+        futures::executor::block_on(injector.load_async_modules(modules)).unwrap();
+
+        assert_eq!(*injector.resolve::<u16>(), 8080);
+        assert!(injector.assert_loaded::<RemoteConfigModule>().is_ok());
+    }
+
     #[test]
     fn test_default_imports_returns_empty_vec() {
         let module = EmptyModule;
@@ -387,4 +672,155 @@ mod tests {
             assert_eq!(*module.call_count.lock().unwrap(), 2);
         }
     }
+
+    struct PortModule;
+
+    impl Module for PortModule {
+        fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+            injector.try_provide::<u16>(crate::Provider::root(|_| crate::Shared::new(8080)))
+        }
+    }
+
+    struct GreetingModule;
+
+    impl Module for GreetingModule {
+        fn imports(&self) -> Vec<Box<dyn Module>> {
+            vec![Box::new(EmptyModule)]
+        }
+
+        fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+            injector.try_provide::<String>(crate::Provider::root(|inj| {
+                crate::Shared::new(format!("listening on {}", inj.resolve::<u16>()))
+            }))
+        }
+    }
+
+    #[test]
+    fn test_composite_module_loads_every_child() {
+        let composite = CompositeModule::new()
+            .add(Box::new(PortModule))
+            .add(Box::new(GreetingModule));
+        assert_eq!(composite.module_names().len(), 2);
+
+        let injector = Injector::root();
+        injector.load_modules(vec![Box::new(composite)]).unwrap();
+
+        assert!(injector.contains::<u16>());
+        assert_eq!(*injector.resolve::<String>(), "listening on 8080");
+        assert_eq!(
+            injector.loaded_modules(),
+            [
+                std::any::type_name::<PortModule>(),
+                std::any::type_name::<EmptyModule>(),
+                std::any::type_name::<GreetingModule>(),
+                std::any::type_name::<CompositeModule>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_composite_module_stops_at_first_failure() {
+        let composite = CompositeModule::new()
+            .add(Box::new(PortModule))
+            .add(Box::new(PortModule))
+            .add(Box::new(GreetingModule));
+
+        let injector = Injector::root();
+        let Err(err) = injector.load_modules(vec![Box::new(composite)]) else {
+            panic!("expected the duplicate port to conflict");
+        };
+
+        assert!(err.kind == crate::ErrorKind::ProviderAlreadyRegistered);
+        assert!(!injector.contains::<String>());
+    }
+
+    struct ReportsModule {
+        loads: Shared<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl Module for ReportsModule {
+        fn try_providers(&self, injector: &Injector) -> Result<(), Error> {
+            self.loads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            injector.try_provide::<String>(crate::Provider::root(|_| {
+                crate::Shared::new("monthly report".to_string())
+            }))
+        }
+    }
+
+    #[test]
+    fn test_lazy_module_loads_once_on_first_resolve_of_a_declared_type() {
+        let loads = Shared::new(std::sync::atomic::AtomicUsize::new(0));
+        let root = Shared::new(Injector::root());
+        root.provide::<u16>(crate::Provider::root(|_| crate::Shared::new(8080)));
+        root.provide_lazy_module(
+            &[std::any::TypeId::of::<String>()],
+            Box::new(ReportsModule {
+                loads: loads.clone(),
+            }),
+        );
+
+        let loaded = || loads.load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(*root.resolve::<u16>(), 8080);
+        assert!(!root.contains::<String>());
+        assert_eq!(loaded(), 0);
+
+        let child = Injector::child(root.clone());
+        assert_eq!(*child.resolve::<String>(), "monthly report");
+        assert_eq!(loaded(), 1);
+
+        root.resolve::<String>();
+        assert_eq!(loaded(), 1);
+        assert!(root.assert_loaded::<ReportsModule>().is_ok());
+    }
+
+    #[test]
+    fn test_optional_resolve_loads_a_lazy_module() {
+        let loads = Shared::new(std::sync::atomic::AtomicUsize::new(0));
+        let injector = Injector::root();
+        injector.provide_lazy_module(
+            &[std::any::TypeId::of::<String>()],
+            Box::new(ReportsModule {
+                loads: loads.clone(),
+            }),
+        );
+
+        assert!(!injector.contains::<String>());
+        assert_eq!(
+            injector.optional_resolve::<String>().as_deref(),
+            Some(&"monthly report".to_string())
+        );
+        assert_eq!(*injector.resolve_or_default::<String>(), "monthly report");
+        assert!(injector.optional_resolve::<u16>().is_none());
+        assert_eq!(loads.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_lazy_module_declared_before_a_freeze_still_loads() {
+        let loads = Shared::new(std::sync::atomic::AtomicUsize::new(0));
+        let injector = Injector::root();
+        injector.provide_lazy_module(
+            &[std::any::TypeId::of::<String>()],
+            Box::new(ReportsModule {
+                loads: loads.clone(),
+            }),
+        );
+        injector.freeze();
+
+        assert_eq!(*injector.try_resolve::<String>().unwrap(), "monthly report");
+        assert!(
+            injector
+                .try_provide::<u16>(crate::Provider::root(|_| crate::Shared::new(8080)))
+                .is_err()
+        );
+
+        let injector = Injector::root();
+        injector.freeze();
+        let Err(err) = injector.try_provide_lazy_module(
+            &[std::any::TypeId::of::<String>()],
+            Box::new(ReportsModule { loads }),
+        ) else {
+            panic!("declared a lazy module on a frozen injector");
+        };
+        assert!(err.kind == crate::ErrorKind::InvalidScope);
+    }
 }
@@ -0,0 +1,133 @@
+//! Services built from a value supplied at resolve time.
+//!
+//! Some services need a runtime value that is not known when providers are
+//! registered, such as a `RequestContext` built from the current request.
+//! [`Injector::provide_with_params`](crate::Injector::provide_with_params)
+//! registers a factory that takes such a value alongside the injector, and
+//! [`Injector::resolve_with_params`](crate::Injector::resolve_with_params)
+//! passes it in.
+//!
+//! Because each resolve may carry different parameters, the result is never
+//! cached: every call runs the factory, just like a
+//! [`Provider::transient`](crate::Provider::transient). The factory is
+//! registered under [`ParamFactory<T, P>`], so it does not conflict with a
+//! plain registration of `T`, and the same `T` may be registered once per
+//! parameter type `P`.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Shared};
+//!
+//! struct RequestContext {
+//!     request_id: u64,
+//! }
+//!
+//! let injector = Injector::root();
+//! injector.provide_with_params::<RequestContext, u64, _>(|_, request_id| {
+//!     Shared::new(RequestContext { request_id })
+//! });
+//!
+//! let first = injector.resolve_with_params::<RequestContext, _>(7_u64);
+//! let second = injector.resolve_with_params::<RequestContext, _>(8_u64);
+//! assert_eq!((first.request_id, second.request_id), (7, 8));
+//! ```
+
+use crate::injector::Injector;
+use crate::runtime::Shared;
+
+/// The factory behind [`Injector::provide_with_params`](crate::Injector::provide_with_params).
+pub struct ParamFactory<T: ?Sized + 'static, P: 'static> {
+    #[allow(clippy::type_complexity)]
+    #[cfg(not(feature = "thread-safe"))]
+    factory: Box<dyn Fn(&Injector, P) -> Shared<T>>,
+
+    #[allow(clippy::type_complexity)]
+    #[cfg(feature = "thread-safe")]
+    factory: Box<dyn Fn(&Injector, P) -> Shared<T> + Send + Sync>,
+}
+
+#[cfg(feature = "debug")]
+impl<T: ?Sized + 'static, P: 'static> std::fmt::Debug for ParamFactory<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(std::any::type_name::<Self>())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: ?Sized + 'static, P: 'static> ParamFactory<T, P> {
+    /// Wraps `factory`.
+    pub fn new(factory: impl Fn(&Injector, P) -> Shared<T> + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: ?Sized + 'static, P: 'static> ParamFactory<T, P> {
+    /// Wraps `factory`.
+    pub fn new(factory: impl Fn(&Injector, P) -> Shared<T> + Send + Sync + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static, P: 'static> ParamFactory<T, P> {
+    /// Runs the factory with `params`, returning a new instance.
+    pub fn create(&self, injector: &Injector, params: P) -> Shared<T> {
+        (self.factory)(injector, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Provider;
+
+    struct Config {
+        region: &'static str,
+    }
+
+    struct RequestContext {
+        request_id: String,
+        region: &'static str,
+    }
+
+    #[test]
+    fn test_resolve_with_params_passes_the_request_id_to_the_factory() {
+        let root = Shared::new(Injector::root());
+        root.provide::<Config>(Provider::root(|_| Shared::new(Config { region: "eu" })));
+        root.provide_with_params::<RequestContext, String, _>(|inj, request_id| {
+            Shared::new(RequestContext {
+                request_id,
+                region: inj.resolve::<Config>().region,
+            })
+        });
+
+        let scope = Injector::child(root.clone());
+        let first = scope.resolve_with_params::<RequestContext, _>("req-1".to_string());
+        let second = scope.resolve_with_params::<RequestContext, _>("req-2".to_string());
+
+        assert_eq!(first.request_id, "req-1");
+        assert_eq!(second.request_id, "req-2");
+        assert_eq!(first.region, "eu");
+        assert!(!Shared::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_params_are_part_of_the_registration_key() {
+        let injector = Injector::root();
+        injector.provide_with_params::<String, u32, _>(|_, n| Shared::new(format!("#{n}")));
+
+        assert_eq!(*injector.resolve_with_params::<String, _>(3_u32), "#3");
+        assert!(
+            injector
+                .try_resolve_with_params::<String, _>(3_u64)
+                .is_err()
+        );
+        assert!(!injector.contains::<String>());
+    }
+}
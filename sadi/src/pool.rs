@@ -0,0 +1,295 @@
+//! Reusable objects handed out from a bounded pool.
+//!
+//! Some objects are expensive to construct but cheap to reset, such as a large
+//! buffer or a parser with warmed-up tables. A singleton would share one
+//! instance between all callers, and a transient would build a new one every
+//! time. A pool sits in between: [`Provider::pooled`] registers a [`Pool<T>`],
+//! and [`Injector::resolve_pooled`](crate::Injector::resolve_pooled) checks an
+//! object out of it as a [`PooledGuard<T>`]. The guard gives exclusive access
+//! to the object and, when dropped, puts it back for the next caller instead
+//! of deallocating it. Objects come back as they were left, so callers reset
+//! whatever state they rely on.
+//!
+//! `max_size` bounds how many idle objects the pool keeps, not how many can be
+//! checked out at once: acquiring from an empty pool always constructs a new
+//! object, and an object released into a full pool is dropped.
+//!
+//! # Thread safety
+//!
+//! With the `thread-safe` feature, the pool is shared between threads like any
+//! other singleton and `T` must be `Send + Sync`. Checking an object out or
+//! back in holds the pool's lock only to pop or push it; the factory runs with
+//! no lock held, so threads acquiring from an empty pool concurrently each
+//! construct their own object. A guard may be sent to another thread and
+//! returns its object to the pool from wherever it is dropped.
+//!
+//! Without the feature, the pool and its guards are confined to one thread.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Pool, Provider};
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Pool<Vec<u8>>>(Provider::pooled(2, || Vec::with_capacity(4096)));
+//!
+//! {
+//!     let mut buffer = injector.resolve_pooled::<Vec<u8>>();
+//!     buffer.extend_from_slice(b"request");
+//! }
+//!
+//! let mut buffer = injector.resolve_pooled::<Vec<u8>>();
+//! assert_eq!(buffer.as_slice(), b"request");
+//! buffer.clear();
+//!
+//! assert_eq!(injector.resolve::<Pool<Vec<u8>>>().created(), 1);
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::provider::Provider;
+use crate::runtime::{Shared, Store};
+
+#[cfg(not(feature = "thread-safe"))]
+type PoolFactory<T> = Box<dyn Fn() -> T>;
+
+#[cfg(feature = "thread-safe")]
+type PoolFactory<T> = Box<dyn Fn() -> T + Send + Sync>;
+
+/// A bounded pool of reusable `T`, registered with [`Provider::pooled`].
+pub struct Pool<T: 'static> {
+    max_size: usize,
+    factory: PoolFactory<T>,
+    idle: Store<Vec<T>>,
+    created: AtomicUsize,
+}
+
+#[cfg(feature = "debug")]
+impl<T: 'static> std::fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(std::any::type_name::<Self>())
+            .field("max_size", &self.max_size)
+            .field("idle", &self.idle())
+            .field("created", &self.created())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: 'static> Pool<T> {
+    /// Creates an empty pool keeping at most `max_size` idle objects built by `factory`.
+    pub fn new(max_size: usize, factory: impl Fn() -> T + 'static) -> Self {
+        Self {
+            max_size,
+            factory: Box::new(factory),
+            idle: Store::new(Vec::new()),
+            created: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: 'static> Pool<T> {
+    /// Creates an empty pool keeping at most `max_size` idle objects built by `factory`.
+    pub fn new(max_size: usize, factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self {
+            max_size,
+            factory: Box::new(factory),
+            idle: Store::new(Vec::new()),
+            created: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T: 'static> Pool<T> {
+    /// Checks out an idle object, constructing one if none is left.
+    pub fn acquire(self: Shared<Self>) -> PooledGuard<T> {
+        #[cfg(not(feature = "thread-safe"))]
+        let reused = self.idle.borrow_mut().pop();
+        #[cfg(feature = "thread-safe")]
+        let reused = self.idle.write().unwrap().pop();
+
+        // Constructed with no lock held, so the factory may take its time.
+        let object = reused.unwrap_or_else(|| {
+            self.created.fetch_add(1, Ordering::Relaxed);
+            (self.factory)()
+        });
+
+        PooledGuard {
+            object: Some(object),
+            pool: self,
+        }
+    }
+
+    /// Returns the most idle objects the pool keeps.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the number of objects waiting to be checked out.
+    pub fn idle(&self) -> usize {
+        #[cfg(not(feature = "thread-safe"))]
+        let idle = self.idle.borrow();
+        #[cfg(feature = "thread-safe")]
+        let idle = self.idle.read().unwrap();
+
+        idle.len()
+    }
+
+    /// Returns how many objects the factory has constructed so far.
+    pub fn created(&self) -> usize {
+        self.created.load(Ordering::Relaxed)
+    }
+
+    /// Puts `object` back, or drops it if the pool is full.
+    fn release(&self, object: T) {
+        #[cfg(not(feature = "thread-safe"))]
+        let mut idle = self.idle.borrow_mut();
+        #[cfg(feature = "thread-safe")]
+        let mut idle = self.idle.write().unwrap();
+
+        if idle.len() < self.max_size {
+            idle.push(object);
+            return;
+        }
+
+        // Dropped after the lock, in case `T`'s destructor is slow.
+        drop(idle);
+        drop(object);
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl<T: 'static> Provider<Pool<T>> {
+    /// Creates a root-scoped provider of a [`Pool`] keeping at most `max_size`
+    /// idle objects built by `factory` (single-threaded).
+    ///
+    /// Objects are checked out with [`Injector::resolve_pooled`](crate::Injector::resolve_pooled).
+    pub fn pooled<F>(max_size: usize, factory: F) -> Provider<Pool<T>>
+    where
+        F: Fn() -> T + 'static,
+    {
+        let pool = Shared::new(Pool::new(max_size, factory));
+        Provider::root(move |_| pool.clone())
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<T: Send + Sync + 'static> Provider<Pool<T>> {
+    /// Creates a root-scoped provider of a [`Pool`] keeping at most `max_size`
+    /// idle objects built by `factory` (thread-safe).
+    ///
+    /// Objects are checked out with [`Injector::resolve_pooled`](crate::Injector::resolve_pooled).
+    pub fn pooled<F>(max_size: usize, factory: F) -> Provider<Pool<T>>
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        let pool = Shared::new(Pool::new(max_size, factory));
+        Provider::root(move |_| pool.clone())
+    }
+}
+
+/// An object checked out of a [`Pool`], returned to it on drop.
+pub struct PooledGuard<T: 'static> {
+    /// Only taken when the guard drops.
+    object: Option<T>,
+    pool: Shared<Pool<T>>,
+}
+
+impl<T: 'static> Deref for PooledGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.object.as_ref().unwrap()
+    }
+}
+
+impl<T: 'static> DerefMut for PooledGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.object.as_mut().unwrap()
+    }
+}
+
+impl<T: 'static> Drop for PooledGuard<T> {
+    fn drop(&mut self) {
+        if let Some(object) = self.object.take() {
+            self.pool.release(object);
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T: std::fmt::Debug + 'static> std::fmt::Debug for PooledGuard<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PooledGuard").field(&**self).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind, Injector};
+
+    struct Parser {
+        runs: usize,
+    }
+
+    fn parser_injector(max_size: usize) -> Injector {
+        let injector = Injector::root();
+        injector.provide::<Pool<Parser>>(Provider::pooled(max_size, || Parser { runs: 0 }));
+        injector
+    }
+
+    #[test]
+    fn test_sequential_acquires_reuse_pooled_objects() {
+        let injector = parser_injector(2);
+        let pool = injector.resolve::<Pool<Parser>>();
+
+        for _ in 0..5 {
+            let mut first = injector.resolve_pooled::<Parser>();
+            let mut second = injector.resolve_pooled::<Parser>();
+            first.runs += 1;
+            second.runs += 1;
+        }
+
+        for _ in 0..5 {
+            injector.resolve_pooled::<Parser>().runs += 1;
+        }
+
+        assert!(pool.created() <= pool.max_size());
+        assert_eq!(pool.created(), 2);
+        assert_eq!(pool.idle(), 2);
+
+        let first = injector.resolve_pooled::<Parser>();
+        let second = injector.resolve_pooled::<Parser>();
+        assert_eq!(first.runs + second.runs, 15);
+    }
+
+    #[test]
+    fn test_full_pool_drops_surplus_objects() {
+        let injector = parser_injector(1);
+        let pool = injector.resolve::<Pool<Parser>>();
+
+        let guards: Vec<_> = (0..3)
+            .map(|_| injector.resolve_pooled::<Parser>())
+            .collect();
+        assert_eq!((pool.created(), pool.idle()), (3, 0));
+
+        drop(guards);
+        assert_eq!(pool.idle(), 1);
+
+        let _reused = injector.resolve_pooled::<Parser>();
+        let _fresh = injector.resolve_pooled::<Parser>();
+        assert_eq!(pool.created(), 4);
+    }
+
+    #[test]
+    fn test_resolve_pooled_without_a_pool_fails() {
+        let Err(err) = Injector::root().try_resolve_pooled::<Parser>() else {
+            panic!("checked out of a pool that was never registered");
+        };
+
+        assert_eq!(err.kind, ErrorKind::ServiceNotProvided);
+    }
+}
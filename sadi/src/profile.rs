@@ -0,0 +1,101 @@
+//! Profile-based provider selection.
+//!
+//! A profile is a plain name such as `"dev"` or `"prod"` that is set on an
+//! [`Injector`](crate::Injector) with
+//! [`set_profile`](crate::Injector::set_profile). Providers can be restricted to
+//! some profiles with [`Provider::only_in`](crate::Provider::only_in) or
+//! excluded from others with [`Provider::except_in`](crate::Provider::except_in).
+//!
+//! The filter is applied when a provider is registered: a provider that is not
+//! active in the current profile is skipped, so the same type can be registered
+//! once per profile without conflicting.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! let injector = Injector::root();
+//! injector.set_profile("prod");
+//!
+//! injector.provide::<String>(
+//!     Provider::root(|_| Shared::new("memory".to_string())).only_in("dev"),
+//! );
+//! injector.provide::<String>(
+//!     Provider::root(|_| Shared::new("sqlite".to_string())).except_in("dev"),
+//! );
+//!
+//! assert_eq!(*injector.resolve::<String>(), "sqlite");
+//! ```
+
+/// The set of profiles a provider is active in.
+///
+/// An empty filter is active in every profile, including when no profile is set.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Profiles {
+    only: Vec<String>,
+    except: Vec<String>,
+}
+
+impl Profiles {
+    /// Restricts the filter to `profile`, in addition to any profile already allowed.
+    pub fn only_in(mut self, profile: impl Into<String>) -> Self {
+        self.only.push(profile.into());
+        self
+    }
+
+    /// Excludes `profile` from the filter.
+    pub fn except_in(mut self, profile: impl Into<String>) -> Self {
+        self.except.push(profile.into());
+        self
+    }
+
+    /// Returns `true` if the filter admits `profile`.
+    ///
+    /// With no active profile, only filters without an `only_in` restriction
+    /// are active.
+    pub fn is_active(&self, profile: Option<&str>) -> bool {
+        let Some(profile) = profile else {
+            return self.only.is_empty();
+        };
+
+        if self.except.iter().any(|except| except == profile) {
+            return false;
+        }
+
+        self.only.is_empty() || self.only.iter().any(|only| only == profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_filter_is_always_active() {
+        let profiles = Profiles::default();
+
+        assert!(profiles.is_active(None));
+        assert!(profiles.is_active(Some("dev")));
+    }
+
+    #[test]
+    fn test_only_in_requires_matching_profile() {
+        let profiles = Profiles::default().only_in("prod").only_in("staging");
+
+        assert!(profiles.is_active(Some("prod")));
+        assert!(profiles.is_active(Some("staging")));
+        assert!(!profiles.is_active(Some("dev")));
+        assert!(!profiles.is_active(None));
+    }
+
+    #[test]
+    fn test_except_in_excludes_profile() {
+        let profiles = Profiles::default().except_in("dev");
+
+        assert!(!profiles.is_active(Some("dev")));
+        assert!(profiles.is_active(Some("prod")));
+        assert!(profiles.is_active(None));
+    }
+}
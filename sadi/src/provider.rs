@@ -47,8 +47,14 @@
 //! });
 //! ```
 
+use std::any::{Any, TypeId};
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::fallback::FallbackChain;
 use crate::injector::Injector;
 use crate::instance::Instance;
+use crate::profile::Profiles;
 use crate::runtime::Shared;
 use crate::scope::Scope;
 
@@ -91,6 +97,38 @@ pub struct Provider<T: ?Sized + 'static> {
     /// The lifecycle scope of this provider
     pub scope: Scope,
 
+    /// The profiles this provider is registered in
+    pub profiles: Profiles,
+
+    /// Optional runtime gate checked on every resolve; see [`Provider::gated_by`]
+    pub gate: Option<Gate>,
+
+    /// Precedence over other registrations of the same type; see [`Provider::priority`]
+    pub priority: i32,
+
+    /// Size of a cached instance, if recorded; see [`Provider::track_size`]
+    pub size: Option<usize>,
+
+    /// Whether [`Injector::init_eager`] creates the instance up front; see [`Provider::eager`]
+    pub eager: bool,
+
+    /// Types the factory resolves, as declared; see [`Provider::depends_on`]
+    pub dependencies: Vec<(TypeId, &'static str)>,
+
+    /// How long a resolve waits for the factory; see `Provider::with_deadline`
+    ///
+    /// Only enforced with the `thread-safe` feature.
+    pub deadline: Option<Duration>,
+
+    /// Hook run on every new instance before it is returned; see [`Provider::after_init`]
+    pub after_init: Option<AfterInit<T>>,
+
+    /// Markers this provider's instance is also collected under; see [`Provider::mark`]
+    pub marks: Vec<Mark>,
+
+    /// Providers tried in order in place of this one; see [`Injector::provide_fallback_chain`]
+    pub fallbacks: Option<FallbackChain<T>>,
+
     /// The factory function that creates instances
     ///
     /// In single-threaded mode, the factory only needs to be `'static`.
@@ -105,12 +143,61 @@ pub struct Provider<T: ?Sized + 'static> {
     pub factory: Box<dyn Fn(&Injector) -> Instance<T> + Send + Sync + 'static>,
 }
 
+/// A runtime predicate deciding whether a provider is currently available.
+#[cfg(not(feature = "thread-safe"))]
+pub type Gate = Shared<dyn Fn(&Injector) -> bool + 'static>;
+
+/// A runtime predicate deciding whether a provider is currently available.
+#[cfg(feature = "thread-safe")]
+pub type Gate = Shared<dyn Fn(&Injector) -> bool + Send + Sync + 'static>;
+
+/// A marker's type id, and how to resolve a marked provider as that marker; see [`Provider::mark`].
+#[cfg(not(feature = "thread-safe"))]
+pub type Mark = (
+    TypeId,
+    Shared<dyn Fn(&Injector) -> Result<Shared<dyn Any>, Error> + 'static>,
+);
+
+/// A marker's type id, and how to resolve a marked provider as that marker; see [`Provider::mark`].
+#[cfg(feature = "thread-safe")]
+pub type Mark = (
+    TypeId,
+    Shared<
+        dyn Fn(&Injector) -> Result<Shared<dyn Any + Send + Sync>, Error> + Send + Sync + 'static,
+    >,
+);
+
+/// A hook run on each new instance; see [`Provider::after_init`].
+#[cfg(not(feature = "thread-safe"))]
+pub type AfterInit<T> = Shared<dyn Fn(&T, &Injector) -> Result<(), String> + 'static>;
+
+/// A hook run on each new instance; see [`Provider::after_init`].
+#[cfg(feature = "thread-safe")]
+pub type AfterInit<T> = Shared<dyn Fn(&T, &Injector) -> Result<(), String> + Send + Sync + 'static>;
+
 #[cfg(feature = "debug")]
 impl<T: ?Sized + 'static> std::fmt::Debug for Provider<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut ds = f.debug_struct(std::any::type_name::<Self>());
 
         ds.field("scope", &self.scope);
+        ds.field("profiles", &self.profiles);
+        ds.field("gated", &self.gate.is_some());
+        ds.field("priority", &self.priority);
+        ds.field("size", &self.size);
+        ds.field("eager", &self.eager);
+        ds.field("deadline", &self.deadline);
+        ds.field("after_init", &self.after_init.is_some());
+        ds.field("marks", &self.marks.len());
+        ds.field("fallbacks", &self.fallbacks);
+        ds.field(
+            "dependencies",
+            &self
+                .dependencies
+                .iter()
+                .map(|(_, type_name)| *type_name)
+                .collect::<Vec<_>>(),
+        );
 
         #[cfg(feature = "thread-safe")]
         {
@@ -132,6 +219,125 @@ impl<T: ?Sized + 'static> std::fmt::Debug for Provider<T> {
     }
 }
 
+impl<T: ?Sized + 'static> Provider<T> {
+    /// Restricts this provider to the given profile.
+    ///
+    /// Can be called several times to allow more than one profile. A provider
+    /// restricted this way is skipped at registration unless the injector's
+    /// active profile is one of them. See [`Injector::set_profile`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.set_profile("dev");
+    /// injector.provide::<u16>(Provider::root(|_| Shared::new(443)).only_in("prod"));
+    ///
+    /// assert!(!injector.contains::<u16>());
+    /// ```
+    pub fn only_in(mut self, profile: impl Into<String>) -> Self {
+        self.profiles = self.profiles.only_in(profile);
+        self
+    }
+
+    /// Excludes this provider from the given profile.
+    ///
+    /// The provider is registered under any other profile, and when no profile
+    /// is set at all.
+    pub fn except_in(mut self, profile: impl Into<String>) -> Self {
+        self.profiles = self.profiles.except_in(profile);
+        self
+    }
+
+    /// Sets the precedence of this provider over other registrations of `T`.
+    ///
+    /// When `T` is registered twice on the same injector, the provider with the
+    /// higher priority is kept and the other is dropped; equal priorities fail
+    /// with [`ProviderAlreadyRegistered`](crate::ErrorKind::ProviderAlreadyRegistered).
+    /// The default is `0`, so a library can register a default and let the
+    /// application override it with any positive priority.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<&str>(Provider::root(|_| Shared::new("default")));
+    /// injector.provide::<&str>(Provider::root(|_| Shared::new("override")).priority(100));
+    ///
+    /// assert_eq!(*injector.resolve::<&str>(), "override");
+    /// ```
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Marks this provider for creation by [`Injector::init_eager`].
+    ///
+    /// By default instances are created on first resolve. An eager provider is
+    /// created during startup instead, so a slow or failing factory shows up
+    /// at boot rather than on the first request. Transient providers have no
+    /// instance to keep and are never created eagerly.
+    pub fn eager(mut self) -> Self {
+        self.eager = true;
+        self
+    }
+
+    /// Declares that the factory resolves `D`.
+    ///
+    /// Declarations are not enforced and do not change how `T` is resolved;
+    /// they let [`Injector::check_cycles`] find dependency cycles among the
+    /// registrations without running any factory. Call it once per dependency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Config;
+    /// struct Database(Shared<Config>);
+    ///
+    /// let provider = Provider::root(|injector: &Injector| {
+    ///     Shared::new(Database(injector.resolve::<Config>()))
+    /// })
+    /// .depends_on::<Config>();
+    ///
+    /// assert_eq!(provider.dependencies.len(), 1);
+    /// ```
+    pub fn depends_on<D: ?Sized + 'static>(mut self) -> Self {
+        self.dependencies
+            .push((TypeId::of::<D>(), std::any::type_name::<D>()));
+        self
+    }
+}
+
+impl<T: 'static> Provider<T> {
+    /// Records `size_of::<T>()` so cached instances count towards
+    /// [`InjectorStats::retained_bytes`](crate::InjectorStats::retained_bytes).
+    ///
+    /// Only the inline size is known; heap memory owned by the value is not
+    /// included. Trait objects have no static size and cannot opt in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<[u8; 64]>(Provider::root(|_| Shared::new([0; 64])).track_size());
+    /// injector.resolve::<[u8; 64]>();
+    ///
+    /// assert_eq!(injector.stats().retained_bytes, 64);
+    /// ```
+    pub fn track_size(mut self) -> Self {
+        self.size = Some(std::mem::size_of::<T>());
+        self
+    }
+}
+
 #[cfg(not(feature = "thread-safe"))]
 impl<T: ?Sized + 'static> Provider<T> {
     /// Creates a singleton provider with module scope (single-threaded).
@@ -174,6 +380,16 @@ impl<T: ?Sized + 'static> Provider<T> {
 
         Provider::<T> {
             scope: Scope::Module,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies: Vec::new(),
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: None,
             factory: Box::new(move |injector| {
                 #[cfg(feature = "tracing")]
                 debug!("Executing singleton factory for type instantiation");
@@ -233,6 +449,16 @@ impl<T: ?Sized + 'static> Provider<T> {
 
         Provider::<T> {
             scope: Scope::Transient,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies: Vec::new(),
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: None,
             factory: Box::new(move |injector| {
                 #[cfg(feature = "tracing")]
                 debug!("Executing transient factory - creating new instance");
@@ -290,6 +516,16 @@ impl<T: ?Sized + 'static> Provider<T> {
 
         Provider::<T> {
             scope: Scope::Root,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies: Vec::new(),
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: None,
             factory: Box::new(move |injector| {
                 #[cfg(feature = "tracing")]
                 debug!("Executing root factory for type instantiation");
@@ -298,6 +534,207 @@ impl<T: ?Sized + 'static> Provider<T> {
             }),
         }
     }
+
+    /// Creates a root-scoped provider with an asynchronous, fallible factory (single-threaded).
+    ///
+    /// The factory receives a handle to the resolving injector and returns a
+    /// future of `Result<Shared<T>, Error>`. Register the provider with
+    /// [`Injector::provide_async`] and resolve it with
+    /// [`Injector::resolve_async`]; see [`AsyncProvider`](crate::AsyncProvider).
+    #[cfg(feature = "async")]
+    pub fn async_root<F, Fut>(factory: F) -> crate::AsyncProvider<T>
+    where
+        F: Fn(Injector) -> Fut + 'static,
+        Fut: std::future::Future<Output = Result<Shared<T>, crate::Error>> + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        info!("Creating async root provider with Root scope (not thread-safe)");
+
+        crate::AsyncProvider {
+            scope: Scope::Root,
+            retry: None,
+            factory: Box::new(move |injector| Box::pin(factory(injector))),
+        }
+    }
+
+    /// Returns a provider that applies `f` to every value this provider creates (single-threaded).
+    ///
+    /// The new provider keeps the original [`Scope`]. For singleton and root scopes
+    /// the mapped value is what gets cached, so `f` runs once per cached instance
+    /// rather than on every resolution. This makes `map` a natural fit for
+    /// decorators such as logging or caching wrappers.
+    ///
+    /// # Type Parameters
+    ///
+    /// - `U`: The type produced by the mapping. Can be a trait object.
+    /// - `F`: Function that takes the inner `Shared<T>` and returns `Shared<U>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Repo;
+    /// struct LoggingRepo {
+    ///     inner: Shared<Repo>,
+    /// }
+    ///
+    /// let base = Provider::singleton(|_| Shared::new(Repo));
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<LoggingRepo>(base.map(|inner| Shared::new(LoggingRepo { inner })));
+    ///
+    /// let repo = injector.resolve::<LoggingRepo>();
+    /// let _inner: &Repo = &repo.inner;
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This is the single-threaded version. The mapping function does not need to be `Send + Sync`.
+    pub fn map<U, F>(self, f: F) -> Provider<U>
+    where
+        U: ?Sized + 'static,
+        F: Fn(Shared<T>) -> Shared<U> + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        debug!("Mapping provider output with {} scope", self.scope);
+
+        let factory = self.factory;
+        let f = Shared::new(f);
+
+        Provider::<U> {
+            scope: self.scope,
+            profiles: self.profiles,
+            gate: self.gate,
+            priority: self.priority,
+            size: None,
+            eager: self.eager,
+            dependencies: self.dependencies,
+            deadline: self.deadline,
+            // The hook and the marks take a `T`, which the mapped provider no longer produces.
+            after_init: None,
+            marks: Vec::new(),
+            // The winner of a fallback chain is mapped too.
+            fallbacks: self.fallbacks.map(|chain| {
+                let f = f.clone();
+                chain.then(move |value, _| (*f)(value))
+            }),
+            factory: Box::new(move |injector| Instance::new((*f)(factory(injector).value()))),
+        }
+    }
+
+    /// Makes this provider available only while `gate` returns `true`.
+    ///
+    /// The gate is evaluated with the resolving injector on every resolve of
+    /// `T`, including cache hits. While it returns `false`, resolving `T` fails
+    /// with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) as if
+    /// nothing were registered; an instance cached earlier is kept and served
+    /// again once the gate reopens. Registration is unaffected, so
+    /// [`Injector::contains`] still reports the type.
+    pub fn gated_by<F>(mut self, gate: F) -> Self
+    where
+        F: Fn(&Injector) -> bool + 'static,
+    {
+        self.gate = Some(Shared::new(gate));
+        self
+    }
+
+    /// Runs `hook` on every instance this provider creates, before it is returned.
+    ///
+    /// For a singleton or root provider the hook therefore runs exactly once,
+    /// right after the instance is first created and cached, which makes it the
+    /// place for follow-up work such as seeding a freshly opened database. The
+    /// hook may resolve other services, and resolving `T` itself from it yields
+    /// the cached instance. For a transient provider it runs on each new
+    /// instance. Keyed providers ignore it, and [`map`](Provider::map) drops it.
+    ///
+    /// If the hook returns an error, the instance is evicted from the cache
+    /// and the resolve fails with
+    /// [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed);
+    /// the next resolve creates a new instance and runs the hook again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    ///
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Database {
+    ///     rows: Mutex<Vec<String>>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Database>(
+    ///     Provider::root(|_| Shared::new(Database { rows: Mutex::new(Vec::new()) }))
+    ///         .after_init(|database: &Database, _| {
+    ///             database.rows.lock().unwrap().push("admin".to_string());
+    ///             Ok(())
+    ///         }),
+    /// );
+    ///
+    /// assert_eq!(*injector.resolve::<Database>().rows.lock().unwrap(), ["admin"]);
+    /// ```
+    pub fn after_init<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&T, &Injector) -> Result<(), String> + 'static,
+    {
+        self.after_init = Some(Shared::new(hook));
+        self
+    }
+
+    /// Also collects this provider's instance under the marker `M`, usually a trait object.
+    ///
+    /// [`Injector::resolve_marked`] returns the instances of every provider
+    /// marked with `M`, whatever type each is registered under, which suits
+    /// lifecycle orchestration such as starting every `dyn Startable`. `cast`
+    /// turns the resolved `Shared<T>` into a `Shared<M>`; for a trait object
+    /// that is just the unsizing coercion. The instance is resolved as `T`, so
+    /// a singleton is the same whether it is reached as `T` or through its
+    /// marker. A provider can carry several markers; [`map`](Provider::map)
+    /// drops them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Startable: Send + Sync {
+    ///     fn start(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Database;
+    /// impl Startable for Database {
+    ///     fn start(&self) -> &'static str {
+    ///         "database"
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Database>(
+    ///     Provider::root(|_| Shared::new(Database))
+    ///         .mark::<dyn Startable, _>(|database| database as Shared<dyn Startable>),
+    /// );
+    ///
+    /// let started: Vec<_> = injector
+    ///     .resolve_marked::<dyn Startable>()
+    ///     .iter()
+    ///     .map(|service| service.start())
+    ///     .collect();
+    /// assert_eq!(started, ["database"]);
+    /// ```
+    pub fn mark<M, F>(mut self, cast: F) -> Self
+    where
+        M: ?Sized + 'static,
+        F: Fn(Shared<T>) -> Shared<M> + 'static,
+    {
+        let resolve = move |injector: &Injector| {
+            let value = cast(injector.try_resolve::<T>()?);
+            Ok(Shared::new(value) as Shared<dyn Any>)
+        };
+        self.marks.push((TypeId::of::<M>(), Shared::new(resolve)));
+        self
+    }
 }
 
 #[cfg(feature = "thread-safe")]
@@ -364,6 +801,16 @@ impl<T: ?Sized + 'static> Provider<T> {
 
         Provider::<T> {
             scope: Scope::Module,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies: Vec::new(),
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: None,
             factory: Box::new(move |injector| {
                 #[cfg(feature = "tracing")]
                 debug!("Executing singleton factory for type instantiation");
@@ -427,6 +874,16 @@ impl<T: ?Sized + 'static> Provider<T> {
 
         Provider::<T> {
             scope: Scope::Transient,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies: Vec::new(),
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: None,
             factory: Box::new(move |injector| {
                 #[cfg(feature = "tracing")]
                 debug!("Executing transient factory - creating new instance");
@@ -490,6 +947,16 @@ impl<T: ?Sized + 'static> Provider<T> {
 
         Provider::<T> {
             scope: Scope::Root,
+            profiles: Profiles::default(),
+            gate: None,
+            priority: 0,
+            size: None,
+            eager: false,
+            dependencies: Vec::new(),
+            deadline: None,
+            after_init: None,
+            marks: Vec::new(),
+            fallbacks: None,
             factory: Box::new(move |injector| {
                 #[cfg(feature = "tracing")]
                 debug!("Executing root factory for type instantiation");
@@ -498,6 +965,254 @@ impl<T: ?Sized + 'static> Provider<T> {
             }),
         }
     }
+
+    /// Creates a root-scoped provider with an asynchronous, fallible factory (thread-safe).
+    ///
+    /// The factory receives a handle to the resolving injector and returns a
+    /// future of `Result<Shared<T>, Error>`. Register the provider with
+    /// [`Injector::provide_async`] and resolve it with
+    /// [`Injector::resolve_async`]; see [`AsyncProvider`](crate::AsyncProvider).
+    #[cfg(feature = "async")]
+    pub fn async_root<F, Fut>(factory: F) -> crate::AsyncProvider<T>
+    where
+        F: Fn(Injector) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<Shared<T>, crate::Error>> + Send + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        info!("Creating async root provider with Root scope (thread-safe)");
+
+        crate::AsyncProvider {
+            scope: Scope::Root,
+            retry: None,
+            factory: Box::new(move |injector| Box::pin(factory(injector))),
+        }
+    }
+
+    /// Returns a provider that applies `f` to every value this provider creates (thread-safe).
+    ///
+    /// The new provider keeps the original [`Scope`]. For singleton and root scopes
+    /// the mapped value is what gets cached, so `f` runs once per cached instance
+    /// rather than on every resolution. This makes `map` a natural fit for
+    /// decorators such as logging or caching wrappers.
+    ///
+    /// # Type Parameters
+    ///
+    /// - `U`: The type produced by the mapping. Can be a trait object.
+    /// - `F`: Function that takes the inner `Shared<T>` and returns `Shared<U>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Repo;
+    /// struct LoggingRepo {
+    ///     inner: Shared<Repo>,
+    /// }
+    ///
+    /// let base = Provider::singleton(|_| Shared::new(Repo));
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<LoggingRepo>(base.map(|inner| Shared::new(LoggingRepo { inner })));
+    ///
+    /// let repo = injector.resolve::<LoggingRepo>();
+    /// let _inner: &Repo = &repo.inner;
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The mapping function must be `Send + Sync`, like the factory it wraps.
+    pub fn map<U, F>(self, f: F) -> Provider<U>
+    where
+        U: ?Sized + 'static,
+        F: Fn(Shared<T>) -> Shared<U> + Send + Sync + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        debug!("Mapping provider output with {} scope", self.scope);
+
+        let factory = self.factory;
+        let f = Shared::new(f);
+
+        Provider::<U> {
+            scope: self.scope,
+            profiles: self.profiles,
+            gate: self.gate,
+            priority: self.priority,
+            size: None,
+            eager: self.eager,
+            dependencies: self.dependencies,
+            deadline: self.deadline,
+            // The hook and the marks take a `T`, which the mapped provider no longer produces.
+            after_init: None,
+            marks: Vec::new(),
+            // The winner of a fallback chain is mapped too.
+            fallbacks: self.fallbacks.map(|chain| {
+                let f = f.clone();
+                chain.then(move |value, _| (*f)(value))
+            }),
+            factory: Box::new(move |injector| Instance::new((*f)(factory(injector).value()))),
+        }
+    }
+
+    /// Makes this provider available only while `gate` returns `true`.
+    ///
+    /// The gate is evaluated with the resolving injector on every resolve of
+    /// `T`, including cache hits. While it returns `false`, resolving `T` fails
+    /// with [`ServiceNotProvided`](crate::ErrorKind::ServiceNotProvided) as if
+    /// nothing were registered; an instance cached earlier is kept and served
+    /// again once the gate reopens. Registration is unaffected, so
+    /// [`Injector::contains`] still reports the type.
+    pub fn gated_by<F>(mut self, gate: F) -> Self
+    where
+        F: Fn(&Injector) -> bool + Send + Sync + 'static,
+    {
+        self.gate = Some(Shared::new(gate));
+        self
+    }
+
+    /// Fails the resolve if the factory has not returned within `deadline`.
+    ///
+    /// The factory then runs on a thread of its own while the resolving
+    /// thread waits. If the deadline passes first, the resolve fails with
+    /// [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed)
+    /// and nothing is cached, so the next resolve runs the factory again. A
+    /// panic in the factory is propagated to the resolving thread.
+    ///
+    /// Rust cannot cancel a thread, so an abandoned factory keeps running in
+    /// the background until it returns, holding whatever it acquired; its
+    /// result is then dropped. Use this to bound how long callers wait on a
+    /// call that may hang, such as a blocking connect, not to reclaim it.
+    ///
+    /// The factory's thread continues the resolve in progress, so a circular
+    /// dependency through it still fails with
+    /// [`CircularDependency`](crate::ErrorKind::CircularDependency).
+    /// Keyed providers ignore the deadline. Requires the `thread-safe` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use sadi::{ErrorKind, Injector, Provider, Shared};
+    ///
+    /// struct Connection;
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Connection>(
+    ///     Provider::root(|_| {
+    ///         std::thread::sleep(Duration::from_millis(200));
+    ///         Shared::new(Connection)
+    ///     })
+    ///     .with_deadline(Duration::from_millis(10)),
+    /// );
+    ///
+    /// let Err(err) = injector.try_resolve::<Connection>() else {
+    ///     panic!("the factory should have timed out");
+    /// };
+    /// assert!(err.kind == ErrorKind::FactoryExecutionFailed);
+    /// ```
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Runs `hook` on every instance this provider creates, before it is returned.
+    ///
+    /// For a singleton or root provider the hook therefore runs exactly once,
+    /// right after the instance is first created and cached, which makes it the
+    /// place for follow-up work such as seeding a freshly opened database. The
+    /// hook may resolve other services, and resolving `T` itself from it yields
+    /// the cached instance. For a transient provider it runs on each new
+    /// instance. Keyed providers ignore it, and [`map`](Provider::map) drops it.
+    ///
+    /// If the hook returns an error, the instance is evicted from the cache
+    /// and the resolve fails with
+    /// [`FactoryExecutionFailed`](crate::ErrorKind::FactoryExecutionFailed);
+    /// the next resolve creates a new instance and runs the hook again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    ///
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// struct Database {
+    ///     rows: Mutex<Vec<String>>,
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Database>(
+    ///     Provider::root(|_| Shared::new(Database { rows: Mutex::new(Vec::new()) }))
+    ///         .after_init(|database: &Database, _| {
+    ///             database.rows.lock().unwrap().push("admin".to_string());
+    ///             Ok(())
+    ///         }),
+    /// );
+    ///
+    /// assert_eq!(*injector.resolve::<Database>().rows.lock().unwrap(), ["admin"]);
+    /// ```
+    pub fn after_init<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&T, &Injector) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.after_init = Some(Shared::new(hook));
+        self
+    }
+
+    /// Also collects this provider's instance under the marker `M`, usually a trait object.
+    ///
+    /// [`Injector::resolve_marked`] returns the instances of every provider
+    /// marked with `M`, whatever type each is registered under, which suits
+    /// lifecycle orchestration such as starting every `dyn Startable`. `cast`
+    /// turns the resolved `Shared<T>` into a `Shared<M>`; for a trait object
+    /// that is just the unsizing coercion. The instance is resolved as `T`, so
+    /// a singleton is the same whether it is reached as `T` or through its
+    /// marker. A provider can carry several markers; [`map`](Provider::map)
+    /// drops them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sadi::{Injector, Provider, Shared};
+    ///
+    /// trait Startable: Send + Sync {
+    ///     fn start(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Database;
+    /// impl Startable for Database {
+    ///     fn start(&self) -> &'static str {
+    ///         "database"
+    ///     }
+    /// }
+    ///
+    /// let injector = Injector::root();
+    /// injector.provide::<Database>(
+    ///     Provider::root(|_| Shared::new(Database))
+    ///         .mark::<dyn Startable, _>(|database| database as Shared<dyn Startable>),
+    /// );
+    ///
+    /// let started: Vec<_> = injector
+    ///     .resolve_marked::<dyn Startable>()
+    ///     .iter()
+    ///     .map(|service| service.start())
+    ///     .collect();
+    /// assert_eq!(started, ["database"]);
+    /// ```
+    pub fn mark<M, F>(mut self, cast: F) -> Self
+    where
+        T: Send + Sync,
+        M: ?Sized + Send + Sync + 'static,
+        F: Fn(Shared<T>) -> Shared<M> + Send + Sync + 'static,
+    {
+        let resolve = move |injector: &Injector| {
+            let value = cast(injector.try_resolve::<T>()?);
+            Ok(Shared::new(value) as Shared<dyn Any + Send + Sync>)
+        };
+        self.marks.push((TypeId::of::<M>(), Shared::new(resolve)));
+        self
+    }
 }
 
 #[cfg(test)]
@@ -983,4 +1698,54 @@ mod tests {
         // Each thread should get a unique ID
         assert_eq!(ids, vec![0, 1, 2, 3]);
     }
+
+    #[derive(Debug)]
+    struct DecoratedService {
+        inner: Shared<TestService>,
+        decoration: u32,
+    }
+
+    #[test]
+    fn test_map_preserves_scope() {
+        let provider = Provider::root(|_| {
+            Shared::new(TestService {
+                id: 1,
+                name: "base".to_string(),
+            })
+        })
+        .map(|inner| Shared::new(inner.id));
+
+        assert_eq!(provider.scope, Scope::Root);
+    }
+
+    #[test]
+    fn test_map_decorates_singleton_once() {
+        let counter = Shared::new(Counter::new());
+        let counter_clone = counter.clone();
+
+        let provider = Provider::singleton(|_| {
+            Shared::new(TestService {
+                id: 5,
+                name: "repo".to_string(),
+            })
+        })
+        .map(move |inner| {
+            Shared::new(DecoratedService {
+                inner,
+                decoration: counter_clone.increment(),
+            })
+        });
+
+        let injector = Injector::root();
+        injector.provide::<DecoratedService>(provider);
+
+        let first = injector.resolve::<DecoratedService>();
+        let second = injector.resolve::<DecoratedService>();
+
+        assert!(Shared::ptr_eq(&first, &second));
+        assert_eq!(first.inner.id, 5);
+        assert_eq!(first.decoration, 0);
+        // Only one decoration happened, so the next one is numbered 1.
+        assert_eq!(counter.increment(), 1);
+    }
 }
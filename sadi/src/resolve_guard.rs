@@ -29,6 +29,23 @@ impl ResolveGuard {
             Ok(Self { type_id })
         })
     }
+
+    /// Returns how many resolutions are currently in progress on this thread.
+    pub fn depth() -> usize {
+        RESOLVE_STACK.with(|stack| stack.borrow().len())
+    }
+
+    /// Returns the resolutions in progress on this thread, outermost first.
+    #[cfg(feature = "thread-safe")]
+    pub fn stack() -> Vec<TypeId> {
+        RESOLVE_STACK.with(|stack| stack.borrow().clone())
+    }
+
+    /// Continues `stack` on this thread, for a resolve handed over from another one.
+    #[cfg(feature = "thread-safe")]
+    pub fn inherit(stack: Vec<TypeId>) {
+        RESOLVE_STACK.with(|current| *current.borrow_mut() = stack);
+    }
 }
 
 impl Drop for ResolveGuard {
@@ -44,6 +44,36 @@ use std::{cell::RefCell, rc::Rc};
 /// let data = Shared::new(vec![1, 2, 3]);
 /// let clone = Shared::clone(&data);
 /// ```
+///
+/// # Canonical Form
+///
+/// Register and resolve trait objects by the trait itself, as `dyn Trait`; the
+/// injector stores and returns them as `Shared<dyn Trait>`. With `thread-safe`
+/// that is the same type as `Arc<dyn Trait>`, so a field declared as
+/// `Arc<dyn Trait>` takes the resolved value as is and shares the cached
+/// instance. Resolving `Arc<dyn Trait>` as the type parameter instead asks for
+/// a separate registration of the pointer type and is almost never intended.
+///
+/// ```
+/// # #[cfg(feature = "thread-safe")]
+/// # {
+/// use std::sync::Arc;
+/// use sadi::{Injector, Provider, Shared};
+///
+/// trait Repository: Send + Sync {}
+/// struct SqlRepository;
+/// impl Repository for SqlRepository {}
+///
+/// let injector = Injector::root();
+/// injector.provide::<dyn Repository>(Provider::root(|_| {
+///     Shared::new(SqlRepository) as Shared<dyn Repository>
+/// }));
+///
+/// let shared: Shared<dyn Repository> = injector.resolve::<dyn Repository>();
+/// let arc: Arc<dyn Repository> = injector.resolve::<dyn Repository>();
+/// assert!(Arc::ptr_eq(&shared, &arc));
+/// # }
+/// ```
 #[cfg(feature = "thread-safe")]
 pub type Shared<T> = Arc<T>;
 #[cfg(not(feature = "thread-safe"))]
@@ -81,10 +111,161 @@ pub type Store<T> = RwLock<T>;
 #[cfg(not(feature = "thread-safe"))]
 pub type Store<T> = RefCell<T>;
 
+/// A handle to part of a [`Shared`] value that keeps the whole value alive.
+///
+/// Created with [`SharedProjection::new`], it dereferences to a field (or any
+/// other data borrowed from the value) without exposing the rest of it, much
+/// like `Arc::map` in other crates. Cloning a projection does not touch the
+/// parent's strong count; the parent is held once for as long as any clone of
+/// the projection exists.
+///
+/// # Soundness
+///
+/// The projection stores the parent `Shared<T>` alongside a raw pointer into
+/// it. This is sound because:
+///
+/// - the value behind a `Shared` never moves while a strong reference exists,
+///   and the projection holds one until it is dropped;
+/// - the projecting closure must return a reference with the same lifetime as
+///   its argument, so it can only point into the parent or at `'static` data;
+/// - only shared access is ever handed out, matching what `Shared` allows.
+///
+/// With the `thread-safe` feature a projection is `Send` and `Sync` when the
+/// projected type is `Sync`, as sending it only ever shares `&U` across threads.
+///
+/// # Examples
+///
+/// ```
+/// use sadi::{Shared, SharedProjection};
+///
+/// struct Config {
+///     database_url: String,
+///     port: u16,
+/// }
+///
+/// let config = Shared::new(Config {
+///     database_url: "sqlite::memory:".to_string(),
+///     port: 8080,
+/// });
+///
+/// let url: SharedProjection<str> = SharedProjection::new(config.clone(), |c| c.database_url.as_str());
+/// drop(config);
+///
+/// assert_eq!(&*url, "sqlite::memory:");
+/// ```
+pub struct SharedProjection<U: ?Sized + 'static> {
+    _owner: Owner,
+    value: std::ptr::NonNull<U>,
+}
+
+#[cfg(not(feature = "thread-safe"))]
+type Owner = Shared<dyn std::any::Any>;
+
+#[cfg(feature = "thread-safe")]
+type Owner = Shared<dyn std::any::Any + Send + Sync>;
+
+#[cfg(not(feature = "thread-safe"))]
+impl<U: ?Sized + 'static> SharedProjection<U> {
+    /// Projects `shared` through `project`, keeping `shared` alive (single-threaded).
+    pub fn new<T, F>(shared: Shared<T>, project: F) -> Self
+    where
+        T: ?Sized + 'static,
+        F: for<'a> FnOnce(&'a T) -> &'a U,
+    {
+        let value = std::ptr::NonNull::from(project(&shared));
+
+        Self {
+            _owner: Shared::new(shared),
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl<U: ?Sized + 'static> SharedProjection<U> {
+    /// Projects `shared` through `project`, keeping `shared` alive (thread-safe).
+    pub fn new<T, F>(shared: Shared<T>, project: F) -> Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: for<'a> FnOnce(&'a T) -> &'a U,
+    {
+        let value = std::ptr::NonNull::from(project(&shared));
+
+        Self {
+            _owner: Shared::new(shared),
+            value,
+        }
+    }
+}
+
+impl<U: ?Sized + 'static> Clone for SharedProjection<U> {
+    fn clone(&self) -> Self {
+        Self {
+            _owner: self._owner.clone(),
+            value: self.value,
+        }
+    }
+}
+
+impl<U: ?Sized + 'static> std::ops::Deref for SharedProjection<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: `value` points into the allocation kept alive by `_owner`,
+        // which is never mutated through this handle.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<U: ?Sized + std::fmt::Debug + 'static> std::fmt::Debug for SharedProjection<U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, f)
+    }
+}
+
+// SAFETY: the owner is `Send + Sync` in thread-safe mode, and the projection
+// only hands out `&U`, which is safe to share or send when `U: Sync`.
+#[cfg(feature = "thread-safe")]
+unsafe impl<U: ?Sized + Sync + 'static> Send for SharedProjection<U> {}
+
+#[cfg(feature = "thread-safe")]
+unsafe impl<U: ?Sized + Sync + 'static> Sync for SharedProjection<U> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "thread-safe")]
+    #[test]
+    fn test_shared_and_arc_resolve_the_same_instance() {
+        use crate::injector::Injector;
+        use crate::provider::Provider;
+
+        trait Repository: Send + Sync {}
+        struct SqlRepository;
+        impl Repository for SqlRepository {}
+
+        struct UseCase {
+            repository: Arc<dyn Repository>,
+        }
+
+        let injector = Injector::root();
+        injector.provide::<dyn Repository>(Provider::singleton(|_| {
+            Shared::new(SqlRepository) as Shared<dyn Repository>
+        }));
+        injector.provide::<UseCase>(Provider::transient(|injector| {
+            Shared::new(UseCase {
+                repository: injector.resolve::<dyn Repository>(),
+            })
+        }));
+
+        let shared: Shared<dyn Repository> = injector.resolve::<dyn Repository>();
+        let use_case = injector.resolve::<UseCase>();
+
+        assert!(Arc::ptr_eq(&shared, &use_case.repository));
+    }
+
     #[test]
     fn test_shared_can_be_cloned() {
         let data = Shared::new(100);
@@ -182,6 +363,48 @@ mod tests {
         }
     }
 
+    struct Config {
+        database_url: String,
+    }
+
+    #[test]
+    fn test_projection_keeps_parent_alive() {
+        let config = Shared::new(Config {
+            database_url: "sqlite::memory:".to_string(),
+        });
+
+        let url = SharedProjection::new(config.clone(), |c| c.database_url.as_str());
+        let url_clone = url.clone();
+
+        #[cfg(feature = "thread-safe")]
+        assert_eq!(Arc::strong_count(&config), 2);
+
+        #[cfg(not(feature = "thread-safe"))]
+        assert_eq!(Rc::strong_count(&config), 2);
+
+        drop(url);
+        assert_eq!(&*url_clone, "sqlite::memory:");
+
+        drop(url_clone);
+
+        #[cfg(feature = "thread-safe")]
+        assert_eq!(Arc::strong_count(&config), 1);
+
+        #[cfg(not(feature = "thread-safe"))]
+        assert_eq!(Rc::strong_count(&config), 1);
+    }
+
+    #[test]
+    fn test_projection_outlives_original_handle() {
+        let config = Shared::new(Config {
+            database_url: "postgres://localhost".to_string(),
+        });
+
+        let url = SharedProjection::new(config, |c| &c.database_url);
+
+        assert_eq!(url.len(), "postgres://localhost".len());
+    }
+
     #[test]
     fn test_multiple_shared_references() {
         let data = Shared::new(42);
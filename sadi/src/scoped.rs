@@ -0,0 +1,125 @@
+//! Handles that cannot outlive the injector they were resolved from.
+//!
+//! A request-scoped child injector holds instances that only make sense for
+//! the duration of one request. A plain [`Shared`] handle can be stored
+//! anywhere, so nothing stops a long-lived service from keeping one after the
+//! child injector is dropped: the classic captive-dependency bug.
+//!
+//! [`Injector::resolve_scoped`](crate::Injector::resolve_scoped) returns a
+//! [`ScopedShared<'a, T>`](ScopedShared) instead, whose lifetime is tied to a
+//! borrow of the resolving injector. Keeping it past the injector's drop, or
+//! moving it into something that must be `'static`, is a compile error.
+//!
+//! # Trade-offs
+//!
+//! The borrow costs some ergonomics compared with [`Shared`], and it only
+//! protects the handle it is attached to:
+//!
+//! - A `ScopedShared` cannot be moved into a spawned thread or task, nor stored
+//!   in a struct without carrying the lifetime along.
+//! - While one is alive, the injector cannot be moved or dropped, although it
+//!   can still be used through `&self`.
+//! - It guards the handle returned here, not the ones a factory receives from
+//!   [`resolve`](crate::Injector::resolve). A root singleton whose factory
+//!   captures a scoped dependency is not caught.
+//! - Calling [`resolve`](crate::Injector::resolve) directly still hands out an
+//!   unrestricted [`Shared`], which is the escape hatch when the handle really
+//!   must outlive the scope.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! struct RequestContext {
+//!     request_id: u64,
+//! }
+//!
+//! let root = Shared::new(Injector::root());
+//! let scope = Injector::child(root);
+//! scope.provide::<RequestContext>(Provider::singleton(|_| {
+//!     Shared::new(RequestContext { request_id: 7 })
+//! }));
+//!
+//! let context = scope.resolve_scoped::<RequestContext>();
+//! assert_eq!(context.request_id, 7);
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::injector::Injector;
+use crate::runtime::Shared;
+
+/// A [`Shared`] handle borrowed from the injector that resolved it.
+///
+/// Returned by [`Injector::resolve_scoped`](crate::Injector::resolve_scoped);
+/// see the [module docs](self).
+pub struct ScopedShared<'a, T: ?Sized> {
+    value: Shared<T>,
+    _scope: PhantomData<&'a Injector>,
+}
+
+impl<'a, T: ?Sized> ScopedShared<'a, T> {
+    pub(crate) fn new(value: Shared<T>, _scope: &'a Injector) -> Self {
+        Self {
+            value,
+            _scope: PhantomData,
+        }
+    }
+
+    /// Returns `true` if both handles point to the same instance.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Shared::ptr_eq(&this.value, &other.value)
+    }
+}
+
+impl<T: ?Sized> Clone for ScopedShared<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            _scope: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for ScopedShared<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for ScopedShared<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&*self.value, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Provider;
+
+    struct RequestContext {
+        request_id: u64,
+    }
+
+    #[test]
+    fn test_scoped_handles_share_the_instance_within_the_scope() {
+        let root = Shared::new(Injector::root());
+        let scope = Injector::child(root);
+        scope.provide::<RequestContext>(Provider::singleton(|_| {
+            Shared::new(RequestContext { request_id: 42 })
+        }));
+
+        let first = scope.resolve_scoped::<RequestContext>();
+        let second = scope.resolve_scoped::<RequestContext>();
+
+        assert_eq!(first.request_id, 42);
+        assert!(ScopedShared::ptr_eq(&first, &second.clone()));
+        assert!(scope.try_resolve_scoped::<u8>().is_err());
+    }
+}
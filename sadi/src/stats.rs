@@ -0,0 +1,43 @@
+//! Memory accounting for injectors.
+//!
+//! [`InjectorStats`] is a snapshot of what a single injector holds: how many
+//! providers it registers per [`Scope`](crate::Scope), how many instances it
+//! keeps cached, and roughly how many bytes those instances occupy. A host
+//! managing many containers can poll it to spot one that keeps growing.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! let injector = Injector::root();
+//! injector.provide::<u64>(Provider::root(|_| Shared::new(7)).track_size());
+//! injector.provide::<String>(Provider::transient(|_| Shared::new(String::new())));
+//! injector.resolve::<u64>();
+//!
+//! let stats = injector.stats();
+//! assert_eq!((stats.root, stats.transient), (1, 1));
+//! assert_eq!(stats.cached_singletons, 1);
+//! assert_eq!(stats.retained_bytes, 8);
+//! ```
+
+/// A snapshot of one injector's registrations and cache.
+///
+/// Counts cover only the injector the stats were taken from, not its
+/// ancestors, so the numbers of several injectors can be summed.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct InjectorStats {
+    /// Providers registered with [`Scope::Root`](crate::Scope::Root).
+    pub root: usize,
+    /// Providers registered with [`Scope::Module`](crate::Scope::Module).
+    pub module: usize,
+    /// Providers registered with [`Scope::Transient`](crate::Scope::Transient).
+    pub transient: usize,
+    /// Instances currently held in the cache.
+    pub cached_singletons: usize,
+    /// The summed inline size of cached instances whose provider opted in with
+    /// [`Provider::track_size`](crate::Provider::track_size). Instances of other
+    /// providers count as zero, so this is a lower bound.
+    pub retained_bytes: usize,
+}
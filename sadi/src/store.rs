@@ -0,0 +1,320 @@
+//! Pluggable storage for cached singletons.
+//!
+//! Every injector caches the root- and module-scoped instances it creates in a
+//! [`SingletonStore`]. The default, [`HashMapStore`], keeps them in an
+//! in-process `HashMap`. Hosts with special requirements, such as keeping
+//! singletons in a shared-memory arena or instrumenting the cache, can supply
+//! their own with [`Injector::with_store`](crate::Injector::with_store).
+//!
+//! The injector serializes access to its store behind its own lock, so an
+//! implementation needs no interior mutability. It must hand back exactly the
+//! [`CachedInstance`] it was given: the injector downcasts it to its own
+//! wrapper around the resolved value.
+//!
+//! With the `tracing` feature, the injector logs a `debug` event, tagged with
+//! the service's type name and an [`EvictionReason`], whenever it drops a
+//! cached instance. A store that evicts entries on its own, for example to stay
+//! within a capacity, can report them the same way with `trace_eviction`.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::any::TypeId;
+//!
+//! use sadi::{CachedInstance, HashMapStore, Injector, Provider, Shared, SingletonStore};
+//!
+//! #[derive(Default)]
+//! struct CountingStore {
+//!     inner: HashMapStore,
+//!     inserts: usize,
+//! }
+//!
+//! impl SingletonStore for CountingStore {
+//!     fn get(&self, type_id: TypeId) -> Option<&CachedInstance> {
+//!         self.inner.get(type_id)
+//!     }
+//!
+//!     fn insert(&mut self, type_id: TypeId, instance: CachedInstance) {
+//!         self.inserts += 1;
+//!         self.inner.insert(type_id, instance);
+//!     }
+//!
+//!     fn remove(&mut self, type_id: TypeId) -> Option<CachedInstance> {
+//!         self.inner.remove(type_id)
+//!     }
+//!
+//!     fn type_ids(&self) -> Vec<TypeId> {
+//!         self.inner.type_ids()
+//!     }
+//! }
+//!
+//! let injector = Injector::root().with_store(CountingStore::default());
+//! injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+//!
+//! assert_eq!(*injector.resolve::<u32>(), 7);
+//! assert_eq!(injector.stats().cached_singletons, 1);
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::runtime::Shared;
+
+/// A cached instance as the injector passes it to a [`SingletonStore`].
+#[cfg(not(feature = "thread-safe"))]
+pub type CachedInstance = Shared<dyn Any>;
+
+#[cfg(feature = "thread-safe")]
+pub type CachedInstance = Shared<dyn Any + Send + Sync>;
+
+/// Why a cached singleton was dropped from an injector's cache.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[non_exhaustive]
+pub enum EvictionReason {
+    /// Removed on request, by [`clear_singleton`](crate::Injector::clear_singleton)
+    /// or [`drop_tenant`](crate::Injector::drop_tenant), or because its
+    /// [`after_init`](crate::Provider::after_init) hook rejected it.
+    Manual,
+    /// Evicted by a [`SingletonStore`] to stay within its capacity.
+    ///
+    /// The injector never evicts for capacity itself; only stores that report
+    /// it through `trace_eviction` produce this reason.
+    LruCapacity,
+    /// Dropped by [`shutdown`](crate::Injector::shutdown).
+    Shutdown,
+    /// The provider that built it was overridden, outranked or removed.
+    Replace,
+}
+
+impl EvictionReason {
+    /// Returns the reason as it appears in eviction events.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::LruCapacity => "lru_capacity",
+            Self::Shutdown => "shutdown",
+            Self::Replace => "replace",
+        }
+    }
+}
+
+/// Logs a `debug` event recording that the cached instance of `service` was evicted.
+///
+/// The injector calls this for every eviction it makes; custom stores may
+/// call it for their own. Requires the `tracing` feature.
+#[cfg(feature = "tracing")]
+pub fn trace_eviction(service: &str, reason: EvictionReason) {
+    tracing::debug!(
+        service,
+        reason = reason.as_str(),
+        "evicted cached singleton"
+    );
+}
+
+/// The cache an injector keeps its singletons in.
+///
+/// See the [module docs](self) for what an implementation must uphold.
+#[cfg(not(feature = "thread-safe"))]
+pub trait SingletonStore {
+    /// Returns the instance cached for `type_id`.
+    ///
+    /// It is borrowed, so a cache hit costs the injector a single clone.
+    fn get(&self, type_id: TypeId) -> Option<&CachedInstance>;
+
+    /// Caches `instance` for `type_id`, replacing any previous one.
+    fn insert(&mut self, type_id: TypeId, instance: CachedInstance);
+
+    /// Removes and returns the instance cached for `type_id`.
+    fn remove(&mut self, type_id: TypeId) -> Option<CachedInstance>;
+
+    /// Returns the type ids of every cached instance, in any order.
+    fn type_ids(&self) -> Vec<TypeId>;
+
+    /// Returns the number of cached instances.
+    fn len(&self) -> usize {
+        self.type_ids().len()
+    }
+
+    /// Returns `true` if nothing is cached.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every cached instance, returning them so the caller decides when they drop.
+    fn drain(&mut self) -> Vec<CachedInstance> {
+        let type_ids = self.type_ids();
+        type_ids
+            .into_iter()
+            .filter_map(|type_id| self.remove(type_id))
+            .collect()
+    }
+}
+
+/// The cache an injector keeps its singletons in.
+///
+/// See the [module docs](self) for what an implementation must uphold.
+#[cfg(feature = "thread-safe")]
+pub trait SingletonStore: Send + Sync {
+    /// Returns the instance cached for `type_id`.
+    ///
+    /// It is borrowed, so a cache hit costs the injector a single clone.
+    fn get(&self, type_id: TypeId) -> Option<&CachedInstance>;
+
+    /// Caches `instance` for `type_id`, replacing any previous one.
+    fn insert(&mut self, type_id: TypeId, instance: CachedInstance);
+
+    /// Removes and returns the instance cached for `type_id`.
+    fn remove(&mut self, type_id: TypeId) -> Option<CachedInstance>;
+
+    /// Returns the type ids of every cached instance, in any order.
+    fn type_ids(&self) -> Vec<TypeId>;
+
+    /// Returns the number of cached instances.
+    fn len(&self) -> usize {
+        self.type_ids().len()
+    }
+
+    /// Returns `true` if nothing is cached.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every cached instance, returning them so the caller decides when they drop.
+    fn drain(&mut self) -> Vec<CachedInstance> {
+        let type_ids = self.type_ids();
+        type_ids
+            .into_iter()
+            .filter_map(|type_id| self.remove(type_id))
+            .collect()
+    }
+}
+
+/// The default [`SingletonStore`], backed by a `HashMap`.
+#[derive(Default)]
+pub struct HashMapStore {
+    instances: HashMap<TypeId, CachedInstance>,
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for HashMapStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashMapStore")
+            .field("instances", &self.instances.len())
+            .finish()
+    }
+}
+
+impl SingletonStore for HashMapStore {
+    fn get(&self, type_id: TypeId) -> Option<&CachedInstance> {
+        self.instances.get(&type_id)
+    }
+
+    fn insert(&mut self, type_id: TypeId, instance: CachedInstance) {
+        self.instances.insert(type_id, instance);
+    }
+
+    fn remove(&mut self, type_id: TypeId) -> Option<CachedInstance> {
+        self.instances.remove(&type_id)
+    }
+
+    fn type_ids(&self) -> Vec<TypeId> {
+        self.instances.keys().copied().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    fn drain(&mut self) -> Vec<CachedInstance> {
+        self.instances
+            .drain()
+            .map(|(_, instance)| instance)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{Injector, Provider};
+
+    /// Wraps the default store, counting inserts into a counter the test keeps.
+    struct CountingStore {
+        inner: HashMapStore,
+        inserts: Shared<AtomicUsize>,
+    }
+
+    impl SingletonStore for CountingStore {
+        fn get(&self, type_id: TypeId) -> Option<&CachedInstance> {
+            self.inner.get(type_id)
+        }
+
+        fn insert(&mut self, type_id: TypeId, instance: CachedInstance) {
+            self.inserts.fetch_add(1, Ordering::SeqCst);
+            self.inner.insert(type_id, instance);
+        }
+
+        fn remove(&mut self, type_id: TypeId) -> Option<CachedInstance> {
+            self.inner.remove(type_id)
+        }
+
+        fn type_ids(&self) -> Vec<TypeId> {
+            self.inner.type_ids()
+        }
+    }
+
+    /// Resolves a root singleton and a transient twice each, then clears the singleton.
+    fn exercise(injector: &Injector) -> (bool, bool, bool, usize) {
+        injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+        injector.provide::<String>(Provider::transient(|_| Shared::new("fresh".to_string())));
+
+        let singleton = Shared::ptr_eq(&injector.resolve::<u32>(), &injector.resolve::<u32>());
+        let transient =
+            Shared::ptr_eq(&injector.resolve::<String>(), &injector.resolve::<String>());
+        let cleared = injector.clear_singleton::<u32>();
+
+        (
+            singleton,
+            transient,
+            cleared,
+            injector.stats().cached_singletons,
+        )
+    }
+
+    #[test]
+    fn test_custom_store_caches_like_the_default() {
+        let inserts = Shared::new(AtomicUsize::new(0));
+        let custom = Injector::root().with_store(CountingStore {
+            inner: HashMapStore::default(),
+            inserts: inserts.clone(),
+        });
+
+        let expected = exercise(&Injector::root());
+        assert_eq!(expected, (true, false, true, 0));
+        assert_eq!(exercise(&custom), expected);
+        assert_eq!(inserts.load(Ordering::SeqCst), 1);
+
+        custom.resolve::<u32>();
+        assert_eq!(inserts.load(Ordering::SeqCst), 2);
+        assert_eq!(custom.stats().cached_singletons, 1);
+    }
+
+    #[test]
+    fn test_with_store_carries_over_cached_instances() {
+        let injector = Injector::root();
+        injector.provide::<u32>(Provider::root(|_| Shared::new(7)));
+        let cached = injector.resolve::<u32>();
+
+        let inserts = Shared::new(AtomicUsize::new(0));
+        let injector = injector.with_store(CountingStore {
+            inner: HashMapStore::default(),
+            inserts: inserts.clone(),
+        });
+
+        assert!(Shared::ptr_eq(&cached, &injector.resolve::<u32>()));
+        assert_eq!(inserts.load(Ordering::SeqCst), 1);
+    }
+}
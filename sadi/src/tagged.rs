@@ -0,0 +1,74 @@
+//! Type-level tags for registering several providers of the same type.
+//!
+//! Two registrations of `Pool` would share a [`TypeId`](std::any::TypeId) and
+//! conflict. Wrapping them as [`Tagged<Pool, Primary>`] and
+//! [`Tagged<Pool, Replica>`] gives each its own key, with zero-sized marker types
+//! in place of string names. [`Injector::provide_tagged`](crate::Injector::provide_tagged)
+//! and [`Injector::resolve_tagged`](crate::Injector::resolve_tagged) do the
+//! wrapping and unwrapping.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! struct Pool {
+//!     url: &'static str,
+//! }
+//!
+//! struct Primary;
+//! struct Replica;
+//!
+//! let injector = Injector::root();
+//! injector.provide_tagged::<Pool, Primary>(Provider::root(|_| Shared::new(Pool { url: "db-1" })));
+//! injector.provide_tagged::<Pool, Replica>(Provider::root(|_| Shared::new(Pool { url: "db-2" })));
+//!
+//! assert_eq!(injector.resolve_tagged::<Pool, Primary>().url, "db-1");
+//! assert_eq!(injector.resolve_tagged::<Pool, Replica>().url, "db-2");
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::runtime::Shared;
+
+/// A value of type `T` registered under the marker type `Tag`.
+///
+/// `Tag` is never instantiated; it only distinguishes registrations.
+pub struct Tagged<T: ?Sized + 'static, Tag: 'static> {
+    value: Shared<T>,
+    _tag: PhantomData<fn() -> Tag>,
+}
+
+impl<T: ?Sized + 'static, Tag: 'static> Tagged<T, Tag> {
+    /// Wraps `value` under `Tag`.
+    pub fn new(value: Shared<T>) -> Self {
+        Self {
+            value,
+            _tag: PhantomData,
+        }
+    }
+
+    /// Returns a clone of the shared reference to the wrapped value.
+    pub fn value(&self) -> Shared<T> {
+        self.value.clone()
+    }
+}
+
+impl<T: ?Sized + 'static, Tag: 'static> Deref for Tagged<T, Tag> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T: ?Sized + std::fmt::Debug + 'static, Tag: 'static> std::fmt::Debug for Tagged<T, Tag> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tagged")
+            .field("tag", &std::any::type_name::<Tag>())
+            .field("value", &self.value)
+            .finish()
+    }
+}
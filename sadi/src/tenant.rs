@@ -0,0 +1,156 @@
+//! Singletons cached once per tenant.
+//!
+//! A multi-tenant service often needs a singleton per tenant rather than per
+//! application, such as a connection pool for each tenant's database.
+//! [`Injector::tenant_scope`](crate::Injector::tenant_scope) returns a
+//! [`TenantScope`], an injector below the root that caches
+//! [`singleton`](crate::Provider::singleton) instances for one tenant. Every
+//! scope returned for the same tenant id shares that cache, so the instances
+//! live as long as the tenant rather than for one request. Root-scoped
+//! providers are still cached on the root and shared by every tenant, and
+//! transient ones are created anew as usual.
+//!
+//! [`Injector::drop_tenant`](crate::Injector::drop_tenant) evicts a tenant's
+//! cache; the next scope for that tenant starts from scratch.
+//!
+//! Resolve per-tenant singletons through tenant scopes only: like any child
+//! injector, a scope falls back to an instance already cached on the root, so
+//! one created by resolving on the root itself is shared by every tenant.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! struct Config;
+//! struct DbPool(Shared<Config>);
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Config>(Provider::root(|_| Shared::new(Config)));
+//! injector.provide::<DbPool>(Provider::singleton(|inj| Shared::new(DbPool(inj.resolve::<Config>()))));
+//!
+//! let acme = injector.tenant_scope("acme").resolve::<DbPool>();
+//! let globex = injector.tenant_scope("globex").resolve::<DbPool>();
+//!
+//! assert!(Shared::ptr_eq(&acme, &injector.tenant_scope("acme").resolve::<DbPool>()));
+//! assert!(!Shared::ptr_eq(&acme, &globex));
+//! assert!(Shared::ptr_eq(&acme.0, &globex.0));
+//! ```
+
+use std::ops::Deref;
+
+use crate::injector::Injector;
+
+/// An injector whose singletons belong to one tenant.
+///
+/// Returned by [`Injector::tenant_scope`](crate::Injector::tenant_scope); see
+/// the [module docs](self). It dereferences to the underlying [`Injector`].
+pub struct TenantScope {
+    tenant: String,
+    injector: Injector,
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for TenantScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantScope")
+            .field("tenant", &self.tenant)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TenantScope {
+    pub(crate) fn new(tenant: String, injector: Injector) -> Self {
+        Self { tenant, injector }
+    }
+
+    /// The tenant id this scope was created for.
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    /// Returns the injector serving this tenant.
+    pub fn injector(&self) -> &Injector {
+        &self.injector
+    }
+}
+
+impl Deref for TenantScope {
+    type Target = Injector;
+
+    fn deref(&self) -> &Injector {
+        &self.injector
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Provider, Shared};
+
+    use super::*;
+
+    struct Config;
+    struct DbPool;
+
+    fn tenant_injector() -> Injector {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::root(|_| Shared::new(Config)));
+        injector.provide::<DbPool>(Provider::singleton(|_| Shared::new(DbPool)));
+        injector
+    }
+
+    #[test]
+    fn test_tenants_get_their_own_singletons_and_share_root_services() {
+        let injector = tenant_injector();
+
+        let acme = injector.tenant_scope("acme");
+        let globex = injector.tenant_scope("globex");
+        assert_eq!((acme.tenant(), globex.tenant()), ("acme", "globex"));
+
+        let pool = acme.resolve::<DbPool>();
+        assert!(!Shared::ptr_eq(&pool, &globex.resolve::<DbPool>()));
+        assert!(Shared::ptr_eq(
+            &pool,
+            &injector.tenant_scope("acme").resolve::<DbPool>()
+        ));
+        assert!(Shared::ptr_eq(
+            &acme.resolve::<Config>(),
+            &globex.resolve::<Config>()
+        ));
+        assert!(Shared::ptr_eq(
+            &acme.resolve::<Config>(),
+            &injector.resolve::<Config>()
+        ));
+    }
+
+    #[test]
+    fn test_drop_tenant_evicts_only_that_tenant() {
+        let injector = tenant_injector();
+        let acme = injector.tenant_scope("acme").resolve::<DbPool>();
+        let globex = injector.tenant_scope("globex").resolve::<DbPool>();
+
+        assert!(injector.drop_tenant("acme"));
+        assert!(!injector.drop_tenant("acme"));
+
+        assert!(!Shared::ptr_eq(
+            &acme,
+            &injector.tenant_scope("acme").resolve::<DbPool>()
+        ));
+        assert!(Shared::ptr_eq(
+            &globex,
+            &injector.tenant_scope("globex").resolve::<DbPool>()
+        ));
+    }
+
+    #[test]
+    fn test_shutdown_drops_tenant_singletons() {
+        let injector = tenant_injector();
+        let acme = injector.tenant_scope("acme");
+        let pool = Shared::downgrade(&acme.resolve::<DbPool>());
+
+        injector.shutdown();
+
+        assert!(pool.upgrade().is_none());
+        assert!(acme.try_resolve::<DbPool>().is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Test helpers, enabled with the `test-util` feature.
+//!
+//! [`TestInjector`] wires a minimal root injector in a single expression, so a
+//! unit test can hand a use case its fakes without writing providers by hand.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::Shared;
+//! use sadi::test::TestInjector;
+//!
+//! trait Clock: Send + Sync {
+//!     fn now(&self) -> u64;
+//! }
+//!
+//! struct FixedClock(u64);
+//! impl Clock for FixedClock {
+//!     fn now(&self) -> u64 {
+//!         self.0
+//!     }
+//! }
+//!
+//! let injector = TestInjector::new()
+//!     .with_singleton(String::from("test"))
+//!     .with_fake::<dyn Clock>(Shared::new(FixedClock(42)));
+//!
+//! assert_eq!(injector.resolve_or_panic::<dyn Clock>().now(), 42);
+//! assert_eq!(*injector.resolve_or_panic::<String>(), "test");
+//! ```
+
+use crate::injector::Injector;
+use crate::provider::Provider;
+use crate::runtime::Shared;
+
+/// A builder for injectors used in tests.
+///
+/// Every registration is a root provider, and each `with_*` method panics if
+/// the type is already registered.
+pub struct TestInjector {
+    injector: Injector,
+}
+
+impl Default for TestInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestInjector {
+    /// Starts from an empty root injector.
+    pub fn new() -> Self {
+        Self {
+            injector: Injector::root(),
+        }
+    }
+
+    /// Returns the injector built so far.
+    pub fn injector(&self) -> &Injector {
+        &self.injector
+    }
+
+    /// Finishes the builder, returning the injector.
+    pub fn build(self) -> Injector {
+        self.injector
+    }
+}
+
+#[cfg(not(feature = "thread-safe"))]
+impl TestInjector {
+    /// Registers `value` as the single instance of `T`.
+    pub fn with_singleton<T: 'static>(self, value: T) -> Self {
+        let value = Shared::new(value);
+        self.injector
+            .provide::<T>(Provider::root(move |_| value.clone()));
+        self
+    }
+
+    /// Registers `factory` as a transient provider for `T`.
+    pub fn with_factory<T, F>(self, factory: F) -> Self
+    where
+        T: ?Sized + 'static,
+        F: Fn(&Injector) -> Shared<T> + 'static,
+    {
+        self.injector.provide::<T>(Provider::transient(factory));
+        self
+    }
+
+    /// Registers `fake` as the implementation of `T`, typically a trait object.
+    pub fn with_fake<T: ?Sized + 'static>(self, fake: Shared<T>) -> Self {
+        self.injector
+            .provide::<T>(Provider::root(move |_| fake.clone()));
+        self
+    }
+
+    /// Resolves `T`, panicking with the type and the resolution error on failure.
+    pub fn resolve_or_panic<T: ?Sized + 'static>(&self) -> Shared<T> {
+        match self.injector.try_resolve::<T>() {
+            Ok(value) => value,
+            Err(err) => panic!("failed to resolve {}: {}", std::any::type_name::<T>(), err),
+        }
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+impl TestInjector {
+    /// Registers `value` as the single instance of `T`.
+    pub fn with_singleton<T: Send + Sync + 'static>(self, value: T) -> Self {
+        let value = Shared::new(value);
+        self.injector
+            .provide::<T>(Provider::root(move |_| value.clone()));
+        self
+    }
+
+    /// Registers `factory` as a transient provider for `T`.
+    pub fn with_factory<T, F>(self, factory: F) -> Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(&Injector) -> Shared<T> + Send + Sync + 'static,
+    {
+        self.injector.provide::<T>(Provider::transient(factory));
+        self
+    }
+
+    /// Registers `fake` as the implementation of `T`, typically a trait object.
+    pub fn with_fake<T: ?Sized + Send + Sync + 'static>(self, fake: Shared<T>) -> Self {
+        self.injector
+            .provide::<T>(Provider::root(move |_| fake.clone()));
+        self
+    }
+
+    /// Resolves `T`, panicking with the type and the resolution error on failure.
+    pub fn resolve_or_panic<T: ?Sized + Send + Sync + 'static>(&self) -> Shared<T> {
+        match self.injector.try_resolve::<T>() {
+            Ok(value) => value,
+            Err(err) => panic!("failed to resolve {}: {}", std::any::type_name::<T>(), err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    struct FakeGreeter;
+    impl Greeter for FakeGreeter {
+        fn greet(&self) -> String {
+            "hello from fake".to_string()
+        }
+    }
+
+    struct Counter(usize);
+
+    #[test]
+    fn test_with_singleton_returns_same_instance() {
+        let injector = TestInjector::new().with_singleton(Counter(3));
+
+        let first = injector.resolve_or_panic::<Counter>();
+        let second = injector.resolve_or_panic::<Counter>();
+
+        assert_eq!(first.0, 3);
+        assert!(Shared::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_with_factory_runs_per_resolve() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let injector = TestInjector::new()
+            .with_factory(|_| Shared::new(Counter(CALLS.fetch_add(1, Ordering::SeqCst))));
+
+        injector.resolve_or_panic::<Counter>();
+        injector.resolve_or_panic::<Counter>();
+
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_with_fake_registers_trait_object() {
+        let injector = TestInjector::new()
+            .with_fake::<dyn Greeter>(Shared::new(FakeGreeter))
+            .build();
+
+        assert_eq!(injector.resolve::<dyn Greeter>().greet(), "hello from fake");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to resolve")]
+    fn test_resolve_or_panic_names_missing_type() {
+        TestInjector::new().resolve_or_panic::<Counter>();
+    }
+}
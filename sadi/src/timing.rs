@@ -0,0 +1,80 @@
+//! Built-in factory timing, enabled with the `timing` feature.
+//!
+//! Every time a provider's factory runs, the elapsed time is added to a
+//! per-type [`ResolveTiming`] kept by the root injector. Cache hits do not run
+//! a factory and are not counted. Read the totals with
+//! [`Injector::timing_report`](crate::Injector::timing_report).
+//!
+//! Timings are inclusive: a factory that resolves its own dependencies also
+//! pays for creating them the first time.
+
+use std::time::Duration;
+
+/// Aggregated factory execution time for one type.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ResolveTiming {
+    /// How many times the factory ran.
+    pub count: usize,
+    /// Total time spent in the factory.
+    pub total: Duration,
+    /// Longest single run of the factory.
+    pub max: Duration,
+}
+
+impl ResolveTiming {
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::injector::Injector;
+    use crate::provider::Provider;
+    use crate::runtime::Shared;
+
+    struct Slow;
+
+    #[test]
+    fn test_timing_report_counts_factory_runs() {
+        let injector = Injector::root();
+        injector.provide::<Slow>(Provider::transient(|_| {
+            std::thread::sleep(Duration::from_millis(1));
+            Shared::new(Slow)
+        }));
+        injector.provide::<u8>(Provider::singleton(|_| Shared::new(1)));
+
+        for _ in 0..3 {
+            injector.resolve::<Slow>();
+            injector.resolve::<u8>();
+        }
+
+        let report = injector.timing_report();
+        let (_, slow) = report
+            .iter()
+            .find(|(name, _)| name.ends_with("Slow"))
+            .unwrap();
+        let (_, cached) = report.iter().find(|(name, _)| *name == "u8").unwrap();
+
+        assert_eq!(slow.count, 3);
+        assert!(slow.total >= Duration::from_millis(3));
+        assert!(slow.max <= slow.total);
+        assert_eq!(cached.count, 1);
+    }
+
+    #[test]
+    fn test_record_tracks_max() {
+        let mut timing = ResolveTiming::default();
+        timing.record(Duration::from_millis(2));
+        timing.record(Duration::from_millis(5));
+        timing.record(Duration::from_millis(1));
+
+        assert_eq!(timing.count, 3);
+        assert_eq!(timing.total, Duration::from_millis(8));
+        assert_eq!(timing.max, Duration::from_millis(5));
+    }
+}
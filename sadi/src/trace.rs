@@ -0,0 +1,141 @@
+//! Ordered resolution recording for golden tests.
+//!
+//! [`Injector::record_resolutions`](crate::Injector::record_resolutions) returns
+//! a [`ResolutionTrace`] that lists, in order, every type resolved through that
+//! injector from then on, including the nested resolves made by factories it
+//! runs. Comparing the trace with an expected sequence pins down exactly which
+//! services a handler touches: a new dependency, or one that disappears, fails
+//! the test.
+//!
+//! Cache hits are recorded too, since they are resolves all the same.
+//!
+//! # Examples
+//!
+//! ```
+//! use sadi::{Injector, Provider, Shared};
+//!
+//! struct Config;
+//! struct Service(Shared<Config>);
+//!
+//! let injector = Injector::root();
+//! injector.provide::<Config>(Provider::root(|_| Shared::new(Config)));
+//! injector.provide::<Service>(Provider::transient(|inj| {
+//!     Shared::new(Service(inj.resolve::<Config>()))
+//! }));
+//!
+//! let scope = Injector::child(Shared::new(injector.clone()));
+//! let trace = scope.record_resolutions();
+//! scope.resolve::<Service>();
+//!
+//! assert_eq!(
+//!     trace.type_names(),
+//!     [std::any::type_name::<Service>(), std::any::type_name::<Config>()]
+//! );
+//! ```
+
+use std::any::TypeId;
+
+use crate::runtime::{Shared, Store};
+
+/// The ordered list of resolves seen by a recording injector.
+///
+/// Cloning yields another handle to the same trace, which keeps growing for as
+/// long as the injector records into it.
+#[derive(Clone, Default)]
+pub struct ResolutionTrace {
+    entries: Shared<Store<Vec<(TypeId, &'static str)>>>,
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for ResolutionTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.type_names()).finish()
+    }
+}
+
+impl ResolutionTrace {
+    pub(crate) fn push<T: ?Sized + 'static>(&self) {
+        #[cfg(not(feature = "thread-safe"))]
+        let mut entries = self.entries.borrow_mut();
+        #[cfg(feature = "thread-safe")]
+        let mut entries = self.entries.write().unwrap();
+
+        entries.push((TypeId::of::<T>(), std::any::type_name::<T>()));
+    }
+
+    /// Returns the recorded resolves as `(TypeId, type name)` pairs, in order.
+    pub fn entries(&self) -> Vec<(TypeId, &'static str)> {
+        #[cfg(not(feature = "thread-safe"))]
+        let entries = self.entries.borrow();
+        #[cfg(feature = "thread-safe")]
+        let entries = self.entries.read().unwrap();
+
+        entries.clone()
+    }
+
+    /// Returns the type ids of the recorded resolves, in order.
+    pub fn type_ids(&self) -> Vec<TypeId> {
+        self.entries()
+            .into_iter()
+            .map(|(type_id, _)| type_id)
+            .collect()
+    }
+
+    /// Returns the type names of the recorded resolves, in order.
+    pub fn type_names(&self) -> Vec<&'static str> {
+        self.entries()
+            .into_iter()
+            .map(|(_, type_name)| type_name)
+            .collect()
+    }
+
+    /// Returns the number of recorded resolves.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Returns `true` if nothing has been resolved yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::injector::Injector;
+    use crate::provider::Provider;
+
+    struct Config;
+    struct Service;
+
+    #[test]
+    fn test_trace_records_cache_hits_and_restarts() {
+        let injector = Injector::root();
+        injector.provide::<Config>(Provider::root(|_| Shared::new(Config)));
+        injector.provide::<Service>(Provider::transient(|inj| {
+            inj.resolve::<Config>();
+            Shared::new(Service)
+        }));
+
+        let first = injector.record_resolutions();
+        injector.resolve::<Service>();
+        injector.resolve::<Service>();
+
+        assert_eq!(
+            first.type_ids(),
+            [
+                TypeId::of::<Service>(),
+                TypeId::of::<Config>(),
+                TypeId::of::<Service>(),
+                TypeId::of::<Config>(),
+            ]
+        );
+
+        let second = injector.record_resolutions();
+        injector.resolve::<Config>();
+
+        assert_eq!(first.len(), 4);
+        assert_eq!(second.type_ids(), [TypeId::of::<Config>()]);
+    }
+}
@@ -0,0 +1,14 @@
+use sadi::{Injector, Provider, Shared};
+
+struct RequestContext;
+
+fn main() {
+    let root = Shared::new(Injector::root());
+    root.provide::<RequestContext>(Provider::transient(|_| Shared::new(RequestContext)));
+
+    let escaped = {
+        let scope = Injector::child(root.clone());
+        scope.resolve_scoped::<RequestContext>()
+    };
+    drop(escaped);
+}